@@ -0,0 +1,50 @@
+//! Times reads which fail against reads which succeed, to keep the failure path as cheap as the success path in speculative parsing.
+//!
+//! Run with `cargo bench --bench failure_path`.
+
+use std::hint::black_box;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use seqbytes::prelude::*;
+use seqbytes::reader::SeqReader;
+
+const ITERATIONS: u32 = 10_000_000;
+
+/// Runs `f` [`ITERATIONS`] times and prints the mean time of a call.
+fn bench(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed: Duration = start.elapsed();
+
+    println!(
+        "{:<32} {:>8.2} ns/iter",
+        name,
+        elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+    );
+}
+
+fn main() {
+    let mut short = Cursor::new(vec![0u8; 3]);
+    bench("cursor shift::<u64> failing", || {
+        black_box(short.shift::<u64>());
+    });
+
+    let mut long = Cursor::new(vec![0u8; 8]);
+    bench("cursor shift::<u64> succeeding", || {
+        black_box(long.shift::<u64>());
+        long.set_position(0);
+    });
+
+    let mut reader = SeqReader::new(Cursor::new(vec![0u8; 3]));
+    bench("SeqReader shift_e::<u64> failing", || {
+        black_box(reader.shift_e::<u64>(true));
+    });
+
+    let mut speculative = Cursor::new(vec![0u8; 6]);
+    bench("cursor atomic rollback", || {
+        black_box(speculative.atomic(|r| Some((r.shift::<u32>()?, r.shift::<u32>()?))));
+    });
+}
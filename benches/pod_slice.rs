@@ -0,0 +1,60 @@
+//! Times reading slices of plain-old-data values in place with `shift_pod_slice` against decoding them one by one with `shift_vec`.
+//!
+//! Run with `cargo bench --bench pod_slice --features bytemuck`.
+
+use std::hint::black_box;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use seqbytes::prelude::*;
+
+/// The number of values read by all iterations of one benchmark, so each takes about as long whatever the slice length.
+const VALUES: usize = 100_000_000;
+
+/// Runs `f`, which reads `count` values, until [`VALUES`] have been read, and prints the mean time of a value.
+fn bench(name: &str, count: usize, mut f: impl FnMut()) {
+    let iterations = VALUES / count;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed: Duration = start.elapsed();
+
+    println!(
+        "{:<36} {:>8.3} ns/value",
+        name,
+        elapsed.as_nanos() as f64 / (iterations * count) as f64
+    );
+}
+
+fn main() {
+    for count in [16, 1024, 65536] {
+        let mut cursor = Cursor::new(vec![0u8; count * 4]);
+
+        bench(&format!("shift_pod_slice::<u32>({})", count), count, || {
+            black_box(cursor.shift_pod_slice::<u32>(count));
+            cursor.set_position(0);
+        });
+        bench(&format!("shift_vec::<u32>({})", count), count, || {
+            black_box(cursor.shift_vec::<u32>(count));
+            cursor.set_position(0);
+        });
+        bench(
+            &format!("shift_pod_slice::<[f32; 3]>({})", count / 3),
+            count / 3,
+            || {
+                black_box(cursor.shift_pod_slice::<[f32; 3]>(count / 3));
+                cursor.set_position(0);
+            },
+        );
+        bench(
+            &format!("shift_vec::<[f32; 3]>({})", count / 3),
+            count / 3,
+            || {
+                black_box(cursor.shift_vec::<[f32; 3]>(count / 3));
+                cursor.set_position(0);
+            },
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+
+use super::bytes::{checked_len, SeqByteReaderCore};
+use super::traits::SizedNumber;
+
+/// An owned buffer of `U`s whose allocation is aligned to a chosen power of two, for consumers such as SIMD code which require more
+/// alignment than [`Vec`] guarantees. Dereferences to `[U]`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let a = 1.5f32.to_bytes().repeat(8);
+/// let mut cursor = Cursor::new(a);
+///
+/// let values = cursor.shift_vec_aligned::<f32>(8, 32).unwrap();
+///
+/// assert_eq!(values.as_ptr() as usize % 32, 0);
+/// assert_eq!(*values, [1.5; 8]);
+/// ```
+pub struct AlignedVec<U> {
+    ptr: NonNull<U>,
+    len: usize,
+    layout: Layout,
+}
+
+// SAFETY: `AlignedVec` owns its elements like a `Vec` does.
+unsafe impl<U: Send> Send for AlignedVec<U> {}
+// SAFETY: `AlignedVec` only hands out shared references to its elements through `&self`.
+unsafe impl<U: Sync> Sync for AlignedVec<U> {}
+
+impl<U> AlignedVec<U> {
+    /// Allocates zeroed, uninitialized room for `capacity` elements aligned to `align` bytes. Returns [`None`] if `align` is not a power of two,
+    /// is less than the alignment of `U`, or the size overflows.
+    fn allocate(capacity: usize, align: usize) -> Option<Self> {
+        if !align.is_power_of_two() || align < std::mem::align_of::<U>() {
+            return None;
+        }
+
+        let layout =
+            Layout::from_size_align(checked_len(capacity, std::mem::size_of::<U>()).ok()?, align)
+                .ok()?;
+
+        let ptr = if layout.size() == 0 {
+            // A dangling pointer, which is still aligned as requested.
+            NonNull::new(ptr::without_provenance_mut(align))?
+        } else {
+            // SAFETY: the layout has a non-zero size.
+            let ptr = unsafe { alloc::alloc_zeroed(layout) };
+            NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+        };
+
+        Some(Self {
+            ptr: ptr.cast(),
+            len: 0,
+            layout,
+        })
+    }
+    /// Returns the alignment of the allocation, in bytes.
+    pub fn align(&self) -> usize {
+        self.layout.align()
+    }
+    /// Returns a raw pointer to the first element.
+    pub fn as_ptr(&self) -> *const U {
+        self.ptr.as_ptr()
+    }
+    /// Returns a raw mutable pointer to the first element.
+    pub fn as_mut_ptr(&mut self) -> *mut U {
+        self.ptr.as_ptr()
+    }
+}
+
+impl<U: SizedNumber> AlignedVec<U> {
+    /// Reads `count` values of `U` from `reader` in one read into a buffer aligned to `align` bytes. When the size of `U` in bytes matches its
+    /// size in memory, the values are decoded in place. Returns [`None`] if the alignment is invalid, the bytes could not be read, or any value
+    /// fails to decode.
+    pub(crate) fn read<R: SeqByteReaderCore + ?Sized>(
+        reader: &mut R,
+        count: usize,
+        align: usize,
+    ) -> Option<Self> {
+        let mut out = Self::allocate(count, align)?;
+        let size = U::SIZE;
+
+        if size == std::mem::size_of::<U>() {
+            // SAFETY: the allocation is `count * size` zeroed bytes.
+            let bytes = unsafe {
+                std::slice::from_raw_parts_mut(out.ptr.cast::<u8>().as_ptr(), out.layout.size())
+            };
+            reader.fill(bytes)?;
+
+            for i in 0..count {
+                // SAFETY: element `i` is in bounds, and its bytes are only read before the decoded value is written over them.
+                unsafe {
+                    let slot = out.ptr.as_ptr().add(i);
+                    let value = U::from_bytes(std::slice::from_raw_parts(slot.cast::<u8>(), size))?;
+                    ptr::write(slot, value);
+                }
+                out.len = i + 1;
+            }
+        } else {
+            let mut bytes = vec![0u8; checked_len(count, size).ok()?];
+            reader.fill(&mut bytes)?;
+
+            for i in 0..count {
+                let value = U::from_bytes(&bytes[i * size..(i + 1) * size])?;
+
+                // SAFETY: element `i` is in bounds and uninitialized.
+                unsafe { ptr::write(out.ptr.as_ptr().add(i), value) };
+                out.len = i + 1;
+            }
+        }
+
+        Some(out)
+    }
+}
+
+impl<U> Deref for AlignedVec<U> {
+    type Target = [U];
+
+    fn deref(&self) -> &[U] {
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<U> DerefMut for AlignedVec<U> {
+    fn deref_mut(&mut self) -> &mut [U] {
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<U> Drop for AlignedVec<U> {
+    fn drop(&mut self) {
+        // SAFETY: the first `len` elements are initialized, and the allocation was made with `layout` if its size is non-zero.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len));
+
+            if self.layout.size() != 0 {
+                alloc::dealloc(self.ptr.as_ptr().cast(), self.layout);
+            }
+        }
+    }
+}
+
+impl<U: fmt::Debug> fmt::Debug for AlignedVec<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: PartialEq> PartialEq for AlignedVec<U> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
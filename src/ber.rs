@@ -0,0 +1,126 @@
+use std::fmt;
+
+/// The class of a [`BerTag`], taken from the two most significant bits of its first byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BerClass {
+    /// Types defined by ASN.1 itself, such as `INTEGER` and `SEQUENCE`.
+    Universal,
+    /// Types defined by an application.
+    Application,
+    /// Types whose meaning depends on the enclosing structure, such as the `[0]` version field of a certificate.
+    ContextSpecific,
+    /// Types defined by a private specification.
+    Private,
+}
+
+/// The identifier of a BER or DER element.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::ber::{BerClass, BerTag};
+///
+/// let sequence = BerTag::from_bytes(&[0x30]).unwrap();
+///
+/// assert_eq!(sequence, BerTag { class: BerClass::Universal, constructed: true, number: 16 });
+/// assert_eq!(BerTag::from_bytes(&[0x5F, 0x81, 0x00]).unwrap().number, 128);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BerTag {
+    /// The class of the tag.
+    pub class: BerClass,
+    /// Whether the contents are themselves a series of elements rather than a primitive value.
+    pub constructed: bool,
+    /// The tag number within its class, such as 2 for a universal `INTEGER`.
+    pub number: u32,
+}
+
+impl BerTag {
+    /// Parses a whole encoded tag, in the low-tag-number form of a single byte or the high-tag-number form of a first byte with all five
+    /// number bits set followed by the number in base 128, most significant group first. Returns [`None`] if `bytes` holds anything other
+    /// than exactly one tag, or if the number does not fit in a [`u32`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (&first, rest) = bytes.split_first()?;
+        let mut tag = Self::from_first(first);
+
+        if first & 0x1F != 0x1F {
+            return rest.is_empty().then_some(tag);
+        }
+
+        let (last, groups) = rest.split_last()?;
+        if last & 0x80 != 0 || groups.iter().any(|b| b & 0x80 == 0) {
+            return None;
+        }
+
+        tag.number = 0;
+        for b in rest {
+            if tag.number >> 25 != 0 {
+                return None;
+            }
+            tag.number = (tag.number << 7) | (b & 0x7F) as u32;
+        }
+
+        Some(tag)
+    }
+    /// Returns the tag described by the first byte of an encoded tag, with the number taken from its five low bits.
+    pub(crate) fn from_first(first: u8) -> Self {
+        let class = match first >> 6 {
+            0 => BerClass::Universal,
+            1 => BerClass::Application,
+            2 => BerClass::ContextSpecific,
+            _ => BerClass::Private,
+        };
+
+        Self {
+            class,
+            constructed: first & 0x20 != 0,
+            number: (first & 0x1F) as u32,
+        }
+    }
+}
+
+/// The error returned when reading BER or DER elements, such as by [`crate::bytes::SeqByteReader::shift_ber_tlv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BerError {
+    /// There were not enough bytes to read the element starting at `offset`.
+    Truncated {
+        /// The absolute offset of the start of the element.
+        offset: u64,
+    },
+    /// The element starting at `offset` uses the indefinite length form, which is only allowed in BER and is not supported.
+    IndefiniteLength {
+        /// The absolute offset of the start of the element.
+        offset: u64,
+    },
+    /// The length of the element starting at `offset` uses the reserved form `0xFF`, or does not fit in a [`usize`].
+    InvalidLength {
+        /// The absolute offset of the start of the element.
+        offset: u64,
+    },
+    /// The tag number of the element starting at `offset` does not fit in a [`u32`].
+    InvalidTag {
+        /// The absolute offset of the start of the element.
+        offset: u64,
+    },
+}
+
+impl fmt::Display for BerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BerError::Truncated { offset } => {
+                write!(f, "truncated element at offset {}", offset)
+            }
+            BerError::IndefiniteLength { offset } => {
+                write!(f, "indefinite length in element at offset {}", offset)
+            }
+            BerError::InvalidLength { offset } => {
+                write!(f, "invalid length in element at offset {}", offset)
+            }
+            BerError::InvalidTag { offset } => {
+                write!(f, "tag number too large in element at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BerError {}
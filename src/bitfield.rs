@@ -0,0 +1,283 @@
+use std::fmt;
+
+use super::bytes::{BitOrder, ESeqByteReader, ESeqByteWriter};
+use super::traits::EndianNumber;
+
+/// The error returned by [`BitLayout::check`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BitLayoutError {
+    /// The widths of the fields do not add up to the width of the storage word.
+    Width {
+        /// The width of the storage word, in bits.
+        expected: u32,
+        /// The sum of the widths of the fields, in bits.
+        actual: u32,
+    },
+    /// The field at the contained index is zero bits wide.
+    EmptyField(usize),
+}
+
+impl fmt::Display for BitLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitLayoutError::Width { expected, actual } => write!(
+                f,
+                "fields take {} bits of a {} bit storage word",
+                actual, expected
+            ),
+            BitLayoutError::EmptyField(index) => write!(f, "field {} is zero bits wide", index),
+        }
+    }
+}
+
+impl std::error::Error for BitLayoutError {}
+
+/// A runtime description of the bit fields packed into a storage word of type `W`, such as a header word holding a 12-bit id, a 4-bit
+/// type and a 16-bit length, which unpacks a word into named fields and packs them back.
+///
+/// With [`BitOrder::MsbFirst`] the first field takes the most significant bits of the word, as in the diagrams of network protocols. With
+/// [`BitOrder::LsbFirst`] it takes the least significant bits, as C compilers lay out bit fields on little-endian targets. The widths of the
+/// fields must add up to the width of the word exactly, which [`BitLayout::check`] verifies.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::bitfield::BitLayout;
+/// use seqbytes::bytes::BitOrder;
+/// use std::io::Cursor;
+///
+/// let header = BitLayout::<u32>::new(BitOrder::MsbFirst)
+///     .field("id", 12)
+///     .field("kind", 4)
+///     .field("len", 16);
+///
+/// let fields = header.shift_from(&mut Cursor::new(vec![0xAB, 0xC5, 0x00, 0x12]), true).unwrap();
+///
+/// assert_eq!(fields.get("id"), Some(0xABC));
+/// assert_eq!(fields.get("kind"), Some(5));
+/// assert_eq!(fields.get("len"), Some(0x12));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitLayout<W> {
+    order: BitOrder,
+    fields: Vec<(String, u32)>,
+    word: std::marker::PhantomData<W>,
+}
+
+impl<W: EndianNumber + Copy + Into<u64> + TryFrom<u64>> BitLayout<W> {
+    /// Creates a layout without any fields, packed in the specified bit order.
+    pub fn new(order: BitOrder) -> Self {
+        Self {
+            order,
+            fields: Vec::new(),
+            word: std::marker::PhantomData,
+        }
+    }
+    /// Appends a field `bits` bits wide.
+    pub fn field(mut self, name: &str, bits: u32) -> Self {
+        self.fields.push((name.to_string(), bits));
+        self
+    }
+    /// Returns the bit order of the layout.
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+    /// Returns the width of the storage word, in bits.
+    pub fn width(&self) -> u32 {
+        W::SIZE as u32 * 8
+    }
+    /// Checks that no field is empty and that the widths of the fields add up to the width of the storage word.
+    pub fn check(&self) -> Result<(), BitLayoutError> {
+        if let Some(index) = self.fields.iter().position(|(_, bits)| *bits == 0) {
+            return Err(BitLayoutError::EmptyField(index));
+        }
+
+        let actual = self
+            .fields
+            .iter()
+            .fold(0u32, |sum, (_, bits)| sum.saturating_add(*bits));
+        if actual != self.width() {
+            return Err(BitLayoutError::Width {
+                expected: self.width(),
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+    /// Unpacks `word` into its fields. Returns [`None`] if the layout fails [`BitLayout::check`].
+    pub fn unpack(&self, word: W) -> Option<BitFields> {
+        self.check().ok()?;
+        let word: u64 = word.into();
+
+        let values = self
+            .fields
+            .iter()
+            .zip(self.shifts())
+            .map(|((name, bits), shift)| (name.clone(), (word >> shift) & mask(*bits)))
+            .collect();
+
+        Some(BitFields { values })
+    }
+    /// Packs `values`, one for each field in order, into a word. Returns [`None`] if the layout fails [`BitLayout::check`], if the number of
+    /// values differs from the number of fields, or if a value does not fit in its field.
+    pub fn pack(&self, values: &[u64]) -> Option<W> {
+        self.check().ok()?;
+        if values.len() != self.fields.len() {
+            return None;
+        }
+
+        let mut word = 0u64;
+        for ((&value, (_, bits)), shift) in values.iter().zip(&self.fields).zip(self.shifts()) {
+            if value & !mask(*bits) != 0 {
+                return None;
+            }
+            word |= value << shift;
+        }
+
+        W::try_from(word).ok()
+    }
+    /// Reads a storage word with the specified endianness, shifting the position, and unpacks it. Returns [`None`] if there are not enough
+    /// bytes to be read or the layout fails [`BitLayout::check`], in which case the position is left unchanged.
+    pub fn shift_from<R: ESeqByteReader + ?Sized>(
+        &self,
+        reader: &mut R,
+        bigendian: bool,
+    ) -> Option<BitFields> {
+        self.check().ok()?;
+        self.unpack(reader.shift_e::<W>(bigendian)?)
+    }
+    /// Packs `values` as [`BitLayout::pack`] does and writes the word with the specified endianness. Returns [`None`] if the values cannot
+    /// be packed or the word could not be written.
+    pub fn push_to<V: ESeqByteWriter + ?Sized>(
+        &self,
+        writer: &mut V,
+        values: &[u64],
+        bigendian: bool,
+    ) -> Option<()> {
+        writer.push_e(self.pack(values)?, bigendian)
+    }
+    /// Returns the shift of the lowest bit of each field, in order.
+    fn shifts(&self) -> impl Iterator<Item = u32> + '_ {
+        let (width, order) = (self.width(), self.order);
+
+        self.fields.iter().scan(0, move |used, (_, bits)| {
+            *used += bits;
+            Some(match order {
+                BitOrder::MsbFirst => width - *used,
+                BitOrder::LsbFirst => *used - bits,
+            })
+        })
+    }
+}
+
+/// Returns a mask of the low `bits` bits.
+fn mask(bits: u32) -> u64 {
+    u64::MAX >> (64 - bits)
+}
+
+/// The fields of a word unpacked by a [`BitLayout`], in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitFields {
+    values: Vec<(String, u64)>,
+}
+
+impl BitFields {
+    /// Returns the value of the field named `name`.
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, v)| *v)
+    }
+    /// Returns the values of the fields in order, as taken by [`BitLayout::pack`].
+    pub fn values(&self) -> Vec<u64> {
+        self.values.iter().map(|(_, v)| *v).collect()
+    }
+    /// Returns the names and values of the fields in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.values.iter().map(|(n, v)| (n.as_str(), *v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// The first word of an IPv4 header: version 4, a 20 byte header, DSCP 46 (expedited forwarding), ECN 1 and a total length of 60.
+    const WORD: [u8; 4] = [0x45, 0xB9, 0x00, 0x3C];
+    const VALUES: [u64; 5] = [4, 5, 46, 1, 60];
+
+    #[test]
+    fn ipv4_test() {
+        let msb = BitLayout::<u32>::new(BitOrder::MsbFirst)
+            .field("version", 4)
+            .field("ihl", 4)
+            .field("dscp", 6)
+            .field("ecn", 2)
+            .field("total_length", 16);
+        let mut cursor = Cursor::new(WORD.to_vec());
+        let fields = msb.shift_from(&mut cursor, true).unwrap();
+
+        assert_eq!(fields.values(), VALUES);
+        assert_eq!(fields.get("dscp"), Some(46));
+        assert_eq!(fields.iter().next(), Some(("version", 4)));
+        assert_eq!(cursor.position(), 4);
+
+        let mut out = Vec::new();
+        msb.push_to(&mut out, &fields.values(), true).unwrap();
+        assert_eq!(out, WORD);
+
+        // The same word read little endian puts the last field in the lowest bits, so it is described least significant field first.
+        let lsb = BitLayout::<u32>::new(BitOrder::LsbFirst)
+            .field("total_length", 16)
+            .field("ecn", 2)
+            .field("dscp", 6)
+            .field("ihl", 4)
+            .field("version", 4);
+        let reversed: Vec<u8> = WORD.iter().rev().copied().collect();
+        let fields = lsb
+            .shift_from(&mut Cursor::new(reversed.clone()), false)
+            .unwrap();
+
+        assert_eq!(
+            fields.values(),
+            VALUES.iter().rev().copied().collect::<Vec<_>>()
+        );
+        let mut out = Vec::new();
+        lsb.push_to(&mut out, &fields.values(), false).unwrap();
+        assert_eq!(out, reversed);
+    }
+
+    #[test]
+    fn check_test() {
+        let short = BitLayout::<u16>::new(BitOrder::MsbFirst)
+            .field("a", 7)
+            .field("b", 8);
+        assert_eq!(
+            short.check(),
+            Err(BitLayoutError::Width {
+                expected: 16,
+                actual: 15
+            })
+        );
+        assert_eq!(short.unpack(0), None);
+        assert_eq!(short.shift_from(&mut Cursor::new(vec![0, 0]), true), None);
+
+        let empty = BitLayout::<u8>::new(BitOrder::LsbFirst)
+            .field("a", 8)
+            .field("b", 0);
+        assert_eq!(empty.check(), Err(BitLayoutError::EmptyField(1)));
+
+        // Values which do not fit their field are rejected rather than masked.
+        let flags = BitLayout::<u8>::new(BitOrder::LsbFirst)
+            .field("low", 3)
+            .field("high", 5);
+        assert_eq!(flags.pack(&[0b101, 0b11]), Some(0b11_101));
+        assert_eq!(flags.pack(&[0b1000, 0]), None);
+        assert_eq!(flags.pack(&[1]), None);
+        assert_eq!(flags.width(), 8);
+
+        let whole = BitLayout::<u64>::new(BitOrder::MsbFirst).field("all", 64);
+        assert_eq!(whole.unpack(u64::MAX).unwrap().get("all"), Some(u64::MAX));
+        assert_eq!(whole.pack(&[u64::MAX]), Some(u64::MAX));
+    }
+}
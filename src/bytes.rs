@@ -1,234 +1,3679 @@
-use std::io::{Read, Seek, SeekFrom};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::ControlFlow;
 
+use super::aligned::AlignedVec;
+use super::ber::{BerError, BerTag};
+#[cfg(feature = "crc")]
+use super::crc::{CrcAlgo, FrameCheckError};
 use super::traits::*;
 
-/// Represents a sequential byte reader which can read bytes. Can be used on types that implement [`Read`] + [`Seek`].
+/// The order in which bits are packed into a byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The first bit is stored in the least significant bit of each byte.
+    LsbFirst,
+    /// The first bit is stored in the most significant bit of each byte.
+    MsbFirst,
+}
+
+impl BitOrder {
+    /// Returns the mask selecting the `bit`th bit (0 to 7) of a byte in this order.
+    fn mask(self, bit: usize) -> u8 {
+        match self {
+            BitOrder::LsbFirst => 1 << bit,
+            BitOrder::MsbFirst => 0x80 >> bit,
+        }
+    }
+}
+
+/// The result of [`ESeqByteReader::binary_search_record`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchResult {
+    /// The first record whose key equals the needle, with its index and absolute offset.
+    Found {
+        /// The index of the record.
+        index: u64,
+        /// The absolute offset of the record.
+        offset: u64,
+    },
+    /// No record's key equals the needle. Holds the index and absolute offset at which a record with the needle as its key would be inserted
+    /// to keep the records sorted.
+    NotFound {
+        /// The index of the insertion point.
+        index: u64,
+        /// The absolute offset of the insertion point.
+        offset: u64,
+    },
+}
+
+impl SearchResult {
+    /// Returns the index of the found record, or of the insertion point.
+    pub fn index(&self) -> u64 {
+        match *self {
+            SearchResult::Found { index, .. } | SearchResult::NotFound { index, .. } => index,
+        }
+    }
+    /// Returns the absolute offset of the found record, or of the insertion point.
+    pub fn offset(&self) -> u64 {
+        match *self {
+            SearchResult::Found { offset, .. } | SearchResult::NotFound { offset, .. } => offset,
+        }
+    }
+    /// Returns `true` if a record with the needle as its key was found.
+    pub fn is_found(&self) -> bool {
+        matches!(self, SearchResult::Found { .. })
+    }
+}
+
+/// The layout of a file of fixed-size records: a header of `header_len` bytes, followed by records of `record_size` bytes each. Converts
+/// between record indices and absolute offsets with every step checked for overflow, and against `count` if the number of records is
+/// known.
 ///
 /// # Examples
 ///
 /// ```
 /// use seqbytes::prelude::*;
-/// use std::io::Cursor;
-/// use std::str::FromStr;
-///
-/// let a = vec![69, 96, 255, 255, 0x68, 0x65, 0x6C, 0x6C, 0x6F];
-/// let mut cursor = Cursor::new(a);
 ///
-/// let num : i32 = cursor.shift().unwrap();
-/// let s = &*cursor.shift_string(5).unwrap();
+/// let geometry = RecordGeometry { header_len: 16, record_size: 8, count: Some(4) };
 ///
-/// assert_eq!(num, -40891);
-/// assert_eq!(*s, *"hello");
+/// assert_eq!(geometry.offset_of(3), Some(40));
+/// assert_eq!(geometry.offset_of(4), None);
+/// assert_eq!(geometry.index_at(35), Some((2, 3)));
+/// assert_eq!(geometry.index_at(12), None);
 /// ```
-pub trait SeqByteReader {
-    /// Peaks the next `U` from the current position, reading the size of `U`'s amount of bytes, and converting to the `U`. Returns [`None`]
-    /// if there are not enough bytes to be read.
-    fn next<U: SizedNumber>(&mut self) -> Option<U>;
-    /// Peaks the next `U` from the current position, shifting and reading the size of `U`'s amount of bytes, and converting to the `U`. Returns [`None`]
-    /// if there are not enough bytes to be read.
-    ///
-    fn shift<U: SizedNumber>(&mut self) -> Option<U>;
-    /// Peaks the next `amount` of bytes. Returns a [`Vec<u8>`] containing the bytes.
-    fn next_slice(&mut self, amount: usize) -> Option<Vec<u8>>;
-    /// Peaks the next `amount` bytes, and shifting the position by `amount` bytes. Returns a [`Vec<u8>`] containing the bytes.
-    fn shift_slice(&mut self, amount: usize) -> Option<Vec<u8>>;
-    /// Peaks the next `amount` bytes. Returns a [`String`] containing the bytes. Returns [`None`] if there are no
-    /// more bytes to be read. If unimplemented, internally calls `next_slice` and converts it to a lossy UTF-8 String.
-    fn next_string(&mut self, amount: usize) -> Option<String> {
-        let slice = self.next_slice(amount)?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordGeometry {
+    /// The length of the header before the first record, in bytes.
+    pub header_len: u64,
+    /// The size of each record, in bytes.
+    pub record_size: u64,
+    /// The number of records, if known.
+    pub count: Option<u64>,
+}
 
-        Some(String::from_utf8_lossy(&slice).to_string())
-    }
-    /// Peaks the next `amount` bytes, and shifting the position by `amount` bytes. Returns a [`String`] containing the bytes. Returns [`None`] if there are no
-    /// more bytes to be read. If unimplemented, internally calls `shift_slice` and converts it to a lossy UTF-8 String.
-    fn shift_string(&mut self, amount: usize) -> Option<String> {
-        let slice = self.shift_slice(amount)?;
+impl RecordGeometry {
+    /// Returns the absolute offset of the record at `index`. Returns [`None`] if `index` is not less than the number of records, if known,
+    /// or the offset overflows a [`u64`].
+    pub fn offset_of(&self, index: u64) -> Option<u64> {
+        if self.count.is_some_and(|count| index >= count) {
+            return None;
+        }
 
-        Some(String::from_utf8_lossy(&slice).to_string())
+        index
+            .checked_mul(self.record_size)?
+            .checked_add(self.header_len)
     }
+    /// Returns the index of the record containing the absolute offset `offset`, along with the offset within that record. Returns [`None`]
+    /// if `offset` is within the header, the records are zero bytes long, or `offset` is past the last record, if the number of records is
+    /// known.
+    pub fn index_at(&self, offset: u64) -> Option<(u64, u64)> {
+        let relative = offset.checked_sub(self.header_len)?;
+        let index = relative.checked_div(self.record_size)?;
+        if self.count.is_some_and(|count| index >= count) {
+            return None;
+        }
 
-    /* Not sure if I should keep these methods. Should I ?
-    fn next_u8(&mut self) -> Option<u8> {
-        self.next::<u8>()
-    }
-    fn next_i8(&mut self) -> Option<i8> {
-        self.next::<i8>()
+        Some((index, relative % self.record_size))
     }
-    fn next_u16(&mut self) -> Option<u16> {
-        self.next::<u16>()
+}
+
+/// The encoding of the length prefix of a record read by [`SeqByteReader::shift_versioned_record`]. The length counts the bytes of the record
+/// after the prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VersionedLen {
+    /// A `u8`.
+    U8,
+    /// A `u16` with the contained byte order.
+    U16(Endianness),
+    /// A `u32` with the contained byte order.
+    U32(Endianness),
+    /// A variable-length quantity, as read by [`SeqByteReader::shift_vlq`].
+    Vlq,
+}
+
+impl VersionedLen {
+    /// Reads a length in this encoding, without restoring the position on failure.
+    pub(crate) fn read<R: SeqByteReaderCore + ?Sized>(self, reader: &mut R) -> Option<u64> {
+        match self {
+            VersionedLen::U8 => reader.shift::<u8>().map(u64::from),
+            VersionedLen::U16(e) => reader.shift_e::<u16>(e.is_big()).map(u64::from),
+            VersionedLen::U32(e) => reader.shift_e::<u32>(e.is_big()).map(u64::from),
+            VersionedLen::Vlq => reader.shift_vlq().map(u64::from),
+        }
     }
-    fn next_i16(&mut self) -> Option<i16> {
-        self.next::<i16>()
+    /// Encodes `len` in this encoding, or returns [`None`] if it does not fit.
+    fn encode(self, len: usize) -> Option<Vec<u8>> {
+        match self {
+            VersionedLen::U8 => Some(u8::try_from(len).ok()?.to_bytes()),
+            VersionedLen::U16(e) => Some(u16::try_from(len).ok()?.to_bytes_e(e.is_big())),
+            VersionedLen::U32(e) => Some(u32::try_from(len).ok()?.to_bytes_e(e.is_big())),
+            VersionedLen::Vlq => {
+                let mut bytes = Vec::new();
+                bytes.push_vlq(u32::try_from(len).ok()?)?;
+
+                Some(bytes)
+            }
+        }
     }
-    fn next_u32(&mut self) -> Option<u32> {
-        self.next::<u32>()
+}
+
+/// The error returned by [`SeqByteReader::shift_checked`] and [`ESeqByteReader::shift_checked_e`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckError<E> {
+    /// There were not enough bytes to read the value, or it failed to convert.
+    Truncated,
+    /// The value was read, but rejected by the check with the contained error.
+    Invalid(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CheckError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::Truncated => write!(f, "not enough bytes to read the value"),
+            CheckError::Invalid(e) => write!(f, "invalid value: {}", e),
+        }
     }
-    fn next_i32(&mut self) -> Option<i32> {
-        self.next::<i32>()
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for CheckError<E> {}
+
+/// The error returned by [`SeqByteReader::expect`] and its variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpectError<U> {
+    /// There were not enough bytes to read the value at `offset`, or it failed to convert.
+    Truncated {
+        /// The absolute offset of the value.
+        offset: u64,
+    },
+    /// The value at `offset` is not the expected one.
+    Mismatch {
+        /// The absolute offset of the value.
+        offset: u64,
+        /// The expected value.
+        expected: U,
+        /// The value read.
+        actual: U,
+    },
+}
+
+impl<U: fmt::Debug> fmt::Display for ExpectError<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectError::Truncated { offset } => {
+                write!(f, "not enough bytes to read the value at offset {}", offset)
+            }
+            ExpectError::Mismatch {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {:?} at offset {}, found {:?}",
+                expected, offset, actual
+            ),
+        }
     }
-    fn next_f32(&mut self) -> Option<f32> {
-        self.next::<f32>()
+}
+
+impl<U: fmt::Debug> std::error::Error for ExpectError<U> {}
+
+/// The error returned by [`ESeqByteReader::shift_narrow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NarrowError<W> {
+    /// There were not enough bytes to read the value at `offset`, or it failed to convert.
+    Truncated {
+        /// The absolute offset of the value.
+        offset: u64,
+    },
+    /// The value at `offset` does not fit in the narrower type.
+    OutOfRange {
+        /// The absolute offset of the value.
+        offset: u64,
+        /// The value read, as the wider type.
+        value: W,
+    },
+}
+
+impl<W: fmt::Debug> fmt::Display for NarrowError<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NarrowError::Truncated { offset } => {
+                write!(f, "not enough bytes to read the value at offset {}", offset)
+            }
+            NarrowError::OutOfRange { offset, value } => {
+                write!(f, "value {:?} at offset {} is out of range", value, offset)
+            }
+        }
     }
-    fn next_u64(&mut self) -> Option<u64> {
-        self.next::<u64>()
+}
+
+impl<W: fmt::Debug> std::error::Error for NarrowError<W> {}
+
+/// The error returned by [`ESeqByteReader::shift_delta_decoded`] and [`SeqByteReader::shift_varint_delta_decoded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeltaError {
+    /// The delta starting at `offset` could not be read, because the bytes ran out before its end or, for varint deltas, it is longer than a
+    /// [`u64`]. Also returned, with `offset` 0, if the position of the reader is unknown.
+    Truncated {
+        /// The absolute offset of the delta.
+        offset: u64,
+    },
+    /// Adding the delta at `offset`, the `index`th one read, to the running sum overflowed a [`u64`].
+    Overflow {
+        /// The absolute offset of the delta.
+        offset: u64,
+        /// The index of the delta among those read.
+        index: usize,
+    },
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeltaError::Truncated { offset } => {
+                write!(f, "not enough bytes to read the delta at offset {}", offset)
+            }
+            DeltaError::Overflow { offset, index } => {
+                write!(
+                    f,
+                    "delta {} at offset {} overflows the running sum",
+                    index, offset
+                )
+            }
+        }
     }
-    fn next_i64(&mut self) -> Option<i64> {
-        self.next::<i64>()
+}
+
+impl std::error::Error for DeltaError {}
+
+/// The error returned by [`ESeqByteReader::shift_hash_map`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapError<K> {
+    /// The count or an entry could not be read.
+    Truncated,
+    /// The contained key appeared in more than one entry.
+    DuplicateKey(K),
+}
+
+impl<K: fmt::Debug> fmt::Display for MapError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Truncated => write!(f, "not enough bytes to read the map"),
+            MapError::DuplicateKey(key) => write!(f, "duplicate key {:?}", key),
+        }
     }
-    fn next_f64(&mut self) -> Option<f64> {
-        self.next::<f64>()
+}
+
+impl<K: fmt::Debug> std::error::Error for MapError<K> {}
+
+/// A record visited by [`ESeqByteReader::walk_records`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordInfo {
+    /// The index of the record, counting from zero.
+    pub index: usize,
+    /// The absolute offset of the start of the record, which is its length prefix.
+    pub offset: u64,
+    /// The declared length of the payload, in bytes.
+    pub len: u64,
+}
+
+/// The summary returned by [`ESeqByteReader::walk_records`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WalkSummary {
+    /// The number of records walked.
+    pub records: usize,
+    /// The total length of the payloads walked, in bytes.
+    pub payload_bytes: u64,
+}
+
+/// The error returned by [`ESeqByteReader::walk_records`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalkError {
+    /// The length prefix of the record at `offset` was cut off by the end of the source.
+    Truncated {
+        /// The index of the record.
+        index: usize,
+        /// The absolute offset of the start of the record.
+        offset: u64,
+    },
+    /// The length prefix of the record at `offset` is negative.
+    InvalidLength {
+        /// The index of the record.
+        index: usize,
+        /// The absolute offset of the start of the record.
+        offset: u64,
+    },
+    /// The payload of the record at `offset` is longer than the bytes remaining in the source.
+    PayloadOverrun {
+        /// The index of the record.
+        index: usize,
+        /// The absolute offset of the start of the record.
+        offset: u64,
+        /// The declared length of the payload, in bytes.
+        len: u64,
+    },
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalkError::Truncated { index, offset } => write!(
+                f,
+                "truncated length of record {} at offset {}",
+                index, offset
+            ),
+            WalkError::InvalidLength { index, offset } => {
+                write!(f, "invalid length of record {} at offset {}", index, offset)
+            }
+            WalkError::PayloadOverrun { index, offset, len } => write!(
+                f,
+                "payload of record {} at offset {} runs {} bytes past the end",
+                index, offset, len
+            ),
+        }
     }
+}
 
-    fn shift_u8(&mut self) -> Option<u8> {
-        self.shift::<u8>()
+impl std::error::Error for WalkError {}
+
+/// The error returned by [`ESeqByteReader::visit_offset_table`] and [`ESeqByteReader::visit_offset_table_dedup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetTableError {
+    /// The count or the offsets could not be read.
+    Truncated,
+    /// The offset of entry `index` is negative or lies past the end of the source.
+    OutOfBounds {
+        /// The index of the entry in the table.
+        index: usize,
+    },
+    /// The visitor failed at the offset of entry `index`.
+    Visit {
+        /// The index of the entry in the table.
+        index: usize,
+        /// The absolute offset the visitor was called at.
+        offset: u64,
+    },
+}
+
+impl fmt::Display for OffsetTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffsetTableError::Truncated => write!(f, "not enough bytes to read the offset table"),
+            OffsetTableError::OutOfBounds { index } => {
+                write!(f, "offset of entry {} is out of bounds", index)
+            }
+            OffsetTableError::Visit { index, offset } => {
+                write!(f, "failed to read entry {} at offset {}", index, offset)
+            }
+        }
     }
-    fn shift_i8(&mut self) -> Option<i8> {
-        self.shift::<i8>()
+}
+
+impl std::error::Error for OffsetTableError {}
+
+/// The error returned by [`SeqByteReader::ensure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotEnough {
+    /// The absolute offset the bytes were needed from, or [`None`] if the position of the reader is unknown.
+    pub offset: Option<u64>,
+    /// The number of bytes needed.
+    pub needed: usize,
+    /// The number of bytes remaining, if both the offset and the end of the source are known.
+    pub remaining: Option<u64>,
+}
+
+impl fmt::Display for NotEnough {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.offset {
+            Some(offset) => write!(f, "needed {} bytes at offset {}", self.needed, offset)?,
+            None => write!(f, "needed {} bytes at an unknown offset", self.needed)?,
+        }
+        match self.remaining {
+            Some(remaining) => write!(f, ", but only {} remain", remaining),
+            None => write!(f, ", but the source ends first"),
+        }
     }
-    fn shift_u16(&mut self) -> Option<u16> {
-        self.shift::<u16>()
+}
+
+impl std::error::Error for NotEnough {}
+
+/// The error returned by [`checked_len`] when the byte length of `count` items of `size` bytes each does not fit in a [`usize`], such as
+/// for a hostile element count on a 32-bit target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LengthOverflow {
+    /// The number of items.
+    pub count: usize,
+    /// The size of each item in bytes.
+    pub size: usize,
+}
+
+impl fmt::Display for LengthOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} items of {} bytes overflow the length of a buffer",
+            self.count, self.size
+        )
     }
-    fn shift_i16(&mut self) -> Option<i16> {
-        self.shift::<i16>()
+}
+
+impl std::error::Error for LengthOverflow {}
+
+/// Returns the length in bytes of `count` items of `size` bytes each, or [`LengthOverflow`] if it does not fit in a [`usize`]. Every read
+/// which multiplies a count read from the source by a size goes through this, so that an overflowing count fails instead of wrapping to a
+/// small buffer.
+///
+/// The readers which take a count, such as [`SeqByteReader::shift_vec`] and [`ESeqByteReader::shift_counted`], return an [`Option`], so they
+/// fold a [`LengthOverflow`] into [`None`] like any other failure, and [`crate::reader::SeqReader::take_error`] does not record it. Callers
+/// which need to tell an overflowing count apart from a truncated source check the count with this first.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::bytes::{checked_len, LengthOverflow};
+///
+/// assert_eq!(checked_len(3, 4), Ok(12));
+/// assert_eq!(checked_len(usize::MAX / 4, 4), Ok(usize::MAX - 3));
+/// assert_eq!(
+///     checked_len(usize::MAX / 4 + 1, 4),
+///     Err(LengthOverflow { count: usize::MAX / 4 + 1, size: 4 })
+/// );
+/// ```
+pub fn checked_len(count: usize, size: usize) -> Result<usize, LengthOverflow> {
+    count
+        .checked_mul(size)
+        .ok_or(LengthOverflow { count, size })
+}
+
+/// The core of a sequential byte reader, which every method of [`SeqByteReader`] and [`ESeqByteReader`] is built on. It is implemented for
+/// every type implementing [`Read`] and [`Seek`], and can be implemented directly for sources which are neither, such as a client of a remote
+/// block store, to get both extension traits. It is object safe, so readers can be passed around as `&mut dyn SeqByteReaderCore`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+///
+/// /// A source of repeated copies of a single byte.
+/// struct Repeat {
+///     byte: u8,
+///     pos: u64,
+/// }
+///
+/// impl SeqByteReaderCore for Repeat {
+///     fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+///         buf.fill(self.byte);
+///         self.pos += buf.len() as u64;
+///         Some(())
+///     }
+///
+///     fn pos(&mut self) -> Option<u64> {
+///         Some(self.pos)
+///     }
+///
+///     fn set_pos(&mut self, pos: u64) -> Option<()> {
+///         self.pos = pos;
+///         Some(())
+///     }
+/// }
+///
+/// let mut repeat = Repeat { byte: 1, pos: 0 };
+///
+/// assert_eq!(repeat.shift_e::<u16>(true), Some(0x0101));
+/// assert_eq!(repeat.pos(), Some(2));
+/// ```
+pub trait SeqByteReaderCore {
+    /// Fills `buf` with the next bytes, shifting the position by its length. Returns [`None`] if there are not enough bytes to be read, in
+    /// which case the position is unspecified.
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()>;
+    /// Fills `buf` with the next bytes without shifting the position. Returns [`None`] if there are not enough bytes to be read, in which case
+    /// the position is unspecified. If unimplemented, calls `fill` and then restores the position with `set_pos`.
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let pos = self.pos()?;
+        self.fill(buf)?;
+
+        self.set_pos(pos)
     }
-    fn shift_u32(&mut self) -> Option<u32> {
-        self.shift::<u32>()
+    /// Returns the current position, as an absolute offset in bytes.
+    fn pos(&mut self) -> Option<u64>;
+    /// Sets the current position to the absolute offset `pos`, in bytes. Together with [`SeqByteReaderCore::pos`], this lets code generic
+    /// over readers follow offsets stored in the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// /// Reads the `u16` at the offset stored in the next byte, leaving the position just after that byte.
+    /// fn follow(reader: &mut impl SeqByteReader) -> Option<u16> {
+    ///     let offset = reader.shift::<u8>()?;
+    ///     let back = reader.pos()?;
+    ///
+    ///     reader.set_pos(offset.into())?;
+    ///     let value = reader.shift_e::<u16>(true);
+    ///     reader.set_pos(back)?;
+    ///
+    ///     value
+    /// }
+    ///
+    /// let mut cursor = Cursor::new(vec![3, 4, 0xFF, 0x01, 0x02]);
+    ///
+    /// assert_eq!(follow(&mut cursor), Some(0x0102));
+    /// assert_eq!(cursor.pos(), Some(1));
+    /// assert_eq!(follow(&mut cursor), None);
+    /// assert_eq!(cursor.pos(), Some(2));
+    /// ```
+    fn set_pos(&mut self, pos: u64) -> Option<()>;
+    /// Returns the absolute offset of the end of the source, in bytes, if it is known. Used to reject lengths read from the source which
+    /// exceed what remains before allocating for them. If unimplemented, returns [`None`].
+    fn end_pos(&mut self) -> Option<u64> {
+        None
     }
-    fn shift_i32(&mut self) -> Option<i32> {
-        self.shift::<i32>()
+    /// Fills `buf` with the next bytes, shifting the position by its length. Returns [`None`] if there are not enough bytes to be read, in
+    /// which case the position is left where it was. The fixed-size readers of [`SeqByteReader`] are built on this, so they only touch the
+    /// position when a read fails.
+    ///
+    /// If unimplemented, queries the position before calling `fill` and sets it back on failure. Readers whose position is unknown are
+    /// filled without being restored.
+    fn try_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let Some(start) = self.pos() else {
+            return self.fill(buf);
+        };
+
+        let filled = self.fill(buf);
+        if filled.is_none() {
+            let _ = self.set_pos(start);
+        }
+
+        filled
     }
-    fn shift_f32(&mut self) -> Option<f32> {
-        self.shift::<f32>()
+    /// Fills `buf` with the next bytes without shifting the position, as `peek_fill` does. Returns [`None`] if there are not enough bytes to
+    /// be read, in which case the position is left where it was. The one exception is a reader which refuses to seek backwards: the bytes
+    /// read cannot be given back, so the peek fails with them consumed.
+    ///
+    /// If unimplemented, queries the position before calling `peek_fill` and sets it back on failure.
+    fn try_peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let Some(start) = self.pos() else {
+            return self.peek_fill(buf);
+        };
+
+        let filled = self.peek_fill(buf);
+        if filled.is_none() {
+            let _ = self.set_pos(start);
+        }
+
+        filled
     }
-    fn shift_u64(&mut self) -> Option<u64> {
-        self.shift::<u64>()
+    /// Shifts the position backwards by `amount` bytes. Returns [`None`] without moving if that is before the start of the source, or the
+    /// position could not be set. If unimplemented, goes through `pos` and `set_pos`.
+    fn step_back(&mut self, amount: u64) -> Option<()> {
+        let target = self.pos()?.checked_sub(amount)?;
+        self.set_pos(target)
     }
-    fn shift_i64(&mut self) -> Option<i64> {
-        self.shift::<i64>()
+}
+
+/// A reader which can be forked into an independent reader over the same bytes at the same position, for speculative parsing: a parse is
+/// tried on the fork, and the original only advances if it succeeds.
+///
+/// Implemented for [`Cursor`]s over any cloneable bytes, which is cheap for `Cursor<&[u8]>` and `Cursor<Arc<[u8]>>` but copies a
+/// `Cursor<Vec<u8>>`, and for [`crate::shared::SharedReader`], which shares its source through an [`std::sync::Arc`], including memory
+/// maps. It is not implemented for [`std::fs::File`], since the handles returned by [`std::fs::File::try_clone`] share one position. Use a
+/// [`crate::shared::SharedReader`] over the file instead.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// // Reads a version as a `u16` if it is a known one, leaving the reader untouched otherwise.
+/// fn known_version<R: SeqByteReader + ForkableReader>(reader: &mut R) -> Option<u16> {
+///     reader.speculate(|r| r.shift::<u16>().filter(|v| *v <= 3))
+/// }
+///
+/// let mut cursor = Cursor::new(&[2, 0, 9, 0][..]);
+///
+/// assert_eq!(known_version(&mut cursor), Some(2));
+/// assert_eq!(known_version(&mut cursor), None);
+/// assert_eq!(cursor.position(), 2);
+/// ```
+pub trait ForkableReader: Sized {
+    /// Returns an independent reader over the same bytes, at the same position.
+    fn fork(&self) -> Self;
+    /// Runs `f` on a fork of `self`, and replaces `self` with the fork if `f` returns [`Some`], so the position only advances on success.
+    fn speculate<U, F: FnOnce(&mut Self) -> Option<U>>(&mut self, f: F) -> Option<U> {
+        let mut fork = self.fork();
+        let value = f(&mut fork)?;
+
+        *self = fork;
+        Some(value)
     }
-    fn shift_f64(&mut self) -> Option<f64> {
-        self.shift::<f64>()
+}
+
+impl<T: AsRef<[u8]> + Clone> ForkableReader for Cursor<T> {
+    fn fork(&self) -> Self {
+        self.clone()
     }
-    */
 }
-/// Represents a sequential byte reader which can read bytes with a specified endianness. Can be used on types that implement [`Read`] + [`Seek`]
+
+/// Represents a sequential byte reader which can read bytes. Implemented for every type implementing [`SeqByteReaderCore`], including
+/// types that implement [`Read`] + [`Seek`].
+///
+/// Every method is atomic: if it returns [`None`] or an error, the position is where it was before the call, however far the read got
+/// before failing, so the bytes can be read again or interpreted another way. This holds for any reader whose position can be queried and
+/// set.
 ///
 /// # Examples
 ///
 /// ```
 /// use seqbytes::prelude::*;
 /// use std::io::Cursor;
+/// use std::str::FromStr;
 ///
-/// let a = vec![69, 96, 255, 255];
+/// let a = vec![69, 96, 255, 255, 0x68, 0x65, 0x6C, 0x6C, 0x6F];
 /// let mut cursor = Cursor::new(a);
 ///
-/// let num : i32 = cursor.next_e(false).unwrap();
-/// let num2 : i32 = cursor.shift_e(true).unwrap();
-/// let num3 : Option<i32> = cursor.shift_e(false);
+/// let num : i32 = cursor.shift().unwrap();
+/// let s = &*cursor.shift_string(5).unwrap();
 ///
-/// assert_ne!(num, num2);
 /// assert_eq!(num, -40891);
-/// assert_eq!(num2, 1163984895);
-/// assert_eq!(num3, None);
+/// assert_eq!(*s, *"hello");
 /// ```
-pub trait ESeqByteReader {
-    /// Peaks the next `U` from the current position, reading the size of `U`'s amount of bytes, and converting to the `U` with the specified endianness. Returns [`None`]
+pub trait SeqByteReader: SeqByteReaderCore {
+    /// Peaks the next `U` from the current position, reading the size of `U`'s amount of bytes, and converting to the `U`. Returns [`None`]
     /// if there are not enough bytes to be read.
     ///
+    /// Peeking reads the bytes and then seeks back over them, so on a reader which refuses to seek backwards, such as a pipe, it fails
+    /// with the bytes consumed. Only [`SeqByteReader::shift`] and the other shifting readers are recoverable on such readers.
+    fn next<U: SizedNumber>(&mut self) -> Option<U>;
+    /// Peaks the next `U` from the current position, shifting and reading the size of `U`'s amount of bytes, and converting to the `U`. Returns [`None`]
+    /// if there are not enough bytes to be read, or if they fail to convert, such as a byte other than `0` or `1` for a [`bool`], in which
+    /// case the position is restored.
+    ///
+    fn shift<U: SizedNumber>(&mut self) -> Option<U>;
+    /// Reads the next `count` values of `U` in a single read, shifting the position. Returns [`None`] if there are not enough bytes to be read,
+    /// if any value fails to convert, or if the length of `count` values overflows a [`usize`], as [`checked_len`] tells apart. If the end of
+    /// the source is known, a `count` which exceeds what remains is rejected before the buffer is allocated.
+    ///
     /// # Examples
     ///
     /// ```
     /// use seqbytes::prelude::*;
     /// use std::io::Cursor;
     ///
-    /// let a = vec![69, 96, 255, 255];
-    /// let mut cursor = Cursor::new(a);
+    /// let mut cursor = Cursor::new(vec![1, 0, 2, 0, 3, 0]);
     ///
-    /// let pos1 = cursor.position();
-    /// let num : i32 = cursor.next_e(false).unwrap();
-    /// let pos2 = cursor.position();
+    /// assert_eq!(cursor.shift_vec::<u16>(3).unwrap(), [1, 2, 3]);
+    /// ```
+    fn shift_vec<U: SizedNumber>(&mut self, count: usize) -> Option<Vec<U>>;
+    /// Peaks the next `amount` of bytes. Returns a [`Vec<u8>`] containing the bytes.
+    fn next_slice(&mut self, amount: usize) -> Option<Vec<u8>>;
+    /// Peaks the next `amount` bytes, and shifting the position by `amount` bytes. Returns a [`Vec<u8>`] containing the bytes. If the end
+    /// of the source is known, an `amount` which exceeds what remains is rejected before the buffer is allocated.
+    fn shift_slice(&mut self, amount: usize) -> Option<Vec<u8>>;
+    /// Reads the `amount` bytes ending at the current position, shifting the position backwards by `amount` bytes. Returns a [`Vec<u8>`]
+    /// containing the bytes in their stream order. Returns [`None`] without moving if there are fewer than `amount` bytes before the position.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(pos1, pos2);
-    /// assert_eq!(num, -40891);
     /// ```
-    fn next_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U>;
-    /// Peaks the next `U` from the current position, shifting and reading the size of `U`'s amount of bytes, and converting to the `U` with the specified endianness. Returns [`None`]
-    /// if there are not enough bytes to be read.
+    /// use seqbytes::prelude::*;
+    /// use std::io::{Cursor, Seek, SeekFrom};
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 2, 3, 4]);
+    /// cursor.seek(SeekFrom::End(0)).unwrap();
+    ///
+    /// assert_eq!(cursor.shift_slice_back(3).unwrap(), [2, 3, 4]);
+    /// assert_eq!(cursor.position(), 1);
+    /// assert!(cursor.shift_slice_back(2).is_none());
+    /// assert_eq!(cursor.position(), 1);
+    /// ```
+    fn shift_slice_back(&mut self, amount: usize) -> Option<Vec<u8>>;
+    /// Reads the `U` ending at the current position, shifting the position backwards by the size of `U`. The bytes are converted in their stream
+    /// order, as [`SeqByteReader::shift`] would. Returns [`None`] without moving if there are not enough bytes before the position, or if the
+    /// value fails to convert.
     ///
     /// # Examples
     ///
     /// ```
     /// use seqbytes::prelude::*;
-    /// use std::io::Cursor;
+    /// use std::io::{Cursor, Seek, SeekFrom};
     ///
-    /// let a = vec![69, 96, 255, 255];
-    /// let mut cursor = Cursor::new(a);
+    /// let mut cursor = Cursor::new(vec![1, 0, 2, 0]);
+    /// cursor.seek(SeekFrom::End(0)).unwrap();
     ///
-    /// let pos1 = cursor.position();
-    /// let num : i32 = cursor.shift_e(false).unwrap();
-    /// let pos2 = cursor.position();
+    /// assert_eq!(cursor.shift_back::<u16>(), Some(2));
+    /// assert_eq!(cursor.shift_back::<u16>(), Some(1));
+    /// assert_eq!(cursor.shift_back::<u16>(), None);
+    /// ```
+    fn shift_back<U: SizedNumber>(&mut self) -> Option<U>;
+    /// Shifts the position backwards by `amount` bytes without reading them, such as to put back a value which turned out to start the next
+    /// record. Returns [`None`] without moving if there are fewer than `amount` bytes before the position, or if the position could not be
+    /// set, such as on a reader which refuses to seek backwards.
+    ///
+    /// Named `rewind_by` rather than `rewind` so that it does not clash with [`std::io::Seek::rewind`], which moves every [`Seek`] reader
+    /// back to the start.
+    ///
+    /// # Examples
     ///
-    /// assert_ne!(pos1, pos2);
-    /// assert_eq!(num, -40891);
     /// ```
-    fn shift_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U>;
-}
-
-impl<T: Seek + Read> SeqByteReader for T {
-    fn next<U: SizedNumber>(&mut self) -> Option<U> {
-        let size = U::size() as isize;
-
-        let mut a = vec![0u8; size as usize];
-        self.read_exact(&mut a).ok()?;
-
-        self.seek(SeekFrom::Current(-size as i64)).unwrap(); // Should not panic, as it is shifting backwards the same amount of bytes as moving forward.
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 2, 3]);
+    /// cursor.shift_slice(3).unwrap();
+    ///
+    /// assert_eq!(cursor.rewind_by(2), Some(()));
+    /// assert_eq!(cursor.position(), 1);
+    /// assert_eq!(cursor.rewind_by(2), None);
+    /// assert_eq!(cursor.position(), 1);
+    /// ```
+    fn rewind_by(&mut self, amount: usize) -> Option<()> {
+        let target = self.pos()?.checked_sub(amount as u64)?;
+        self.set_pos(target)
+    }
+    /// Shifts the position backwards by the size of `U` without reading, undoing a [`SeqByteReader::shift`] of a `U`, as
+    /// [`SeqByteReader::rewind_by`] does.
+    fn unshift<U: SizedNumber>(&mut self) -> Option<()> {
+        self.rewind_by(U::SIZE)
+    }
+    /// Peaks the next `amount` bytes. Returns a [`String`] containing the bytes. Returns [`None`] if there are no
+    /// more bytes to be read. If unimplemented, internally calls `next_slice` and converts it to a lossy UTF-8 String.
+    fn next_string(&mut self, amount: usize) -> Option<String> {
+        let slice = self.next_slice(amount)?;
+
+        Some(String::from_utf8_lossy(&slice).to_string())
+    }
+    /// Peaks the next `amount` bytes, and shifting the position by `amount` bytes. Returns a [`String`] containing the bytes. Returns [`None`] if there are no
+    /// more bytes to be read. If unimplemented, internally calls `shift_slice` and converts it to a lossy UTF-8 String.
+    fn shift_string(&mut self, amount: usize) -> Option<String> {
+        let slice = self.shift_slice(amount)?;
+
+        Some(String::from_utf8_lossy(&slice).to_string())
+    }
+    /// Reads the next `amount` bytes into `out`, shifting the position by `amount` bytes. `out` is cleared first, and its capacity is reused, so
+    /// no allocation happens if it can already hold `amount` bytes. Returns [`None`] if there are not enough bytes to be read, in which case
+    /// `out` is left empty and the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 2, 3, 4, 5]);
+    /// let mut buf = Vec::with_capacity(16);
+    ///
+    /// cursor.shift_slice_into_vec(3, &mut buf).unwrap();
+    /// assert_eq!(buf, [1, 2, 3]);
+    ///
+    /// assert_eq!(cursor.shift_slice_into_vec(3, &mut buf), None);
+    /// assert!(buf.is_empty());
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    fn shift_slice_into_vec(&mut self, amount: usize, out: &mut Vec<u8>) -> Option<()>;
+    /// Reads the next `amount` bytes into `out` as a lossy UTF-8 string, shifting the position by `amount` bytes. `out` is cleared first, and
+    /// its capacity is reused when the bytes are valid UTF-8. Returns [`None`] if there are not enough bytes to be read, in which case `out`
+    /// is left empty and the position is restored. If unimplemented, internally calls `shift_slice_into_vec` on the buffer of `out`.
+    fn shift_string_into(&mut self, amount: usize, out: &mut String) -> Option<()> {
+        let mut buf = std::mem::take(out).into_bytes();
+
+        if self.shift_slice_into_vec(amount, &mut buf).is_none() {
+            *out = String::from_utf8(buf).unwrap_or_default();
+            return None;
+        }
+
+        *out = match String::from_utf8(buf) {
+            Ok(s) => s,
+            Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        };
+
+        Some(())
+    }
+    /// Reads the next `ceil(nbits / 8)` bytes, shifting the position, and unpacks exactly `nbits` booleans in the specified bit order. The unused
+    /// padding bits in the final byte are ignored. Returns [`None`] if there are not enough bytes to be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0b0000_0101, 0b1111_1110]);
+    ///
+    /// let bits = cursor.shift_bitvec(9, BitOrder::LsbFirst).unwrap();
+    ///
+    /// assert_eq!(bits, [true, false, true, false, false, false, false, false, false]);
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    fn shift_bitvec(&mut self, nbits: usize, order: BitOrder) -> Option<Vec<bool>> {
+        let slice = self.shift_slice(nbits.div_ceil(8))?;
+
+        Some(
+            (0..nbits)
+                .map(|i| slice[i / 8] & order.mask(i % 8) != 0)
+                .collect(),
+        )
+    }
+
+    /// Reads the next `ceil(septet_count * 7 / 8)` bytes, shifting the position, and decodes `septet_count` GSM 03.38 packed 7-bit characters.
+    /// Septets are unpacked starting from the least significant bit of the first byte, and mapped through the GSM basic character set,
+    /// with escape sequences resolved through the extension table (e.g. `0x1B 0x65` decodes to `€`). Any fill bits after the final septet are
+    /// ignored. Returns [`None`] if there are not enough bytes to be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0xE8, 0x32, 0x9B, 0xFD, 0x46, 0x97, 0xD9, 0xEC, 0x37]);
+    ///
+    /// assert_eq!(cursor.shift_gsm7(10).unwrap(), "hellohello");
+    /// ```
+    fn shift_gsm7(&mut self, septet_count: usize) -> Option<String> {
+        let slice = self.shift_slice(checked_len(septet_count, 7).ok()?.div_ceil(8))?;
+
+        Some(crate::gsm7::decode(&crate::gsm7::unpack(
+            &slice,
+            septet_count,
+        )))
+    }
+
+    /// Runs `f` on the reader, restoring the position to where it was before `f` ran if `f` returns [`None`], so a compound read either
+    /// completes or leaves the stream untouched for another attempt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 0, 0xFF]);
+    ///
+    /// // The second value is cut short, so the first one is unread as well.
+    /// assert_eq!(cursor.atomic(|r| Some((r.shift::<u16>()?, r.shift::<u16>()?))), None);
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    fn atomic<T, F: FnOnce(&mut Self) -> Option<T>>(&mut self, f: F) -> Option<T> {
+        let start = self.pos()?;
+
+        with_restore(self, start, f)
+    }
+    /// Reads the next `U`, shifting the position, and returns it if `pred` accepts it. Returns [`None`] if there are not enough bytes to be
+    /// read, the value fails to convert, or `pred` rejects it, in which case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![4]);
+    ///
+    /// assert_eq!(cursor.shift_validated::<u8, _>(|v| (2..=3).contains(v)), None);
+    /// assert_eq!(cursor.position(), 0);
+    /// assert_eq!(cursor.shift_validated::<u8, _>(|v| *v == 4), Some(4));
+    /// ```
+    fn shift_validated<U: SizedNumber, F: FnOnce(&U) -> bool>(&mut self, pred: F) -> Option<U> {
+        self.atomic(|r| r.shift::<U>().filter(pred))
+    }
+    /// Reads the next `U`, shifting the position, and returns it if `check` accepts it. Returns [`CheckError::Truncated`] if there are not
+    /// enough bytes to be read or the value fails to convert, and [`CheckError::Invalid`] with the error of `check` if it rejects the value. On
+    /// failure, the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![4]);
+    /// let version = cursor.shift_checked::<u8, _, _>(|v| match v {
+    ///     2 | 3 => Ok(()),
+    ///     v => Err(format!("unsupported version {}", v)),
+    /// });
+    ///
+    /// assert_eq!(version, Err(CheckError::Invalid("unsupported version 4".to_string())));
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    fn shift_checked<U: SizedNumber, E, F: FnOnce(&U) -> Result<(), E>>(
+        &mut self,
+        check: F,
+    ) -> Result<U, CheckError<E>> {
+        let mut error = CheckError::Truncated;
+
+        self.atomic(|r| {
+            let value = r.shift::<U>()?;
+            check(&value)
+                .map_err(|e| error = CheckError::Invalid(e))
+                .ok()?;
+            Some(value)
+        })
+        .ok_or(error)
+    }
+    /// Reads an optional `U` encoded as a presence byte, `0` if absent and `1` if present, followed by the value only if present, shifting the
+    /// position. Returns `Some(None)` if the value is absent. Returns [`None`] if there are not enough bytes to be read or the presence byte
+    /// is anything other than `0` or `1`, in which case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 7, 0, 2, 9]);
+    ///
+    /// assert_eq!(cursor.shift_optional::<u8>(), Some(Some(7)));
+    /// assert_eq!(cursor.shift_optional::<u8>(), Some(None));
+    /// assert_eq!(cursor.shift_optional::<u8>(), None);
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    fn shift_optional<U: SizedNumber>(&mut self) -> Option<Option<U>> {
+        self.atomic(|r| match r.shift::<bool>()? {
+            true => r.shift::<U>().map(Some),
+            false => Some(None),
+        })
+    }
+    /// Reads an optional `U` as [`SeqByteReader::shift_optional`] does, without shifting the position.
+    fn next_optional<U: SizedNumber>(&mut self) -> Option<Option<U>> {
+        let start = self.pos()?;
+        let value = self.shift_optional()?;
+        self.set_pos(start)?;
+
+        Some(value)
+    }
+    /// Reads the discriminant of an `E`, shifting the position, and converts it to `E`. Returns [`None`] if there are not enough bytes to
+    /// be read or the discriminant is not one of `E`, in which case the position is restored. Use [`ESeqByteReader::shift_narrow`] with
+    /// [`TaggedEnum::Repr`] to learn which discriminant was rejected.
+    fn shift_enum<E: TaggedEnum>(&mut self) -> Option<E> {
+        self.atomic(|r| E::try_from(r.shift::<E::Repr>()?).ok())
+    }
+    /// Reads an `E` as [`SeqByteReader::shift_enum`] does, without shifting the position.
+    fn next_enum<E: TaggedEnum>(&mut self) -> Option<E> {
+        E::try_from(self.next::<E::Repr>()?).ok()
+    }
+    /// Returns whether the next bytes equal `prefix`, without shifting the position. Returns `Some(false)` rather than [`None`] if fewer
+    /// bytes than `prefix` remain, so several candidates can be sniffed in turn. Returns [`None`] only if the position could not be restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"GIF89a".to_vec());
+    ///
+    /// assert_eq!(cursor.starts_with(b"\x89PNG"), Some(false));
+    /// assert_eq!(cursor.starts_with(b"GIF8"), Some(true));
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    fn starts_with(&mut self, prefix: &[u8]) -> Option<bool> {
+        let start = self.pos()?;
+
+        let mut a = vec![0u8; prefix.len()];
+        let matched = self.fill(&mut a).is_some() && a == prefix;
+
+        self.set_pos(start)?;
+        Some(matched)
+    }
+    /// Shifts the position past `prefix` if the next bytes equal it, and returns whether they did. The position is left unchanged otherwise,
+    /// including if fewer bytes than `prefix` remain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"\xEF\xBB\xBFtext".to_vec());
+    ///
+    /// assert_eq!(cursor.consume_prefix(b"\xEF\xBB\xBF"), Some(true));
+    /// assert_eq!(cursor.consume_prefix(b"\xEF\xBB\xBF"), Some(false));
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    fn consume_prefix(&mut self, prefix: &[u8]) -> Option<bool> {
+        let matched = self.starts_with(prefix)?;
+        if matched {
+            let start = self.pos()?;
+            self.set_pos(start + prefix.len() as u64)?;
+        }
+
+        Some(matched)
+    }
+    /// Shifts the position to the next occurrence of `pattern`, or just past it if `include_pattern` is `true`, and returns the number of bytes
+    /// skipped. The stream is scanned in chunks when the end of the source is known, and byte by byte otherwise. Returns [`None`] if `pattern`
+    /// does not occur, in which case the position is restored. An empty `pattern` occurs at the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"noise\xFF\xD8frame".to_vec());
+    ///
+    /// assert_eq!(cursor.skip_until(b"\xFF\xD8", true), Some(7));
+    /// assert_eq!(cursor.shift_string(5).unwrap(), "frame");
+    /// assert_eq!(cursor.skip_until(b"\xFF\xD8", true), None);
+    /// assert_eq!(cursor.position(), 12);
+    /// ```
+    fn skip_until(&mut self, pattern: &[u8], include_pattern: bool) -> Option<u64> {
+        let start = self.pos()?;
+
+        with_restore(self, start, |r| {
+            let found = find_forward(r, pattern)?;
+
+            let end = found
+                + if include_pattern {
+                    pattern.len() as u64
+                } else {
+                    0
+                };
+            r.set_pos(end)?;
+
+            Some(end - start)
+        })
+    }
+    /// Returns the number of bytes between the position and the end of the source, without shifting the position, such as to size a run of
+    /// records filling the rest of a file. Returns `Some(0)` if the position is past the end, and [`None`] if either is unknown. The total
+    /// length of the source is [`SeqByteReader::total_len`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 6]);
+    /// cursor.set_position(2);
+    ///
+    /// assert_eq!(cursor.remaining(), Some(4));
+    /// assert_eq!(cursor.end_pos(), Some(6));
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    fn remaining(&mut self) -> Option<u64> {
+        let pos = self.pos()?;
+        Some(self.end_pos()?.saturating_sub(pos))
+    }
+    /// Returns the total length of the source in bytes, wherever the position is, without shifting it. Returns [`None`] if the end of the
+    /// source is unknown. The same as [`SeqByteReaderCore::end_pos`], which seeks to the end and restores the position for a [`Seek`]
+    /// source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 6]);
+    /// cursor.set_position(2);
+    ///
+    /// assert_eq!(cursor.total_len(), Some(6));
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    fn total_len(&mut self) -> Option<u64> {
+        self.end_pos()
+    }
+    /// Returns whether at least `at_least` more bytes can be read, without shifting the position. If the end of the source is known, it is
+    /// compared against. Otherwise, the bytes are probed by reading through them in bounded chunks and then restoring the position, so
+    /// memory use does not grow with `at_least`. Returns [`None`] if the position cannot be queried or restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 6]);
+    /// cursor.set_position(2);
+    ///
+    /// assert_eq!(cursor.available(4), Some(true));
+    /// assert_eq!(cursor.available(5), Some(false));
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    fn available(&mut self, at_least: usize) -> Option<bool> {
+        let start = self.pos()?;
+        if let Some(end) = self.end_pos() {
+            return Some(end.saturating_sub(start) >= at_least as u64);
+        }
+
+        let mut probe = vec![0u8; at_least.min(PROBE_CHUNK)];
+        let mut left = at_least;
+        while left > 0 {
+            let chunk = left.min(PROBE_CHUNK);
+            if self.fill(&mut probe[..chunk]).is_none() {
+                self.set_pos(start)?;
+                return Some(false);
+            }
+
+            left -= chunk;
+        }
+
+        self.set_pos(start)?;
+        Some(true)
+    }
+    /// Checks that at least `n` more bytes can be read, as [`SeqByteReader::available`] does, without shifting the position. Returns
+    /// [`NotEnough`] with the offset and, if the end of the source is known, the remaining bytes if there are fewer. If the position of the
+    /// reader is unknown, the check fails, and neither the offset nor the remaining bytes are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 6]);
+    ///
+    /// assert_eq!(cursor.ensure(6), Ok(()));
+    /// assert_eq!(cursor.ensure(8), Err(NotEnough { offset: Some(0), needed: 8, remaining: Some(6) }));
+    /// ```
+    fn ensure(&mut self, n: usize) -> Result<(), NotEnough> {
+        let offset = self.pos();
+        if self.available(n) == Some(true) {
+            return Ok(());
+        }
+
+        Err(NotEnough {
+            offset,
+            needed: n,
+            remaining: offset.and_then(|offset| Some(self.end_pos()?.saturating_sub(offset))),
+        })
+    }
+    /// Shifts the position past the next `amount` bytes without reading them into a buffer. If the end of the source is known, the position
+    /// is set directly. Otherwise, the bytes are read through in bounded chunks and discarded, so that skipping past the end fails as reading
+    /// would. Returns [`None`] if there are not enough bytes to be skipped, in which case the position is restored. The position is never
+    /// moved past the end, so skipping zero bytes succeeds at the end but fails from a position already past it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 6]);
+    ///
+    /// assert_eq!(cursor.skip_slice(4), Some(()));
+    /// assert_eq!(cursor.skip_slice(3), None);
+    /// assert_eq!(cursor.position(), 4);
+    /// ```
+    fn skip_slice(&mut self, amount: usize) -> Option<()> {
+        let start = self.pos()?;
+
+        with_restore(self, start, |r| {
+            let target = start.checked_add(amount as u64)?;
+            if let Some(end) = r.end_pos() {
+                if target > end {
+                    return None;
+                }
+
+                return r.set_pos(target);
+            }
+
+            let mut discard = [0u8; PROBE_CHUNK];
+            let mut left = amount;
+            while left > 0 {
+                let chunk = left.min(PROBE_CHUNK);
+                r.fill(&mut discard[..chunk])?;
+
+                left -= chunk;
+            }
+
+            Some(())
+        })
+    }
+    /// Shifts the position past a value of type `U` without converting it, as [`SeqByteReader::skip_slice`] does with the size of `U`.
+    /// For a fixed-size record type, this skips the whole record in one jump. Returns [`None`] if there are not enough bytes to be skipped,
+    /// in which case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 6]);
+    ///
+    /// assert_eq!(cursor.skip_value::<u32>(), Some(()));
+    /// assert_eq!(cursor.skip_value::<[u8; 2]>(), Some(()));
+    /// assert_eq!(cursor.skip_value::<u8>(), None);
+    /// ```
+    fn skip_value<U: SizedNumber>(&mut self) -> Option<()> {
+        self.skip_slice(U::SIZE)
+    }
+    /// Shifts the position past padding to the next multiple of `alignment` bytes from the start of the source, returning the number of
+    /// padding bytes skipped, which is `0` if the position is already aligned. Returns [`None`] if `alignment` is `0`, or if the padding is
+    /// not all there, in which case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![7, 0, 0, 0, 9, 0, 0]);
+    ///
+    /// assert_eq!(cursor.shift::<u8>(), Some(7));
+    /// assert_eq!(cursor.align_to(4), Some(3));
+    /// assert_eq!(cursor.align_to(4), Some(0));
+    /// assert_eq!(cursor.shift::<u8>(), Some(9));
+    /// assert_eq!(cursor.align_to(4), None);
+    /// assert_eq!(cursor.position(), 5);
+    /// ```
+    fn align_to(&mut self, alignment: usize) -> Option<usize> {
+        self.align_to_from(alignment, 0)
+    }
+    /// Shifts the position past padding to the next multiple of `alignment` bytes from `base_offset`, such as the start of a chunk whose
+    /// fields are aligned relative to it, as [`SeqByteReader::align_to`] does. Returns [`None`] if the position is before `base_offset`.
+    fn align_to_from(&mut self, alignment: usize, base_offset: u64) -> Option<usize> {
+        let alignment = u64::try_from(alignment).ok().filter(|a| *a > 0)?;
+        let offset = self.pos()?.checked_sub(base_offset)?;
+
+        let padding = ((alignment - offset % alignment) % alignment) as usize;
+        self.skip_slice(padding)?;
+
+        Some(padding)
+    }
+    /// Sets the position to the start of the record at `index` of a file laid out as `geometry`. Returns [`None`] if
+    /// [`RecordGeometry::offset_of`] does, or the position could not be set, in which case the position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// // A two byte header followed by three records of two bytes each.
+    /// let mut cursor = Cursor::new(vec![0xFF, 0xFF, 1, 10, 2, 20, 3, 30]);
+    /// let geometry = RecordGeometry { header_len: 2, record_size: 2, count: Some(3) };
+    ///
+    /// assert_eq!(cursor.seek_to_record(geometry, 2), Some(()));
+    /// assert_eq!(cursor.shift_slice(2).unwrap(), [3, 30]);
+    /// assert_eq!(cursor.seek_to_record(geometry, 3), None);
+    /// ```
+    fn seek_to_record(&mut self, geometry: RecordGeometry, index: u64) -> Option<()> {
+        let offset = geometry.offset_of(index)?;
+        self.set_pos(offset)
+    }
+    /// Returns the index of the record of a file laid out as `geometry` that the position is in, along with the offset of the position
+    /// within that record, without shifting the position. Returns [`None`] if [`RecordGeometry::index_at`] does, or the position could
+    /// not be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 8]);
+    /// let geometry = RecordGeometry { header_len: 2, record_size: 2, count: Some(3) };
+    ///
+    /// cursor.set_position(5);
+    /// assert_eq!(cursor.current_record(geometry), Some((1, 1)));
+    /// ```
+    fn current_record(&mut self, geometry: RecordGeometry) -> Option<(u64, u64)> {
+        geometry.index_at(self.pos()?)
+    }
+    /// Reads a length as `L`, then shifts the position past that many bytes without reading them, such as to skip a length-prefixed string
+    /// or blob without allocating for it. Returns the length skipped. Returns [`None`] if the length does not fit in a [`usize`] or there are
+    /// not enough bytes to be skipped, in which case the position is restored to before the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"\x05hello\x09short".to_vec());
+    ///
+    /// assert_eq!(cursor.skip_string_prefixed::<u8>(), Some(5));
+    /// assert_eq!(cursor.skip_string_prefixed::<u8>(), None);
+    /// assert_eq!(cursor.position(), 6);
+    /// ```
+    fn skip_string_prefixed<L: SizedNumber + TryInto<usize>>(&mut self) -> Option<usize> {
+        let start = self.pos()?;
+
+        with_restore(self, start, |r| {
+            let len: usize = r.shift::<L>()?.try_into().ok()?;
+            r.skip_slice(len)?;
+
+            Some(len)
+        })
+    }
+    /// Reads up to `total` bytes in chunks of at most `chunk_size` bytes, calling `f` with each chunk, for relaying a large payload to a
+    /// writer without holding all of it in memory. The chunks are read into one buffer which is reused. Returns the number of bytes
+    /// consumed, which is less than `total` if `f` breaks or the source ends first. The position is left just after the last chunk
+    /// passed to `f`. A `chunk_size` of zero is treated as one byte. Returns [`None`] if the position cannot be queried or restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let mut cursor = Cursor::new((0..10).collect::<Vec<u8>>());
+    /// let mut relayed = Vec::new();
+    ///
+    /// let consumed = cursor.shift_chunks(8, 3, |chunk| {
+    ///     relayed.extend_from_slice(chunk);
+    ///     ControlFlow::Continue(())
+    /// });
+    ///
+    /// assert_eq!(consumed, Some(8));
+    /// assert_eq!(relayed, [0, 1, 2, 3, 4, 5, 6, 7]);
+    /// assert_eq!(cursor.position(), 8);
+    /// ```
+    fn shift_chunks<F: FnMut(&[u8]) -> ControlFlow<()>>(
+        &mut self,
+        total: u64,
+        chunk_size: usize,
+        mut f: F,
+    ) -> Option<u64> {
+        let start = self.pos()?;
+        let end = self.end_pos();
+        let chunk_size = chunk_size.max(1);
+        let mut buf = vec![0u8; total.min(chunk_size as u64) as usize];
+        let mut consumed = 0;
+
+        while consumed < total {
+            let mut len = (total - consumed).min(chunk_size as u64) as usize;
+            if let Some(end) = end {
+                len = len.min(end.saturating_sub(start + consumed) as usize);
+            }
+            if len == 0 {
+                break;
+            }
+
+            if self.fill(&mut buf[..len]).is_none() {
+                // The end of the source is not known, so the rest of it is read a byte at a time.
+                self.set_pos(start + consumed)?;
+                len = 0;
+                while len < buf.len() && self.fill(&mut buf[len..len + 1]).is_some() {
+                    len += 1;
+                }
+                self.set_pos(start + consumed + len as u64)?;
+
+                if len == 0 {
+                    break;
+                }
+            }
+
+            consumed += len as u64;
+            if f(&buf[..len]).is_break() {
+                break;
+            }
+        }
+
+        Some(consumed)
+    }
+    /// Reads a MIDI variable-length quantity, shifting the position past it. Each byte holds 7 bits of the value, most significant group
+    /// first, with the most significant bit set on every byte but the last. This is the opposite group order of LEB128. Returns [`None`] if
+    /// there are not enough bytes to be read, or if the quantity is longer than the 4 bytes allowed by the Standard MIDI File format, in which
+    /// case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x81, 0x00, 0xC0, 0x00]);
+    ///
+    /// assert_eq!(cursor.shift_vlq(), Some(0x80));
+    /// assert_eq!(cursor.shift_vlq(), Some(0x2000));
+    /// ```
+    fn shift_vlq(&mut self) -> Option<u32> {
+        self.atomic(|r| {
+            let mut value = 0u32;
+
+            for _ in 0..4 {
+                let b = r.shift::<u8>()?;
+                value = (value << 7) | (b & 0x7F) as u32;
+
+                if b & 0x80 == 0 {
+                    return Some(value);
+                }
+            }
+
+            None
+        })
+    }
+    /// Reads `count` deltas encoded as unsigned LEB128 varints, shifting the position, and returns their running sums starting from `base`,
+    /// such as a sorted column of timestamps stored as the gaps between them. Returns [`DeltaError::Overflow`] if a sum does not fit in a
+    /// [`u64`] rather than wrapping, and [`DeltaError::Truncated`] if there are not enough bytes to be read or a varint is longer than a
+    /// [`u64`]. On failure, the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x05, 0xAC, 0x02, 0x00]);
+    ///
+    /// assert_eq!(cursor.shift_varint_delta_decoded(3, 1000), Ok(vec![1005, 1305, 1305]));
+    /// ```
+    fn shift_varint_delta_decoded(
+        &mut self,
+        count: usize,
+        base: u64,
+    ) -> Result<Vec<u64>, DeltaError> {
+        decode_deltas(self, count, base, read_uleb128)
+    }
+    /// Reads a record of a format whose records grow new fields over time, prefixed with its length encoded as `len_field`, shifting the
+    /// position past the whole record. `f` reads the fields it knows from a reader over the bytes of the record alone, and any fields after
+    /// them, such as those appended by newer writers, are skipped. Returns [`None`] if the length or the record could not be read, or if `f`
+    /// returns [`None`] or shifts its position past the end of the record, such as when reading a field an older writer did not write. On
+    /// failure, the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// // A version 2 record with a trailing field that version 1 readers do not know about.
+    /// let mut cursor = Cursor::new(vec![3, 0x2A, 0x07, 0xFF, 0x10]);
+    ///
+    /// let id = cursor.shift_versioned_record(VersionedLen::U8, |r| r.shift::<u16>());
+    ///
+    /// assert_eq!(id, Some(0x072A));
+    /// assert_eq!(cursor.shift::<u8>(), Some(0x10));
+    /// ```
+    fn shift_versioned_record<U, F: FnOnce(&mut Cursor<&[u8]>) -> Option<U>>(
+        &mut self,
+        len_field: VersionedLen,
+        f: F,
+    ) -> Option<U> {
+        self.atomic(|r| {
+            let len = usize::try_from(len_field.read(r)?).ok()?;
+            if !r.available(len)? {
+                return None;
+            }
+
+            let record = r.shift_slice(len)?;
+            let mut fields = Cursor::new(&record[..]);
+            let value = f(&mut fields)?;
+
+            (fields.position() <= len as u64).then_some(value)
+        })
+    }
+    /// Reads the next `U`, shifting the position, and checks that it equals `expected`, such as the magic number or version at the start of a
+    /// format. Returns [`ExpectError::Truncated`] if there are not enough bytes to be read or the value fails to convert, and
+    /// [`ExpectError::Mismatch`] with both values if they differ. On failure, the position is restored, so the bytes can be read again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 0, 3, 0]);
+    ///
+    /// assert_eq!(cursor.expect(1u16), Ok(()));
+    /// assert_eq!(
+    ///     cursor.expect(2u16),
+    ///     Err(ExpectError::Mismatch { offset: 2, expected: 2, actual: 3 })
+    /// );
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    fn expect<U: SizedNumber + PartialEq + fmt::Debug>(
+        &mut self,
+        expected: U,
+    ) -> Result<(), ExpectError<U>> {
+        expect_with(self, expected, |r| r.shift::<U>())
+    }
+    /// Reads the next `expected.len()` bytes, shifting the position, and checks that they equal `expected`, such as the magic bytes
+    /// `b"\x7fELF"`. Returns the same errors as [`SeqByteReader::expect`], and restores the position on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"\x7fELF\x02".to_vec());
+    ///
+    /// assert_eq!(cursor.expect_bytes(b"\x7fELF"), Ok(()));
+    /// assert!(cursor.expect_bytes(b"\x01").is_err());
+    /// ```
+    fn expect_bytes(&mut self, expected: &[u8]) -> Result<(), ExpectError<Vec<u8>>> {
+        let offset = self.pos().ok_or(ExpectError::Truncated { offset: 0 })?;
+
+        // Compares in place, so only a mismatch allocates, for the bytes it reports.
+        with_restore(self, offset, |r| {
+            with_value_buf(expected.len(), |actual| {
+                r.fill(actual).ok_or(ExpectError::Truncated { offset })?;
+                if actual != expected {
+                    return Err(ExpectError::Mismatch {
+                        offset,
+                        expected: expected.to_vec(),
+                        actual: actual.to_vec(),
+                    });
+                }
+
+                Ok(())
+            })
+        })
+    }
+    /// Reads the next `count` values of `U` in a single read, shifting the position, into a buffer whose allocation is aligned to `align`
+    /// bytes. Returns [`None`] if `align` is not a power of two or is less than the alignment of `U`, if there are not enough bytes to be read,
+    /// or if any value fails to convert.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let a = vec![1, 0, 2, 0, 3, 0];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let values = cursor.shift_vec_aligned::<u16>(3, 64).unwrap();
+    ///
+    /// assert_eq!(values.as_ptr() as usize % 64, 0);
+    /// assert_eq!(*values, [1, 2, 3]);
+    /// assert!(cursor.shift_vec_aligned::<u16>(0, 24).is_none());
+    /// ```
+    fn shift_vec_aligned<U: SizedNumber>(
+        &mut self,
+        count: usize,
+        align: usize,
+    ) -> Option<AlignedVec<U>>;
+    /// Reads the next `count` values of `U` in a single read straight into a buffer of `U`s, shifting the position, without decoding each
+    /// value. The bytes are interpreted in the native layout of `U`, including native endianness, so handling the byte order of the source is
+    /// the caller's responsibility. Returns [`None`] if there are not enough bytes to be read, in which case the position is restored. If
+    /// the end of the source is known, a `count` which exceeds what remains is rejected before the buffer is allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let a: Vec<u8> = [1.5f32, -2.0, 0.25].iter().flat_map(|v| v.to_ne_bytes()).collect();
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// assert_eq!(cursor.shift_pod_slice::<[f32; 3]>(1).unwrap(), [[1.5, -2.0, 0.25]]);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    fn shift_pod_slice<U: bytemuck::Pod>(&mut self, count: usize) -> Option<Vec<U>>;
+    /// Reads a BER or DER identifier, shifting the position past it. Both the single byte form and the high-tag-number form are supported.
+    /// On failure, the position is restored.
+    fn shift_ber_tag(&mut self) -> Result<BerTag, BerError>;
+    /// Reads a BER or DER length, shifting the position past it. Both the short form of a single byte below `0x80` and the long form of a
+    /// byte `0x80 | n` followed by an `n` byte big endian length are supported. Returns [`BerError::IndefiniteLength`] for the indefinite form
+    /// `0x80`. On failure, the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::ber::BerError;
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x05, 0x82, 0x01, 0x0A, 0x80]);
+    ///
+    /// assert_eq!(cursor.shift_ber_len(), Ok(5));
+    /// assert_eq!(cursor.shift_ber_len(), Ok(266));
+    /// assert_eq!(cursor.shift_ber_len(), Err(BerError::IndefiniteLength { offset: 4 }));
+    /// ```
+    fn shift_ber_len(&mut self) -> Result<usize, BerError>;
+    /// Reads a definite length BER or DER element, shifting the position past it, and returns its tag and contents. The contents of
+    /// constructed elements are returned undecoded, and can be walked by reading elements from them in turn. On failure, the position is
+    /// restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::ber::{BerClass, BerTag};
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// // The `countryName` attribute of a distinguished name: a SET holding a SEQUENCE of an OID and a PrintableString.
+    /// let a = vec![0x31, 0x0B, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x06, 0x13, 0x02, b'U', b'S'];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let (set, contents) = cursor.shift_ber_tlv().unwrap();
+    /// assert_eq!(set, BerTag { class: BerClass::Universal, constructed: true, number: 17 });
+    ///
+    /// let mut contents = Cursor::new(contents);
+    /// let (_, attribute) = contents.shift_ber_tlv().unwrap();
+    ///
+    /// let mut attribute = Cursor::new(attribute);
+    /// assert_eq!(attribute.shift_ber_tlv().unwrap().1, [0x55, 0x04, 0x06]);
+    /// assert_eq!(attribute.shift_ber_tlv().unwrap().1, b"US");
+    /// ```
+    fn shift_ber_tlv(&mut self) -> Result<(BerTag, Vec<u8>), BerError>;
+
+    /* Not sure if I should keep these methods. Should I ?
+    fn next_u8(&mut self) -> Option<u8> {
+        self.next::<u8>()
+    }
+    fn next_i8(&mut self) -> Option<i8> {
+        self.next::<i8>()
+    }
+    fn next_u16(&mut self) -> Option<u16> {
+        self.next::<u16>()
+    }
+    fn next_i16(&mut self) -> Option<i16> {
+        self.next::<i16>()
+    }
+    fn next_u32(&mut self) -> Option<u32> {
+        self.next::<u32>()
+    }
+    fn next_i32(&mut self) -> Option<i32> {
+        self.next::<i32>()
+    }
+    fn next_f32(&mut self) -> Option<f32> {
+        self.next::<f32>()
+    }
+    fn next_u64(&mut self) -> Option<u64> {
+        self.next::<u64>()
+    }
+    fn next_i64(&mut self) -> Option<i64> {
+        self.next::<i64>()
+    }
+    fn next_f64(&mut self) -> Option<f64> {
+        self.next::<f64>()
+    }
+
+    fn shift_u8(&mut self) -> Option<u8> {
+        self.shift::<u8>()
+    }
+    fn shift_i8(&mut self) -> Option<i8> {
+        self.shift::<i8>()
+    }
+    fn shift_u16(&mut self) -> Option<u16> {
+        self.shift::<u16>()
+    }
+    fn shift_i16(&mut self) -> Option<i16> {
+        self.shift::<i16>()
+    }
+    fn shift_u32(&mut self) -> Option<u32> {
+        self.shift::<u32>()
+    }
+    fn shift_i32(&mut self) -> Option<i32> {
+        self.shift::<i32>()
+    }
+    fn shift_f32(&mut self) -> Option<f32> {
+        self.shift::<f32>()
+    }
+    fn shift_u64(&mut self) -> Option<u64> {
+        self.shift::<u64>()
+    }
+    fn shift_i64(&mut self) -> Option<i64> {
+        self.shift::<i64>()
+    }
+    fn shift_f64(&mut self) -> Option<f64> {
+        self.shift::<f64>()
+    }
+    */
+}
+/// Represents a sequential byte reader which can read bytes with a specified endianness. Implemented for every type implementing
+/// [`SeqByteReaderCore`], including types that implement [`Read`] + [`Seek`].
+///
+/// Like those of [`SeqByteReader`], every method restores the position to where it was before the call if it returns [`None`] or an error.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let a = vec![69, 96, 255, 255];
+/// let mut cursor = Cursor::new(a);
+///
+/// let num : i32 = cursor.next_e(false).unwrap();
+/// let num2 : i32 = cursor.shift_e(true).unwrap();
+/// let num3 : Option<i32> = cursor.shift_e(false);
+///
+/// assert_ne!(num, num2);
+/// assert_eq!(num, -40891);
+/// assert_eq!(num2, 1163984895);
+/// assert_eq!(num3, None);
+/// ```
+pub trait ESeqByteReader: SeqByteReaderCore {
+    /// Peaks the next `U` from the current position, reading the size of `U`'s amount of bytes, and converting to the `U` with the specified endianness. Returns [`None`]
+    /// if there are not enough bytes to be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let a = vec![69, 96, 255, 255];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let pos1 = cursor.position();
+    /// let num : i32 = cursor.next_e(false).unwrap();
+    /// let pos2 = cursor.position();
+    ///
+    /// assert_eq!(pos1, pos2);
+    /// assert_eq!(num, -40891);
+    /// ```
+    fn next_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U>;
+    /// Peaks the next `U` from the current position, shifting and reading the size of `U`'s amount of bytes, and converting to the `U` with the specified endianness. Returns [`None`]
+    /// if there are not enough bytes to be read, or if they fail to convert, in which case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let a = vec![69, 96, 255, 255];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let pos1 = cursor.position();
+    /// let num : i32 = cursor.shift_e(false).unwrap();
+    /// let pos2 = cursor.position();
+    ///
+    /// assert_ne!(pos1, pos2);
+    /// assert_eq!(num, -40891);
+    /// ```
+    fn shift_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U>;
+    /// Reads the next `U` with the byte order `endianness`, without shifting the position, as [`ESeqByteReader::next_e`] does.
+    fn next_endian<U: EndianNumber>(&mut self, endianness: Endianness) -> Option<U> {
+        self.next_e(endianness.is_big())
+    }
+    /// Reads the next `U` with the byte order `endianness`, shifting the position, as [`ESeqByteReader::shift_e`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x12, 0x34, 0x12, 0x34]);
+    ///
+    /// assert_eq!(cursor.next_endian::<u16>(Endianness::Big), Some(0x1234));
+    /// assert_eq!(cursor.shift_endian::<u16>(Endianness::Big), Some(0x1234));
+    /// assert_eq!(cursor.shift_endian::<u16>(Endianness::Little), Some(0x3412));
+    /// ```
+    fn shift_endian<U: EndianNumber>(&mut self, endianness: Endianness) -> Option<U> {
+        self.shift_e(endianness.is_big())
+    }
+    /// Reads the next `U` in the byte order `O`, without shifting the position, as [`ESeqByteReader::next_e`] does.
+    fn next_as<U: EndianNumber, O: ByteOrder>(&mut self) -> Option<U> {
+        self.next_e(O::ENDIANNESS.is_big())
+    }
+    /// Reads the next `U` in the byte order `O`, shifting the position, as [`ESeqByteReader::shift_e`] does. The byte order is a type
+    /// parameter, so code generic over it is compiled once for each order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x12, 0x34, 0x12, 0x34]);
+    ///
+    /// assert_eq!(cursor.shift_as::<u16, BE>(), Some(0x1234));
+    /// assert_eq!(cursor.shift_as::<u16, LE>(), Some(0x3412));
+    /// ```
+    fn shift_as<U: EndianNumber, O: ByteOrder>(&mut self) -> Option<U> {
+        self.shift_e(O::ENDIANNESS.is_big())
+    }
+    /// Reads the next `U` in the byte order of the target, without shifting the position, as [`ESeqByteReader::next_e`] does. Pointer-sized
+    /// types keep their 8-byte width, as for [`EndianNumber::from_bytes_ne`].
+    fn next_ne<U: EndianNumber>(&mut self) -> Option<U> {
+        self.next_e(cfg!(target_endian = "big"))
+    }
+    /// Reads the next `U` in the byte order of the target, shifting the position, as [`ESeqByteReader::shift_e`] does, for data which never
+    /// leaves the machine. Only the byte order is native: [`usize`] and [`isize`] still take 8 bytes, as for
+    /// [`EndianNumber::from_bytes_ne`], so the bytes of a 32-bit target's memory are not read as its pointer-sized values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(0x1234_5678u32.to_ne_bytes().to_vec());
+    ///
+    /// assert_eq!(cursor.shift_ne::<u32>(), Some(0x1234_5678));
+    /// ```
+    fn shift_ne<U: EndianNumber>(&mut self) -> Option<U> {
+        self.shift_e(cfg!(target_endian = "big"))
+    }
+    /// Reads the `U` ending at the current position with the specified endianness, shifting the position backwards by the size of `U`. The
+    /// bytes are converted in their stream order, as [`ESeqByteReader::shift_e`] would. Returns [`None`] without moving if there are not
+    /// enough bytes before the position, or if the value fails to convert.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::{Cursor, Seek, SeekFrom};
+    ///
+    /// let mut cursor = Cursor::new(vec![0, 1, 0, 2]);
+    /// cursor.seek(SeekFrom::End(0)).unwrap();
+    ///
+    /// assert_eq!(cursor.shift_back_e::<u16>(true), Some(2));
+    /// assert_eq!(cursor.shift_back_e::<u16>(true), Some(1));
+    /// ```
+    fn shift_back_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U>;
+    /// Reads the next `count` values of `U` with the specified endianness in a single read, shifting the position. Returns [`None`] if there
+    /// are not enough bytes to be read, if any value fails to convert, or if the length of `count` values overflows a [`usize`], as
+    /// [`checked_len`] tells apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0, 1, 0, 2, 0, 3]);
+    ///
+    /// assert_eq!(cursor.shift_vec_e::<u16>(3, true).unwrap(), [1, 2, 3]);
+    /// ```
+    fn shift_vec_e<U: EndianNumber>(&mut self, count: usize, bigendian: bool) -> Option<Vec<U>>;
+    /// Reads the next `count` values of `U` with the specified endianness in a single read, shifting the position, and adds them to `out`
+    /// through [`Extend`]. Returns the number of values passed to `out`, which for sets may exceed the number of new elements. Returns
+    /// [`None`] if there are not enough bytes to be read, or if any value fails to convert, in which case `out` is left untouched. If
+    /// unimplemented, internally calls `shift_vec_e`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::collections::HashSet;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 0, 2, 0, 1, 0]);
+    /// let mut seen = HashSet::new();
+    ///
+    /// assert_eq!(cursor.shift_extend::<u16, _>(3, &mut seen, false), Some(3));
+    /// assert_eq!(seen.len(), 2);
+    /// ```
+    fn shift_extend<U: EndianNumber, C: Extend<U>>(
+        &mut self,
+        count: usize,
+        out: &mut C,
+        bigendian: bool,
+    ) -> Option<usize> {
+        let values = self.shift_vec_e::<U>(count, bigendian)?;
+        let added = values.len();
+
+        out.extend(values);
+        Some(added)
+    }
+    /// Reads a count as `L`, then that many values of `U` in a single read, all with the specified endianness, shifting the position. When the
+    /// end of the source is known, the count is checked against the remaining bytes before allocating. Returns [`None`] if the count or its
+    /// length in bytes does not fit in a [`usize`], there are not enough bytes to be read, or any value fails to convert, in which case the
+    /// position is restored to before the count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![2, 0, 0, 0, 1, 0, 2, 0]);
+    ///
+    /// assert_eq!(cursor.shift_counted::<u32, u16>(false).unwrap(), [1, 2]);
+    /// ```
+    fn shift_counted<L: EndianNumber + TryInto<usize>, U: EndianNumber>(
+        &mut self,
+        bigendian: bool,
+    ) -> Option<Vec<U>> {
+        self.atomic(|r| {
+            let count: usize = r.shift_e::<L>(bigendian)?.try_into().ok()?;
+            let len = checked_len(count, U::SIZE).ok()?;
+
+            if let Some(end) = r.end_pos() {
+                if r.pos()?.checked_add(len as u64)? > end {
+                    return None;
+                }
+            }
+
+            r.shift_vec_e::<U>(count, bigendian)
+        })
+    }
+    /// Reads a length as `L` with the specified endianness, then shifts the position past that many bytes without reading them, as
+    /// [`SeqByteReader::skip_string_prefixed`] does. Returns the length skipped. Returns [`None`] if the length does not fit in a [`usize`]
+    /// or there are not enough bytes to be skipped, in which case the position is restored to before the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"\0\x03keyvalue".to_vec());
+    ///
+    /// assert_eq!(cursor.skip_string_prefixed_e::<u16>(true), Some(3));
+    /// assert_eq!(cursor.shift_string(5).unwrap(), "value");
+    /// ```
+    fn skip_string_prefixed_e<L: EndianNumber + TryInto<usize>>(
+        &mut self,
+        bigendian: bool,
+    ) -> Option<usize> {
+        let start = self.pos()?;
+
+        with_restore(self, start, |r| {
+            let len: usize = r.shift_e::<L>(bigendian)?.try_into().ok()?;
+            r.skip_slice(len)?;
+
+            Some(len)
+        })
+    }
+    /// Reads a count as `L` with the specified endianness, then that many key/value entries with `parse_entry`, shifting the position. Since
+    /// entries may be of any size, such as strings or nested records, the count is only used to reserve room for as many entries as there are
+    /// remaining bytes when the end of the source is known. Returns [`None`] if the count does not fit in a [`usize`] or any entry fails to
+    /// parse, in which case the position is restored to before the count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![2, 1, 10, 2, 20]);
+    /// let entries = cursor.shift_map::<u8, u8, u8, _>(false, |r| Some((r.shift()?, r.shift()?)));
+    ///
+    /// assert_eq!(entries.unwrap(), [(1, 10), (2, 20)]);
+    /// ```
+    fn shift_map<L, K, V, F>(&mut self, bigendian: bool, mut parse_entry: F) -> Option<Vec<(K, V)>>
+    where
+        L: EndianNumber + TryInto<usize>,
+        F: FnMut(&mut Self) -> Option<(K, V)>,
+    {
+        self.atomic(|r| {
+            let count: usize = r.shift_e::<L>(bigendian)?.try_into().ok()?;
+
+            let capacity = match r.end_pos() {
+                Some(end) => count.min(usize::try_from(end.saturating_sub(r.pos()?)).ok()?),
+                None => 0,
+            };
+
+            let mut entries = Vec::with_capacity(capacity);
+            for _ in 0..count {
+                entries.push(parse_entry(r)?);
+            }
+
+            Some(entries)
+        })
+    }
+    /// Reads a count-prefixed map as [`ESeqByteReader::shift_map`] does, and collects it into a [`HashMap`]. Returns
+    /// [`MapError::Truncated`] if the count or an entry could not be read, and [`MapError::DuplicateKey`] if a key appears more than once. On
+    /// failure, the position is restored to before the count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![2, 1, 10, 1, 20]);
+    /// let map = cursor.shift_hash_map::<u8, u8, u8, _>(false, |r| Some((r.shift()?, r.shift()?)));
+    ///
+    /// assert_eq!(map, Err(MapError::DuplicateKey(1)));
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    fn shift_hash_map<L, K, V, F>(
+        &mut self,
+        bigendian: bool,
+        parse_entry: F,
+    ) -> Result<HashMap<K, V>, MapError<K>>
+    where
+        L: EndianNumber + TryInto<usize>,
+        K: Eq + Hash,
+        F: FnMut(&mut Self) -> Option<(K, V)>,
+    {
+        let start = self.pos().ok_or(MapError::Truncated)?;
+
+        with_restore(self, start, |r| {
+            let entries = r
+                .shift_map::<L, K, V, F>(bigendian, parse_entry)
+                .ok_or(MapError::Truncated)?;
+
+            let mut map = HashMap::with_capacity(entries.len());
+            for (key, value) in entries {
+                if map.contains_key(&key) {
+                    return Err(MapError::DuplicateKey(key));
+                }
+
+                map.insert(key, value);
+            }
+
+            Ok(map)
+        })
+    }
+    /// Reads a count as `L`, then that many absolute offsets as `O` (both with the specified endianness), and calls `visit` with the index of
+    /// each entry at its offset, collecting the results in table order. The position is restored between visits, and left just past the
+    /// table once every entry has been visited, so entries may point anywhere, in any order, and at the same offset.
+    ///
+    /// Returns [`OffsetTableError::Truncated`] if the table could not be read, [`OffsetTableError::OutOfBounds`] naming the first entry whose
+    /// offset is negative or past the end of the source, and [`OffsetTableError::Visit`] naming the first entry for which `visit` returns
+    /// [`None`]. On failure, the position is restored to before the count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// // Two entries pointing at length-prefixed names after the table.
+    /// let mut cursor = Cursor::new(b"\x02\x06\x03\x02ab\x02cd".to_vec());
+    ///
+    /// let names = cursor
+    ///     .visit_offset_table::<u8, u8, _, _>(false, |_, r| {
+    ///         let len = r.shift::<u8>()?;
+    ///         r.shift_string(len as usize)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(names, ["cd", "ab"]);
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    fn visit_offset_table<L, O, U, F>(
+        &mut self,
+        bigendian: bool,
+        mut visit: F,
+    ) -> Result<Vec<U>, OffsetTableError>
+    where
+        L: EndianNumber + TryInto<usize>,
+        O: EndianNumber + TryInto<u64>,
+        F: FnMut(usize, &mut Self) -> Option<U>,
+    {
+        let start = self.pos().ok_or(OffsetTableError::Truncated)?;
+        let offsets = read_offset_table::<_, L, O>(self, bigendian)?;
+
+        visit_offsets(self, start, &offsets, |index, r| visit(index, r))
+    }
+    /// Reads an offset table as [`ESeqByteReader::visit_offset_table`] does, but calls `visit` only once for each distinct offset, cloning
+    /// the result for the other entries pointing at it, such as the entries of a string table sharing one string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"\x03\x04\x04\x04\x02hi".to_vec());
+    /// let mut visits = 0;
+    ///
+    /// let names = cursor
+    ///     .visit_offset_table_dedup::<u8, u8, _, _>(false, |_, r| {
+    ///         visits += 1;
+    ///         let len = r.shift::<u8>()?;
+    ///         r.shift_string(len as usize)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(names.len(), 3);
+    /// assert_eq!(visits, 1);
+    /// ```
+    fn visit_offset_table_dedup<L, O, U, F>(
+        &mut self,
+        bigendian: bool,
+        mut visit: F,
+    ) -> Result<Vec<U>, OffsetTableError>
+    where
+        L: EndianNumber + TryInto<usize>,
+        O: EndianNumber + TryInto<u64>,
+        U: Clone,
+        F: FnMut(usize, &mut Self) -> Option<U>,
+    {
+        let start = self.pos().ok_or(OffsetTableError::Truncated)?;
+        let offsets = read_offset_table::<_, L, O>(self, bigendian)?;
+        let mut visited: HashMap<u64, U> = HashMap::new();
+
+        visit_offsets(self, start, &offsets, |index, r| {
+            let offset = r.pos()?;
+            if let Some(value) = visited.get(&offset) {
+                return Some(value.clone());
+            }
+
+            let value = visit(index, r)?;
+            visited.insert(offset, value.clone());
+            Some(value)
+        })
+    }
+    /// Reads the next `U` with the specified endianness, shifting the position, and checks that it equals `expected`. Returns the same errors
+    /// as [`SeqByteReader::expect`], and restores the position on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"RIFF".to_vec());
+    ///
+    /// assert_eq!(cursor.expect_e(0x52494646u32, true), Ok(()));
+    /// ```
+    fn expect_e<U: EndianNumber + PartialEq + fmt::Debug>(
+        &mut self,
+        expected: U,
+        bigendian: bool,
+    ) -> Result<(), ExpectError<U>> {
+        expect_with(self, expected, |r| r.shift_e::<U>(bigendian))
+    }
+    /// Reads the next `W` with the specified endianness, shifting the position, and converts it to the narrower `U`, such as a `u64` size
+    /// stored in a file to a [`usize`]. Returns [`NarrowError::OutOfRange`] with the value read if it does not fit in `U`, and
+    /// [`NarrowError::Truncated`] if there are not enough bytes to be read. On failure, the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0, 1, 1, 0]);
+    ///
+    /// assert_eq!(cursor.shift_narrow::<u16, u8>(true), Ok(1));
+    /// assert_eq!(cursor.shift_narrow::<u16, u8>(true), Err(NarrowError::OutOfRange { offset: 2, value: 0x100 }));
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    fn shift_narrow<W: EndianNumber + Copy, U: TryFrom<W>>(
+        &mut self,
+        bigendian: bool,
+    ) -> Result<U, NarrowError<W>> {
+        let offset = self.pos().ok_or(NarrowError::Truncated { offset: 0 })?;
+
+        with_restore(self, offset, |r| {
+            let value = r
+                .shift_e::<W>(bigendian)
+                .ok_or(NarrowError::Truncated { offset })?;
+
+            U::try_from(value).map_err(|_| NarrowError::OutOfRange { offset, value })
+        })
+    }
+    /// Reads `count` deltas of wire type `U` with the specified endianness, shifting the position, and returns their running sums starting from
+    /// `base`, such as a sorted column of timestamps stored as the gaps between them. Returns [`DeltaError::Overflow`] if a sum does not fit
+    /// in a [`u64`] rather than wrapping, and [`DeltaError::Truncated`] if there are not enough bytes to be read. On failure, the position is
+    /// restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0, 5, 1, 44, 0, 0]);
+    ///
+    /// assert_eq!(cursor.shift_delta_decoded::<u16>(3, 1000, true), Ok(vec![1005, 1305, 1305]));
+    /// ```
+    fn shift_delta_decoded<U: EndianNumber + Into<u64>>(
+        &mut self,
+        count: usize,
+        base: u64,
+        bigendian: bool,
+    ) -> Result<Vec<u64>, DeltaError> {
+        decode_deltas(self, count, base, |r| {
+            r.shift_e::<U>(bigendian).map(Into::into)
+        })
+    }
+    /// Reads the next `U` with the specified endianness, shifting the position, and returns it if `pred` accepts it. Returns [`None`] if there
+    /// are not enough bytes to be read, the value fails to convert, or `pred` rejects it, in which case the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0, 2]);
+    ///
+    /// assert_eq!(cursor.shift_validated_e::<u16, _>(true, |v| *v == 2), Some(2));
+    /// ```
+    fn shift_validated_e<U: EndianNumber, F: FnOnce(&U) -> bool>(
+        &mut self,
+        bigendian: bool,
+        pred: F,
+    ) -> Option<U> {
+        self.atomic(|r| r.shift_e::<U>(bigendian).filter(pred))
+    }
+    /// Reads the next `U` with the specified endianness, shifting the position, and returns it if `check` accepts it. Returns
+    /// [`CheckError::Truncated`] if there are not enough bytes to be read or the value fails to convert, and [`CheckError::Invalid`] with the
+    /// error of `check` if it rejects the value. On failure, the position is restored.
+    fn shift_checked_e<U: EndianNumber, E, F: FnOnce(&U) -> Result<(), E>>(
+        &mut self,
+        bigendian: bool,
+        check: F,
+    ) -> Result<U, CheckError<E>> {
+        let mut error = CheckError::Truncated;
+
+        self.atomic(|r| {
+            let value = r.shift_e::<U>(bigendian)?;
+            check(&value)
+                .map_err(|e| error = CheckError::Invalid(e))
+                .ok()?;
+            Some(value)
+        })
+        .ok_or(error)
+    }
+    /// Reads an optional `U` encoded as a presence byte followed by the value only if present, as [`SeqByteReader::shift_optional`] does,
+    /// converting the value with the specified endianness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![1, 0x01, 0x02, 1, 0x03]);
+    ///
+    /// assert_eq!(cursor.shift_optional_e::<u16>(true), Some(Some(0x0102)));
+    /// assert_eq!(cursor.shift_optional_e::<u16>(true), None);
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    fn shift_optional_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<Option<U>> {
+        self.atomic(|r| match r.shift::<bool>()? {
+            true => r.shift_e::<U>(bigendian).map(Some),
+            false => Some(None),
+        })
+    }
+    /// Reads an optional `U` with the specified endianness as [`ESeqByteReader::shift_optional_e`] does, without shifting the position.
+    fn next_optional_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<Option<U>> {
+        let start = self.pos()?;
+        let value = self.shift_optional_e(bigendian)?;
+        self.set_pos(start)?;
+
+        Some(value)
+    }
+    /// Reads the discriminant of an `E` with the specified endianness, shifting the position, and converts it to `E`, as
+    /// [`SeqByteReader::shift_enum`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Opcode(u16);
+    ///
+    /// impl TryFrom<u16> for Opcode {
+    ///     type Error = ();
+    ///
+    ///     fn try_from(code: u16) -> Result<Self, ()> {
+    ///         (code < 0x100).then_some(Opcode(code)).ok_or(())
+    ///     }
+    /// }
+    ///
+    /// impl TaggedEnum for Opcode {
+    ///     type Repr = u16;
+    /// }
+    ///
+    /// let mut cursor = Cursor::new(vec![0x00, 0x2A, 0x01, 0x00]);
+    ///
+    /// assert_eq!(cursor.shift_enum_e::<Opcode>(true), Some(Opcode(0x2A)));
+    /// assert_eq!(cursor.shift_enum_e::<Opcode>(true), None);
+    /// ```
+    fn shift_enum_e<E: TaggedEnum>(&mut self, bigendian: bool) -> Option<E> {
+        self.atomic(|r| E::try_from(r.shift_e::<E::Repr>(bigendian)?).ok())
+    }
+    /// Reads an `E` with the specified endianness as [`ESeqByteReader::shift_enum_e`] does, without shifting the position.
+    fn next_enum_e<E: TaggedEnum>(&mut self, bigendian: bool) -> Option<E> {
+        E::try_from(self.next_e::<E::Repr>(bigendian)?).ok()
+    }
+    /// Reads `frames * channels` interleaved values of `U` with the specified endianness in a single read, shifting the position, and
+    /// distributes them into `channels` [`Vec`]s of length `frames`, such that the `i`th value read belongs to channel `i % channels`. Returns
+    /// [`None`] if there are not enough bytes to be read, in which case no partially filled channels are returned, or if the length of the
+    /// values overflows a [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let a = vec![1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let channels = cursor.shift_deinterleaved::<i16>(3, 2, false).unwrap();
+    ///
+    /// assert_eq!(channels, [[1, 3, 5], [2, 4, 6]]);
+    /// ```
+    fn shift_deinterleaved<U: EndianNumber>(
+        &mut self,
+        frames: usize,
+        channels: usize,
+        bigendian: bool,
+    ) -> Option<Vec<Vec<U>>>;
+    /// Reads one `U` with the specified endianness from each of `count` fixed-size records of `stride` bytes, located `field_offset` bytes
+    /// into each record, and shifts the position to the end of the last record (`count * stride` bytes after the current position). Small
+    /// tables with small gaps between fields are read in one go, while larger ones seek from field to field.
+    ///
+    /// Returns [`None`], without reading anything, if the field does not fit inside a record (`field_offset + U::SIZE > stride`), or if the
+    /// end of the reader is known and comes before the end of the last record, even if every field is there. Also returns [`None`] if there
+    /// are not enough bytes to be read, in which case the position is restored. Readers which do not know their end are only checked up to
+    /// the last field, so the position may be shifted past their end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// // Three 4-byte records, each with a u16 at offset 2.
+    /// let a = vec![0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let values = cursor.shift_strided::<u16>(3, 4, 2, false).unwrap();
+    ///
+    /// assert_eq!(values, [1, 2, 3]);
+    /// assert_eq!(cursor.position(), 12);
+    /// ```
+    fn shift_strided<U: EndianNumber>(
+        &mut self,
+        count: usize,
+        stride: usize,
+        field_offset: usize,
+        bigendian: bool,
+    ) -> Option<Vec<U>>;
+    /// Binary searches `count` fixed-size records of `record_size` bytes, starting at the absolute offset `start` and sorted by a key of type
+    /// `K` located `key_offset` bytes into each record, for `needle`. Only the keys of the probed records are read. If several records have
+    /// the needle as their key, the first is found.
+    ///
+    /// Returns the index and absolute offset of the found record, or of the insertion point when no record matches, and leaves the position
+    /// at that offset. Returns [`None`] if the key does not fit inside a record, or if a key could not be read, in which case the position is
+    /// restored.
+    ///
+    /// In debug builds, panics if the probed keys reveal that the records are not sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// // Five 2-byte records, each a sorted u8 key followed by a u8 value.
+    /// let a = vec![1, 10, 3, 30, 3, 31, 5, 50, 9, 90];
+    /// let mut cursor = Cursor::new(a);
+    ///
+    /// let result = cursor.binary_search_record(0, 5, 2, 0, false, &3u8).unwrap();
+    /// assert_eq!(result, SearchResult::Found { index: 1, offset: 2 });
+    /// assert_eq!(cursor.shift_slice(2).unwrap(), [3, 30]);
+    ///
+    /// let result = cursor.binary_search_record(0, 5, 2, 0, false, &4u8).unwrap();
+    /// assert_eq!(result, SearchResult::NotFound { index: 3, offset: 6 });
+    /// ```
+    fn binary_search_record<K: EndianNumber + Ord>(
+        &mut self,
+        start: u64,
+        count: u64,
+        record_size: u64,
+        key_offset: u64,
+        bigendian: bool,
+        needle: &K,
+    ) -> Option<SearchResult>;
+    /// Walks a stream of records, each laid out as a length prefix `L` with the specified endianness followed by that many bytes of payload,
+    /// until the end of the source, without holding more than one chunk of a payload in memory. `on_header` is called with each record
+    /// before its payload, and `on_payload_chunk` with each chunk of the payload in turn, which are at most 64 KiB long.
+    ///
+    /// Returns the number of records and payload bytes walked. On failure, the error holds the index and offset of the offending record, and
+    /// the position is restored to where the walk started. If the end of the source is known, a payload running past it is rejected before
+    /// any of it is passed on. Otherwise, the chunks before the end are passed on first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![2, b'h', b'i', 0, 3, b'y', b'o', b'u']);
+    /// let mut lens = Vec::new();
+    /// let mut text = Vec::new();
+    ///
+    /// let summary = cursor
+    ///     .walk_records::<u8, _, _>(false, |record| lens.push(record.len), |_, chunk| text.extend_from_slice(chunk))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(summary, WalkSummary { records: 3, payload_bytes: 5 });
+    /// assert_eq!(lens, [2, 0, 3]);
+    /// assert_eq!(text, b"hiyou");
+    /// ```
+    fn walk_records<L, H, P>(
+        &mut self,
+        bigendian: bool,
+        on_header: H,
+        on_payload_chunk: P,
+    ) -> Result<WalkSummary, WalkError>
+    where
+        L: EndianNumber + TryInto<u64>,
+        H: FnMut(RecordInfo),
+        P: FnMut(&RecordInfo, &[u8]);
+    /// Reads a frame laid out as a `u16` payload length, the payload, and a `u32` checksum of the payload computed with `algo` (the length and
+    /// checksum with the specified endianness), shifting the position past the frame, and returns the payload once its checksum is verified.
+    ///
+    /// Returns [`FrameCheckError::Truncated`] if there are not enough bytes to be read, and [`FrameCheckError::Mismatch`] with the stored and
+    /// computed checksums if they differ. On failure, the position is restored to the start of the frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::crc::CrcAlgo;
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(Vec::new());
+    /// cursor.push_checked_frame(b"hello", true, CrcAlgo::Crc32).unwrap();
+    /// cursor.set_position(0);
+    ///
+    /// assert_eq!(cursor.shift_checked_frame(true, CrcAlgo::Crc32).unwrap(), b"hello");
+    /// ```
+    #[cfg(feature = "crc")]
+    fn shift_checked_frame(
+        &mut self,
+        bigendian: bool,
+        algo: CrcAlgo,
+    ) -> Result<Vec<u8>, FrameCheckError>;
+    /// Reads a zstd-compressed block laid out as a `u32` compressed length, a `u32` raw length (both with the specified endianness) and the
+    /// compressed bytes, shifting the position past the block, and returns the decompressed bytes.
+    ///
+    /// Returns [`None`] if the raw length exceeds `max_raw`, if the compressed length exceeds what `max_raw` bytes can compress to, if there
+    /// are not enough bytes to be read, if decompression fails, or if the decompressed size does not equal the raw length. On failure, the
+    /// position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(Vec::new());
+    /// cursor.push_zstd_block(b"hello hello hello", 3, false).unwrap();
+    /// cursor.set_position(0);
+    ///
+    /// assert_eq!(cursor.shift_zstd_block(false, 1024).unwrap(), b"hello hello hello");
+    /// assert_eq!(cursor.shift_zstd_block(false, 1024), None);
+    /// ```
+    #[cfg(feature = "zstd")]
+    fn shift_zstd_block(&mut self, bigendian: bool, max_raw: usize) -> Option<Vec<u8>>;
+}
+
+/// Represents a sequential byte writer which can write bytes. Can be used on types that implement [`Write`].
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(Vec::new());
+///
+/// cursor.push(-40891i32).unwrap();
+/// cursor.push_slice(b"hello").unwrap();
+///
+/// assert_eq!(cursor.into_inner(), [69, 96, 255, 255, 0x68, 0x65, 0x6C, 0x6C, 0x6F]);
+/// ```
+pub trait SeqByteWriter {
+    /// Writes the byte representation of `value`, shifting the position by the size of `U`. Returns [`None`] if the bytes could not be written.
+    fn push<U: SizedNumber>(&mut self, value: U) -> Option<()>;
+    /// Writes all of `bytes`, shifting the position by `bytes.len()`. Returns [`None`] if the bytes could not be written.
+    fn push_slice(&mut self, bytes: &[u8]) -> Option<()>;
+    /// Packs `bits` into `ceil(bits.len() / 8)` bytes in the specified bit order and writes them. The unused padding bits in the final byte
+    /// are zero. If unimplemented, internally calls `push_slice` with the packed bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    ///
+    /// let mut a = Vec::new();
+    /// a.push_bitvec(&[true, false, true], BitOrder::MsbFirst).unwrap();
+    ///
+    /// assert_eq!(a, [0b1010_0000]);
+    /// ```
+    fn push_bitvec(&mut self, bits: &[bool], order: BitOrder) -> Option<()> {
+        let mut packed = vec![0u8; bits.len().div_ceil(8)];
+
+        for (i, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+            packed[i / 8] |= order.mask(i % 8);
+        }
+
+        self.push_slice(&packed)
+    }
+    /// Writes `value` as a MIDI variable-length quantity in the fewest bytes possible, as read by [`SeqByteReader::shift_vlq`]. Returns
+    /// [`None`] without writing anything if `value` exceeds `0x0FFFFFFF`, the largest value 4 bytes can hold, or if the bytes could not be
+    /// written. If unimplemented, internally calls `push_slice` with the encoded bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    ///
+    /// let mut a = Vec::new();
+    /// a.push_vlq(0x80).unwrap();
+    /// a.push_vlq(0x7F).unwrap();
+    ///
+    /// assert_eq!(a, [0x81, 0x00, 0x7F]);
+    /// assert_eq!(a.push_vlq(0x1000_0000), None);
+    /// ```
+    fn push_vlq(&mut self, value: u32) -> Option<()> {
+        if value > 0x0FFF_FFFF {
+            return None;
+        }
+
+        let len = (1..4).take_while(|i| value >> (7 * i) != 0).count() + 1;
+        let encoded: Vec<u8> = (0..len)
+            .rev()
+            .map(|i| {
+                let continuation = if i == 0 { 0 } else { 0x80 };
+                ((value >> (7 * i)) & 0x7F) as u8 | continuation
+            })
+            .collect();
+
+        self.push_slice(&encoded)
+    }
+    /// Writes the differences between consecutive values of `values`, starting from `base`, as unsigned LEB128 varints, the counterpart of
+    /// [`SeqByteReader::shift_varint_delta_decoded`]. Returns [`None`] without writing anything if `values` is not sorted in ascending order
+    /// from `base`, or if the bytes could not be written. If unimplemented, internally calls `push_slice` with the encoded bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    ///
+    /// let mut a = Vec::new();
+    /// a.push_varint_delta_encoded(&[1005, 1305, 1305], 1000).unwrap();
+    ///
+    /// assert_eq!(a, [0x05, 0xAC, 0x02, 0x00]);
+    /// assert_eq!(a.push_varint_delta_encoded(&[3, 2], 0), None);
+    /// ```
+    fn push_varint_delta_encoded(&mut self, values: &[u64], base: u64) -> Option<()> {
+        let mut encoded = Vec::new();
+
+        for mut delta in encode_deltas(values, base)? {
+            while delta >= 0x80 {
+                encoded.push(delta as u8 | 0x80);
+                delta >>= 7;
+            }
+            encoded.push(delta as u8);
+        }
+
+        self.push_slice(&encoded)
+    }
+    /// Writes a record whose fields are written by `f`, prefixed with its length encoded as `len_field`, the counterpart of
+    /// [`SeqByteReader::shift_versioned_record`]. The fields are written to a buffer first, so the length is known before anything is written.
+    /// Returns [`None`] without writing anything if `f` returns [`None`] or the length does not fit in `len_field`, and [`None`] if the bytes
+    /// could not be written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    ///
+    /// let mut a = Vec::new();
+    /// a.push_versioned_record(VersionedLen::U16(Endianness::Big), |r| {
+    ///     r.push_e(0x072Au16, false)?;
+    ///     r.push_slice(&[0xFF])
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(a, [0, 3, 0x2A, 0x07, 0xFF]);
+    /// ```
+    fn push_versioned_record<F: FnOnce(&mut Vec<u8>) -> Option<()>>(
+        &mut self,
+        len_field: VersionedLen,
+        f: F,
+    ) -> Option<()> {
+        let mut fields = Vec::new();
+        f(&mut fields)?;
+
+        let mut record = len_field.encode(fields.len())?;
+        record.extend(fields);
+
+        self.push_slice(&record)
+    }
+}
+/// Represents a sequential byte writer which can write bytes with a specified endianness. Can be used on types that implement [`Write`].
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+///
+/// let mut a = Vec::new();
+///
+/// a.push_e(-40891i32, false).unwrap();
+/// a.push_e(-40891i32, true).unwrap();
+///
+/// assert_eq!(a, [69, 96, 255, 255, 255, 255, 96, 69]);
+/// ```
+pub trait ESeqByteWriter {
+    /// Writes the byte representation of `value` with the specified endianness, shifting the position by the size of `U`. Returns [`None`]
+    /// if the bytes could not be written.
+    fn push_e<U: EndianNumber>(&mut self, value: U, bigendian: bool) -> Option<()>;
+    /// Writes the byte representation of `value` with the byte order `endianness`, as [`ESeqByteWriter::push_e`] does.
+    fn push_endian<U: EndianNumber>(&mut self, value: U, endianness: Endianness) -> Option<()> {
+        self.push_e(value, endianness.is_big())
+    }
+    /// Writes the byte representation of `value` in the byte order `O`, as [`ESeqByteWriter::push_e`] does.
+    fn push_as<U: EndianNumber, O: ByteOrder>(&mut self, value: U) -> Option<()> {
+        self.push_e(value, O::ENDIANNESS.is_big())
+    }
+    /// Writes the byte representation of `value` in the byte order of the target, as [`ESeqByteWriter::push_e`] does. Pointer-sized types
+    /// keep their 8-byte width, as for [`EndianNumber::from_bytes_ne`].
+    fn push_ne<U: EndianNumber>(&mut self, value: U) -> Option<()> {
+        self.push_e(value, cfg!(target_endian = "big"))
+    }
+    /// Writes the number of `values` as `L`, then the values, all with the specified endianness. Returns [`None`] without writing anything if
+    /// the number of values does not fit in `L`, or if the bytes could not be written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(Vec::new());
+    /// cursor.push_counted::<u8, u16>(&[1, 2], true).unwrap();
+    ///
+    /// assert_eq!(cursor.get_ref(), &[2, 0, 1, 0, 2]);
+    /// assert!(cursor.push_counted::<u8, u16>(&[0; 256], true).is_none());
+    /// ```
+    fn push_counted<L: EndianNumber + TryFrom<usize>, U: EndianNumber>(
+        &mut self,
+        values: &[U],
+        bigendian: bool,
+    ) -> Option<()>;
+    /// Writes the differences between consecutive values of `values`, starting from `base`, as `U` with the specified endianness, the
+    /// counterpart of [`ESeqByteReader::shift_delta_decoded`]. Returns [`None`] without writing anything if `values` is not sorted in
+    /// ascending order from `base`, a difference does not fit in `U`, or the bytes could not be written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    ///
+    /// let mut a = Vec::new();
+    /// a.push_delta_encoded::<u16>(&[1005, 1305, 1305], 1000, true).unwrap();
+    ///
+    /// assert_eq!(a, [0, 5, 1, 44, 0, 0]);
+    /// assert!(a.push_delta_encoded::<u8>(&[1005, 1305], 1000, true).is_none());
+    /// ```
+    fn push_delta_encoded<U: EndianNumber + TryFrom<u64>>(
+        &mut self,
+        values: &[u64],
+        base: u64,
+        bigendian: bool,
+    ) -> Option<()>;
+    /// Writes `payload` as a frame laid out as a `u16` payload length, the payload, and a `u32` checksum of the payload computed with `algo`
+    /// (the length and checksum with the specified endianness). Returns [`None`] if the payload is longer than [`u16::MAX`] bytes, or the
+    /// bytes could not be written.
+    #[cfg(feature = "crc")]
+    fn push_checked_frame(&mut self, payload: &[u8], bigendian: bool, algo: CrcAlgo) -> Option<()>;
+    /// Compresses `data` with zstd at the specified compression level, and writes it as a `u32` compressed length, a `u32` raw length (both
+    /// with the specified endianness) and the compressed bytes. Returns [`None`] if compression fails, either length does not fit in a `u32`,
+    /// or the bytes could not be written.
+    #[cfg(feature = "zstd")]
+    fn push_zstd_block(&mut self, data: &[u8], level: i32, bigendian: bool) -> Option<()>;
+}
+
+/// The result of a reader method, which is either a success or a failure.
+trait Outcome {
+    /// Returns `true` if the method failed.
+    fn failed(&self) -> bool;
+}
+
+impl<T> Outcome for Option<T> {
+    fn failed(&self) -> bool {
+        self.is_none()
+    }
+}
+
+impl<T, E> Outcome for Result<T, E> {
+    fn failed(&self) -> bool {
+        self.is_err()
+    }
+}
+
+/// Runs `f` on `reader`, which is at `start`, and sets the position back to `start` if `f` fails. Every reader method which makes several
+/// reads goes through this, which is what makes them atomic. Single reads go through [`SeqByteReaderCore::try_fill`] instead, which only
+/// touches the position on failure.
+fn with_restore<R: SeqByteReaderCore + ?Sized, O: Outcome>(
+    reader: &mut R,
+    start: u64,
+    f: impl FnOnce(&mut R) -> O,
+) -> O {
+    let outcome = f(reader);
+    if outcome.failed() {
+        let _ = reader.set_pos(start);
+    }
+
+    outcome
+}
+
+/// Shifts the position of `reader` back over the `consumed` bytes just read if `value` is [`None`], because they failed to convert, and
+/// returns `value`.
+fn give_back_on_failure<R: SeqByteReaderCore + ?Sized, U>(
+    reader: &mut R,
+    consumed: usize,
+    value: Option<U>,
+) -> Option<U> {
+    if value.is_none() {
+        let _ = reader.step_back(consumed as u64);
+    }
+
+    value
+}
+
+/// Runs `f` on a zeroed buffer of `size` bytes, which is kept on the stack unless it is larger than [`STACK_BUF`], so that reading or
+/// writing a fixed-size value, or failing to, does not allocate.
+fn with_value_buf<O>(size: usize, f: impl FnOnce(&mut [u8]) -> O) -> O {
+    if size <= STACK_BUF {
+        f(&mut [0u8; STACK_BUF][..size])
+    } else {
+        f(&mut vec![0u8; size])
+    }
+}
+
+/// Returns whether `len` more bytes can be read from `reader`, checked against its end if it is known so that lengths which exceed what
+/// remains are rejected before a buffer is allocated for them. Lengths up to [`PROBE_CHUNK`] bytes are not checked, since querying the end
+/// of a file costs seeks which the read itself does not need.
+fn fits<R: SeqByteReaderCore + ?Sized>(reader: &mut R, len: usize) -> bool {
+    if len <= PROBE_CHUNK {
+        return true;
+    }
+
+    match reader.end_pos() {
+        Some(end) => reader
+            .pos()
+            .and_then(|pos| pos.checked_add(len as u64))
+            .is_some_and(|target| target <= end),
+        None => true,
+    }
+}
+
+/// Reads the `amount` bytes ending at the current position of `reader`, leaving the position at the first of them. Returns [`None`] without
+/// moving if there are fewer than `amount` bytes before the position.
+fn read_back<R: SeqByteReaderCore + ?Sized>(reader: &mut R, amount: usize) -> Option<Vec<u8>> {
+    let end = reader.pos()?;
+    let start = end.checked_sub(amount as u64)?;
+
+    let mut a = vec![0u8; amount];
+    reader.set_pos(start)?;
+
+    if reader.fill(&mut a).is_none() {
+        reader.set_pos(end)?;
+        return None;
+    }
+
+    reader.set_pos(start)?;
+    Some(a)
+}
+
+/// Reads a BER identifier, reporting errors at `offset`, without restoring the position on failure.
+fn read_ber_tag<R: SeqByteReaderCore + ?Sized>(
+    reader: &mut R,
+    offset: u64,
+) -> Result<BerTag, BerError> {
+    let first = reader.shift::<u8>().ok_or(BerError::Truncated { offset })?;
+    let mut tag = BerTag::from_first(first);
+
+    if first & 0x1F == 0x1F {
+        tag.number = 0;
+
+        loop {
+            let b = reader.shift::<u8>().ok_or(BerError::Truncated { offset })?;
+            if tag.number >> 25 != 0 {
+                return Err(BerError::InvalidTag { offset });
+            }
+
+            tag.number = (tag.number << 7) | (b & 0x7F) as u32;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+
+    Ok(tag)
+}
+
+/// Reads a BER length, reporting errors at `offset`, without restoring the position on failure.
+fn read_ber_len<R: SeqByteReaderCore + ?Sized>(
+    reader: &mut R,
+    offset: u64,
+) -> Result<usize, BerError> {
+    let first = reader.shift::<u8>().ok_or(BerError::Truncated { offset })?;
+
+    match first {
+        0..=0x7F => Ok(first as usize),
+        0x80 => Err(BerError::IndefiniteLength { offset }),
+        0xFF => Err(BerError::InvalidLength { offset }),
+        _ => {
+            let count = (first & 0x7F) as usize;
+            if count > 8 {
+                return Err(BerError::InvalidLength { offset });
+            }
+
+            let bytes = reader
+                .shift_slice(count)
+                .ok_or(BerError::Truncated { offset })?;
+            let len = bytes.iter().fold(0u64, |len, &b| (len << 8) | b as u64);
+
+            usize::try_from(len).map_err(|_| BerError::InvalidLength { offset })
+        }
+    }
+}
+
+/// Reads an unsigned LEB128 varint, without restoring the position on failure. Returns [`None`] if it is longer than a [`u64`].
+fn read_uleb128<R: SeqByteReaderCore + ?Sized>(reader: &mut R) -> Option<u64> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let b = reader.shift::<u8>()?;
+        let group = (b & 0x7F) as u64;
+        if group << shift >> shift != group {
+            return None;
+        }
+
+        value |= group << shift;
+        if b & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+/// Reads `count` deltas with `read_delta` and returns their running sums starting from `base`, restoring the position on failure.
+fn decode_deltas<R, F>(
+    reader: &mut R,
+    count: usize,
+    base: u64,
+    mut read_delta: F,
+) -> Result<Vec<u64>, DeltaError>
+where
+    R: SeqByteReaderCore + ?Sized,
+    F: FnMut(&mut R) -> Option<u64>,
+{
+    let start = reader.pos().ok_or(DeltaError::Truncated { offset: 0 })?;
+
+    with_restore(reader, start, |r| {
+        let mut values = Vec::new();
+        let mut sum = base;
+        let mut offset = start;
+
+        while values.len() < count {
+            let delta = read_delta(r).ok_or(DeltaError::Truncated { offset })?;
+            sum = sum.checked_add(delta).ok_or(DeltaError::Overflow {
+                offset,
+                index: values.len(),
+            })?;
+
+            values.push(sum);
+            offset = r.pos().ok_or(DeltaError::Truncated { offset })?;
+        }
+
+        Ok(values)
+    })
+}
+
+/// Returns the differences between consecutive values of `values`, starting from `base`, or [`None`] if they are not sorted in
+/// ascending order from `base`.
+fn encode_deltas(values: &[u64], base: u64) -> Option<Vec<u64>> {
+    let mut previous = base;
+
+    values
+        .iter()
+        .map(|&value| {
+            let delta = value.checked_sub(previous)?;
+            previous = value;
+            Some(delta)
+        })
+        .collect()
+}
+
+/// The largest chunk of a payload [`ESeqByteReader::walk_records`] passes on at once, in bytes.
+const WALK_CHUNK: usize = 64 * 1024;
+
+/// The size of the chunks [`SeqByteReader::available`] probes at a time, in bytes.
+const PROBE_CHUNK: usize = 4096;
+
+/// The largest value [`with_value_buf`] reads or writes through a buffer on the stack, in bytes, which covers every primitive and most fixed-size
+/// records.
+const STACK_BUF: usize = 64;
+
+/// The size of the chunks [`SeqByteReader::skip_until`] scans at a time, in bytes.
+const SCAN_CHUNK: usize = 8192;
+
+/// Returns the absolute offset of the next occurrence of `pattern` from the current position of `reader`, leaving the position unspecified.
+/// If there is none, returns [`None`] and leaves the position at the end of the stream.
+fn find_forward<R: SeqByteReaderCore + ?Sized>(reader: &mut R, pattern: &[u8]) -> Option<u64> {
+    let mut base = reader.pos()?;
+    if pattern.is_empty() {
+        return Some(base);
+    }
+
+    // Holds the last `pattern.len() - 1` bytes scanned, followed by the next chunk, so matches spanning two chunks are found. `base` is
+    // the absolute offset of its first byte.
+    let mut window: Vec<u8> = Vec::new();
+    let end = reader.end_pos();
+
+    loop {
+        let chunk = match end {
+            Some(end) => SCAN_CHUNK.min(usize::try_from(end.saturating_sub(reader.pos()?)).ok()?),
+            None => 1,
+        };
+
+        let filled = window.len();
+        window.resize(filled + chunk, 0);
+        if chunk == 0 || reader.fill(&mut window[filled..]).is_none() {
+            return None;
+        }
+
+        if let Some(i) = window.windows(pattern.len()).position(|w| w == pattern) {
+            return Some(base + i as u64);
+        }
+
+        let keep = (pattern.len() - 1).min(window.len());
+        base += (window.len() - keep) as u64;
+        window.drain(..window.len() - keep);
+    }
+}
+
+/// Reads the count and offsets of an offset table, checking every offset against the end of the source if it is known. On failure, the
+/// position is restored to before the count.
+fn read_offset_table<R, L, O>(reader: &mut R, bigendian: bool) -> Result<Vec<u64>, OffsetTableError>
+where
+    R: SeqByteReaderCore + ?Sized,
+    L: EndianNumber + TryInto<usize>,
+    O: EndianNumber + TryInto<u64>,
+{
+    let start = reader.pos().ok_or(OffsetTableError::Truncated)?;
+
+    with_restore(reader, start, |r| {
+        let offsets = r
+            .shift_counted::<L, O>(bigendian)
+            .ok_or(OffsetTableError::Truncated)?;
+        let end = r.end_pos();
+
+        offsets
+            .into_iter()
+            .enumerate()
+            .map(|(index, offset)| match offset.try_into() {
+                Ok(offset) if end.is_none_or(|end| offset <= end) => Ok(offset),
+                _ => Err(OffsetTableError::OutOfBounds { index }),
+            })
+            .collect()
+    })
+}
+
+/// Calls `visit` at each of `offsets` in turn, then sets the position back to where it was. On failure, the position is restored to
+/// `table_start`, the start of the table the offsets were read from.
+fn visit_offsets<R, U, F>(
+    reader: &mut R,
+    table_start: u64,
+    offsets: &[u64],
+    mut visit: F,
+) -> Result<Vec<U>, OffsetTableError>
+where
+    R: SeqByteReaderCore + ?Sized,
+    F: FnMut(usize, &mut R) -> Option<U>,
+{
+    let after_table = reader.pos().ok_or(OffsetTableError::Truncated)?;
+    let mut values = Vec::with_capacity(offsets.len());
+
+    for (index, &offset) in offsets.iter().enumerate() {
+        let value = reader.set_pos(offset).and_then(|_| visit(index, reader));
+
+        match value {
+            Some(value) => values.push(value),
+            None => {
+                let _ = reader.set_pos(table_start);
+                return Err(OffsetTableError::Visit { index, offset });
+            }
+        }
+    }
+
+    reader
+        .set_pos(after_table)
+        .ok_or(OffsetTableError::Truncated)?;
+    Ok(values)
+}
+
+/// Reads a value with `read` and compares it to `expected`, restoring the position of `reader` on failure.
+fn expect_with<R: SeqByteReaderCore + ?Sized, U: PartialEq>(
+    reader: &mut R,
+    expected: U,
+    read: impl FnOnce(&mut R) -> Option<U>,
+) -> Result<(), ExpectError<U>> {
+    let offset = reader.pos().ok_or(ExpectError::Truncated { offset: 0 })?;
+
+    with_restore(reader, offset, |r| match read(r) {
+        Some(actual) if actual == expected => Ok(()),
+        Some(actual) => Err(ExpectError::Mismatch {
+            offset,
+            expected,
+            actual,
+        }),
+        None => Err(ExpectError::Truncated { offset }),
+    })
+}
+
+impl<T: Read + Seek> SeqByteReaderCore for T {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.read_exact(buf).ok()
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.try_peek_fill(buf)
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        self.stream_position().ok()
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        self.seek(SeekFrom::Start(pos)).ok()?;
+        Some(())
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        let pos = self.stream_position().ok()?;
+
+        let end = self.seek(SeekFrom::End(0)).ok();
+        self.seek(SeekFrom::Start(pos)).ok()?;
+
+        end
+    }
+
+    fn try_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let read = read_counted(self, buf);
+        if read < buf.len() {
+            // Only the bytes actually consumed are given back, so a successful read never seeks.
+            let _ = self.step_back(read as u64);
+            return None;
+        }
+
+        Some(())
+    }
+
+    fn try_peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.try_fill(buf)?;
 
-        return U::from_bytes(&a[..]);
+        // Seeking back over the bytes just read can still fail, for readers which refuse to seek backwards.
+        self.step_back(buf.len() as u64)
+    }
+
+    fn step_back(&mut self, amount: u64) -> Option<()> {
+        if amount == 0 {
+            return Some(());
+        }
+
+        self.seek(SeekFrom::Current(-i64::try_from(amount).ok()?))
+            .ok()?;
+        Some(())
+    }
+}
+
+/// Reads into `buf` until it is full, the source ends or a read fails, returning the number of bytes read. Unlike [`Read::read_exact`], the
+/// number of bytes consumed is known on failure, so they can be given back with a relative seek.
+fn read_counted<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> usize {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+
+    read
+}
+
+impl<T: SeqByteReaderCore + ?Sized> SeqByteReader for T {
+    fn next<U: SizedNumber>(&mut self) -> Option<U> {
+        with_value_buf(U::SIZE, |a| {
+            self.try_peek_fill(a)?;
+            U::from_bytes(a)
+        })
     }
 
     fn shift<U: SizedNumber>(&mut self) -> Option<U> {
-        let size = U::size() as isize;
+        with_value_buf(U::SIZE, |a| {
+            self.try_fill(a)?;
+            give_back_on_failure(self, a.len(), U::from_bytes(a))
+        })
+    }
 
-        let mut a = vec![0u8; size as usize];
-        self.read_exact(&mut a).ok()?;
+    fn shift_vec<U: SizedNumber>(&mut self, count: usize) -> Option<Vec<U>> {
+        let size = U::SIZE;
+        let len = checked_len(count, size).ok()?;
+        if !fits(self, len) {
+            return None;
+        }
 
-        return U::from_bytes(&a[..]);
+        let mut a = vec![0u8; len];
+        self.try_fill(&mut a)?;
+
+        let values = (0..count)
+            .map(|i| U::from_bytes(&a[i * size..(i + 1) * size]))
+            .collect();
+        give_back_on_failure(self, a.len(), values)
     }
 
     fn next_slice(&mut self, amount: usize) -> Option<Vec<u8>> {
-        let mut a = vec![0u8; amount];
-        self.read_exact(&mut a).ok()?;
+        if !fits(self, amount) {
+            return None;
+        }
 
-        self.seek(SeekFrom::Current(-(amount as i64))).unwrap();
+        let mut a = vec![0u8; amount];
+        self.try_peek_fill(&mut a)?;
 
-        return Some(a);
+        Some(a)
     }
 
     fn shift_slice(&mut self, amount: usize) -> Option<Vec<u8>> {
+        if !fits(self, amount) {
+            return None;
+        }
+
         let mut a = vec![0u8; amount];
-        self.read_exact(&mut a).ok()?;
+        self.try_fill(&mut a)?;
+
+        Some(a)
+    }
+
+    fn shift_slice_back(&mut self, amount: usize) -> Option<Vec<u8>> {
+        read_back(self, amount)
+    }
+
+    fn shift_back<U: SizedNumber>(&mut self) -> Option<U> {
+        let end = self.pos()?;
+
+        with_restore(self, end, |r| U::from_bytes(&read_back(r, U::SIZE)?))
+    }
+
+    fn shift_vec_aligned<U: SizedNumber>(
+        &mut self,
+        count: usize,
+        align: usize,
+    ) -> Option<AlignedVec<U>> {
+        let start = self.pos()?;
+
+        with_restore(self, start, |r| AlignedVec::read(r, count, align))
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn shift_pod_slice<U: bytemuck::Pod>(&mut self, count: usize) -> Option<Vec<U>> {
+        let len = checked_len(count, std::mem::size_of::<U>()).ok()?;
+        if !fits(self, len) {
+            return None;
+        }
+
+        // A buffer of `U`s is aligned for `U`, so its bytes can be filled in place.
+        let mut out = vec![U::zeroed(); count];
+        self.try_fill(bytemuck::cast_slice_mut(&mut out))?;
+
+        Some(out)
+    }
+
+    fn shift_slice_into_vec(&mut self, amount: usize, out: &mut Vec<u8>) -> Option<()> {
+        out.clear();
+        if !fits(self, amount) {
+            return None;
+        }
+
+        out.resize(amount, 0);
+        let filled = self.try_fill(out);
+        if filled.is_none() {
+            out.clear();
+        }
 
-        return Some(a);
+        filled
+    }
+
+    fn shift_ber_tag(&mut self) -> Result<BerTag, BerError> {
+        let offset = self.pos().ok_or(BerError::Truncated { offset: 0 })?;
+
+        with_restore(self, offset, |r| read_ber_tag(r, offset))
+    }
+
+    fn shift_ber_len(&mut self) -> Result<usize, BerError> {
+        let offset = self.pos().ok_or(BerError::Truncated { offset: 0 })?;
+
+        with_restore(self, offset, |r| read_ber_len(r, offset))
+    }
+
+    fn shift_ber_tlv(&mut self) -> Result<(BerTag, Vec<u8>), BerError> {
+        let offset = self.pos().ok_or(BerError::Truncated { offset: 0 })?;
+        let truncated = BerError::Truncated { offset };
+
+        with_restore(self, offset, |r| {
+            let tag = read_ber_tag(r, offset)?;
+            let len = read_ber_len(r, offset)?;
+
+            if let Some(end) = r.end_pos() {
+                if r.pos().ok_or(truncated)?.saturating_add(len as u64) > end {
+                    return Err(truncated);
+                }
+            }
+
+            let contents = r.shift_slice(len).ok_or(truncated)?;
+            Ok((tag, contents))
+        })
     }
 }
-impl<T: Seek + Read> ESeqByteReader for T {
+impl<T: SeqByteReaderCore + ?Sized> ESeqByteReader for T {
     fn next_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U> {
-        let size = U::size() as isize;
+        with_value_buf(U::SIZE, |a| {
+            self.try_peek_fill(a)?;
+            U::from_bytes_e(a, bigendian)
+        })
+    }
 
-        let mut a = vec![0u8; size as usize];
-        self.read_exact(&mut a).ok()?;
+    fn shift_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U> {
+        with_value_buf(U::SIZE, |a| {
+            self.try_fill(a)?;
+            give_back_on_failure(self, a.len(), U::from_bytes_e(a, bigendian))
+        })
+    }
 
-        self.seek(SeekFrom::Current(-size as i64)).unwrap(); // Should not panic, as it is shifting backwards the same amount of bytes as moving forward.
+    fn shift_back_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U> {
+        let end = self.pos()?;
 
-        return U::from_bytes_e(&a[..], bigendian);
+        with_restore(self, end, |r| {
+            U::from_bytes_e(&read_back(r, U::SIZE)?, bigendian)
+        })
     }
 
-    fn shift_e<U: EndianNumber>(&mut self, bigendian: bool) -> Option<U> {
-        let size = U::size() as isize;
+    fn shift_vec_e<U: EndianNumber>(&mut self, count: usize, bigendian: bool) -> Option<Vec<U>> {
+        let size = U::SIZE;
+        let len = checked_len(count, size).ok()?;
+        if !fits(self, len) {
+            return None;
+        }
+
+        let mut a = vec![0u8; len];
+        self.try_fill(&mut a)?;
+
+        let values = (0..count)
+            .map(|i| U::from_bytes_e(&a[i * size..(i + 1) * size], bigendian))
+            .collect();
+        give_back_on_failure(self, a.len(), values)
+    }
+
+    fn shift_deinterleaved<U: EndianNumber>(
+        &mut self,
+        frames: usize,
+        channels: usize,
+        bigendian: bool,
+    ) -> Option<Vec<Vec<U>>> {
+        let size = U::SIZE;
+        let count = checked_len(frames, channels).ok()?;
+        let start = self.pos()?;
+        let len = checked_len(count, size).ok()?;
+        if !fits(self, len) {
+            return None;
+        }
+
+        let mut a = vec![0u8; len];
+        with_restore(self, start, |r| {
+            r.fill(&mut a)?;
+
+            let mut out: Vec<Vec<U>> = (0..channels).map(|_| Vec::with_capacity(frames)).collect();
+
+            for (i, bytes) in a.chunks_exact(size).enumerate() {
+                out[i % channels].push(U::from_bytes_e(bytes, bigendian)?);
+            }
+
+            Some(out)
+        })
+    }
+
+    fn shift_strided<U: EndianNumber>(
+        &mut self,
+        count: usize,
+        stride: usize,
+        field_offset: usize,
+        bigendian: bool,
+    ) -> Option<Vec<U>> {
+        let size = U::SIZE;
+        if field_offset.checked_add(size)? > stride {
+            return None;
+        }
+
+        let start = self.pos()?;
+        let end = start.checked_add((count as u64).checked_mul(stride as u64)?)?;
+        if self.end_pos().is_some_and(|stream_end| end > stream_end) {
+            return None;
+        }
+
+        with_restore(self, start, |r| {
+            let values = read_strided(r, start, count, stride, field_offset, bigendian)?;
+            r.set_pos(end)?;
+
+            Some(values)
+        })
+    }
+
+    fn binary_search_record<K: EndianNumber + Ord>(
+        &mut self,
+        start: u64,
+        count: u64,
+        record_size: u64,
+        key_offset: u64,
+        bigendian: bool,
+        needle: &K,
+    ) -> Option<SearchResult> {
+        if key_offset.checked_add(K::SIZE as u64)? > record_size {
+            return None;
+        }
+
+        let position = self.pos()?;
+        let offset_of = |index: u64| index.checked_mul(record_size)?.checked_add(start);
+        offset_of(count)?;
+
+        #[cfg(debug_assertions)]
+        let mut probes: Vec<(u64, K)> = Vec::new();
+
+        let (mut lo, mut hi) = (0, count);
+        let mut found = false;
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            let key = self
+                .set_pos(offset_of(mid)? + key_offset)
+                .and_then(|_| self.shift_e::<K>(bigendian));
+            let Some(key) = key else {
+                self.set_pos(position)?;
+                return None;
+            };
+
+            if key < *needle {
+                lo = mid + 1;
+            } else {
+                found = key == *needle;
+                hi = mid;
+            }
+
+            #[cfg(debug_assertions)]
+            {
+                debug_assert!(
+                    probes
+                        .iter()
+                        .all(|(index, probe)| (*index < mid && *probe <= key)
+                            || (*index > mid && *probe >= key)),
+                    "records are not sorted by their key"
+                );
+                probes.push((mid, key));
+            }
+        }
+
+        // `found` holds whether the last record narrowing `hi` matched, which is the record at `lo` when the search ends.
+        let offset = offset_of(lo)?;
+        self.set_pos(offset)?;
+
+        Some(if found {
+            SearchResult::Found { index: lo, offset }
+        } else {
+            SearchResult::NotFound { index: lo, offset }
+        })
+    }
+
+    fn walk_records<L, H, P>(
+        &mut self,
+        bigendian: bool,
+        mut on_header: H,
+        mut on_payload_chunk: P,
+    ) -> Result<WalkSummary, WalkError>
+    where
+        L: EndianNumber + TryInto<u64>,
+        H: FnMut(RecordInfo),
+        P: FnMut(&RecordInfo, &[u8]),
+    {
+        let start = self.pos().ok_or(WalkError::Truncated {
+            index: 0,
+            offset: 0,
+        })?;
+
+        with_restore(self, start, |r| {
+            let mut summary = WalkSummary::default();
+            let mut offset = start;
+            let end = r.end_pos();
+            let mut chunk = Vec::new();
+
+            loop {
+                let index = summary.records;
+                let truncated = WalkError::Truncated { index, offset };
+
+                let at_end = match end {
+                    Some(end) => offset >= end,
+                    None => r.peek_fill(&mut [0u8]).is_none(),
+                };
+                if at_end {
+                    let _ = r.set_pos(offset);
+                    return Ok(summary);
+                }
+
+                let len = match r.shift_e::<L>(bigendian) {
+                    None => Err(truncated),
+                    Some(len) => len
+                        .try_into()
+                        .map_err(|_| WalkError::InvalidLength { index, offset }),
+                };
+                let record = len.and_then(|len| {
+                    let record = RecordInfo { index, offset, len };
+                    let overrun = WalkError::PayloadOverrun { index, offset, len };
+
+                    let payload_start = r.pos().ok_or(truncated)?;
+                    if end.is_some_and(|end| payload_start.saturating_add(len) > end) {
+                        return Err(overrun);
+                    }
+                    Ok((record, payload_start, overrun))
+                });
+                let (record, payload_start, overrun) = record?;
+
+                on_header(record);
+
+                let mut remaining = record.len;
+                while remaining > 0 {
+                    chunk.resize(remaining.min(WALK_CHUNK as u64) as usize, 0);
+                    if r.fill(&mut chunk).is_none() {
+                        return Err(overrun);
+                    }
+
+                    on_payload_chunk(&record, &chunk);
+                    remaining -= chunk.len() as u64;
+                }
+
+                summary.records += 1;
+                summary.payload_bytes += record.len;
+                offset = payload_start + record.len;
+            }
+        })
+    }
+
+    #[cfg(feature = "crc")]
+    fn shift_checked_frame(
+        &mut self,
+        bigendian: bool,
+        algo: CrcAlgo,
+    ) -> Result<Vec<u8>, FrameCheckError> {
+        let offset = self.pos().ok_or(FrameCheckError::Truncated { offset: 0 })?;
+
+        with_restore(self, offset, |r| {
+            let truncated = FrameCheckError::Truncated { offset };
+
+            let len = r.shift_e::<u16>(bigendian).ok_or(truncated)?;
+            let payload = r.shift_slice(len as usize).ok_or(truncated)?;
+            let expected = r.shift_e::<u32>(bigendian).ok_or(truncated)?;
+
+            let computed = algo.checksum(&payload);
+            if computed != expected {
+                return Err(FrameCheckError::Mismatch {
+                    offset,
+                    expected,
+                    computed,
+                });
+            }
+
+            Ok(payload)
+        })
+    }
+
+    #[cfg(feature = "zstd")]
+    fn shift_zstd_block(&mut self, bigendian: bool, max_raw: usize) -> Option<Vec<u8>> {
+        let start = self.pos()?;
+
+        with_restore(self, start, |r| read_zstd_block(r, bigendian, max_raw))
+    }
+}
+
+/// Reads the block of [`ESeqByteReader::shift_zstd_block`], leaving the position unspecified on failure.
+#[cfg(feature = "zstd")]
+fn read_zstd_block<T: SeqByteReaderCore + ?Sized>(
+    reader: &mut T,
+    bigendian: bool,
+    max_raw: usize,
+) -> Option<Vec<u8>> {
+    let compressed_len = usize::try_from(reader.shift_e::<u32>(bigendian)?).ok()?;
+    let raw_len = usize::try_from(reader.shift_e::<u32>(bigendian)?).ok()?;
+
+    if raw_len > max_raw || compressed_len > zstd::zstd_safe::compress_bound(max_raw) {
+        return None;
+    }
+
+    let compressed = reader.shift_slice(compressed_len)?;
+    let raw = zstd::bulk::decompress(&compressed, raw_len).ok()?;
+
+    (raw.len() == raw_len).then_some(raw)
+}
+
+/// The largest span of a strided table, in bytes, which is read in one go rather than field by field.
+const STRIDED_BULK_LIMIT: usize = 1 << 20;
+/// The largest gap between two strided fields, in bytes, which is read through rather than seeked over.
+const STRIDED_SEEK_GAP: usize = 4096;
+
+/// Reads the fields of [`ESeqByteReader::shift_strided`] from a table starting at `start`, leaving the position unspecified.
+fn read_strided<T: SeqByteReaderCore + ?Sized, U: EndianNumber>(
+    reader: &mut T,
+    start: u64,
+    count: usize,
+    stride: usize,
+    field_offset: usize,
+    bigendian: bool,
+) -> Option<Vec<U>> {
+    let size = U::SIZE;
+    if count == 0 {
+        return Some(Vec::new());
+    }
+
+    // The span from the start of the first record to the end of the last field.
+    let span = checked_len(count - 1, stride)
+        .ok()?
+        .checked_add(field_offset.checked_add(size)?)?;
+
+    if span <= STRIDED_BULK_LIMIT && stride - size <= STRIDED_SEEK_GAP {
+        let mut a = vec![0u8; span];
+        reader.fill(&mut a)?;
+
+        return (0..count)
+            .map(|i| {
+                let offset = i * stride + field_offset;
+                U::from_bytes_e(&a[offset..offset + size], bigendian)
+            })
+            .collect();
+    }
+
+    let mut a = vec![0u8; size];
+
+    (0..count)
+        .map(|i| {
+            let offset = (i as u64)
+                .checked_mul(stride as u64)?
+                .checked_add(start.checked_add(field_offset as u64)?)?;
+
+            reader.set_pos(offset)?;
+            reader.fill(&mut a)?;
+
+            U::from_bytes_e(&a, bigendian)
+        })
+        .collect()
+}
+
+impl<T: Write> SeqByteWriter for T {
+    fn push<U: SizedNumber>(&mut self, value: U) -> Option<()> {
+        if U::WRITES_IN_PLACE {
+            let in_place = with_value_buf(U::SIZE, |buf| {
+                let written = value.to_bytes_into(buf) == Some(U::SIZE);
+                written.then(|| self.write_all(buf).ok())
+            });
+            if let Some(result) = in_place {
+                return result;
+            }
+        }
+
+        self.write_all(&value.to_bytes()).ok()
+    }
+
+    fn push_slice(&mut self, bytes: &[u8]) -> Option<()> {
+        self.write_all(bytes).ok()
+    }
+}
+impl<T: Write> ESeqByteWriter for T {
+    fn push_e<U: EndianNumber>(&mut self, value: U, bigendian: bool) -> Option<()> {
+        if U::WRITES_IN_PLACE {
+            let in_place = with_value_buf(U::SIZE, |buf| {
+                let written = value.to_bytes_into_e(buf, bigendian) == Some(U::SIZE);
+                written.then(|| self.write_all(buf).ok())
+            });
+            if let Some(result) = in_place {
+                return result;
+            }
+        }
+
+        self.write_all(&value.to_bytes_e(bigendian)).ok()
+    }
+
+    fn push_counted<L: EndianNumber + TryFrom<usize>, U: EndianNumber>(
+        &mut self,
+        values: &[U],
+        bigendian: bool,
+    ) -> Option<()> {
+        let count = L::try_from(values.len()).ok()?;
+
+        let mut bytes = count.to_bytes_e(bigendian);
+        for value in values {
+            bytes.extend(value.to_bytes_e(bigendian));
+        }
+
+        self.write_all(&bytes).ok()
+    }
+
+    fn push_delta_encoded<U: EndianNumber + TryFrom<u64>>(
+        &mut self,
+        values: &[u64],
+        base: u64,
+        bigendian: bool,
+    ) -> Option<()> {
+        let mut bytes = Vec::new();
+        for delta in encode_deltas(values, base)? {
+            bytes.extend(U::try_from(delta).ok()?.to_bytes_e(bigendian));
+        }
+
+        self.write_all(&bytes).ok()
+    }
+
+    #[cfg(feature = "crc")]
+    fn push_checked_frame(&mut self, payload: &[u8], bigendian: bool, algo: CrcAlgo) -> Option<()> {
+        self.push_e(u16::try_from(payload.len()).ok()?, bigendian)?;
+        self.write_all(payload).ok()?;
+        self.push_e(algo.checksum(payload), bigendian)
+    }
 
-        let mut a = vec![0u8; size as usize];
-        self.read_exact(&mut a).ok()?;
+    #[cfg(feature = "zstd")]
+    fn push_zstd_block(&mut self, data: &[u8], level: i32, bigendian: bool) -> Option<()> {
+        let compressed = zstd::bulk::compress(data, level).ok()?;
 
-        return U::from_bytes_e(&a[..], bigendian);
+        self.push_e(u32::try_from(compressed.len()).ok()?, bigendian)?;
+        self.push_e(u32::try_from(data.len()).ok()?, bigendian)?;
+        self.write_all(&compressed).ok()
     }
 }
@@ -0,0 +1,100 @@
+use std::fmt;
+
+/// A CRC-32 algorithm, used for checksumming frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrcAlgo {
+    /// CRC-32 as used by Ethernet, zlib and PNG (reflected polynomial `0xEDB88320`).
+    Crc32,
+    /// CRC-32C (Castagnoli) as used by iSCSI and SCTP (reflected polynomial `0x82F63B78`).
+    Crc32c,
+}
+
+const fn table(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = table(0xEDB8_8320);
+const CRC32C_TABLE: [u32; 256] = table(0x82F6_3B78);
+
+impl CrcAlgo {
+    /// Computes the checksum of `data`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::crc::CrcAlgo;
+    ///
+    /// assert_eq!(CrcAlgo::Crc32.checksum(b"123456789"), 0xCBF43926);
+    /// assert_eq!(CrcAlgo::Crc32c.checksum(b"123456789"), 0xE3069283);
+    /// ```
+    pub fn checksum(&self, data: &[u8]) -> u32 {
+        let table = match self {
+            CrcAlgo::Crc32 => &CRC32_TABLE,
+            CrcAlgo::Crc32c => &CRC32C_TABLE,
+        };
+
+        !data.iter().fold(!0u32, |crc, &byte| {
+            table[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+        })
+    }
+}
+
+/// The error returned by [`crate::bytes::ESeqByteReader::shift_checked_frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameCheckError {
+    /// There were not enough bytes to read the frame starting at `offset`.
+    Truncated {
+        /// The absolute offset of the start of the frame.
+        offset: u64,
+    },
+    /// The checksum stored in the frame starting at `offset` does not match the checksum computed over its payload.
+    Mismatch {
+        /// The absolute offset of the start of the frame.
+        offset: u64,
+        /// The checksum stored in the frame.
+        expected: u32,
+        /// The checksum computed over the payload.
+        computed: u32,
+    },
+}
+
+impl fmt::Display for FrameCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameCheckError::Truncated { offset } => {
+                write!(f, "truncated frame at offset {}", offset)
+            }
+            FrameCheckError::Mismatch {
+                offset,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "checksum mismatch in frame at offset {}: expected {:#010x}, computed {:#010x}",
+                offset, expected, computed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FrameCheckError {}
@@ -0,0 +1,253 @@
+use std::io::{self, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// The size of the buffer of zeros written to fill gaps, in bytes.
+const ZERO_CHUNK: usize = 4096;
+
+/// A writer which guarantees that every byte it leaves behind is either written explicitly or zero, for producing byte-for-byte
+/// reproducible output.
+///
+/// Seeking past the furthest byte written and writing there leaves no stale or uninitialized gap: the gap is filled with zeros first, even
+/// if the inner writer already held other bytes there, such as a reused buffer. Regions set aside with [`DeterministicWriter::reserve`]
+/// are zeroed as well. Only bytes written through [`Write`] count as written, so [`DeterministicWriter::verify_no_gaps`] reports gaps and
+/// reserved regions which were never filled in.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::deterministic::DeterministicWriter;
+/// use seqbytes::prelude::*;
+/// use std::io::{Cursor, Seek, SeekFrom};
+///
+/// let mut writer = DeterministicWriter::new(Cursor::new(vec![0xEE; 8])).unwrap();
+///
+/// writer.push_slice(b"HD").unwrap();
+/// let len_at = writer.reserve(2).unwrap();
+/// writer.seek(SeekFrom::Start(6)).unwrap();
+/// writer.push_slice(b"!").unwrap();
+///
+/// assert_eq!(writer.verify_no_gaps(), [2..6]);
+///
+/// writer.seek(SeekFrom::Start(len_at)).unwrap();
+/// writer.push_e(7u16, true).unwrap();
+///
+/// assert_eq!(writer.verify_no_gaps(), [4..6]);
+/// assert_eq!(writer.get_ref().get_ref(), b"HD\0\x07\0\0!\xEE");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeterministicWriter<W> {
+    inner: W,
+    start: u64,
+    pos: u64,
+    end: u64,
+    written: Vec<Range<u64>>,
+}
+
+impl<W: Write + Seek> DeterministicWriter<W> {
+    /// Wraps `inner`. Bytes before its current position are not tracked, and the output is taken to start there.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        let start = inner.stream_position()?;
+
+        Ok(Self {
+            inner,
+            start,
+            pos: start,
+            end: start,
+            written: Vec::new(),
+        })
+    }
+    /// Writes `len` zeros at the current position, shifting it past them, and returns the offset of the first. The zeros do not count as
+    /// written, so the region is reported by [`DeterministicWriter::verify_no_gaps`] until it is written over, such as with a length
+    /// which is only known once what follows it has been written.
+    pub fn reserve(&mut self, len: u64) -> io::Result<u64> {
+        self.fill_gap()?;
+
+        let offset = self.pos;
+        write_zeros(&mut self.inner, len)?;
+        self.advance(len);
+
+        Ok(offset)
+    }
+    /// Returns the ranges of offsets between where the output starts and the furthest byte written which were never written explicitly,
+    /// in order. These are zero in the output. Returns an empty [`Vec`] if every byte was written.
+    pub fn verify_no_gaps(&self) -> Vec<Range<u64>> {
+        let mut gaps = Vec::new();
+        let mut next = self.start;
+
+        for range in &self.written {
+            if range.start > next {
+                gaps.push(next..range.start);
+            }
+            next = range.end;
+        }
+        if self.end > next {
+            gaps.push(next..self.end);
+        }
+
+        gaps
+    }
+    /// Fills the gap between the furthest byte written and the current position with zeros, if the position is past it.
+    fn fill_gap(&mut self) -> io::Result<()> {
+        if self.pos <= self.end {
+            return Ok(());
+        }
+
+        self.inner.seek(SeekFrom::Start(self.end))?;
+        write_zeros(&mut self.inner, self.pos - self.end)?;
+        self.end = self.pos;
+
+        Ok(())
+    }
+    fn advance(&mut self, len: u64) {
+        self.pos += len;
+        self.end = self.end.max(self.pos);
+    }
+    fn mark_written(&mut self, range: Range<u64>) {
+        insert_range(&mut self.written, range);
+    }
+}
+
+/// Adds `range` to `ranges`, a sorted list of disjoint ranges, merging it with those it overlaps or touches.
+pub(crate) fn insert_range(ranges: &mut Vec<Range<u64>>, range: Range<u64>) {
+    if range.is_empty() {
+        return;
+    }
+
+    let first = ranges.partition_point(|r| r.end < range.start);
+    let last = ranges.partition_point(|r| r.start <= range.end);
+
+    let overlapping = &ranges[first..last];
+    let merged = match (overlapping.first(), overlapping.last()) {
+        (Some(head), Some(tail)) => head.start.min(range.start)..tail.end.max(range.end),
+        _ => range,
+    };
+    ranges.splice(first..last, [merged]);
+}
+
+impl<W> DeterministicWriter<W> {
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /// Unwraps the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Writes `len` zeros to `writer`.
+fn write_zeros<W: Write>(writer: &mut W, mut len: u64) -> io::Result<()> {
+    let zeros = [0u8; ZERO_CHUNK];
+
+    while len > 0 {
+        let chunk = len.min(ZERO_CHUNK as u64) as usize;
+        writer.write_all(&zeros[..chunk])?;
+        len -= chunk as u64;
+    }
+
+    Ok(())
+}
+
+impl<W: Write + Seek> Write for DeterministicWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.fill_gap()?;
+
+        let written = self.inner.write(buf)?;
+        self.mark_written(self.pos..self.pos + written as u64);
+        self.advance(written as u64);
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + Seek> Seek for DeterministicWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn gap_test() {
+        // A reused buffer still holding the bytes of an earlier, longer output.
+        let mut writer = DeterministicWriter::new(Cursor::new(vec![0xEE; 16])).unwrap();
+
+        writer.push_e(0xCAFEu16, true).unwrap();
+        writer.seek(SeekFrom::Current(6)).unwrap();
+        writer.push_e(1u32, false).unwrap();
+
+        assert_eq!(
+            writer.get_ref().get_ref()[..12],
+            [0xCA, 0xFE, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]
+        );
+        assert_eq!(writer.get_ref().get_ref()[12..], [0xEE; 4]);
+        assert_eq!(writer.verify_no_gaps(), [Range { start: 2, end: 8 }]);
+
+        writer.seek(SeekFrom::Start(64)).unwrap();
+        assert_eq!(writer.verify_no_gaps(), [Range { start: 2, end: 8 }]);
+        writer.push_slice(b"end").unwrap();
+        assert_eq!(writer.get_ref().get_ref().len(), 67);
+        assert!(writer.get_ref().get_ref()[12..64].iter().all(|&b| b == 0));
+        assert_eq!(writer.verify_no_gaps(), [2..8, 12..64]);
+    }
+
+    #[test]
+    fn reserve_test() {
+        let mut writer = DeterministicWriter::new(Cursor::new(Vec::new())).unwrap();
+
+        writer.push_slice(b"CHNK").unwrap();
+        let size_at = writer.reserve(4).unwrap();
+        let crc_at = writer.reserve(4).unwrap();
+        writer.push_slice(&[9; 10]).unwrap();
+        assert_eq!((size_at, crc_at), (4, 8));
+        assert_eq!(writer.verify_no_gaps(), [Range { start: 4, end: 12 }]);
+
+        // Only the size is filled in, and the checksum is forgotten.
+        writer.seek(SeekFrom::Start(size_at)).unwrap();
+        writer.push_e(10u32, true).unwrap();
+        writer.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(writer.verify_no_gaps(), [Range { start: 8, end: 12 }]);
+
+        writer.seek(SeekFrom::Start(crc_at)).unwrap();
+        writer.push_e(0xDEADBEEFu32, true).unwrap();
+        assert!(writer.verify_no_gaps().is_empty());
+        assert_eq!(writer.into_inner().into_inner().len(), 22);
+    }
+
+    #[test]
+    fn fully_written_test() {
+        let mut cursor = Cursor::new(vec![0xEE; 4]);
+        cursor.set_position(4);
+
+        let mut writer = DeterministicWriter::new(cursor).unwrap();
+        for i in 0..100u16 {
+            writer.push_e(i, false).unwrap();
+        }
+        writer.seek(SeekFrom::Start(10)).unwrap();
+        writer.push_slice(&[1, 2, 3]).unwrap();
+
+        assert!(writer.verify_no_gaps().is_empty());
+        assert_eq!(writer.get_ref().get_ref().len(), 204);
+        assert_eq!(writer.get_ref().get_ref()[..4], [0xEE; 4]);
+    }
+
+    #[test]
+    fn mark_written_test() {
+        let mut writer = DeterministicWriter::new(Cursor::new(Vec::new())).unwrap();
+        for range in [10..12, 0..2, 4..6, 20..30, 2..3, 5..11, 30..31] {
+            writer.mark_written(range);
+        }
+
+        assert_eq!(writer.written, [0..3, 4..12, 20..31]);
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::bytes::{SeqByteReader, SeqByteReaderCore, VersionedLen};
+
+/// A parser registered with [`TaggedDispatcher::on`], which reads the body of a record after its tag.
+pub type TagHandler<T> = Box<dyn FnMut(&mut dyn SeqByteReaderCore) -> Option<T>>;
+
+/// What a [`TaggedDispatcher`] does with a record whose tag has no handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnknownTag {
+    /// The record fails with [`DispatchError::Unknown`].
+    Error,
+    /// The tag is followed by the length of the record body in the contained encoding, and the body is skipped.
+    Skip(VersionedLen),
+}
+
+/// The error returned by [`TaggedDispatcher::try_dispatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DispatchError {
+    /// The record at `offset` has a tag without a handler, and unknown tags are errors.
+    Unknown {
+        /// The tag read.
+        tag: u8,
+        /// The absolute offset of the tag.
+        offset: u64,
+    },
+    /// The handler of the record at `offset` returned [`None`], or the body of an unknown record to be skipped was cut short.
+    Rejected {
+        /// The tag read.
+        tag: u8,
+        /// The absolute offset of the tag.
+        offset: u64,
+    },
+}
+
+impl fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DispatchError::Unknown { tag, offset } => {
+                write!(f, "unknown tag {:#04x} at offset {}", tag, offset)
+            }
+            DispatchError::Rejected { tag, offset } => {
+                write!(
+                    f,
+                    "malformed record with tag {:#04x} at offset {}",
+                    tag, offset
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DispatchError {}
+
+/// Reads streams of records which start with a one byte tag, by handing the rest of each record to the parser registered for its tag.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::bytes::VersionedLen;
+/// use seqbytes::dispatch::{TaggedDispatcher, UnknownTag};
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Event {
+///     Key(u8),
+///     Move(i16, i16),
+/// }
+///
+/// let mut dispatcher = TaggedDispatcher::new()
+///     .on(1, Box::new(|r| Some(Event::Key(r.shift()?))))
+///     .on(2, Box::new(|r| Some(Event::Move(r.shift_e(true)?, r.shift_e(true)?))))
+///     .on_unknown(UnknownTag::Skip(VersionedLen::U8));
+///
+/// // The record tagged 9 is unknown, and is skipped by its length.
+/// let mut cursor = Cursor::new(vec![1, b'a', 9, 2, 0xAA, 0xBB, 2, 0, 3, 0xFF, 0xFF]);
+/// let events: Vec<_> = dispatcher.iter(&mut cursor).collect::<Result<_, _>>().unwrap();
+///
+/// assert_eq!(events, [Event::Key(b'a'), Event::Move(3, -1)]);
+/// ```
+pub struct TaggedDispatcher<T> {
+    handlers: HashMap<u8, TagHandler<T>>,
+    unknown: UnknownTag,
+}
+
+impl<T> TaggedDispatcher<T> {
+    /// Creates a dispatcher without any handlers, which treats every tag as an error.
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            unknown: UnknownTag::Error,
+        }
+    }
+    /// Registers `parser` for records tagged `tag`, replacing any parser registered for it before.
+    pub fn on(mut self, tag: u8, parser: TagHandler<T>) -> Self {
+        self.handlers.insert(tag, parser);
+        self
+    }
+    /// Sets what is done with records whose tag has no handler. Defaults to [`UnknownTag::Error`].
+    pub fn on_unknown(mut self, unknown: UnknownTag) -> Self {
+        self.unknown = unknown;
+        self
+    }
+    /// Returns `true` if a parser is registered for `tag`.
+    pub fn handles(&self, tag: u8) -> bool {
+        self.handlers.contains_key(&tag)
+    }
+    /// Reads the next record with a handler, skipping unknown records before it if configured to. Returns `Ok(None)` at the end of the
+    /// stream, or if the position of the reader cannot be read. On failure, and at the end of the stream, the position is restored to where it
+    /// was before the call, so skipped records are only consumed along with the record after them.
+    pub fn try_dispatch(
+        &mut self,
+        reader: &mut dyn SeqByteReaderCore,
+    ) -> Result<Option<T>, DispatchError> {
+        let Some(start) = reader.pos() else {
+            return Ok(None);
+        };
+
+        let result = self.read_record(reader);
+        if !matches!(result, Ok(Some(_))) {
+            reader.set_pos(start);
+        }
+
+        result
+    }
+    /// Reads the next record with a handler like [`TaggedDispatcher::try_dispatch`], returning [`None`] at the end of the stream and on any
+    /// error.
+    pub fn dispatch(&mut self, reader: &mut dyn SeqByteReaderCore) -> Option<T> {
+        self.try_dispatch(reader).ok().flatten()
+    }
+    /// Returns an iterator over the records of `reader`, which ends at the end of the stream and after the first error.
+    pub fn iter<'a>(&'a mut self, reader: &'a mut dyn SeqByteReaderCore) -> Dispatch<'a, T> {
+        Dispatch {
+            dispatcher: self,
+            reader,
+            done: false,
+        }
+    }
+    /// Reads records until one with a handler is read, without restoring the position.
+    fn read_record(
+        &mut self,
+        reader: &mut dyn SeqByteReaderCore,
+    ) -> Result<Option<T>, DispatchError> {
+        loop {
+            let Some(offset) = reader.pos() else {
+                return Ok(None);
+            };
+            // A record is at least its tag, so a missing tag is the end of the stream.
+            let Some(tag) = reader.shift::<u8>() else {
+                return Ok(None);
+            };
+
+            if let Some(handler) = self.handlers.get_mut(&tag) {
+                return match handler(reader) {
+                    Some(value) => Ok(Some(value)),
+                    None => Err(DispatchError::Rejected { tag, offset }),
+                };
+            }
+
+            match self.unknown {
+                UnknownTag::Error => return Err(DispatchError::Unknown { tag, offset }),
+                UnknownTag::Skip(len_field) => len_field
+                    .read(reader)
+                    .and_then(|len| usize::try_from(len).ok())
+                    .and_then(|len| reader.skip_slice(len))
+                    .ok_or(DispatchError::Rejected { tag, offset })?,
+            }
+        }
+    }
+}
+
+impl<T> Default for TaggedDispatcher<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for TaggedDispatcher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tags: Vec<u8> = self.handlers.keys().copied().collect();
+        tags.sort_unstable();
+
+        f.debug_struct("TaggedDispatcher")
+            .field("tags", &tags)
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+/// An iterator over the records of a reader, returned by [`TaggedDispatcher::iter`].
+pub struct Dispatch<'a, T> {
+    dispatcher: &'a mut TaggedDispatcher<T>,
+    reader: &'a mut dyn SeqByteReaderCore,
+    done: bool,
+}
+
+impl<T> Iterator for Dispatch<'_, T> {
+    type Item = Result<T, DispatchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.dispatcher.try_dispatch(self.reader).transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Shape {
+        Point,
+        Circle(u16),
+        Rect(u16, u16),
+    }
+
+    fn dispatcher(unknown: UnknownTag) -> TaggedDispatcher<Shape> {
+        TaggedDispatcher::new()
+            .on(0, Box::new(|_| Some(Shape::Point)))
+            .on(1, Box::new(|r| Some(Shape::Circle(r.shift_e(true)?))))
+            .on(
+                2,
+                Box::new(|r| Some(Shape::Rect(r.shift_e(true)?, r.shift_e(true)?))),
+            )
+            .on_unknown(unknown)
+    }
+
+    #[test]
+    fn dispatch_test() {
+        let mut dispatcher = dispatcher(UnknownTag::Error);
+        let mut cursor = Cursor::new(vec![1, 0, 5, 0, 2, 0, 3, 0, 4, 2, 0]);
+
+        assert!(dispatcher.handles(2) && !dispatcher.handles(3));
+        assert_eq!(dispatcher.dispatch(&mut cursor), Some(Shape::Circle(5)));
+        assert_eq!(dispatcher.dispatch(&mut cursor), Some(Shape::Point));
+        assert_eq!(dispatcher.dispatch(&mut cursor), Some(Shape::Rect(3, 4)));
+
+        // The last rectangle is cut short, so it is left unread.
+        assert_eq!(
+            dispatcher.try_dispatch(&mut cursor),
+            Err(DispatchError::Rejected { tag: 2, offset: 9 })
+        );
+        assert_eq!(cursor.position(), 9);
+
+        cursor.get_mut().extend_from_slice(&[1, 0, 1]);
+        assert_eq!(dispatcher.dispatch(&mut cursor), Some(Shape::Rect(1, 1)));
+        assert_eq!(dispatcher.try_dispatch(&mut cursor), Ok(None));
+    }
+
+    #[test]
+    fn unknown_test() {
+        // A record tagged 7 with a three byte body sits between two known records.
+        let stream = vec![1, 0, 9, 7, 3, 0xAA, 0xBB, 0xCC, 0];
+
+        let mut skipping = dispatcher(UnknownTag::Skip(VersionedLen::U8));
+        let shapes: Result<Vec<_>, _> = skipping.iter(&mut Cursor::new(stream.clone())).collect();
+        assert_eq!(shapes, Ok(vec![Shape::Circle(9), Shape::Point]));
+
+        // An unknown record whose body is cut short cannot be skipped.
+        let mut cursor = Cursor::new(vec![7, 3, 0xAA]);
+        assert_eq!(
+            skipping.try_dispatch(&mut cursor),
+            Err(DispatchError::Rejected { tag: 7, offset: 0 })
+        );
+        assert_eq!(cursor.position(), 0);
+
+        let mut strict = dispatcher(UnknownTag::Error);
+        let mut cursor = Cursor::new(stream);
+        let shapes: Vec<_> = strict.iter(&mut cursor).collect();
+        assert_eq!(
+            shapes,
+            [
+                Ok(Shape::Circle(9)),
+                Err(DispatchError::Unknown { tag: 7, offset: 3 })
+            ]
+        );
+        assert_eq!(cursor.position(), 3);
+    }
+}
@@ -0,0 +1,521 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+use super::bytes::{SeqByteReader, SeqByteWriter};
+use super::traits::SizedNumber;
+
+/// The default number of bytes of records sorted in memory at once.
+const DEFAULT_CHUNK_BYTES: usize = 64 << 20;
+
+/// Distinguishes the runs of sorts running at the same time in one process.
+static NEXT_SORT: AtomicU64 = AtomicU64::new(0);
+
+/// A step of an [`ExternalSort`], passed to its progress callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortProgress {
+    /// A chunk of records was sorted and spilled to a temporary file.
+    Spilled {
+        /// The index of the run, counting from zero.
+        run: usize,
+        /// The number of records in the run.
+        records: u64,
+    },
+    /// Sorted records were written to the destination. Reported once per chunk of records, and once more when every record is written.
+    Merged {
+        /// The number of records written so far.
+        records: u64,
+        /// The number of records being sorted.
+        total: u64,
+    },
+}
+
+/// Sorts a source of fixed-size records which may not fit in memory, for sorting large files by a key field.
+///
+/// Records are read in chunks of at most [`ExternalSort::chunk_bytes`] bytes, each sorted in memory and spilled as a run to a temporary
+/// file in [`ExternalSort::temp_dir`]. The runs are then merged into the destination. A source which fits in one chunk is sorted in memory
+/// without any temporary files. The sort is stable, so records with equal keys keep the order they had in the source, and the temporary
+/// files are removed whether or not the sort succeeds.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::extsort::ExternalSort;
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut source = Cursor::new(vec![3, 0, 1, 0, 2, 0, 1, 1]);
+/// let mut sorted = Vec::new();
+///
+/// let mut steps = 0;
+/// let count = ExternalSort::new()
+///     .chunk_bytes(4)
+///     .on_progress(|_| steps += 1)
+///     .sort(&mut source, &mut sorted, |record: &[u8; 2]| record[0])
+///     .unwrap();
+///
+/// assert_eq!(count, 4);
+/// // Two runs of two records are spilled, and the merge reports after every two records.
+/// assert_eq!(steps, 4);
+/// assert_eq!(sorted, [1, 0, 1, 1, 2, 0, 3, 0]);
+/// ```
+pub struct ExternalSort<P = fn(SortProgress)> {
+    chunk_bytes: usize,
+    temp_dir: PathBuf,
+    progress: P,
+}
+
+impl ExternalSort {
+    /// Creates a sort with chunks of 64 MiB, spilling to [`std::env::temp_dir`] and without a progress callback.
+    pub fn new() -> Self {
+        Self {
+            chunk_bytes: DEFAULT_CHUNK_BYTES,
+            temp_dir: std::env::temp_dir(),
+            progress: |_| {},
+        }
+    }
+}
+
+impl Default for ExternalSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: FnMut(SortProgress)> ExternalSort<P> {
+    /// Sets the number of bytes of records sorted in memory at once. A chunk always holds at least one record.
+    pub fn chunk_bytes(mut self, chunk_bytes: usize) -> Self {
+        self.chunk_bytes = chunk_bytes;
+        self
+    }
+    /// Sets the directory the sorted runs are spilled to.
+    pub fn temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+    /// Sets the callback which is called with each [`SortProgress`].
+    pub fn on_progress<Q: FnMut(SortProgress)>(self, progress: Q) -> ExternalSort<Q> {
+        ExternalSort {
+            chunk_bytes: self.chunk_bytes,
+            temp_dir: self.temp_dir,
+            progress,
+        }
+    }
+    /// Reads every record of type `U` from `reader` until its end, and writes them to `writer` in the order of the keys returned by `key`.
+    /// Returns the number of records written.
+    ///
+    /// Returns an error of kind [`io::ErrorKind::UnexpectedEof`] if the source ends in a partial record or holds bytes which are not a
+    /// valid `U`, and of kind [`io::ErrorKind::Other`] if a record could not be written. Errors of the temporary files are returned as they
+    /// are, and a run which ends before all of its records are merged, such as one truncated by another process, fails the sort with an
+    /// error of kind [`io::ErrorKind::UnexpectedEof`] rather than dropping its records.
+    pub fn sort<U, K, R, W, F>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+        mut key: F,
+    ) -> io::Result<u64>
+    where
+        U: SizedNumber,
+        K: Ord,
+        R: SeqByteReader + ?Sized,
+        W: SeqByteWriter + ?Sized,
+        F: FnMut(&U) -> K,
+    {
+        let per_chunk = (self.chunk_bytes / U::SIZE.max(1)).max(1);
+        let mut runs = Runs {
+            paths: Vec::new(),
+            lens: Vec::new(),
+            sort: NEXT_SORT.fetch_add(1, AtomicOrdering::Relaxed),
+        };
+        let mut total = 0;
+
+        loop {
+            let mut chunk = read_chunk::<U, R>(reader, per_chunk)?;
+            if chunk.is_empty() {
+                break;
+            }
+
+            total += chunk.len() as u64;
+            chunk.sort_by_cached_key(&mut key);
+
+            // Everything fit in the first chunk, so it is written out directly.
+            if runs.paths.is_empty() && reader.available(1) != Some(true) {
+                for record in chunk {
+                    writer.push(record).ok_or_else(write_error)?;
+                }
+                (self.progress)(SortProgress::Merged {
+                    records: total,
+                    total,
+                });
+
+                return Ok(total);
+            }
+
+            let records = chunk.len() as u64;
+            runs.spill(&self.temp_dir, chunk)?;
+            (self.progress)(SortProgress::Spilled {
+                run: runs.paths.len() - 1,
+                records,
+            });
+        }
+
+        let mut left = runs.lens.clone();
+        let mut sources = Vec::with_capacity(runs.paths.len());
+        let mut heads = BinaryHeap::with_capacity(runs.paths.len());
+        for (run, path) in runs.paths.iter().enumerate() {
+            let mut source = BufReader::new(File::open(path)?);
+            if let Some(record) = shift_run::<U, _>(&mut source, run, &mut left[run])? {
+                heads.push(Head {
+                    key: key(&record),
+                    run,
+                    record,
+                });
+            }
+            sources.push(source);
+        }
+
+        let mut merged = 0;
+        while let Some(Head { run, record, .. }) = heads.pop() {
+            writer.push(record).ok_or_else(write_error)?;
+            merged += 1;
+
+            if let Some(record) = shift_run::<U, _>(&mut sources[run], run, &mut left[run])? {
+                heads.push(Head {
+                    key: key(&record),
+                    run,
+                    record,
+                });
+            }
+            if merged == total || merged.is_multiple_of(per_chunk as u64) {
+                (self.progress)(SortProgress::Merged {
+                    records: merged,
+                    total,
+                });
+            }
+        }
+
+        if merged != total {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("merged {} of {} records", merged, total),
+            ));
+        }
+
+        Ok(merged)
+    }
+}
+
+/// Sorts the records of type `U` in `reader` by the keys returned by `key` into `writer`, with the defaults of [`ExternalSort::new`].
+/// Returns the number of records written.
+pub fn external_sort<U, K, R, W, F>(reader: &mut R, writer: &mut W, key: F) -> io::Result<u64>
+where
+    U: SizedNumber,
+    K: Ord,
+    R: SeqByteReader + ?Sized,
+    W: SeqByteWriter + ?Sized,
+    F: FnMut(&U) -> K,
+{
+    ExternalSort::new().sort(reader, writer, key)
+}
+
+/// Reads up to `count` records, fewer only at the end of the source.
+fn read_chunk<U: SizedNumber, R: SeqByteReader + ?Sized>(
+    reader: &mut R,
+    count: usize,
+) -> io::Result<Vec<U>> {
+    if let Some(chunk) = reader.shift_vec(count) {
+        return Ok(chunk);
+    }
+
+    // Fewer than `count` records are left, so the rest are read one at a time.
+    let mut chunk = Vec::new();
+    while let Some(record) = reader.shift() {
+        chunk.push(record);
+    }
+    if reader.available(1) == Some(true) {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the source does not end in a whole record",
+        ));
+    }
+
+    Ok(chunk)
+}
+
+/// Reads the next record of the run at index `run`, which has `left` records left. Returns an error of kind
+/// [`io::ErrorKind::UnexpectedEof`] if the run ends before them.
+fn shift_run<U: SizedNumber, R: SeqByteReader + ?Sized>(
+    source: &mut R,
+    run: usize,
+    left: &mut u64,
+) -> io::Result<Option<U>> {
+    if *left == 0 {
+        return Ok(None);
+    }
+
+    let record = source.shift().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("run {} ends {} records early", run, left),
+        )
+    })?;
+    *left -= 1;
+
+    Ok(Some(record))
+}
+
+fn write_error() -> io::Error {
+    io::Error::other("could not write a sorted record")
+}
+
+/// The temporary files of the runs of one sort, removed when it ends.
+struct Runs {
+    paths: Vec<PathBuf>,
+    /// The number of records in each run.
+    lens: Vec<u64>,
+    sort: u64,
+}
+
+impl Runs {
+    /// Writes `chunk` to a new temporary file in `dir`.
+    fn spill<U: SizedNumber>(&mut self, dir: &std::path::Path, chunk: Vec<U>) -> io::Result<()> {
+        let path = dir.join(format!(
+            "seqbytes-extsort-{}-{}-{}",
+            std::process::id(),
+            self.sort,
+            self.paths.len()
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        self.paths.push(path);
+        self.lens.push(chunk.len() as u64);
+
+        let mut file = BufWriter::new(file);
+        for record in chunk {
+            file.push(record).ok_or_else(write_error)?;
+        }
+
+        file.flush()
+    }
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// The next record of a run, ordered so that a [`BinaryHeap`] pops the lowest key first, and of equal keys the one from the earliest run.
+struct Head<K, U> {
+    key: K,
+    run: usize,
+    record: U,
+}
+
+impl<K: Ord, U> Ord for Head<K, U> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&other.key, other.run).cmp(&(&self.key, self.run))
+    }
+}
+
+impl<K: Ord, U> PartialOrd for Head<K, U> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, U> PartialEq for Head<K, U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, U> Eq for Head<K, U> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::path::Path;
+
+    const RECORDS: u32 = 4000;
+
+    /// A record of a big endian `u32` key followed by the index of the record in the source.
+    type Record = [u8; 12];
+
+    fn record_key(record: &Record) -> u32 {
+        u32::from_be_bytes([record[0], record[1], record[2], record[3]])
+    }
+
+    /// Returns records with pseudo-random keys, many of them equal, in a buffer of 48000 bytes.
+    fn records() -> Vec<Record> {
+        let mut state = 0x2545_F491u32;
+        (0..RECORDS)
+            .map(|i| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+
+                let mut record = [0; 12];
+                record[..4].copy_from_slice(&(state >> 22).to_be_bytes());
+                record[4..].copy_from_slice(&(i as u64).to_le_bytes());
+                record
+            })
+            .collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("seqbytes-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn is_empty_dir(dir: &Path) -> bool {
+        fs::read_dir(dir).unwrap().next().is_none()
+    }
+
+    #[test]
+    fn sort_test() {
+        let records = records();
+        let dir = temp_dir("extsort-sort");
+
+        let mut source = Cursor::new(records.concat());
+        let mut sorted = Cursor::new(Vec::new());
+        let mut progress = Vec::new();
+
+        // Chunks of 83 records, so the 4000 records spill into 49 runs.
+        let count = ExternalSort::new()
+            .chunk_bytes(1000)
+            .temp_dir(&dir)
+            .on_progress(|step| progress.push(step))
+            .sort(&mut source, &mut sorted, record_key)
+            .unwrap();
+
+        assert_eq!(count, RECORDS as u64);
+        assert!(is_empty_dir(&dir));
+
+        let spilled: Vec<_> = progress
+            .iter()
+            .filter_map(|step| match step {
+                SortProgress::Spilled { run, records } => Some((*run, *records)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(spilled.len(), 49);
+        assert!(spilled.iter().enumerate().all(|(i, &(run, _))| run == i));
+        assert_eq!(
+            spilled.iter().map(|(_, records)| records).sum::<u64>(),
+            4000
+        );
+        assert_eq!(
+            progress.last(),
+            Some(&SortProgress::Merged {
+                records: 4000,
+                total: 4000
+            })
+        );
+
+        // Every record comes out whole, in the order of a stable sort in memory.
+        let mut expected = records;
+        expected.sort_by_key(record_key);
+        sorted.set_position(0);
+        assert_eq!(
+            sorted.shift_vec::<Record>(RECORDS as usize).unwrap(),
+            expected
+        );
+        assert_eq!(sorted.available(1), Some(false));
+
+        fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn in_memory_test() {
+        let mut source = Cursor::new(records()[..50].concat());
+        let mut sorted = Vec::new();
+
+        // The directory does not exist, which only matters if anything is spilled.
+        let count = ExternalSort::new()
+            .temp_dir("/nonexistent/seqbytes")
+            .sort(&mut source, &mut sorted, |record: &Record| {
+                std::cmp::Reverse(record_key(record))
+            })
+            .unwrap();
+
+        assert_eq!(count, 50);
+        let keys: Vec<u32> = sorted
+            .chunks(12)
+            .map(|record| record_key(record.try_into().unwrap()))
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        assert_eq!(
+            external_sort(&mut Cursor::new(Vec::new()), &mut sorted, record_key).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn partial_record_test() {
+        let dir = temp_dir("extsort-partial");
+
+        let mut bytes = records().concat();
+        bytes.extend_from_slice(&[1, 2, 3]);
+        let mut sorted = Vec::new();
+
+        let error = ExternalSort::new()
+            .chunk_bytes(1000)
+            .temp_dir(&dir)
+            .sort(&mut Cursor::new(bytes), &mut sorted, record_key)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(sorted.is_empty());
+        assert!(is_empty_dir(&dir));
+
+        fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_run_test() {
+        let dir = temp_dir("extsort-truncated");
+        let mut source = Cursor::new(records()[..300].concat());
+
+        // Once the second run is spilled, the first is cut off partway through its fifth record.
+        let error = ExternalSort::new()
+            .chunk_bytes(1200)
+            .temp_dir(&dir)
+            .on_progress(|step| {
+                if step
+                    == (SortProgress::Spilled {
+                        run: 1,
+                        records: 100,
+                    })
+                {
+                    let first = fs::read_dir(&dir)
+                        .unwrap()
+                        .map(|entry| entry.unwrap().path())
+                        .find(|path| path.to_string_lossy().ends_with("-0"))
+                        .unwrap();
+                    OpenOptions::new()
+                        .write(true)
+                        .open(first)
+                        .unwrap()
+                        .set_len(4 * 12 + 6)
+                        .unwrap();
+                }
+            })
+            .sort(&mut source, &mut Vec::new(), record_key)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(error.to_string(), "run 0 ends 96 records early");
+        assert!(is_empty_dir(&dir));
+
+        fs::remove_dir(dir).unwrap();
+    }
+}
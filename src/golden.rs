@@ -0,0 +1,268 @@
+use std::fmt::Debug;
+use std::io::Cursor;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::prelude::*;
+use crate::schema::{Count, FieldType, Schema, Value};
+use crate::traits::{I48, U24};
+
+/// Parses the hexadecimal bytes of a fixture.
+fn parse_fixture(text: &str) -> Vec<u8> {
+    let digits: String = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.chars().filter(|c| !c.is_whitespace()))
+        .collect();
+    assert!(
+        digits.len().is_multiple_of(2),
+        "odd number of hex digits in fixture"
+    );
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("invalid hex digit in fixture"))
+        .collect()
+}
+
+/// Formats bytes as a fixture, sixteen to a line.
+fn format_fixture(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .map(|line| {
+            let hex: Vec<String> = line.iter().map(|b| format!("{:02x}", b)).collect();
+            hex.join(" ") + "\n"
+        })
+        .collect()
+}
+
+/// Checks the encoding of `value` against the fixture `name`: `write` must produce exactly the bytes of the fixture, and `read` must
+/// return `value` from them and consume every byte.
+fn golden<T: PartialEq + Debug>(
+    name: &str,
+    value: T,
+    write: impl FnOnce(&mut Cursor<Vec<u8>>, &T) -> Option<()>,
+    read: impl FnOnce(&mut Cursor<Vec<u8>>) -> Option<T>,
+) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "fixtures", "golden", name]
+        .iter()
+        .collect::<PathBuf>()
+        .with_extension("hex");
+
+    let mut writer = Cursor::new(Vec::new());
+    write(&mut writer, &value).unwrap_or_else(|| panic!("writing {} failed", name));
+    let written = writer.into_inner();
+
+    if std::env::var_os("SEQBYTES_BLESS").is_some() {
+        std::fs::write(&path, format_fixture(&written)).unwrap();
+    }
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "missing fixture {} ({}); run with SEQBYTES_BLESS=1 to create it",
+            path.display(),
+            e
+        )
+    });
+    let fixture = parse_fixture(&text);
+
+    assert_eq!(
+        written, fixture,
+        "the writer changed the format of {}",
+        name
+    );
+
+    let len = fixture.len() as u64;
+    let mut reader = Cursor::new(fixture);
+    assert_eq!(
+        read(&mut reader).as_ref(),
+        Some(&value),
+        "the reader changed the format of {}",
+        name
+    );
+    assert_eq!(reader.position(), len, "{} left bytes unread", name);
+}
+
+#[test]
+fn option_test() {
+    let values = [NonZeroU32::new(0x0102_0304), None];
+    golden(
+        "option",
+        values,
+        |w, v| w.push_e(*v, true),
+        |r| r.shift_e(true),
+    );
+}
+
+#[test]
+fn presence_test() {
+    let values = [Some(0x0102u16), None];
+    golden(
+        "presence",
+        values,
+        |w, v| {
+            v.iter().try_for_each(|value| match value {
+                Some(value) => w.push_e((true, *value), true),
+                None => w.push(false),
+            })
+        },
+        |r| Some([r.shift_optional_e(true)?, r.shift_optional_e(true)?]),
+    );
+}
+
+#[test]
+fn counted_test() {
+    let values = vec![1u32, 0xDEAD_BEEF];
+    golden(
+        "counted",
+        values,
+        |w, v| w.push_counted::<u16, u32>(v, true),
+        |r| r.shift_counted::<u16, u32>(true),
+    );
+}
+
+#[test]
+fn vlq_test() {
+    let values = vec![0u32, 0x7F, 0x80, 0x3FFF, 0x0FFF_FFFF];
+    golden(
+        "vlq",
+        values,
+        |w, v| v.iter().try_for_each(|&value| w.push_vlq(value)),
+        |r| (0..5).map(|_| r.shift_vlq()).collect(),
+    );
+}
+
+#[test]
+fn varint_delta_test() {
+    let values = vec![100u64, 101, 300, 70_000];
+    golden(
+        "varint_delta",
+        values,
+        |w, v| w.push_varint_delta_encoded(v, 90),
+        |r| r.shift_varint_delta_decoded(4, 90).ok(),
+    );
+}
+
+#[test]
+fn delta_test() {
+    let values = vec![1000u64, 1001, 1300];
+    golden(
+        "delta",
+        values,
+        |w, v| w.push_delta_encoded::<u16>(v, 1000, true),
+        |r| r.shift_delta_decoded::<u16>(3, 1000, true).ok(),
+    );
+}
+
+#[test]
+fn bitvec_test() {
+    let bits = vec![
+        true, false, true, true, false, false, false, false, true, true, false,
+    ];
+    golden(
+        "bitvec",
+        bits,
+        |w, v| w.push_bitvec(v, BitOrder::MsbFirst),
+        |r| r.shift_bitvec(11, BitOrder::MsbFirst),
+    );
+}
+
+#[test]
+fn versioned_record_test() {
+    let len_field = VersionedLen::U16(Endianness::Big);
+    golden(
+        "versioned_record",
+        (7u8, 0x0102u16),
+        |w, v| w.push_versioned_record(len_field, |r| r.push_slice(&v.to_bytes_e(true))),
+        |r| r.shift_versioned_record(len_field, |f| f.shift_e(true)),
+    );
+}
+
+#[test]
+fn strings_test() {
+    let schema = Schema::new()
+        .field("pascal", FieldType::PrefixedStr(Box::new(FieldType::U8)))
+        .field(
+            "wide",
+            FieldType::PrefixedStr(Box::new(FieldType::U16(Endianness::Big))),
+        )
+        .field("fixed", FieldType::FixedStr(8))
+        .field("raw", FieldType::Bytes(Count::Fixed(2)));
+    let value = Value::Record(vec![
+        ("pascal".into(), Value::Str("seq".into())),
+        ("wide".into(), Value::Str("bytes".into())),
+        ("fixed".into(), Value::Str("pad".into())),
+        ("raw".into(), Value::Bytes(vec![0xCA, 0xFE])),
+    ]);
+    golden(
+        "strings",
+        value,
+        |w, v| schema.write(v, w),
+        |r| schema.parse(r),
+    );
+}
+
+#[test]
+fn composite_test() {
+    let value = ((true, 'é', U24::MAX), [-2i16, 3], I48::MIN);
+    golden(
+        "composite",
+        value,
+        |w, v| w.push_e(*v, true),
+        |r| r.shift_e(true),
+    );
+}
+
+#[test]
+fn time_test() {
+    let value = (
+        Duration::new(90, 500),
+        UNIX_EPOCH - Duration::from_millis(1500),
+    );
+    golden(
+        "time",
+        value,
+        |w, v| w.push_e(*v, true),
+        |r| r.shift_e(true),
+    );
+}
+
+#[test]
+fn ip_addr_test() {
+    let value = (Ipv6Addr::LOCALHOST, Ipv4Addr::new(10, 0, 0, 7));
+    golden(
+        "ip_addr",
+        value,
+        |w, v| w.push_e(*v, true),
+        |r| r.shift_e(true),
+    );
+}
+
+#[test]
+fn socket_addr_test() {
+    let value = (
+        SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 7), 8080),
+        SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0),
+    );
+    golden(
+        "socket_addr",
+        value,
+        |w, v| w.push_e(*v, true),
+        |r| r.shift_e(true),
+    );
+}
+
+#[test]
+#[cfg(feature = "crc")]
+fn checked_frame_test() {
+    use crate::crc::CrcAlgo;
+
+    let payload = b"frame".to_vec();
+    golden(
+        "checked_frame",
+        payload,
+        |w, v| w.push_checked_frame(v, true, CrcAlgo::Crc32),
+        |r| r.shift_checked_frame(true, CrcAlgo::Crc32).ok(),
+    );
+}
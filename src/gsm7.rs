@@ -0,0 +1,70 @@
+/// The GSM 03.38 basic character set, indexed by septet value. The escape septet `0x1B` is mapped to a space, as it is only
+/// meaningful as a prefix into the extension table.
+const BASIC: [char; 128] = [
+    '@', '£', '$', '¥', 'è', 'é', 'ù', 'ì', 'ò', 'Ç', '\n', 'Ø', 'ø', '\r', 'Å', 'å', //
+    'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', ' ', 'Æ', 'æ', 'ß', 'É', //
+    ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', //
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?', //
+    '¡', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', //
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§', //
+    '¿', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', //
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à', //
+];
+
+/// The escape septet, which selects a character from the extension table for the septet following it.
+const ESCAPE: u8 = 0x1B;
+
+/// Maps a septet following [`ESCAPE`] to its character in the GSM 03.38 extension table.
+fn extension(septet: u8) -> Option<char> {
+    Some(match septet {
+        0x0A => '\u{0C}',
+        0x14 => '^',
+        0x28 => '{',
+        0x29 => '}',
+        0x2F => '\\',
+        0x3C => '[',
+        0x3D => '~',
+        0x3E => ']',
+        0x40 => '|',
+        0x65 => '€',
+        _ => return None,
+    })
+}
+
+/// Unpacks `count` septets from `bytes`, where septet `i` occupies bits `7 * i` to `7 * i + 6` counting from the least significant bit
+/// of the first byte. `bytes` must hold at least `ceil(count * 7 / 8)` bytes.
+pub(crate) fn unpack(bytes: &[u8], count: usize) -> Vec<u8> {
+    (0..count)
+        .map(|i| {
+            let bit = i * 7;
+            let (index, shift) = (bit / 8, bit % 8);
+
+            let mut septet = bytes[index] >> shift;
+            if shift > 1 {
+                septet |= bytes[index + 1] << (8 - shift);
+            }
+
+            septet & 0x7F
+        })
+        .collect()
+}
+
+/// Maps septets to characters through the basic character set, resolving escape sequences through the extension table. Per GSM 03.38, an
+/// escape followed by a septet without an extension character decodes as the basic character of that septet.
+pub(crate) fn decode(septets: &[u8]) -> String {
+    let mut s = String::with_capacity(septets.len());
+    let mut iter = septets.iter();
+
+    while let Some(&septet) = iter.next() {
+        if septet == ESCAPE {
+            if let Some(&next) = iter.next() {
+                s.push(extension(next).unwrap_or(BASIC[next as usize]));
+                continue;
+            }
+        }
+
+        s.push(BASIC[septet as usize]);
+    }
+
+    s
+}
@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::bytes::{SeqByteReader, SeqByteReaderCore};
+
+/// A store of strings handed out as shared [`Arc<str>`]s, so that identical strings share one allocation.
+pub trait StringInterner {
+    /// Returns the shared copy of `s`, storing it first if there is none. The flag is `true` if `s` was already stored.
+    fn intern(&mut self, s: &str) -> (Arc<str>, bool);
+    /// Returns the number of distinct strings stored.
+    fn len(&self) -> usize;
+    /// Returns `true` if no strings are stored.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The default [`StringInterner`], keeping every string in a [`HashSet`] for as long as it lives.
+#[derive(Debug, Clone, Default)]
+pub struct HashSetInterner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl HashSetInterner {
+    /// Creates an interner without any strings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StringInterner for HashSetInterner {
+    fn intern(&mut self, s: &str) -> (Arc<str>, bool) {
+        if let Some(shared) = self.strings.get(s) {
+            return (shared.clone(), true);
+        }
+
+        let shared: Arc<str> = Arc::from(s);
+        self.strings.insert(shared.clone());
+        (shared, false)
+    }
+
+    fn len(&self) -> usize {
+        self.strings.len()
+    }
+}
+
+/// The counters collected by an [`InterningReader`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct InternStats {
+    /// The number of strings read which were already interned.
+    pub hits: u64,
+    /// The number of strings read which were interned for the first time.
+    pub misses: u64,
+    /// The number of distinct strings held by the interner.
+    pub unique: usize,
+}
+
+/// A reader which interns the strings read through it, for parsing symbol tables and other sources with many duplicate strings without an
+/// allocation for each of them. Every other call is delegated to the inner reader unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::intern::InterningReader;
+/// use std::io::Cursor;
+/// use std::sync::Arc;
+///
+/// let mut reader = InterningReader::new(Cursor::new(b"mainmain\0".to_vec()));
+///
+/// let first = reader.shift_string_interned(4).unwrap();
+/// let second = reader.shift_cstr_interned().unwrap();
+///
+/// assert_eq!(&*second, "main");
+/// assert!(Arc::ptr_eq(&first, &second));
+/// assert_eq!(reader.stats().hits, 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InterningReader<R, I = HashSetInterner> {
+    inner: R,
+    interner: I,
+    hits: u64,
+    misses: u64,
+}
+
+impl<R> InterningReader<R> {
+    /// Wraps `inner` with an empty [`HashSetInterner`].
+    pub fn new(inner: R) -> Self {
+        Self::with_interner(inner, HashSetInterner::new())
+    }
+}
+
+impl<R, I: StringInterner> InterningReader<R, I> {
+    /// Wraps `inner` with `interner`, which may already hold strings, such as those shared with another reader.
+    pub fn with_interner(inner: R, interner: I) -> Self {
+        Self {
+            inner,
+            interner,
+            hits: 0,
+            misses: 0,
+        }
+    }
+    /// Returns the counters collected so far.
+    pub fn stats(&self) -> InternStats {
+        InternStats {
+            hits: self.hits,
+            misses: self.misses,
+            unique: self.interner.len(),
+        }
+    }
+    /// Returns a reference to the interner.
+    pub fn interner(&self) -> &I {
+        &self.interner
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// Unwraps the inner reader and the interner.
+    pub fn into_parts(self) -> (R, I) {
+        (self.inner, self.interner)
+    }
+    fn intern(&mut self, bytes: &[u8]) -> Arc<str> {
+        let (shared, hit) = self.interner.intern(&String::from_utf8_lossy(bytes));
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+
+        shared
+    }
+}
+
+impl<R: SeqByteReaderCore, I: StringInterner> InterningReader<R, I> {
+    /// Reads the next `amount` bytes as a lossy UTF-8 string, shifting the position by `amount` bytes, and returns its interned copy. Returns
+    /// [`None`] if there are not enough bytes to be read.
+    pub fn shift_string_interned(&mut self, amount: usize) -> Option<Arc<str>> {
+        let bytes = self.inner.shift_slice(amount)?;
+
+        Some(self.intern(&bytes))
+    }
+    /// Reads a string terminated by a NUL byte as a lossy UTF-8 string, shifting the position past the terminator, and returns its interned
+    /// copy without the terminator. Returns [`None`] if there is no terminator, in which case the position is restored.
+    pub fn shift_cstr_interned(&mut self) -> Option<Arc<str>> {
+        let start = self.inner.pos()?;
+
+        let len = self.inner.skip_until(&[0], false)?;
+        self.inner.set_pos(start)?;
+
+        let bytes = self.inner.shift_slice(len as usize)?;
+        self.inner.set_pos(start + len + 1)?;
+
+        Some(self.intern(&bytes))
+    }
+}
+
+impl<R: SeqByteReaderCore, I> SeqByteReaderCore for InterningReader<R, I> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.inner.fill(buf)
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.inner.peek_fill(buf)
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        self.inner.pos()
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        self.inner.set_pos(pos)
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        self.inner.end_pos()
+    }
+
+    fn try_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.inner.try_fill(buf)
+    }
+
+    fn try_peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.inner.try_peek_fill(buf)
+    }
+
+    fn step_back(&mut self, amount: u64) -> Option<()> {
+        self.inner.step_back(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    /// A symbol table of a `u8` count, then for each entry a `u8` length, the name and a little endian `u32` address.
+    fn symbol_table(names: &[&str]) -> Vec<u8> {
+        let mut table = vec![names.len() as u8];
+        for (i, name) in names.iter().enumerate() {
+            table.push(name.len() as u8);
+            table.extend_from_slice(name.as_bytes());
+            table.push_e(i as u32 * 0x10, false).unwrap();
+        }
+
+        table
+    }
+
+    #[test]
+    fn interning_test() {
+        let names = ["main", "printf", "main", "malloc", "printf", "main", "free"];
+        let mut reader = InterningReader::new(Cursor::new(symbol_table(&names)));
+
+        let count = reader.shift::<u8>().unwrap();
+        let mut symbols = Vec::new();
+        for _ in 0..count {
+            let len = reader.shift::<u8>().unwrap();
+            let name = reader.shift_string_interned(len as usize).unwrap();
+            let address = reader.shift_e::<u32>(false).unwrap();
+
+            symbols.push((name, address));
+        }
+
+        assert_eq!(symbols.len(), names.len());
+        for (symbol, name) in symbols.iter().zip(names) {
+            assert_eq!(&*symbol.0, name);
+        }
+        assert!(Arc::ptr_eq(&symbols[0].0, &symbols[2].0));
+        assert!(Arc::ptr_eq(&symbols[0].0, &symbols[5].0));
+        assert!(Arc::ptr_eq(&symbols[1].0, &symbols[4].0));
+        assert!(!Arc::ptr_eq(&symbols[0].0, &symbols[1].0));
+        assert_eq!(symbols[6].1, 0x60);
+        assert_eq!(
+            reader.stats(),
+            InternStats {
+                hits: 3,
+                misses: 4,
+                unique: 4
+            }
+        );
+        assert_eq!(reader.shift_string_interned(1), None);
+    }
+
+    #[test]
+    fn cstr_test() {
+        let mut interner = HashSetInterner::new();
+        let (shared, _) = interner.intern(".text");
+
+        let mut reader =
+            InterningReader::with_interner(Cursor::new(b".text\0.data\0\0.te".to_vec()), interner);
+        assert!(Arc::ptr_eq(&reader.shift_cstr_interned().unwrap(), &shared));
+        assert_eq!(&*reader.shift_cstr_interned().unwrap(), ".data");
+        assert_eq!(&*reader.shift_cstr_interned().unwrap(), "");
+        assert_eq!(reader.pos(), Some(13));
+
+        assert_eq!(reader.shift_cstr_interned(), None);
+        assert_eq!(reader.pos(), Some(13));
+        assert_eq!(
+            reader.stats(),
+            InternStats {
+                hits: 1,
+                misses: 2,
+                unique: 3
+            }
+        );
+    }
+}
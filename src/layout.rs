@@ -0,0 +1,1306 @@
+use std::any::TypeId;
+use std::fmt;
+
+use super::bytes::{
+    ESeqByteReader, ESeqByteWriter, ExpectError, SeqByteReader, SeqByteReaderCore, SeqByteWriter,
+};
+use super::traits::{EndianNumber, Endianness};
+
+/// Calls the macro `$m` with the built-in numbers, so that every match over them covers the same types.
+macro_rules! with_primitives {
+    ($m:ident) => {
+        $m!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64, bool, char)
+    };
+}
+
+/// Returns the tag of `U` if it is a built-in number, which is its name.
+fn builtin_tag<U: 'static>() -> Option<&'static str> {
+    macro_rules! tags {
+        ($($t:ty),*) => {$(
+            if TypeId::of::<U>() == TypeId::of::<$t>() {
+                return Some(stringify!($t));
+            }
+        )*};
+    }
+
+    with_primitives!(tags);
+    None
+}
+
+/// The description of a single field of a [`RecordLayout`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldDesc {
+    /// The name of the field. Fields of nested records are named `outer.inner`.
+    pub name: String,
+    /// The offset of the field from the start of the record, in bytes.
+    pub offset: usize,
+    /// The size of the field, in bytes.
+    pub size: usize,
+    /// The name of the type of the field, as returned by [`std::any::type_name`], or `"bytes"` for byte arrays.
+    pub type_name: &'static str,
+    /// The stable tag of the type of the field which [`RecordLayout::layout_id`] hashes: the name of a built-in number, `"bytes"` for
+    /// byte arrays, the tag passed to [`RecordLayout::tagged_field`], or [`None`] for other types.
+    pub tag: Option<&'static str>,
+    /// The byte order of the field, or [`None`] for byte arrays, whose bytes are not reordered.
+    pub endianness: Option<Endianness>,
+}
+
+/// A runtime description of the fields of a fixed-size record, in order and without padding between them, for tooling such as hex viewers
+/// which annotate fields or tools which compare records field by field.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::layout::RecordLayout;
+/// use seqbytes::traits::Endianness;
+///
+/// let point = RecordLayout::new()
+///     .field::<f32>("x", Endianness::Little)
+///     .field::<f32>("y", Endianness::Little);
+///
+/// let line = RecordLayout::new()
+///     .field::<u16>("id", Endianness::Big)
+///     .nested("from", &point)
+///     .nested("to", &point);
+///
+/// assert_eq!(line.size(), 18);
+/// assert_eq!(line.get("to.y").unwrap().offset, 14);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RecordLayout {
+    fields: Vec<FieldDesc>,
+    size: usize,
+}
+
+impl RecordLayout {
+    /// Creates a layout without any fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Appends a field of type `U` with the specified endianness. Built-in numbers are tagged with their name, and other types are left
+    /// untagged, so [`RecordLayout::layout_id`] only tells them apart by size. Use [`RecordLayout::tagged_field`] to tag them.
+    pub fn field<U: EndianNumber + 'static>(self, name: &str, endianness: Endianness) -> Self {
+        self.push(
+            name.to_string(),
+            U::SIZE,
+            std::any::type_name::<U>(),
+            builtin_tag::<U>(),
+            Some(endianness),
+        )
+    }
+    /// Appends a field of type `U` with the specified endianness, tagged with `tag` for [`RecordLayout::layout_id`], such as the name of a
+    /// newtype. The tag is stored in place of the name of the type, so it should stay the same for as long as the files do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::layout::RecordLayout;
+    /// use seqbytes::traits::Endianness;
+    ///
+    /// let plain = RecordLayout::new().field::<u32>("id", Endianness::Big);
+    /// let tagged = RecordLayout::new().tagged_field::<u32>("id", Endianness::Big, "UserId");
+    ///
+    /// assert_eq!(tagged.get("id").unwrap().tag, Some("UserId"));
+    /// assert_ne!(plain.layout_id(), tagged.layout_id());
+    /// ```
+    pub fn tagged_field<U: EndianNumber>(
+        self,
+        name: &str,
+        endianness: Endianness,
+        tag: &'static str,
+    ) -> Self {
+        self.push(
+            name.to_string(),
+            U::SIZE,
+            std::any::type_name::<U>(),
+            Some(tag),
+            Some(endianness),
+        )
+    }
+    /// Appends a byte array field of `len` bytes.
+    pub fn bytes(self, name: &str, len: usize) -> Self {
+        self.push(name.to_string(), len, "bytes", Some("bytes"), None)
+    }
+    /// Appends every field of `record`, flattened into this layout with their names prefixed by `name` and a dot.
+    pub fn nested(self, name: &str, record: &RecordLayout) -> Self {
+        record.fields.iter().fold(self, |layout, field| {
+            layout.push(
+                format!("{}.{}", name, field.name),
+                field.size,
+                field.type_name,
+                field.tag,
+                field.endianness,
+            )
+        })
+    }
+    fn push(
+        mut self,
+        name: String,
+        size: usize,
+        type_name: &'static str,
+        tag: Option<&'static str>,
+        endianness: Option<Endianness>,
+    ) -> Self {
+        self.fields.push(FieldDesc {
+            name,
+            offset: self.size,
+            size,
+            type_name,
+            tag,
+            endianness,
+        });
+        self.size += size;
+
+        self
+    }
+    /// Returns the fields, in order.
+    pub fn fields(&self) -> &[FieldDesc] {
+        &self.fields
+    }
+    /// Returns the field named `name`.
+    pub fn get(&self, name: &str) -> Option<&FieldDesc> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+    /// Returns the size of a record, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    /// Returns a fingerprint of the layout, a 64-bit FNV-1a hash over the [tag](FieldDesc::tag), size and byte order of every field, in
+    /// order. Field names are left out, so renaming a field keeps the fingerprint, while reordering or retyping fields changes it.
+    /// [`Endianness::Native`] is hashed as the byte order it resolves to on the host.
+    ///
+    /// Only the tags are hashed, never the names of the types, so the fingerprint stays the same across compilers and when types are
+    /// moved between modules. Untagged fields are hashed as if their tag were empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::layout::RecordLayout;
+    /// use seqbytes::traits::Endianness;
+    ///
+    /// let old = RecordLayout::new().field::<u32>("id", Endianness::Big);
+    /// let renamed = RecordLayout::new().field::<u32>("key", Endianness::Big);
+    /// let retyped = RecordLayout::new().field::<i32>("id", Endianness::Big);
+    ///
+    /// assert_eq!(old.layout_id(), renamed.layout_id());
+    /// assert_ne!(old.layout_id(), retyped.layout_id());
+    /// ```
+    pub fn layout_id(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+        let mut hash = OFFSET_BASIS;
+        let mut write = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash = (hash ^ u64::from(byte)).wrapping_mul(PRIME);
+            }
+        };
+
+        for field in &self.fields {
+            let order = match field.endianness {
+                None => 0,
+                Some(endianness) if endianness.is_big() => 2,
+                Some(_) => 1,
+            };
+
+            // The length prefix keeps the boundary between fields unambiguous.
+            let tag = field.tag.unwrap_or("");
+            write(&(tag.len() as u64).to_le_bytes());
+            write(tag.as_bytes());
+            write(&(field.size as u64).to_le_bytes());
+            write(&[order]);
+        }
+
+        hash
+    }
+    /// Writes the 8-byte fingerprint returned by [`RecordLayout::layout_id`], little endian, for [`RecordLayout::check_layout_header`] to
+    /// verify at the start of a stream of records.
+    pub fn write_layout_header<W: ESeqByteWriter + ?Sized>(&self, writer: &mut W) -> Option<()> {
+        writer.push_e(self.layout_id(), false)
+    }
+    /// Reads the 8-byte fingerprint written by [`RecordLayout::write_layout_header`], shifting the position, and checks it against this
+    /// layout. Returns [`LayoutHeaderError::Mismatch`] if the stream was written with a different layout, and
+    /// [`LayoutHeaderError::Truncated`] if there are not enough bytes to be read. On failure, the position is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::layout::{LayoutHeaderError, RecordLayout};
+    /// use seqbytes::traits::Endianness;
+    /// use std::io::Cursor;
+    ///
+    /// let old = RecordLayout::new().field::<u16>("id", Endianness::Big);
+    /// let new = RecordLayout::new().field::<u32>("id", Endianness::Big);
+    ///
+    /// let mut file = Vec::new();
+    /// old.write_layout_header(&mut file).unwrap();
+    ///
+    /// assert!(old.check_layout_header(&mut Cursor::new(&file)).is_ok());
+    /// assert!(matches!(
+    ///     new.check_layout_header(&mut Cursor::new(&file)),
+    ///     Err(LayoutHeaderError::Mismatch { offset: 0, .. })
+    /// ));
+    /// ```
+    pub fn check_layout_header<R: SeqByteReader + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<(), LayoutHeaderError> {
+        reader
+            .expect_e(self.layout_id(), false)
+            .map_err(|error| match error {
+                ExpectError::Truncated { offset } => LayoutHeaderError::Truncated { offset },
+                ExpectError::Mismatch {
+                    offset,
+                    expected,
+                    actual,
+                } => LayoutHeaderError::Mismatch {
+                    offset,
+                    expected,
+                    found: actual,
+                },
+            })
+    }
+}
+
+/// The error returned by [`RecordLayout::check_layout_header`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayoutHeaderError {
+    /// There were not enough bytes to read the fingerprint at `offset`.
+    Truncated {
+        /// The absolute offset of the fingerprint.
+        offset: u64,
+    },
+    /// The fingerprint at `offset` is not the one of the layout, so the stream was written with a different record layout.
+    Mismatch {
+        /// The absolute offset of the fingerprint.
+        offset: u64,
+        /// The fingerprint of the layout the stream was checked against.
+        expected: u64,
+        /// The fingerprint read from the stream.
+        found: u64,
+    },
+}
+
+impl fmt::Display for LayoutHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutHeaderError::Truncated { offset } => write!(
+                f,
+                "not enough bytes to read the layout fingerprint at offset {}",
+                offset
+            ),
+            LayoutHeaderError::Mismatch {
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "record layout mismatch at offset {}: expected fingerprint {:#018x}, found {:#018x}, so the stream was written with a different layout",
+                offset, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutHeaderError {}
+
+/// Reads one record laid out as `layout`, shifting the position by its size, and pairs each field with its raw bytes. Returns [`None`] if
+/// there are not enough bytes to be read.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::layout::{annotate, RecordLayout};
+/// use seqbytes::traits::Endianness;
+/// use std::io::Cursor;
+///
+/// let layout = RecordLayout::new()
+///     .bytes("magic", 2)
+///     .field::<u16>("version", Endianness::Big);
+///
+/// let mut cursor = Cursor::new(vec![b'S', b'Q', 0, 2]);
+/// let fields = annotate(&mut cursor, &layout).unwrap();
+///
+/// assert_eq!(fields[0].1, b"SQ");
+/// assert_eq!(fields[1].0.name, "version");
+/// assert_eq!(fields[1].1, [0, 2]);
+/// ```
+pub fn annotate<R: SeqByteReader + ?Sized>(
+    reader: &mut R,
+    layout: &RecordLayout,
+) -> Option<Vec<(FieldDesc, Vec<u8>)>> {
+    let record = reader.shift_slice(layout.size)?;
+
+    Some(
+        layout
+            .fields
+            .iter()
+            .map(|field| {
+                let bytes = record[field.offset..field.offset + field.size].to_vec();
+                (field.clone(), bytes)
+            })
+            .collect(),
+    )
+}
+
+/// A field which differs between the two sources compared by [`diff_records`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldDiff {
+    /// The index of the record the field belongs to.
+    pub record: u64,
+    /// The name of the field.
+    pub name: String,
+    /// The offset of the field from the position the sources were compared from, in bytes.
+    pub offset: u64,
+    /// The decoded value of the field in the first source, the bytes it holds in hex followed by `<end>` if the source ends partway through
+    /// the field, or [`None`] if it ends before the field.
+    pub a: Option<String>,
+    /// The decoded value of the field in the second source, formatted as [`FieldDiff::a`] is.
+    pub b: Option<String>,
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "<end>".to_string());
+
+        write!(
+            f,
+            "record {} field {} at offset {:#x}: {} != {}",
+            self.record,
+            self.name,
+            self.offset,
+            value(&self.a),
+            value(&self.b)
+        )
+    }
+}
+
+/// Formats the bytes of `field` as the number its type names, or as hex for byte arrays and types which are not primitive numbers.
+fn decode_field(field: &FieldDesc, bytes: &[u8]) -> String {
+    macro_rules! decode {
+        ($($t:ty),*) => {
+            match (field.type_name, field.endianness) {
+                $(
+                    (name, Some(endianness)) if name == stringify!($t) => {
+                        <$t>::from_bytes_e(bytes, endianness.is_big()).map(|value| value.to_string())
+                    }
+                )*
+                _ => None,
+            }
+        };
+    }
+
+    with_primitives!(decode).unwrap_or_else(|| hex(bytes))
+}
+
+/// Formats bytes as space-separated hex.
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads the `size` bytes of a field, or returns the fewer bytes which remain if the source ends partway through it.
+fn shift_field<R: SeqByteReader + ?Sized>(reader: &mut R, size: usize) -> Result<Vec<u8>, Vec<u8>> {
+    reader.shift_slice(size).ok_or_else(|| {
+        std::iter::from_fn(|| reader.shift::<u8>())
+            .take(size)
+            .collect()
+    })
+}
+
+/// Formats a field read by [`shift_field`] for a [`FieldDiff`].
+fn format_field(field: &FieldDesc, bytes: &Result<Vec<u8>, Vec<u8>>) -> Option<String> {
+    match bytes {
+        Ok(bytes) => Some(decode_field(field, bytes)),
+        Err(rest) if rest.is_empty() => None,
+        Err(rest) => Some(format!("{} <end>", hex(rest))),
+    }
+}
+
+/// Compares `a` and `b` record by record as laid out by `layout`, shifting both positions, and returns the first `max_diffs` fields which
+/// differ, with their values decoded, for tracking down what changed between two files, such as when a writer no longer matches its golden
+/// files. The comparison runs until either source ends. If only one source ends, or they end at different bytes, the field either ends in
+/// is returned as a difference, so sources which both end partway through a field only compare equal if the bytes they hold of it do.
+/// Returns an empty [`Vec`] if the sources are identical.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::layout::{diff_records, RecordLayout};
+/// use seqbytes::traits::Endianness;
+/// use std::io::Cursor;
+///
+/// let layout = RecordLayout::new()
+///     .bytes("magic", 2)
+///     .field::<u16>("version", Endianness::Big);
+///
+/// let mut golden = Cursor::new(vec![b'S', b'Q', 0, 2]);
+/// let mut output = Cursor::new(vec![b'S', b'Q', 0, 3]);
+/// let diffs = diff_records(&mut golden, &mut output, &layout, 10);
+///
+/// assert_eq!(diffs.len(), 1);
+/// assert_eq!(diffs[0].to_string(), "record 0 field version at offset 0x2: 2 != 3");
+/// ```
+pub fn diff_records<A: SeqByteReader + ?Sized, B: SeqByteReader + ?Sized>(
+    a: &mut A,
+    b: &mut B,
+    layout: &RecordLayout,
+    max_diffs: usize,
+) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    if layout.size == 0 {
+        return diffs;
+    }
+
+    for record in 0u64.. {
+        for field in &layout.fields {
+            if diffs.len() >= max_diffs {
+                return diffs;
+            }
+
+            let (a_bytes, b_bytes) = (shift_field(a, field.size), shift_field(b, field.size));
+            if a_bytes == b_bytes {
+                if a_bytes.is_err() {
+                    return diffs;
+                }
+                continue;
+            }
+
+            diffs.push(FieldDiff {
+                record,
+                name: field.name.clone(),
+                offset: record * layout.size as u64 + field.offset as u64,
+                a: format_field(field, &a_bytes),
+                b: format_field(field, &b_bytes),
+            });
+            if a_bytes.is_err() || b_bytes.is_err() {
+                return diffs;
+            }
+        }
+    }
+
+    diffs
+}
+
+/// The error returned by [`transcode`], naming the record and field it occurred at.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TranscodeError {
+    /// The source ended partway through the field, or before the requested number of records.
+    Truncated {
+        /// The index of the record.
+        record: u64,
+        /// The name of the field.
+        field: String,
+    },
+    /// The type of the field is not a primitive number, so its bytes cannot be reordered.
+    Unsupported {
+        /// The index of the record.
+        record: u64,
+        /// The name of the field.
+        field: String,
+    },
+    /// The record could not be written.
+    Write {
+        /// The index of the record.
+        record: u64,
+    },
+}
+
+impl fmt::Display for TranscodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscodeError::Truncated { record, field } => {
+                write!(f, "source ends in record {} field {}", record, field)
+            }
+            TranscodeError::Unsupported { record, field } => write!(
+                f,
+                "record {} field {} is not a primitive number and cannot be transcoded",
+                record, field
+            ),
+            TranscodeError::Write { record } => write!(f, "could not write record {}", record),
+        }
+    }
+}
+
+impl std::error::Error for TranscodeError {}
+
+/// Reads the bytes of `field` with the endianness `from` and returns them encoded with the endianness `to`, or [`None`] if the type of the
+/// field is not a primitive number. Byte arrays are returned unchanged.
+fn transcode_field(
+    field: &FieldDesc,
+    bytes: &[u8],
+    from: Endianness,
+    to: Endianness,
+) -> Option<Vec<u8>> {
+    macro_rules! transcode {
+        ($($t:ty),*) => {
+            match (field.type_name, field.endianness) {
+                (_, None) => Some(bytes.to_vec()),
+                $(
+                    (name, Some(_)) if name == stringify!($t) => {
+                        <$t>::from_bytes_e(bytes, from.is_big()).map(|value| value.to_bytes_e(to.is_big()))
+                    }
+                )*
+                _ => None,
+            }
+        };
+    }
+
+    with_primitives!(transcode)
+}
+
+/// Converts records laid out as `layout` from `reader` to `writer`, reading every number with the endianness `from` and writing it with
+/// the endianness `to`, such as when porting a file between big and little endian platforms. Byte array fields are copied untouched, and
+/// the endianness each field is declared with in `layout` is ignored.
+///
+/// Converts `count` records, or if it is [`None`], every record until the source ends. Returns the number of records converted. Returns
+/// [`TranscodeError::Truncated`] if the source ends partway through a record or before `count` records, and
+/// [`TranscodeError::Unsupported`] for a field whose type is not a primitive number, such as an array. Records before the one which fails
+/// have already been written.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::layout::{transcode, RecordLayout};
+/// use seqbytes::traits::Endianness;
+/// use std::io::Cursor;
+///
+/// let layout = RecordLayout::new()
+///     .bytes("tag", 2)
+///     .field::<u16>("id", Endianness::Big);
+///
+/// let mut input = Cursor::new(b"AB\x00\x01CD\x01\x00".to_vec());
+/// let mut output = Vec::new();
+///
+/// let records = transcode(&mut input, &mut output, &layout, Endianness::Big, Endianness::Little, None);
+///
+/// assert_eq!(records, Ok(2));
+/// assert_eq!(output, b"AB\x01\x00CD\x00\x01");
+/// ```
+pub fn transcode<R: SeqByteReader + ?Sized, W: SeqByteWriter + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+    layout: &RecordLayout,
+    from: Endianness,
+    to: Endianness,
+    count: Option<u64>,
+) -> Result<u64, TranscodeError> {
+    let mut out = Vec::with_capacity(layout.size);
+    let mut record = 0;
+
+    loop {
+        let done = match count {
+            Some(count) => record >= count,
+            None => layout.size == 0 || reader.available(1) != Some(true),
+        };
+        if done {
+            return Ok(record);
+        }
+
+        out.clear();
+        for field in &layout.fields {
+            let bytes =
+                reader
+                    .shift_slice(field.size)
+                    .ok_or_else(|| TranscodeError::Truncated {
+                        record,
+                        field: field.name.clone(),
+                    })?;
+            let converted = transcode_field(field, &bytes, from, to).ok_or_else(|| {
+                TranscodeError::Unsupported {
+                    record,
+                    field: field.name.clone(),
+                }
+            })?;
+
+            out.extend(converted);
+        }
+
+        writer
+            .push_slice(&out)
+            .ok_or(TranscodeError::Write { record })?;
+        record += 1;
+    }
+}
+
+/// A field declared in a [`ReadPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PlanField {
+    name: String,
+    offset: u64,
+    size: usize,
+    type_id: TypeId,
+    bigendian: bool,
+}
+
+/// A set of fields at fixed offsets to be extracted from many sources, such as the same dozen fields of the headers of many small files.
+///
+/// Fields are declared once, in any order and possibly overlapping, and [`ReadPlan::execute`] reads them in a single forward pass, merging
+/// fields which overlap or touch into one read. Offsets are relative to the position of the reader when the plan is executed.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::layout::ReadPlan;
+/// use seqbytes::prelude::*;
+/// use seqbytes::traits::Endianness;
+/// use std::io::Cursor;
+///
+/// let plan = ReadPlan::new()
+///     .field::<u16>("flags", 0x06, Endianness::Big)
+///     .field::<u32>("width", 0x02, Endianness::Little);
+///
+/// let mut cursor = Cursor::new(vec![0, 0, 0x80, 0x02, 0, 0, 0x01, 0x10]);
+/// let fields = plan.execute(&mut cursor).unwrap();
+///
+/// assert_eq!(fields.get::<u32>("width"), Some(640));
+/// assert_eq!(fields.get::<u16>("flags"), Some(0x0110));
+/// assert_eq!(fields.get::<u8>("flags"), None);
+/// assert_eq!(cursor.position(), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ReadPlan {
+    fields: Vec<PlanField>,
+    require_all: bool,
+}
+
+impl ReadPlan {
+    /// Creates a plan without any fields, which tolerates fields past the end of the source.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Declares a field of type `U` at `offset` with the specified endianness.
+    pub fn field<U: EndianNumber + 'static>(
+        mut self,
+        name: &str,
+        offset: u64,
+        endianness: Endianness,
+    ) -> Self {
+        self.fields.push(PlanField {
+            name: name.to_string(),
+            offset,
+            size: U::SIZE,
+            type_id: TypeId::of::<U>(),
+            bigendian: endianness.is_big(),
+        });
+
+        self
+    }
+    /// Sets whether [`ReadPlan::execute`] fails as a whole if any field lies past the end of the source. If `false`, which is the default,
+    /// such fields are only marked as missing in the [`ExtractedFields`].
+    pub fn require_all(mut self, require_all: bool) -> Self {
+        self.require_all = require_all;
+        self
+    }
+    /// Reads every field from `reader`, in order of offset, and restores the position afterwards. Returns [`None`] if the position cannot
+    /// be queried or restored, or if a field lies past the end of the source and the plan requires all fields.
+    pub fn execute<R: SeqByteReaderCore + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Option<ExtractedFields> {
+        let start = reader.pos()?;
+        let end = reader.end_pos();
+
+        let mut order: Vec<usize> = (0..self.fields.len()).collect();
+        order.sort_by_key(|&i| self.fields[i].offset);
+
+        let mut values: Vec<Option<Vec<u8>>> = vec![None; self.fields.len()];
+        let range = |i: usize| {
+            let field = &self.fields[i];
+            let from = start.checked_add(field.offset)?;
+            Some((from, from.checked_add(field.size as u64)?))
+        };
+
+        let mut rest = &order[..];
+        while let Some(&first) = rest.first() {
+            // Gathers the run of fields which overlap or touch into one span.
+            let mut span = range(first);
+            let mut len = 1;
+            while let (Some((from, to)), Some(&next)) = (span, rest.get(len)) {
+                match range(next) {
+                    Some((next_from, next_to)) if next_from <= to => {
+                        span = Some((from, to.max(next_to)));
+                        len += 1;
+                    }
+                    _ => break,
+                }
+            }
+
+            let (run, tail) = rest.split_at(len);
+            rest = tail;
+
+            let bytes = span.and_then(|(from, to)| {
+                read_range(reader, from, to, end).map(|bytes| (from, bytes))
+            });
+            for &i in run {
+                values[i] = match (&bytes, range(i)) {
+                    (Some((span_from, bytes)), Some((from, to))) => {
+                        Some(bytes[(from - span_from) as usize..(to - span_from) as usize].to_vec())
+                    }
+                    // Fields of a span which runs past the end may still fit on their own.
+                    _ => range(i).and_then(|(from, to)| read_range(reader, from, to, end)),
+                };
+            }
+        }
+
+        reader.set_pos(start)?;
+        if self.require_all && values.iter().any(Option::is_none) {
+            return None;
+        }
+
+        Some(ExtractedFields {
+            fields: self.fields.iter().cloned().zip(values).collect(),
+        })
+    }
+}
+
+/// Reads the bytes in `[from, to)`, without reading anything if `end` is known and the range runs past it.
+fn read_range<R: SeqByteReaderCore + ?Sized>(
+    reader: &mut R,
+    from: u64,
+    to: u64,
+    end: Option<u64>,
+) -> Option<Vec<u8>> {
+    if end.is_some_and(|end| to > end) {
+        return None;
+    }
+
+    let mut bytes = vec![0u8; usize::try_from(to - from).ok()?];
+    reader.set_pos(from)?;
+    reader.fill(&mut bytes)?;
+
+    Some(bytes)
+}
+
+/// The fields read by [`ReadPlan::execute`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExtractedFields {
+    fields: Vec<(PlanField, Option<Vec<u8>>)>,
+}
+
+impl ExtractedFields {
+    /// Returns the value of the field named `name`. Returns [`None`] if there is no such field, if it was declared with a type other than
+    /// `U`, or if it lies past the end of the source.
+    pub fn get<U: EndianNumber + 'static>(&self, name: &str) -> Option<U> {
+        let (field, bytes) = self.fields.iter().find(|(field, _)| field.name == name)?;
+        if field.type_id != TypeId::of::<U>() {
+            return None;
+        }
+
+        U::from_bytes_e(bytes.as_ref()?, field.bigendian)
+    }
+    /// Returns the raw bytes of the field named `name`, in the byte order of the source. Returns [`None`] if there is no such field, or if it
+    /// lies past the end of the source.
+    pub fn bytes(&self, name: &str) -> Option<&[u8]> {
+        let (_, bytes) = self.fields.iter().find(|(field, _)| field.name == name)?;
+        bytes.as_deref()
+    }
+    /// Returns the names of the fields which lie past the end of the source, in the order they were declared.
+    pub fn missing(&self) -> impl Iterator<Item = &str> {
+        self.fields
+            .iter()
+            .filter(|(_, bytes)| bytes.is_none())
+            .map(|(field, _)| field.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn vertex() -> RecordLayout {
+        RecordLayout::new()
+            .field::<f32>("x", Endianness::Little)
+            .field::<f32>("y", Endianness::Little)
+            .field::<u16>("color", Endianness::Big)
+    }
+
+    #[test]
+    fn offsets_test() {
+        let header = RecordLayout::new()
+            .bytes("magic", 4)
+            .field::<u8>("version", Endianness::Little)
+            .field::<u64>("length", Endianness::Big)
+            .field::<i16>("flags", Endianness::Little)
+            .field::<f64>("scale", Endianness::Big);
+
+        let offsets: Vec<_> = header
+            .fields()
+            .iter()
+            .map(|field| (field.name.as_str(), field.offset, field.size))
+            .collect();
+        assert_eq!(
+            offsets,
+            [
+                ("magic", 0, 4),
+                ("version", 4, 1),
+                ("length", 5, 8),
+                ("flags", 13, 2),
+                ("scale", 15, 8)
+            ]
+        );
+        assert_eq!(header.size(), 23);
+
+        let length = header.get("length").unwrap();
+        assert_eq!(length.type_name, "u64");
+        assert_eq!(length.endianness, Some(Endianness::Big));
+        assert_eq!(header.get("magic").unwrap().endianness, None);
+        assert_eq!(header.get("missing"), None);
+    }
+
+    #[test]
+    fn nested_test() {
+        let triangle = RecordLayout::new()
+            .field::<u32>("id", Endianness::Little)
+            .nested("a", &vertex())
+            .nested("b", &vertex())
+            .nested("c", &vertex());
+
+        assert_eq!(triangle.fields().len(), 10);
+        assert_eq!(triangle.size(), 4 + 3 * 10);
+
+        let color = triangle.get("b.color").unwrap();
+        assert_eq!(color.offset, 4 + 10 + 8);
+        assert_eq!(color.endianness, Some(Endianness::Big));
+
+        let mesh = RecordLayout::new().nested("tri", &triangle);
+        assert_eq!(mesh.get("tri.c.x").unwrap().offset, 24);
+    }
+
+    #[test]
+    fn annotate_test() {
+        let layout = RecordLayout::new()
+            .field::<u16>("id", Endianness::Big)
+            .nested("v", &vertex());
+
+        let mut cursor = Cursor::new(vec![0, 7, 0, 0, 128, 63, 0, 0, 0, 64, 0xAB, 0xCD, 0xFF]);
+        let fields = annotate(&mut cursor, &layout).unwrap();
+
+        let annotated: Vec<_> = fields
+            .iter()
+            .map(|(field, bytes)| (field.name.as_str(), bytes.as_slice()))
+            .collect();
+        assert_eq!(
+            annotated,
+            [
+                ("id", &[0, 7][..]),
+                ("v.x", &[0, 0, 128, 63][..]),
+                ("v.y", &[0, 0, 0, 64][..]),
+                ("v.color", &[0xAB, 0xCD][..])
+            ]
+        );
+        assert_eq!(cursor.position(), 12);
+        assert_eq!(annotate(&mut cursor, &layout), None);
+    }
+
+    #[test]
+    fn read_plan_test() {
+        use crate::stats::StatsReader;
+
+        // A synthetic 0x30 byte header, with a little endian block at 0x10 and a big endian one at 0x20.
+        let mut header = vec![0u8; 0x30];
+        header[..4].copy_from_slice(b"SYNH");
+        header[0x10..0x18].copy_from_slice(&[0x80, 0x02, 0, 0, 0xE0, 0x01, 0, 0]);
+        header[0x20..0x24].copy_from_slice(&[0x00, 0x2A, 0x01, 0x00]);
+
+        let plan = ReadPlan::new()
+            .field::<u32>("height", 0x14, Endianness::Little)
+            .field::<u16>("flags", 0x22, Endianness::Big)
+            .field::<u32>("magic", 0, Endianness::Big)
+            .field::<u32>("width", 0x10, Endianness::Little)
+            .field::<u16>("width_low", 0x10, Endianness::Little)
+            .field::<u16>("version", 0x20, Endianness::Big)
+            .field::<u64>("trailer", 0x2C, Endianness::Little);
+
+        let mut reader = StatsReader::new(Cursor::new(header.clone()));
+
+        let fields = plan.execute(&mut reader).unwrap();
+        assert_eq!(fields.get::<u32>("magic"), Some(0x5359_4E48));
+        assert_eq!(fields.get::<u32>("width"), Some(640));
+        assert_eq!(fields.get::<u32>("height"), Some(480));
+        assert_eq!(fields.get::<u16>("width_low"), Some(640));
+        assert_eq!(fields.get::<u16>("version"), Some(42));
+        assert_eq!(fields.get::<u16>("flags"), Some(0x0100));
+        assert_eq!(fields.bytes("flags"), Some(&[0x01, 0x00][..]));
+        assert_eq!(fields.get::<u64>("trailer"), None);
+        assert_eq!(fields.get::<i32>("width"), None);
+        assert_eq!(fields.get::<u32>("depth"), None);
+        assert_eq!(fields.missing().collect::<Vec<_>>(), ["trailer"]);
+
+        // One read for the magic, one for both blocks at 0x10 and one for the block at 0x20.
+        assert_eq!(reader.stats().reads, 3);
+        assert_eq!(reader.stats().bytes_read, 4 + 8 + 4);
+        assert_eq!(reader.get_ref().position(), 0);
+
+        let mut cursor = Cursor::new(header);
+        cursor.set_position(0x0E);
+        assert_eq!(plan.clone().require_all(true).execute(&mut cursor), None);
+        assert_eq!(cursor.position(), 0x0E);
+
+        let fields = plan.execute(&mut cursor).unwrap();
+        assert_eq!(fields.get::<u32>("magic"), Some(0x8002));
+        assert_eq!(fields.get::<u32>("width"), Some(0x2A00_0000));
+        assert_eq!(fields.get::<u32>("height"), Some(1));
+        assert_eq!(fields.get::<u16>("version"), Some(0));
+        assert_eq!(fields.missing().collect::<Vec<_>>(), ["flags", "trailer"]);
+    }
+
+    #[test]
+    fn diff_records_test() {
+        use crate::prelude::*;
+
+        let layout = RecordLayout::new()
+            .field::<u16>("id", Endianness::Big)
+            .nested("v", &vertex())
+            .bytes("tag", 2);
+
+        let mut golden = Vec::new();
+        for id in 0..3u16 {
+            golden.extend(id.to_be_bytes());
+            golden.extend(1.5f32.to_le_bytes());
+            golden.extend((id as f32).to_le_bytes());
+            golden.extend(0xABCDu16.to_be_bytes());
+            golden.extend(b"ok");
+        }
+
+        let same = diff_records(
+            &mut Cursor::new(golden.clone()),
+            &mut Cursor::new(golden.clone()),
+            &layout,
+            10,
+        );
+        assert!(same.is_empty());
+
+        let mut changed = golden.clone();
+        changed[14 + 10..14 + 12].copy_from_slice(&0x1234u16.to_be_bytes());
+        let diffs = diff_records(
+            &mut Cursor::new(golden.clone()),
+            &mut Cursor::new(changed.clone()),
+            &layout,
+            10,
+        );
+        assert_eq!(
+            diffs,
+            [FieldDiff {
+                record: 1,
+                name: "v.color".to_string(),
+                offset: 24,
+                a: Some("43981".to_string()),
+                b: Some("4660".to_string()),
+            }]
+        );
+        assert_eq!(
+            diffs[0].to_string(),
+            "record 1 field v.color at offset 0x18: 43981 != 4660"
+        );
+
+        changed[12..14].copy_from_slice(b"no");
+        let diffs = diff_records(
+            &mut Cursor::new(golden.clone()),
+            &mut Cursor::new(changed),
+            &layout,
+            1,
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].a.as_deref(), Some("6f 6b"));
+        assert_eq!(diffs[0].b.as_deref(), Some("6e 6f"));
+
+        // The second source ends partway through the `y` of the last record.
+        let mut short = Cursor::new(golden[..28 + 8].to_vec());
+        let mut full = Cursor::new(golden);
+        let diffs = diff_records(&mut full, &mut short, &layout, 10);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].name, "v.y");
+        assert_eq!(diffs[0].offset, 34);
+        assert_eq!(diffs[0].a.as_deref(), Some("2"));
+        assert_eq!(diffs[0].b.as_deref(), Some("00 00 <end>"));
+        assert_eq!(
+            diffs[0].to_string(),
+            "record 2 field v.y at offset 0x22: 2 != 00 00 <end>"
+        );
+
+        // A source which ends right before a field reports it as missing.
+        let diffs = diff_records(
+            &mut Cursor::new(full.get_ref().clone()),
+            &mut Cursor::new(full.get_ref()[..34].to_vec()),
+            &layout,
+            10,
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!((diffs[0].name.as_str(), diffs[0].b.clone()), ("v.y", None));
+
+        // Both sources ending partway through the same field only compare equal if the bytes they hold of it do.
+        let mut cut = full.get_ref()[..36].to_vec();
+        assert!(diff_records(
+            &mut Cursor::new(cut.clone()),
+            &mut Cursor::new(cut.clone()),
+            &layout,
+            10
+        )
+        .is_empty());
+        let diffs = diff_records(
+            &mut Cursor::new(cut.clone()),
+            &mut Cursor::new(cut[..35].to_vec()),
+            &layout,
+            10,
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            diffs[0].to_string(),
+            "record 2 field v.y at offset 0x22: 00 00 <end> != 00 <end>"
+        );
+        cut[35] = 0xFF;
+        let diffs = diff_records(
+            &mut Cursor::new(full.get_ref()[..36].to_vec()),
+            &mut Cursor::new(cut),
+            &layout,
+            10,
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            diffs[0].to_string(),
+            "record 2 field v.y at offset 0x22: 00 00 <end> != 00 ff <end>"
+        );
+
+        // Every built-in number which can be transcoded is also decoded.
+        let layout = RecordLayout::new()
+            .field::<usize>("len", Endianness::Little)
+            .field::<isize>("delta", Endianness::Big)
+            .field::<bool>("live", Endianness::Little)
+            .field::<char>("grade", Endianness::Big);
+        let record = |len: usize, delta: isize, live: bool, grade: char| {
+            let mut a = Vec::new();
+            a.push_e(len, false).unwrap();
+            a.push_e(delta, true).unwrap();
+            a.push_e(live, false).unwrap();
+            a.push_e(grade, true).unwrap();
+            a
+        };
+        let diffs = diff_records(
+            &mut Cursor::new(record(3, -1, true, 'A')),
+            &mut Cursor::new(record(4, -2, false, 'B')),
+            &layout,
+            10,
+        );
+        let values: Vec<_> = diffs
+            .iter()
+            .map(|diff| (diff.a.as_deref().unwrap(), diff.b.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            values,
+            [("3", "4"), ("-1", "-2"), ("true", "false"), ("A", "B")]
+        );
+    }
+
+    #[test]
+    fn transcode_test() {
+        use crate::prelude::*;
+
+        let layout = RecordLayout::new()
+            .bytes("magic", 2)
+            .field::<u16>("id", Endianness::Big)
+            .nested("v", &vertex())
+            .field::<i64>("time", Endianness::Big)
+            .field::<u8>("flags", Endianness::Big);
+
+        let mut big = Cursor::new(Vec::new());
+        let mut little = Vec::new();
+        for id in 0..3u16 {
+            for (out, bigendian) in [(big.get_mut(), true), (&mut little, false)] {
+                out.extend(b"RC");
+                out.extend(id.to_bytes_e(bigendian));
+                out.extend((id as f32 * 0.5).to_bytes_e(bigendian));
+                out.extend((-1.0f32).to_bytes_e(bigendian));
+                out.extend(0xABCDu16.to_bytes_e(bigendian));
+                out.extend((-(id as i64) * 1000).to_bytes_e(bigendian));
+                out.extend([id as u8]);
+            }
+        }
+        assert_eq!(big.get_ref().len(), 3 * layout.size());
+
+        let mut output = Vec::new();
+        let records = transcode(
+            &mut big,
+            &mut output,
+            &layout,
+            Endianness::Big,
+            Endianness::Little,
+            None,
+        );
+        assert_eq!(records, Ok(3));
+        assert_eq!(output, little);
+        let second = layout.size();
+        assert_eq!(output[second + 4..second + 8], 0.5f32.to_le_bytes());
+
+        let mut converted = Cursor::new(output);
+        converted.set_position(layout.size() as u64);
+        assert_eq!(converted.shift_slice(2).unwrap(), b"RC");
+        assert_eq!(converted.shift_e::<u16>(false), Some(1));
+        assert_eq!(converted.shift_e::<f32>(false), Some(0.5));
+        assert_eq!(converted.shift_e::<f32>(false), Some(-1.0));
+        assert_eq!(converted.shift_e::<u16>(false), Some(0xABCD));
+        assert_eq!(converted.shift_e::<i64>(false), Some(-1000));
+
+        // Converting back restores the original bytes, and `count` stops early.
+        let mut back = Vec::new();
+        converted.set_position(0);
+        let records = transcode(
+            &mut converted,
+            &mut back,
+            &layout,
+            Endianness::Little,
+            Endianness::Big,
+            Some(2),
+        );
+        assert_eq!(records, Ok(2));
+        assert_eq!(back, big.get_ref()[..2 * layout.size()]);
+    }
+
+    #[test]
+    fn transcode_error_test() {
+        let layout = vertex();
+
+        let mut short = Cursor::new(vec![0; layout.size() + 5]);
+        let result = transcode(
+            &mut short,
+            &mut Vec::new(),
+            &layout,
+            Endianness::Little,
+            Endianness::Big,
+            None,
+        );
+        assert_eq!(
+            result,
+            Err(TranscodeError::Truncated {
+                record: 1,
+                field: "y".to_string()
+            })
+        );
+
+        let mut exact = Cursor::new(vec![0; layout.size()]);
+        let result = transcode(
+            &mut exact,
+            &mut Vec::new(),
+            &layout,
+            Endianness::Little,
+            Endianness::Big,
+            Some(2),
+        );
+        assert_eq!(
+            result,
+            Err(TranscodeError::Truncated {
+                record: 1,
+                field: "x".to_string()
+            })
+        );
+
+        let layout = RecordLayout::new()
+            .field::<u8>("kind", Endianness::Big)
+            .field::<crate::traits::Unorm16>("level", Endianness::Big);
+        let error = transcode(
+            &mut Cursor::new(vec![0; 3]),
+            &mut Vec::new(),
+            &layout,
+            Endianness::Big,
+            Endianness::Little,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "record 0 field level is not a primitive number and cannot be transcoded"
+        );
+    }
+
+    #[test]
+    fn layout_id_test() {
+        // Two versions of the same record, the second of which widened `color` to a `u32`.
+        let old = vertex();
+        let new = RecordLayout::new()
+            .field::<f32>("x", Endianness::Little)
+            .field::<f32>("y", Endianness::Little)
+            .field::<u32>("color", Endianness::Big);
+        let renamed = RecordLayout::new()
+            .field::<f32>("left", Endianness::Little)
+            .field::<f32>("top", Endianness::Little)
+            .field::<u16>("rgb565", Endianness::Big);
+        let reordered = RecordLayout::new()
+            .field::<u16>("color", Endianness::Big)
+            .field::<f32>("x", Endianness::Little)
+            .field::<f32>("y", Endianness::Little);
+        let swapped = RecordLayout::new()
+            .field::<f32>("x", Endianness::Little)
+            .field::<f32>("y", Endianness::Little)
+            .field::<u16>("color", Endianness::Little);
+
+        assert_eq!(old.layout_id(), vertex().layout_id());
+        assert_eq!(old.layout_id(), renamed.layout_id());
+        assert_ne!(old.layout_id(), new.layout_id());
+        assert_ne!(old.layout_id(), reordered.layout_id());
+        assert_ne!(old.layout_id(), swapped.layout_id());
+        assert_ne!(
+            RecordLayout::new().bytes("a", 2).layout_id(),
+            RecordLayout::new().bytes("a", 1).bytes("b", 1).layout_id()
+        );
+        // The fingerprint is stable, so it can be stored.
+        assert_eq!(RecordLayout::new().layout_id(), 0xCBF2_9CE4_8422_2325);
+        assert_eq!(old.layout_id(), 0x8E09_FB89_6089_6E7A);
+
+        // Types other than the built-in numbers are only told apart by size unless they are tagged.
+        use std::num::Wrapping;
+        let wrapped = RecordLayout::new().field::<Wrapping<u16>>("color", Endianness::Big);
+        let saturated =
+            RecordLayout::new().field::<std::num::Saturating<i16>>("color", Endianness::Big);
+        let tagged = RecordLayout::new().tagged_field::<Wrapping<u16>>(
+            "color",
+            Endianness::Big,
+            "Wrapping<u16>",
+        );
+        assert_eq!(wrapped.get("color").unwrap().tag, None);
+        assert_eq!(wrapped.layout_id(), saturated.layout_id());
+        assert_ne!(wrapped.layout_id(), tagged.layout_id());
+        assert_eq!(
+            RecordLayout::new().nested("c", &tagged).layout_id(),
+            tagged.layout_id()
+        );
+
+        let mut file = Vec::new();
+        old.write_layout_header(&mut file).unwrap();
+        assert_eq!(file.len(), 8);
+        let mut cursor = Cursor::new(file.clone());
+        assert_eq!(renamed.check_layout_header(&mut cursor), Ok(()));
+        assert_eq!(cursor.position(), 8);
+
+        let mut cursor = Cursor::new(file);
+        let error = new.check_layout_header(&mut cursor).unwrap_err();
+        assert_eq!(
+            error,
+            LayoutHeaderError::Mismatch {
+                offset: 0,
+                expected: new.layout_id(),
+                found: old.layout_id()
+            }
+        );
+        assert!(error
+            .to_string()
+            .starts_with("record layout mismatch at offset 0"));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            reordered.check_layout_header(&mut cursor),
+            Err(LayoutHeaderError::Mismatch {
+                offset: 0,
+                expected: reordered.layout_id(),
+                found: old.layout_id()
+            })
+        );
+
+        assert_eq!(
+            old.check_layout_header(&mut Cursor::new(vec![0; 7])),
+            Err(LayoutHeaderError::Truncated { offset: 0 })
+        );
+    }
+}
@@ -7,7 +7,9 @@
 //! which represents a type which can be converted to and from bytes with a specific endianness.
 //!
 //! # Implementation
-//! The traits `E$eqByteReader` and `SeqByteReader` are implemented by default on types implementing `Read` + `Seek`.
+//! The traits `E$eqByteReader` and `SeqByteReader` are implemented on types implementing `SeqByteReaderCore`, a small object safe trait of
+//! filling buffers and moving the position, which is implemented by default on types implementing `Read` + `Seek`. Sources which are
+//! neither can implement `SeqByteReaderCore` directly.
 //!
 //! ## Example 1
 //! Using [`SizedNumber`] trait to convert numbers.
@@ -38,15 +40,103 @@
 //! assert_eq!(*s, *"hello");
 //! ```
 
-/// Contains the traits [`seqbytes::bytes::SeqByteReader`] and [`seqbytes::bytes::ESeqByteReader`]
+/// Contains [`seqbytes::aligned::AlignedVec`], a buffer with a chosen alignment.
+pub mod aligned;
+/// Contains [`seqbytes::ber::BerTag`] and [`seqbytes::ber::BerError`], used when reading BER and DER encoded elements.
+pub mod ber;
+/// Contains [`seqbytes::bitfield::BitLayout`], for unpacking and packing the bit fields of a storage word by name.
+pub mod bitfield;
+/// Contains the traits [`seqbytes::bytes::SeqByteReader`] and [`seqbytes::bytes::ESeqByteReader`], and their writer counterparts [`seqbytes::bytes::SeqByteWriter`] and [`seqbytes::bytes::ESeqByteWriter`]
 pub mod bytes;
+/// Contains [`seqbytes::crc::CrcAlgo`], the checksums used by checksummed frames.
+#[cfg(feature = "crc")]
+pub mod crc;
+/// Contains [`seqbytes::deterministic::DeterministicWriter`], a writer which zero-fills gaps and reports bytes never written.
+pub mod deterministic;
+/// Contains [`seqbytes::dispatch::TaggedDispatcher`], for reading streams of records by handing each to the parser registered for its tag.
+pub mod dispatch;
+/// Contains [`seqbytes::extsort::ExternalSort`], for sorting sources of fixed-size records which do not fit in memory.
+#[cfg(feature = "extsort")]
+pub mod extsort;
+/// Contains [`seqbytes::intern::InterningReader`], a reader which interns the strings read through it so duplicates share one allocation.
+pub mod intern;
+/// Contains [`seqbytes::layout::RecordLayout`], a runtime description of the fields of a record, and [`seqbytes::layout::ReadPlan`], for
+/// extracting fields at fixed offsets in one pass.
+pub mod layout;
+/// Contains [`seqbytes::num::SeqFloat`] and [`seqbytes::num::SeqInt`], which bridge to the numeric traits of `num_traits`.
+#[cfg(feature = "num-traits")]
+pub mod num;
+/// Contains [`seqbytes::prefetch::PrefetchReader`], a reader which fetches ahead on a background thread.
+#[cfg(feature = "threads")]
+pub mod prefetch;
 /// Re-exports everything from the module [`seqbytes::bytes`] and [`seqbytes::traits`]
 pub mod prelude;
+/// Contains [`seqbytes::progress::ProgressReader`], a reader which reports how many bytes have been read to a callback.
+pub mod progress;
+/// Contains [`seqbytes::reader::SeqReader`], a reader carrying configuration for how values are read through it, such as
+/// [`seqbytes::reader::Codecs`].
+pub mod reader;
+/// Contains [`seqbytes::resume::ResumableParser`], for parsing values which arrive split across several reads.
+pub mod resume;
+/// Contains [`seqbytes::schema::Schema`], for reading and writing records described at runtime as dynamic [`seqbytes::schema::Value`]s.
+pub mod schema;
+/// Contains [`seqbytes::shared::SharedReader`] and [`seqbytes::shared::ReaderFactory`], for reading one source from several positions and threads at once.
+pub mod shared;
+/// Contains [`seqbytes::sniff::FormatSniffer`], for detecting the format of a source from its magic numbers.
+pub mod sniff;
+/// Contains [`seqbytes::stats::StatsReader`], a reader which counts the bytes and calls going through it.
+pub mod stats;
+/// Contains [`seqbytes::strtab::StringTableBuilder`] and [`seqbytes::strtab::StringTable`], for writing and resolving pools of
+/// NUL-terminated strings referred to by offset.
+pub mod strtab;
+/// Contains functions for reversing the byte order of every element of a buffer in place, such as after a bulk read.
+pub mod swap;
+/// Contains [`seqbytes::testutil::assert_roundtrip`] and [`seqbytes::testutil::check_all_primitives`], for checking that implementations of
+/// the number traits survive a round trip.
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
+/// Contains [`seqbytes::trace::TraceReader`], a reader which records every call going through it for annotated dumps.
+pub mod trace;
 /// Contains all traits in this library.
 pub mod traits;
+/// Contains [`seqbytes::verify::VerifyingWriter`], a writer which reads back what it writes and reports bytes which did not stick.
+pub mod verify;
+
+/// Pins the exact bytes of every composite encoding the crate defines against the fixtures in `fixtures/golden`, so that a change to a
+/// format fails there until its fixture is changed along with it.
+///
+/// Each fixture is a text file of hexadecimal bytes, where whitespace is ignored and `#` starts a comment running to the end of the line.
+/// Running the tests with `SEQBYTES_BLESS=1` writes every fixture from what the writers produce, which is how new fixtures are created; the
+/// result must be checked by hand before it is committed.
+#[cfg(test)]
+mod golden;
+mod gsm7;
 
 #[cfg(test)]
 mod tests {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    /// An allocator which counts the bytes allocated by each thread.
+    struct Counting;
+
+    thread_local! {
+        static ALLOCATED: Cell<u64> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATED.try_with(|count| count.set(count.get() + layout.size() as u64));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: Counting = Counting;
 
     #[test]
     fn it_works() {
@@ -88,4 +178,3882 @@ mod tests {
         assert_eq!(num, -40891);
         assert_eq!(*s, *"hello");
     }
+
+    #[test]
+    fn bitvec_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        fn roundtrip(bits: &[bool], order: BitOrder, bytes: &[u8]) {
+            let mut a = Vec::new();
+            a.push_bitvec(bits, order).unwrap();
+            assert_eq!(a, bytes);
+
+            let mut cursor = Cursor::new(a);
+            assert_eq!(cursor.shift_bitvec(bits.len(), order).unwrap(), bits);
+            assert_eq!(cursor.position(), bytes.len() as u64);
+        }
+
+        roundtrip(&[true], BitOrder::LsbFirst, &[0b0000_0001]);
+        roundtrip(&[true], BitOrder::MsbFirst, &[0b1000_0000]);
+
+        let eight = [true, true, false, false, true, false, false, false];
+        roundtrip(&eight, BitOrder::LsbFirst, &[0b0001_0011]);
+        roundtrip(&eight, BitOrder::MsbFirst, &[0b1100_1000]);
+
+        let nine = [false, false, false, false, false, false, false, true, true];
+        roundtrip(&nine, BitOrder::LsbFirst, &[0b1000_0000, 0b0000_0001]);
+        roundtrip(&nine, BitOrder::MsbFirst, &[0b0000_0001, 0b1000_0000]);
+
+        let sixtyfour: Vec<bool> = (0..64).map(|i| i % 3 == 0).collect();
+        roundtrip(
+            &sixtyfour,
+            BitOrder::LsbFirst,
+            &0x9249_2492_4924_9249u64.to_le_bytes(),
+        );
+        roundtrip(
+            &sixtyfour,
+            BitOrder::MsbFirst,
+            &[0x92, 0x49, 0x24, 0x92, 0x49, 0x24, 0x92, 0x49],
+        );
+
+        // Padding bits are ignored when reading.
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF]);
+        assert_eq!(
+            cursor.shift_bitvec(9, BitOrder::MsbFirst).unwrap(),
+            [true; 9]
+        );
+        let mut cursor = Cursor::new(vec![0xFF]);
+        assert_eq!(cursor.shift_bitvec(9, BitOrder::LsbFirst), None);
+    }
+
+    #[test]
+    fn gsm7_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(vec![0xE8, 0x32, 0x9B, 0xFD, 0x46, 0x97, 0xD9, 0xEC, 0x37]);
+        assert_eq!(cursor.shift_gsm7(10).unwrap(), "hellohello");
+        assert_eq!(cursor.position(), 9);
+
+        // 8 septets fill 7 bytes exactly, with no fill bits.
+        let mut cursor = Cursor::new(vec![0x61, 0xF1, 0x98, 0x5C, 0x36, 0x9F, 0xD1]);
+        assert_eq!(cursor.shift_gsm7(8).unwrap(), "abcdefgh");
+        assert_eq!(cursor.position(), 7);
+
+        // 7 septets leave 7 fill bits in the last byte, which must not be decoded as an extra '@' (septet 0), nor as the CR (septet 0x0D)
+        // that 3GPP TS 23.038 recommends as filler.
+        let mut cursor = Cursor::new(vec![0x61, 0xF1, 0x98, 0x5C, 0x36, 0x9F, 0x01]);
+        assert_eq!(cursor.shift_gsm7(7).unwrap(), "abcdefg");
+        let mut cursor = Cursor::new(vec![0x61, 0xF1, 0x98, 0x5C, 0x36, 0x9F, 0x1B]);
+        assert_eq!(cursor.shift_gsm7(7).unwrap(), "abcdefg");
+
+        // Basic table characters, and an escape sequence into the extension table.
+        let mut cursor = Cursor::new(vec![0x80, 0x4D, 0x19]);
+        assert_eq!(cursor.shift_gsm7(3).unwrap(), "@€");
+
+        let mut cursor = Cursor::new(vec![0xE8, 0x32]);
+        assert_eq!(cursor.shift_gsm7(3), None);
+    }
+
+    #[test]
+    fn deinterleave_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        fn interleave(channels: &[Vec<i16>], bigendian: bool) -> Vec<u8> {
+            let mut a = Vec::new();
+            for frame in 0..channels[0].len() {
+                for channel in channels {
+                    a.push_e(channel[frame], bigendian).unwrap();
+                }
+            }
+            a
+        }
+
+        let stereo = vec![vec![1, -2, 300, i16::MIN], vec![-1, 2, -300, i16::MAX]];
+        let mut cursor = Cursor::new(interleave(&stereo, false));
+        assert_eq!(
+            cursor.shift_deinterleaved::<i16>(4, 2, false).unwrap(),
+            stereo
+        );
+        assert_eq!(cursor.position(), 16);
+
+        let surround: Vec<Vec<i16>> = (0..6)
+            .map(|c| (0..5).map(|f| c * 1000 - f * 7).collect())
+            .collect();
+        let mut cursor = Cursor::new(interleave(&surround, true));
+        assert_eq!(
+            cursor.shift_deinterleaved::<i16>(5, 6, true).unwrap(),
+            surround
+        );
+
+        let mut cursor = Cursor::new(vec![1, 2, 3]);
+        assert_eq!(
+            cursor.shift_deinterleaved::<i16>(4, 0, false).unwrap(),
+            Vec::<Vec<i16>>::new()
+        );
+        assert_eq!(
+            cursor.shift_deinterleaved::<i16>(0, 3, false).unwrap(),
+            vec![Vec::<i16>::new(); 3]
+        );
+        assert_eq!(cursor.position(), 0);
+
+        // A truncated stream does not return partially filled channels.
+        let mut cursor = Cursor::new(interleave(&stereo, false)[..15].to_vec());
+        assert_eq!(cursor.shift_deinterleaved::<i16>(4, 2, false), None);
+    }
+
+    #[test]
+    fn strided_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        fn table(count: u32, stride: usize) -> Vec<u8> {
+            let mut a = Vec::new();
+            for i in 0..count {
+                let mut record = vec![0xAAu8; stride];
+                record[12..16].copy_from_slice(&(i * 3).to_be_bytes());
+                a.extend(record);
+            }
+            a
+        }
+
+        let expected: Vec<u32> = (0..100).map(|i| i * 3).collect();
+
+        // Small table, read in one go.
+        let mut cursor = Cursor::new(table(100, 64));
+        assert_eq!(
+            cursor.shift_strided::<u32>(100, 64, 12, true).unwrap(),
+            expected
+        );
+        assert_eq!(cursor.position(), 6400);
+
+        // Large gaps between fields, read by seeking.
+        let mut cursor = Cursor::new(table(100, 8192));
+        cursor.set_position(8192 * 10);
+        assert_eq!(
+            cursor.shift_strided::<u32>(90, 8192, 12, true).unwrap(),
+            expected[10..]
+        );
+        assert_eq!(cursor.position(), 8192 * 100);
+
+        // Running past the end restores the position, in both modes.
+        for stride in [64, 8192] {
+            let mut cursor = Cursor::new(table(100, stride));
+            cursor.set_position(stride as u64);
+            assert_eq!(cursor.shift_strided::<u32>(100, stride, 12, true), None);
+            assert_eq!(cursor.position(), stride as u64);
+        }
+
+        // A last record cut short after its field is not enough to shift past it.
+        let mut short = table(3, 64);
+        short.truncate(64 * 2 + 16);
+        let mut cursor = Cursor::new(short);
+        assert_eq!(cursor.shift_strided::<u32>(3, 64, 12, true), None);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            cursor.shift_strided::<u32>(2, 64, 12, true).unwrap(),
+            expected[..2]
+        );
+
+        // Misconfigured geometry is rejected up front.
+        let mut cursor = Cursor::new(table(100, 64));
+        assert_eq!(cursor.shift_strided::<u32>(1, 64, 61, true), None);
+        assert_eq!(cursor.shift_strided::<u32>(1, 2, 0, true), None);
+        assert_eq!(cursor.shift_strided::<u32>(0, 64, 60, true).unwrap(), []);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn aligned_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let values: Vec<f32> = (0..37).map(|i| i as f32 * 0.25 - 3.0).collect();
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_bytes()).collect();
+
+        for align in [4, 16, 32, 64, 4096] {
+            let mut cursor = Cursor::new(bytes.clone());
+            let aligned = cursor
+                .shift_vec_aligned::<f32>(values.len(), align)
+                .unwrap();
+
+            assert_eq!(aligned.as_ptr() as usize % align, 0);
+            assert_eq!(aligned.align(), align);
+            assert_eq!(*aligned, *values);
+            assert_eq!(cursor.position(), bytes.len() as u64);
+        }
+
+        let mut cursor = Cursor::new(bytes.clone());
+        let aligned = cursor.shift_vec_aligned::<u8>(5, 32).unwrap();
+        assert_eq!(aligned.as_ptr() as usize % 32, 0);
+        assert_eq!(*aligned, bytes[..5]);
+
+        let empty = cursor.shift_vec_aligned::<u64>(0, 32).unwrap();
+        assert_eq!(empty.as_ptr() as usize % 32, 0);
+        assert!(empty.is_empty());
+
+        // The alignment must be a power of two, and at least the alignment of the type.
+        assert!(cursor.shift_vec_aligned::<f32>(1, 24).is_none());
+        assert!(cursor.shift_vec_aligned::<f32>(1, 0).is_none());
+        assert!(cursor.shift_vec_aligned::<f32>(1, 2).is_none());
+        assert_eq!(cursor.position(), 5);
+
+        // Not enough bytes to be read.
+        assert!(cursor.shift_vec_aligned::<f32>(values.len(), 32).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn zstd_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let data: Vec<u8> = (0..10_000u32)
+            .flat_map(|i| (i % 97).to_le_bytes())
+            .collect();
+
+        for (level, bigendian) in [(1, false), (19, true)] {
+            let mut cursor = Cursor::new(Vec::new());
+            cursor.push_zstd_block(&data, level, bigendian).unwrap();
+            cursor.push_zstd_block(&[], level, bigendian).unwrap();
+            cursor.set_position(0);
+
+            assert_eq!(
+                cursor.shift_zstd_block(bigendian, data.len()).unwrap(),
+                data
+            );
+            assert_eq!(cursor.shift_zstd_block(bigendian, 0).unwrap(), []);
+            assert_eq!(cursor.shift::<u8>(), None);
+        }
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_zstd_block(&data, 3, false).unwrap();
+        let block = cursor.into_inner();
+
+        // The raw length exceeds the limit.
+        let mut cursor = Cursor::new(block.clone());
+        assert_eq!(cursor.shift_zstd_block(false, data.len() - 1), None);
+        assert_eq!(cursor.position(), 0);
+
+        // A corrupted payload fails to decompress.
+        let mut corrupted = block.clone();
+        corrupted[8..16].copy_from_slice(&[0xFF; 8]);
+        let mut cursor = Cursor::new(corrupted);
+        assert_eq!(cursor.shift_zstd_block(false, data.len()), None);
+        assert_eq!(cursor.position(), 0);
+
+        // A raw length which disagrees with the decompressed size is an error, not a truncation.
+        let mut mislabeled = block.clone();
+        mislabeled[4..8].copy_from_slice(&(data.len() as u32 - 1).to_le_bytes());
+        let mut cursor = Cursor::new(mislabeled);
+        assert_eq!(cursor.shift_zstd_block(false, data.len()), None);
+
+        // A truncated payload.
+        let mut cursor = Cursor::new(block[..block.len() - 1].to_vec());
+        assert_eq!(cursor.shift_zstd_block(false, data.len()), None);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn slice_into_vec_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let data: Vec<u8> = (0..=255).collect();
+        let mut cursor = Cursor::new(data.clone());
+
+        let mut buf = Vec::with_capacity(64);
+        let (capacity, ptr) = (buf.capacity(), buf.as_ptr());
+
+        let mut offset = 0;
+        for amount in [10, 64, 0, 1, 33, 64] {
+            cursor.shift_slice_into_vec(amount, &mut buf).unwrap();
+            assert_eq!(buf, data[offset..offset + amount]);
+            offset += amount;
+
+            // No reallocation happens while the capacity suffices.
+            assert_eq!(buf.capacity(), capacity);
+            assert_eq!(buf.as_ptr(), ptr);
+        }
+
+        cursor.shift_slice_into_vec(65, &mut buf).unwrap();
+        assert_eq!(buf, data[offset..offset + 65]);
+        assert!(buf.capacity() >= 65);
+
+        // Failure leaves the buffer empty and the position restored.
+        let position = cursor.position();
+        assert_eq!(cursor.shift_slice_into_vec(1000, &mut buf), None);
+        assert!(buf.is_empty());
+        assert_eq!(cursor.position(), position);
+
+        let mut cursor = Cursor::new(b"hello world\xFF".to_vec());
+        let mut s = String::with_capacity(32);
+        let ptr = s.as_ptr();
+
+        cursor.shift_string_into(5, &mut s).unwrap();
+        assert_eq!(s, "hello");
+        cursor.shift_string_into(6, &mut s).unwrap();
+        assert_eq!(s, " world");
+        assert_eq!(s.as_ptr(), ptr);
+
+        assert_eq!(cursor.shift_string_into(2, &mut s), None);
+        assert!(s.is_empty());
+        assert_eq!(cursor.position(), 11);
+
+        cursor.shift_string_into(1, &mut s).unwrap();
+        assert_eq!(s, "\u{FFFD}");
+    }
+
+    #[test]
+    fn extend_test() {
+        use crate::prelude::*;
+        use std::collections::{BTreeMap, HashSet};
+        use std::io::Cursor;
+
+        let ids = [7u32, 3, 7, 9, 3, 3];
+        let mut a = Vec::new();
+        for id in ids {
+            a.push_e(id, true).unwrap();
+        }
+
+        let mut cursor = Cursor::new(a.clone());
+        let mut v = vec![1u32];
+        assert_eq!(cursor.shift_extend::<u32, _>(6, &mut v, true), Some(6));
+        assert_eq!(v, [1, 7, 3, 7, 9, 3, 3]);
+
+        let mut cursor = Cursor::new(a.clone());
+        let mut seen = HashSet::new();
+        assert_eq!(cursor.shift_extend::<u32, _>(6, &mut seen, true), Some(6));
+        assert_eq!(seen, HashSet::from([3, 7, 9]));
+
+        // Counts occurrences of each key.
+        struct Counter(BTreeMap<u32, usize>);
+        impl Extend<u32> for Counter {
+            fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+                for key in iter {
+                    *self.0.entry(key).or_default() += 1;
+                }
+            }
+        }
+
+        let mut cursor = Cursor::new(a.clone());
+        let mut counter = Counter(BTreeMap::new());
+        assert_eq!(
+            cursor.shift_extend::<u32, _>(6, &mut counter, true),
+            Some(6)
+        );
+        assert_eq!(counter.0, BTreeMap::from([(3, 3), (7, 2), (9, 1)]));
+
+        // Failure partway leaves the collection untouched.
+        let mut cursor = Cursor::new(a[..23].to_vec());
+        assert_eq!(cursor.shift_extend::<u32, _>(6, &mut v, true), None);
+        assert_eq!(v.len(), 7);
+
+        let mut cursor = Cursor::new(a);
+        assert_eq!(
+            cursor.shift_vec::<u32>(2).unwrap(),
+            [0x0700_0000, 0x0300_0000]
+        );
+        assert_eq!(cursor.shift_vec_e::<u32>(4, true).unwrap(), [7, 9, 3, 3]);
+        assert_eq!(cursor.shift_vec::<u32>(0).unwrap(), []);
+    }
+
+    /// Builds a table behind an 8-byte header of 16-byte records, each a u32 payload followed by a big-endian u64 key.
+    fn sorted_records(keys: &[u64]) -> Vec<u8> {
+        use crate::prelude::*;
+
+        let mut a = vec![0xAA; 8];
+        for (i, key) in keys.iter().enumerate() {
+            a.push_e(i as u32, false).unwrap();
+            a.push_e(*key, true).unwrap();
+            a.push_slice(&[0; 4]).unwrap();
+        }
+        a
+    }
+
+    #[test]
+    fn binary_search_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let keys: Vec<u64> = (0..1000).map(|i| 10 + i / 3 * 5).collect();
+        let mut cursor = Cursor::new(sorted_records(&keys));
+        let mut search = |needle: u64| {
+            cursor
+                .binary_search_record(8, keys.len() as u64, 16, 4, true, &needle)
+                .unwrap()
+        };
+
+        // Duplicates return the first record.
+        assert_eq!(
+            search(15),
+            SearchResult::Found {
+                index: 3,
+                offset: 8 + 3 * 16
+            }
+        );
+        assert_eq!(
+            search(10),
+            SearchResult::Found {
+                index: 0,
+                offset: 8
+            }
+        );
+        let last = *keys.last().unwrap();
+        assert_eq!(
+            search(last),
+            SearchResult::Found {
+                index: 999,
+                offset: 8 + 999 * 16
+            }
+        );
+
+        assert_eq!(
+            search(0),
+            SearchResult::NotFound {
+                index: 0,
+                offset: 8
+            }
+        );
+        assert_eq!(
+            search(12),
+            SearchResult::NotFound {
+                index: 3,
+                offset: 8 + 3 * 16
+            }
+        );
+        assert_eq!(
+            search(u64::MAX),
+            SearchResult::NotFound {
+                index: 1000,
+                offset: 8 + 1000 * 16
+            }
+        );
+
+        // The position is left at the found record.
+        let mut cursor = Cursor::new(sorted_records(&keys));
+        let result = cursor
+            .binary_search_record(8, 1000, 16, 4, true, &500u64)
+            .unwrap();
+        assert!(result.is_found());
+        assert_eq!(cursor.position(), result.offset());
+        assert_eq!(cursor.shift::<u32>().unwrap() as u64, result.index());
+
+        // An empty table, a key which does not fit, and a table running past the end.
+        assert_eq!(
+            cursor
+                .binary_search_record(8, 0, 16, 4, true, &5u64)
+                .unwrap(),
+            SearchResult::NotFound {
+                index: 0,
+                offset: 8
+            }
+        );
+        cursor.set_position(3);
+        assert_eq!(cursor.binary_search_record(8, 10, 16, 9, true, &5u64), None);
+        assert_eq!(
+            cursor.binary_search_record(8, 2000, 16, 4, true, &u64::MAX),
+            None
+        );
+        assert_eq!(cursor.position(), 3);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "records are not sorted by their key")]
+    fn binary_search_unsorted_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(sorted_records(&[5, 1, 9]));
+        cursor.binary_search_record(8, 3, 16, 4, true, &1u64);
+    }
+
+    #[test]
+    #[cfg(feature = "crc")]
+    fn checked_frame_test() {
+        use crate::crc::{CrcAlgo, FrameCheckError};
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        for algo in [CrcAlgo::Crc32, CrcAlgo::Crc32c] {
+            for bigendian in [false, true] {
+                let mut cursor = Cursor::new(Vec::new());
+                cursor
+                    .push_checked_frame(b"first", bigendian, algo)
+                    .unwrap();
+                cursor.push_checked_frame(&[], bigendian, algo).unwrap();
+                cursor.set_position(0);
+
+                assert_eq!(
+                    cursor.shift_checked_frame(bigendian, algo).unwrap(),
+                    b"first"
+                );
+                assert_eq!(cursor.shift_checked_frame(bigendian, algo).unwrap(), b"");
+                assert_eq!(
+                    cursor.shift_checked_frame(bigendian, algo),
+                    Err(FrameCheckError::Truncated { offset: 17 })
+                );
+            }
+        }
+
+        let mut cursor = Cursor::new(vec![0xAA; 3]);
+        cursor.set_position(3);
+        cursor
+            .push_checked_frame(b"123456789", false, CrcAlgo::Crc32)
+            .unwrap();
+        let frame = cursor.into_inner();
+        assert_eq!(frame[3..5], [9, 0]);
+        assert_eq!(frame[14..], 0xCBF43926u32.to_le_bytes());
+
+        // A single flipped bit in the payload.
+        let mut corrupted = frame.clone();
+        corrupted[7] ^= 0x10;
+        let mut payload = *b"123456789";
+        payload[2] ^= 0x10;
+        let mut cursor = Cursor::new(corrupted);
+        cursor.set_position(3);
+        assert_eq!(
+            cursor.shift_checked_frame(false, CrcAlgo::Crc32),
+            Err(FrameCheckError::Mismatch {
+                offset: 3,
+                expected: 0xCBF43926,
+                computed: CrcAlgo::Crc32.checksum(&payload),
+            })
+        );
+        assert_eq!(cursor.position(), 3);
+
+        let mut cursor = Cursor::new(frame[..frame.len() - 1].to_vec());
+        cursor.set_position(3);
+        assert_eq!(
+            cursor.shift_checked_frame(false, CrcAlgo::Crc32),
+            Err(FrameCheckError::Truncated { offset: 3 })
+        );
+        assert_eq!(cursor.position(), 3);
+
+        assert_eq!(
+            Vec::new().push_checked_frame(&vec![0; 65536], false, CrcAlgo::Crc32),
+            None
+        );
+    }
+
+    #[test]
+    fn shift_back_test() {
+        use crate::prelude::*;
+        use std::io::{Cursor, Seek, SeekFrom};
+
+        const EOCD_SIGNATURE: u32 = 0x06054B50;
+
+        // Local file data, then an end of central directory record with a comment.
+        let mut zip = vec![0x50, 0x4B, 0x03, 0x04, 0xAA, 0xBB, 0xCC];
+        zip.push_e(EOCD_SIGNATURE, false).unwrap();
+        zip.push_e(0u16, false).unwrap();
+        zip.push_e(1u16, false).unwrap();
+        zip.push_e(3u16, false).unwrap();
+        zip.push_e(4u16, false).unwrap();
+        zip.push_e(0x5Cu32, false).unwrap();
+        zip.push_e(0x1234u32, false).unwrap();
+        zip.push_e(4u16, false).unwrap();
+        zip.extend_from_slice(b"PK\x05!");
+
+        let mut cursor = Cursor::new(zip);
+
+        // The record is at least 22 bytes long, so the scan starts 22 bytes before the end and moves backwards by the length of the comment.
+        cursor.seek(SeekFrom::End(-22)).unwrap();
+        while cursor.next_e::<u32>(false) != Some(EOCD_SIGNATURE) {
+            cursor.shift_back::<u8>().unwrap();
+        }
+        let eocd = cursor.position();
+        assert_eq!(eocd, 7);
+
+        cursor.seek(SeekFrom::Start(eocd + 22)).unwrap();
+        assert_eq!(cursor.shift_back_e::<u16>(false), Some(4));
+        assert_eq!(cursor.shift_back_e::<u32>(false), Some(0x1234));
+        assert_eq!(cursor.shift_back_e::<u32>(false), Some(0x5C));
+        assert_eq!(cursor.shift_back_e::<u16>(false), Some(4));
+        assert_eq!(cursor.shift_back_e::<u16>(false), Some(3));
+        assert_eq!(cursor.shift_back_e::<u16>(false), Some(1));
+        assert_eq!(cursor.shift_back_e::<u16>(false), Some(0));
+        assert_eq!(cursor.shift_back_e::<u32>(false), Some(EOCD_SIGNATURE));
+        assert_eq!(cursor.position(), eocd);
+
+        assert_eq!(
+            cursor.shift_slice_back(4).unwrap(),
+            [0x04, 0xAA, 0xBB, 0xCC]
+        );
+        assert_eq!(cursor.shift_back_e::<u32>(false), None);
+        assert_eq!(cursor.position(), 3);
+        assert_eq!(cursor.shift_back_e::<u16>(true), Some(0x4B03));
+        assert_eq!(cursor.shift_back::<u8>(), Some(0x50));
+        assert_eq!(cursor.shift_back::<u8>(), None);
+        assert_eq!(cursor.shift_slice_back(0).unwrap(), []);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn normalized_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        assert_eq!(Unorm8(0).to_f32(), 0.0);
+        assert_eq!(Unorm8(255).to_f32(), 1.0);
+        assert_eq!(Unorm16(0).to_f32(), 0.0);
+        assert_eq!(Unorm16(65535).to_f32(), 1.0);
+        assert_eq!(Snorm8(0).to_f32(), 0.0);
+        assert_eq!(Snorm8(127).to_f32(), 1.0);
+        assert_eq!(Snorm8(-127).to_f32(), -1.0);
+        assert_eq!(Snorm8(-128).to_f32(), -1.0);
+        assert_eq!(Snorm16(0).to_f32(), 0.0);
+        assert_eq!(Snorm16(32767).to_f32(), 1.0);
+        assert_eq!(Snorm16(-32767).to_f32(), -1.0);
+        assert_eq!(Snorm16(-32768).to_f32(), -1.0);
+
+        assert_eq!(Unorm8::from_f32(-0.5), Unorm8(0));
+        assert_eq!(Unorm8::from_f32(1.5), Unorm8(255));
+        assert_eq!(Unorm8::from_f32(f32::NAN), Unorm8(0));
+        assert_eq!(Unorm16::from_f32(f32::INFINITY), Unorm16(65535));
+        assert_eq!(Snorm8::from_f32(-2.0), Snorm8(-127));
+        assert_eq!(Snorm8::from_f32(2.0), Snorm8(127));
+        assert_eq!(Snorm16::from_f32(f32::NEG_INFINITY), Snorm16(-32767));
+        assert_eq!(Snorm16::from_f32(f32::NAN), Snorm16(0));
+
+        // Every code survives a round trip through f32.
+        for c in 0..=u8::MAX {
+            assert_eq!(Unorm8::from_f32(Unorm8(c).to_f32()), Unorm8(c));
+        }
+        for c in 0..=u16::MAX {
+            assert_eq!(Unorm16::from_f32(Unorm16(c).to_f32()), Unorm16(c));
+        }
+        for c in -127..=i8::MAX {
+            assert_eq!(Snorm8::from_f32(Snorm8(c).to_f32()), Snorm8(c));
+        }
+        for c in -32767..=i16::MAX {
+            assert_eq!(Snorm16::from_f32(Snorm16(c).to_f32()), Snorm16(c));
+        }
+
+        // Every float in range is off by at most half a step after a round trip.
+        for i in 0..=10000 {
+            let f = i as f32 / 10000.0;
+            assert!((Unorm8::from_f32(f).to_f32() - f).abs() <= 0.5 / 255.0 + 1e-6);
+            assert!((Unorm16::from_f32(f).to_f32() - f).abs() <= 0.5 / 65535.0 + 1e-6);
+
+            let f = f * 2.0 - 1.0;
+            assert!((Snorm8::from_f32(f).to_f32() - f).abs() <= 0.5 / 127.0 + 1e-6);
+            assert!((Snorm16::from_f32(f).to_f32() - f).abs() <= 0.5 / 32767.0 + 1e-6);
+        }
+
+        let mut cursor = Cursor::new(vec![0, 128, 255, 64, 0x00, 0x80, 0xFF, 0x7F]);
+        let rgba = cursor.shift::<[Unorm8; 4]>().unwrap();
+        assert_eq!(rgba, [Unorm8(0), Unorm8(128), Unorm8(255), Unorm8(64)]);
+        assert_eq!(cursor.shift_e::<Snorm16>(false), Some(Snorm16(-32768)));
+        assert_eq!(cursor.shift_e::<Snorm16>(true), Some(Snorm16(-129)));
+        assert_eq!(<[Unorm16; 3]>::size(), 6);
+        assert_eq!(<[Unorm8; 2]>::from_bytes(&[1, 2, 3]), None);
+        assert_eq!(rgba.to_bytes(), [0, 128, 255, 64]);
+    }
+
+    #[test]
+    #[cfg(feature = "num-complex")]
+    fn complex_test() {
+        use crate::prelude::*;
+        use num_complex::Complex;
+        use std::io::Cursor;
+
+        assert_eq!(Complex::<f32>::size(), 8);
+        assert_eq!(Complex::<f64>::size(), 16);
+
+        // The real part comes first, and each part is swapped on its own.
+        let iq = Complex::new(1.0f32, -2.0);
+        assert_eq!(iq.to_bytes_e(false), [0, 0, 0x80, 0x3F, 0, 0, 0, 0xC0]);
+        assert_eq!(iq.to_bytes_e(true), [0x3F, 0x80, 0, 0, 0xC0, 0, 0, 0]);
+        assert_eq!(iq.to_bytes(), iq.to_bytes_e(false));
+        assert_eq!(
+            Complex::<f32>::from_bytes_e(&[0x3F, 0x80, 0, 0, 0xC0, 0, 0, 0], true),
+            Some(iq)
+        );
+        assert_eq!(Complex::<f32>::from_bytes(&[0; 7]), None);
+
+        let samples = [
+            Complex::new(f64::NAN, f64::INFINITY),
+            Complex::new(f64::NEG_INFINITY, -0.0),
+            Complex::new(1e-300, f64::MAX),
+        ];
+        for bigendian in [false, true] {
+            let mut cursor = Cursor::new(Vec::new());
+            for sample in samples {
+                cursor.push_e(sample, bigendian).unwrap();
+            }
+            cursor.set_position(0);
+
+            let read = cursor.shift_vec_e::<Complex<f64>>(3, bigendian).unwrap();
+            assert!(read[0].re.is_nan() && read[0].im == f64::INFINITY);
+            assert_eq!(read[1].re, f64::NEG_INFINITY);
+            assert!(read[1].im == 0.0 && read[1].im.is_sign_negative());
+            assert_eq!(read[2], samples[2]);
+            assert_eq!(cursor.shift_e::<Complex<f64>>(bigendian), None);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn f16_test() {
+        use crate::prelude::*;
+        use half::f16;
+        use std::io::Cursor;
+
+        assert_eq!(f16::size(), 2);
+
+        let values = [
+            f16::ONE,
+            f16::from_f32(-65504.0),
+            f16::MIN_POSITIVE_SUBNORMAL,
+            f16::from_bits(0x03FF),
+            f16::INFINITY,
+            f16::NEG_INFINITY,
+            f16::NEG_ZERO,
+            f16::from_bits(0x7E01),
+            f16::from_bits(0xFD55),
+        ];
+        for bigendian in [false, true] {
+            let mut cursor = Cursor::new(Vec::new());
+            for value in values {
+                cursor.push_e(value, bigendian).unwrap();
+            }
+            assert_eq!(cursor.get_ref().len(), 18);
+
+            cursor.set_position(0);
+            for value in values {
+                // Compared by bits, so that NaN payloads and the sign of zero are checked as well.
+                assert_eq!(
+                    cursor.shift_e::<f16>(bigendian).unwrap().to_bits(),
+                    value.to_bits()
+                );
+            }
+        }
+
+        assert_eq!(f16::ONE.to_bytes_e(true), [0x3C, 0x00]);
+        assert_eq!(f16::ONE.to_bytes_e(false), [0x00, 0x3C]);
+        assert_eq!(f16::from_bits(0x0001).to_f32(), 2f32.powi(-24));
+        assert_eq!(f16::from_bytes_e(&[0x3C], true), None);
+    }
+
+    #[test]
+    fn nonzero_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::num::{NonZeroI16, NonZeroI8, NonZeroU128, NonZeroU32, NonZeroU64};
+
+        assert_eq!(Option::<NonZeroU32>::size(), 4);
+        assert_eq!(Option::<NonZeroU128>::size(), 16);
+        assert_eq!(Option::<NonZeroU32>::from_bytes(&[0; 4]), Some(None));
+        assert_eq!(Option::<NonZeroU32>::from_bytes(&[0; 3]), None);
+        assert_eq!(
+            Option::<NonZeroI8>::from_bytes(&[0xFF]),
+            Some(NonZeroI8::new(-1))
+        );
+        assert_eq!(None::<NonZeroU64>.to_bytes_e(true), [0; 8]);
+
+        for value in [1u32, 7, 0x12345678, u32::MAX] {
+            let index = NonZeroU32::new(value);
+            for bigendian in [false, true] {
+                let bytes = index.to_bytes_e(bigendian);
+                assert_eq!(bytes, value.to_bytes_e(bigendian));
+                assert_eq!(
+                    Option::<NonZeroU32>::from_bytes_e(&bytes, bigendian),
+                    Some(index)
+                );
+            }
+        }
+
+        // A record of a u16 tag followed by parent and sibling indexes, where 0 is the null reference.
+        let mut cursor = Cursor::new(vec![0x01, 0x00, 0, 0, 0, 0, 0x00, 0x00, 0x00, 0x05]);
+        assert_eq!(cursor.shift_e::<u16>(false), Some(1));
+        assert_eq!(cursor.shift_e::<Option<NonZeroU32>>(true), Some(None));
+        assert_eq!(
+            cursor.shift_e::<Option<NonZeroU32>>(true),
+            Some(NonZeroU32::new(5))
+        );
+
+        let mut cursor = Cursor::new(vec![0, 0, 0xFE, 0xFF, 1, 0]);
+        assert_eq!(
+            cursor.shift::<[Option<NonZeroI16>; 3]>(),
+            Some([None, NonZeroI16::new(-2), NonZeroI16::new(1)])
+        );
+    }
+
+    #[test]
+    fn custom_core_test() {
+        use crate::prelude::*;
+
+        /// A source of fixed-size blocks which is not `Read` or `Seek`, like a client of a remote block store.
+        struct BlockStore {
+            blocks: Vec<[u8; 4]>,
+            pos: u64,
+        }
+
+        impl SeqByteReaderCore for BlockStore {
+            fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+                for byte in buf.iter_mut() {
+                    let block = self.blocks.get(self.pos as usize / 4)?;
+                    *byte = block[self.pos as usize % 4];
+                    self.pos += 1;
+                }
+
+                Some(())
+            }
+
+            fn pos(&mut self) -> Option<u64> {
+                Some(self.pos)
+            }
+
+            fn set_pos(&mut self, pos: u64) -> Option<()> {
+                self.pos = pos;
+                Some(())
+            }
+        }
+
+        let mut store = BlockStore {
+            blocks: vec![[1, 0, 0, 0], [0x68, 0x65, 0x6C, 0x6C], [0x6F, 0xFF, 0, 0]],
+            pos: 0,
+        };
+
+        assert_eq!(store.next::<u32>(), Some(1));
+        assert_eq!(store.pos(), Some(0));
+        assert_eq!(store.shift::<u32>(), Some(1));
+        assert_eq!(store.next_string(2).unwrap(), "he");
+        assert_eq!(store.shift_string(5).unwrap(), "hello");
+        assert_eq!(store.shift_e::<u16>(true), Some(0xFF00));
+        assert_eq!(store.shift_slice_back(3).unwrap(), [0x6F, 0xFF, 0x00]);
+        assert_eq!(store.pos(), Some(8));
+
+        // Generic code can take the core as a trait object, and still use the extension traits.
+        fn tag(reader: &mut dyn SeqByteReaderCore) -> Option<u8> {
+            reader.shift::<u8>()
+        }
+        assert_eq!(tag(&mut store), Some(0x6F));
+        assert_eq!(store.shift::<u32>(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn pod_slice_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct Vertex {
+            position: [f32; 2],
+            color: u32,
+        }
+
+        // SAFETY: `Vertex` is `repr(C)` without padding, and every bit pattern is a valid value of its fields.
+        unsafe impl bytemuck::Zeroable for Vertex {}
+        // SAFETY: as above.
+        unsafe impl bytemuck::Pod for Vertex {}
+
+        let vertices: Vec<Vertex> = (0..1000u32)
+            .map(|i| Vertex {
+                position: [i as f32, -(i as f32) / 2.0],
+                color: i.wrapping_mul(0x01010101),
+            })
+            .collect();
+
+        // A trailing byte which does not make up a whole vertex.
+        let mut bytes = bytemuck::cast_slice::<Vertex, u8>(&vertices).to_vec();
+        bytes.push(0xEE);
+        let mut cursor = Cursor::new(bytes);
+
+        let read = cursor.shift_pod_slice::<Vertex>(1000).unwrap();
+        assert_eq!(read, vertices);
+        assert_eq!(read.as_ptr() as usize % std::mem::align_of::<Vertex>(), 0);
+        assert_eq!(cursor.shift_pod_slice::<Vertex>(0).unwrap(), []);
+        assert_eq!(cursor.shift_pod_slice::<Vertex>(1), None);
+        assert_eq!(cursor.position(), 12000);
+
+        // On a little-endian host, the reinterpreted values match element-wise little-endian decoding.
+        cursor.set_position(0);
+        let decoded: Vec<Vertex> = (0..1000)
+            .map(|_| Vertex {
+                position: [
+                    cursor.shift_e::<f32>(false).unwrap(),
+                    cursor.shift_e::<f32>(false).unwrap(),
+                ],
+                color: cursor.shift_e::<u32>(false).unwrap(),
+            })
+            .collect();
+        if cfg!(target_endian = "little") {
+            assert_eq!(decoded, read);
+        }
+
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.shift_pod_slice::<u32>(3000).unwrap(),
+            cursor.get_ref()[..12000]
+                .chunks_exact(4)
+                .map(|c| u32::from_ne_bytes(c.try_into().unwrap()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(cursor.shift_pod_slice::<u64>(usize::MAX), None);
+
+        // A count read from a corrupt header is rejected against the end of the source before anything is allocated for it.
+        let before = ALLOCATED.with(Cell::get);
+        assert_eq!(cursor.shift_pod_slice::<u64>(1 << 40), None);
+        assert_eq!(cursor.shift_pod_slice::<Vertex>(1001), None);
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+        assert_eq!(cursor.position(), 12000);
+    }
+
+    #[test]
+    fn const_conversions_test() {
+        use crate::traits::*;
+
+        const MAGIC: [u8; 4] = u32_to_be_array(0x52494646);
+        const HEADER: u16 = u16_from_le_array([0x34, 0x12]);
+        const ONE: [u8; 8] = f64_to_le_array(1.0);
+        const TABLE: [u32; 4] = {
+            let mut table = [0; 4];
+            let mut i = 0;
+            while i < 4 {
+                table[i] = u32_from_be_array([i as u8, 0, 0, 1]);
+                i += 1;
+            }
+            table
+        };
+
+        assert_eq!(&MAGIC, b"RIFF");
+        assert_eq!(HEADER, 0x1234);
+        assert_eq!(ONE.to_vec(), 1.0f64.to_bytes());
+        assert_eq!(TABLE, [1, 0x01000001, 0x02000001, 0x03000001]);
+
+        assert_eq!(i8_to_le_array(-2).to_vec(), (-2i8).to_bytes_e(true));
+        assert_eq!(i16_to_be_array(-2).to_vec(), (-2i16).to_bytes_e(true));
+        assert_eq!(u64_to_le_array(7).to_vec(), 7u64.to_bytes_e(false));
+        assert_eq!(f32_to_be_array(-1.5).to_vec(), (-1.5f32).to_bytes_e(true));
+        assert_eq!(
+            i128_to_be_array(i128::MIN + 3).to_vec(),
+            (i128::MIN + 3).to_bytes_e(true)
+        );
+        assert_eq!(
+            u128_from_le_array([9; 16]),
+            u128::from_bytes(&[9; 16]).unwrap()
+        );
+        assert_eq!(
+            i64_from_be_array([0x80, 0, 0, 0, 0, 0, 0, 1]),
+            i64::from_bytes_e(&[0x80, 0, 0, 0, 0, 0, 0, 1], true).unwrap()
+        );
+        assert_eq!(
+            i32_from_le_array([1, 2, 3, 4]),
+            i32::from_bytes(&[1, 2, 3, 4]).unwrap()
+        );
+        assert_eq!(
+            f64_from_be_array(2.5f64.to_be_bytes()),
+            f64::from_bytes_e(&2.5f64.to_be_bytes(), true).unwrap()
+        );
+        assert_eq!(u8_from_be_array([200]), 200);
+    }
+
+    #[test]
+    fn validated_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(vec![0x53, 0x51, 0, 3, 0, 4, 0xFF]);
+        cursor.set_position(2);
+
+        assert_eq!(
+            cursor.shift_validated_e::<u16, _>(true, |v| (2..=3).contains(v)),
+            Some(3)
+        );
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(
+            cursor.shift_validated_e::<u16, _>(true, |v| (2..=3).contains(v)),
+            None
+        );
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(
+            cursor.shift_checked_e::<u16, _, _>(true, |v| if *v == 3 { Ok(()) } else { Err(*v) }),
+            Err(CheckError::Invalid(4))
+        );
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.shift_checked::<u16, (), _>(|_| Ok(())), Ok(0x0400));
+
+        // Running out of bytes is reported apart from a rejected value, and also restores the position.
+        assert_eq!(
+            cursor.shift_checked::<u16, (), _>(|_| Ok(())),
+            Err(CheckError::Truncated)
+        );
+        assert_eq!(cursor.shift_validated::<u16, _>(|_| true), None);
+        assert_eq!(cursor.position(), 6);
+
+        // A header of a magic and a version, where an unsupported version leaves the whole header for a fallback parser.
+        let header = |r: &mut Cursor<Vec<u8>>| {
+            r.atomic(|r| {
+                let magic = r.shift_slice(2)?;
+                let version = r.shift_validated_e::<u16, _>(true, |v| (2..=3).contains(v))?;
+                Some((magic, version))
+            })
+        };
+
+        cursor.set_position(0);
+        assert_eq!(header(&mut cursor), Some((b"SQ".to_vec(), 3)));
+
+        let mut cursor = Cursor::new(vec![0x53, 0x51, 0, 4]);
+        assert_eq!(header(&mut cursor), None);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.shift_e::<u32>(true), Some(0x53510004));
+    }
+
+    #[test]
+    fn expect_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(b"\x7fELF\x02\x01\x00\x3E".to_vec());
+
+        // Matching bytes are compared without allocating.
+        let before = ALLOCATED.with(Cell::get);
+        assert_eq!(cursor.expect_bytes(b"\x7fELF"), Ok(()));
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+        assert_eq!(cursor.expect(2u8), Ok(()));
+        assert_eq!(
+            cursor.expect(2u8),
+            Err(ExpectError::Mismatch {
+                offset: 5,
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(cursor.position(), 5);
+        assert_eq!(cursor.expect(1u8), Ok(()));
+        assert_eq!(
+            cursor.expect_bytes(b"\x00\x3F"),
+            Err(ExpectError::Mismatch {
+                offset: 6,
+                expected: vec![0x00, 0x3F],
+                actual: vec![0x00, 0x3E]
+            })
+        );
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(cursor.expect_e(0x003Eu16, true), Ok(()));
+
+        // Not enough bytes remain, which is reported apart from a mismatch.
+        cursor.set_position(6);
+        assert_eq!(
+            cursor.expect_e(0x3E00u32, false),
+            Err(ExpectError::Truncated { offset: 6 })
+        );
+        assert_eq!(
+            cursor.expect_bytes(b"\x00\x3E\x00"),
+            Err(ExpectError::Truncated { offset: 6 })
+        );
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(
+            ExpectError::Mismatch {
+                offset: 5,
+                expected: 2u8,
+                actual: 1
+            }
+            .to_string(),
+            "expected 2 at offset 5, found 1"
+        );
+    }
+
+    #[test]
+    fn counted_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_counted::<u32, u16>(&[], false).unwrap();
+        let large: Vec<u32> = (0..100_000).collect();
+        cursor.push_counted::<u32, u32>(&large, true).unwrap();
+        cursor.set_position(0);
+
+        assert_eq!(cursor.shift_counted::<u32, u16>(false).unwrap(), []);
+        assert_eq!(cursor.shift_counted::<u32, u32>(true).unwrap(), large);
+        assert_eq!(cursor.position(), 4 + 4 + 400_000);
+
+        // A count far larger than the remaining bytes is rejected before allocating, and restores the position.
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 1, 0, 0, 0]);
+        assert_eq!(cursor.shift_counted::<u32, u64>(false), None);
+        assert_eq!(cursor.position(), 0);
+
+        let mut cursor = Cursor::new(vec![3, 1, 0, 2, 0]);
+        assert_eq!(cursor.shift_counted::<u8, u16>(false), None);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            cursor.shift_counted::<i8, u16>(false).map(|v| v.len()),
+            None
+        );
+
+        let mut cursor = Cursor::new(vec![0xFF]);
+        assert_eq!(cursor.shift_counted::<i8, u8>(false), None);
+
+        let mut cursor = Cursor::new(Vec::new());
+        assert_eq!(cursor.push_counted::<u8, u8>(&[0; 256], false), None);
+        assert_eq!(cursor.push_counted::<i8, u8>(&[0; 128], false), None);
+        assert!(cursor.get_ref().is_empty());
+        cursor.push_counted::<u8, u8>(&[7; 255], false).unwrap();
+        assert_eq!(cursor.get_ref().len(), 256);
+    }
+
+    #[test]
+    fn map_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        fn write_entry(cursor: &mut Cursor<Vec<u8>>, key: &str, value: u64) {
+            cursor.push(key.len() as u8).unwrap();
+            cursor.push_slice(key.as_bytes()).unwrap();
+            cursor.push_e(value, true).unwrap();
+        }
+
+        fn parse_entry(cursor: &mut Cursor<Vec<u8>>) -> Option<(String, u64)> {
+            let len = cursor.shift::<u8>()?;
+            let key = cursor.shift_string(len as usize)?;
+            Some((key, cursor.shift_e::<u64>(true)?))
+        }
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(3u32, true).unwrap();
+        write_entry(&mut cursor, "width", 640);
+        write_entry(&mut cursor, "height", 480);
+        write_entry(&mut cursor, "depth", 24);
+        let len = cursor.position();
+        cursor.set_position(0);
+
+        let entries = cursor.shift_map::<u32, _, _, _>(true, parse_entry).unwrap();
+        assert_eq!(
+            entries,
+            [
+                ("width".to_string(), 640),
+                ("height".to_string(), 480),
+                ("depth".to_string(), 24)
+            ]
+        );
+        assert_eq!(cursor.position(), len);
+
+        cursor.set_position(0);
+        let map = cursor
+            .shift_hash_map::<u32, _, _, _>(true, parse_entry)
+            .unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["height"], 480);
+
+        // A duplicate key.
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(2u32, true).unwrap();
+        write_entry(&mut cursor, "width", 640);
+        write_entry(&mut cursor, "width", 800);
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.shift_hash_map::<u32, _, _, _>(true, parse_entry),
+            Err(MapError::DuplicateKey("width".to_string()))
+        );
+        assert_eq!(cursor.position(), 0);
+
+        // A final entry cut short.
+        let mut bytes = cursor.into_inner();
+        bytes.pop();
+        let mut cursor = Cursor::new(bytes);
+        assert_eq!(cursor.shift_map::<u32, _, _, _>(true, parse_entry), None);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            cursor.shift_hash_map::<u32, _, _, _>(true, parse_entry),
+            Err(MapError::Truncated)
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn starts_with_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(b"PK\x03\x04rest".to_vec());
+
+        assert_eq!(cursor.starts_with(b"PK\x03\x04"), Some(true));
+        assert_eq!(cursor.starts_with(b"PK\x05\x06"), Some(false));
+        assert_eq!(cursor.starts_with(b""), Some(true));
+        assert_eq!(cursor.position(), 0);
+
+        assert_eq!(cursor.consume_prefix(b"PK\x05\x06"), Some(false));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.consume_prefix(b"PK\x03\x04"), Some(true));
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.consume_prefix(b""), Some(true));
+        assert_eq!(cursor.position(), 4);
+
+        // Fewer bytes remain than the prefix, even though they match its start.
+        assert_eq!(cursor.starts_with(b"rest of it"), Some(false));
+        assert_eq!(cursor.consume_prefix(b"rest of it"), Some(false));
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.consume_prefix(b"rest"), Some(true));
+        assert_eq!(cursor.starts_with(b"\x00"), Some(false));
+        assert_eq!(cursor.position(), 8);
+    }
+
+    #[test]
+    fn skip_until_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        const SYNC: &[u8] = b"\x47\x1F\xFF";
+
+        let mut cursor = Cursor::new(b"\x47\x1F\xFFpayload".to_vec());
+        assert_eq!(cursor.skip_until(SYNC, false), Some(0));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.skip_until(SYNC, true), Some(3));
+        assert_eq!(cursor.skip_until(b"", true), Some(0));
+        assert_eq!(cursor.position(), 3);
+
+        // The chunks are 8192 bytes long, so these matches straddle the boundary between the first two.
+        for offset in 8188..8194 {
+            let mut bytes = vec![0x47; 20000];
+            bytes[offset..offset + 3].copy_from_slice(SYNC);
+
+            let mut cursor = Cursor::new(bytes);
+            cursor.set_position(1);
+            assert_eq!(cursor.skip_until(SYNC, false), Some(offset as u64 - 1));
+            assert_eq!(cursor.position(), offset as u64);
+            assert_eq!(cursor.skip_until(SYNC, true), Some(3));
+            assert_eq!(cursor.skip_until(SYNC, true), None);
+            assert_eq!(cursor.position(), offset as u64 + 3);
+        }
+
+        let mut cursor = Cursor::new(vec![0x47, 0x1F, 0x00, 0x47, 0x1F]);
+        assert_eq!(cursor.skip_until(SYNC, true), None);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn ber_test() {
+        use crate::ber::{BerClass, BerError, BerTag};
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // The `commonName` attribute of a certificate subject, with its value as a short form UTF8String.
+        let mut cursor = Cursor::new(vec![
+            0x30, 0x14, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x0D, b'e', b'x', b'a', b'm', b'p',
+            b'l', b'e', b'.', b'c', b'o', b'm', b'.', b'x',
+        ]);
+        let (tag, attribute) = cursor.shift_ber_tlv().unwrap();
+        assert_eq!(
+            tag,
+            BerTag {
+                class: BerClass::Universal,
+                constructed: true,
+                number: 16
+            }
+        );
+        assert_eq!(cursor.position(), 22);
+
+        let mut attribute = Cursor::new(attribute);
+        assert_eq!(attribute.shift_ber_tlv().unwrap().1, [0x55, 0x04, 0x03]);
+        let (tag, name) = attribute.shift_ber_tlv().unwrap();
+        assert_eq!(tag.number, 12);
+        assert!(!tag.constructed);
+        assert_eq!(name, b"example.com.x");
+
+        // A TBSCertificate with 266 bytes of contents, starting with the `[0] EXPLICIT` version field.
+        let mut contents = vec![0xA0, 0x03, 0x02, 0x01, 0x02];
+        contents.resize(266, 0x05);
+        let mut certificate = vec![0x30, 0x82, 0x01, 0x0A];
+        certificate.extend(&contents);
+        certificate.push(0x00);
+
+        let mut cursor = Cursor::new(certificate);
+        let (tag, tbs) = cursor.shift_ber_tlv().unwrap();
+        assert_eq!(tag.number, 16);
+        assert_eq!(tbs, contents);
+        assert_eq!(cursor.position(), 270);
+
+        let mut tbs = Cursor::new(tbs);
+        let (version, _) = tbs.shift_ber_tlv().unwrap();
+        assert_eq!(version.class, BerClass::ContextSpecific);
+        assert!(version.constructed);
+        assert_eq!(version.number, 0);
+
+        // BER allows an indefinite length, terminated by an end-of-contents element, which DER does not.
+        let mut cursor = Cursor::new(vec![0x00, 0x30, 0x80, 0x02, 0x01, 0x05, 0x00, 0x00]);
+        cursor.set_position(1);
+        assert_eq!(
+            cursor.shift_ber_tlv(),
+            Err(BerError::IndefiniteLength { offset: 1 })
+        );
+        assert_eq!(cursor.position(), 1);
+
+        // High-tag-number form, then a length which runs past the end.
+        let mut cursor = Cursor::new(vec![0x9F, 0x81, 0x00, 0x82, 0x10, 0x00, 0x01]);
+        assert_eq!(
+            cursor.shift_ber_tag().unwrap(),
+            BerTag {
+                class: BerClass::ContextSpecific,
+                constructed: false,
+                number: 128
+            }
+        );
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.shift_ber_tlv(),
+            Err(BerError::Truncated { offset: 0 })
+        );
+        assert_eq!(cursor.position(), 0);
+
+        let mut cursor = Cursor::new(vec![0x1F, 0x90, 0x80, 0x80, 0x80, 0x00, 0x00]);
+        assert_eq!(
+            cursor.shift_ber_tag(),
+            Err(BerError::InvalidTag { offset: 0 })
+        );
+        let mut cursor = Cursor::new(vec![0xFF, 0x89, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        cursor.set_position(1);
+        assert_eq!(
+            cursor.shift_ber_len(),
+            Err(BerError::InvalidLength { offset: 1 })
+        );
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.shift_ber_len(),
+            Err(BerError::InvalidLength { offset: 0 })
+        );
+    }
+
+    #[test]
+    fn vlq_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // The examples from the Standard MIDI File specification.
+        let table: [(u32, &[u8]); 12] = [
+            (0x0000_0000, &[0x00]),
+            (0x0000_0040, &[0x40]),
+            (0x0000_007F, &[0x7F]),
+            (0x0000_0080, &[0x81, 0x00]),
+            (0x0000_2000, &[0xC0, 0x00]),
+            (0x0000_3FFF, &[0xFF, 0x7F]),
+            (0x0000_4000, &[0x81, 0x80, 0x00]),
+            (0x0010_0000, &[0xC0, 0x80, 0x00]),
+            (0x001F_FFFF, &[0xFF, 0xFF, 0x7F]),
+            (0x0020_0000, &[0x81, 0x80, 0x80, 0x00]),
+            (0x0800_0000, &[0xC0, 0x80, 0x80, 0x00]),
+            (0x0FFF_FFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+        ];
+
+        let mut cursor = Cursor::new(Vec::new());
+        for (value, bytes) in table {
+            let mut encoded = Vec::new();
+            encoded.push_vlq(value).unwrap();
+            assert_eq!(encoded, bytes);
+
+            cursor.push_vlq(value).unwrap();
+        }
+
+        cursor.set_position(0);
+        for (value, _) in table {
+            assert_eq!(cursor.shift_vlq(), Some(value));
+        }
+        assert_eq!(cursor.shift_vlq(), None);
+
+        let mut a = vec![0x00];
+        assert_eq!(a.push_vlq(0x1000_0000), None);
+        assert_eq!(a, [0x00]);
+
+        let mut cursor = Cursor::new(vec![0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]);
+        assert_eq!(cursor.shift_vlq(), Some(0));
+        assert_eq!(cursor.shift_vlq(), None);
+        assert_eq!(cursor.position(), 1);
+
+        let mut cursor = Cursor::new(vec![0x81, 0x80]);
+        assert_eq!(cursor.shift_vlq(), None);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn walk_records_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(Vec::new());
+        for payload in [&b"first"[..], &[], &[7; 150_000], b"last"] {
+            cursor.push_e(payload.len() as u32, true).unwrap();
+            cursor.push_slice(payload).unwrap();
+        }
+        let valid_len = cursor.position();
+        cursor.push_e(10u32, true).unwrap();
+        cursor.push_slice(b"short").unwrap();
+        cursor.set_position(0);
+
+        let mut headers = Vec::new();
+        let mut chunks = Vec::new();
+        let result = cursor.walk_records::<u32, _, _>(
+            true,
+            |record| headers.push(record),
+            |record, chunk| chunks.push((record.index, chunk.len(), chunk[0])),
+        );
+
+        assert_eq!(
+            result,
+            Err(WalkError::PayloadOverrun {
+                index: 4,
+                offset: valid_len,
+                len: 10
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            headers
+                .iter()
+                .map(|r| (r.offset, r.len))
+                .collect::<Vec<_>>(),
+            [(0, 5), (9, 0), (13, 150_000), (150_017, 4)]
+        );
+        assert_eq!(
+            chunks,
+            [
+                (0, 5, b'f'),
+                (2, 65536, 7),
+                (2, 65536, 7),
+                (2, 18928, 7),
+                (3, 4, b'l')
+            ]
+        );
+
+        cursor.get_mut().truncate(valid_len as usize);
+        cursor.set_position(0);
+        let summary = cursor.walk_records::<u32, _, _>(true, |_| {}, |_, _| {});
+        assert_eq!(
+            summary,
+            Ok(WalkSummary {
+                records: 4,
+                payload_bytes: 150_009
+            })
+        );
+        assert_eq!(cursor.position(), valid_len);
+
+        let mut cursor = Cursor::new(vec![1, 0xAA, 0xFF]);
+        assert_eq!(
+            cursor.walk_records::<i8, _, _>(false, |_| {}, |_, _| {}),
+            Err(WalkError::InvalidLength {
+                index: 1,
+                offset: 2
+            })
+        );
+        let mut cursor = Cursor::new(vec![1, 0, 0xAA, 0]);
+        assert_eq!(
+            cursor.walk_records::<u16, _, _>(false, |_| {}, |_, _| {}),
+            Err(WalkError::Truncated {
+                index: 1,
+                offset: 3
+            })
+        );
+    }
+
+    #[test]
+    fn fork_test() {
+        use crate::prelude::*;
+        use crate::shared::SharedReader;
+        use std::io::Cursor;
+        use std::sync::Arc;
+
+        // Reads either an escaped `u32` or a plain `u16`, trying the escaped form first.
+        fn parse_value<R: SeqByteReader + ForkableReader>(reader: &mut R) -> Option<u32> {
+            let escaped = reader.speculate(|r| {
+                if r.shift::<u8>()? != 0xFE {
+                    return None;
+                }
+                r.shift::<u32>()
+            });
+
+            escaped.or_else(|| reader.shift::<u16>().map(u32::from))
+        }
+
+        fn parse_all<R: SeqByteReader + ForkableReader>(mut reader: R) -> Vec<u32> {
+            let mut fork = reader.fork();
+            assert_eq!(fork.shift::<u8>(), Some(0xFE));
+
+            std::iter::from_fn(|| parse_value(&mut reader)).collect()
+        }
+
+        let bytes = vec![0xFE, 1, 0, 0, 0, 0x34, 0x12, 0xFE, 9];
+        let expected = [1, 0x1234, 0x09FE];
+
+        assert_eq!(parse_all(Cursor::new(&bytes[..])), expected);
+        assert_eq!(
+            parse_all(SharedReader::new(Arc::new(bytes), 0, 9)),
+            expected
+        );
+    }
+
+    #[test]
+    fn narrow_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(0x1_0000_0005u64, false).unwrap();
+        cursor.push_e(4096u64, false).unwrap();
+        cursor.push_e(-1i32, true).unwrap();
+        cursor.set_position(0);
+
+        assert_eq!(
+            cursor.shift_narrow::<u64, u32>(false),
+            Err(NarrowError::OutOfRange {
+                offset: 0,
+                value: 0x1_0000_0005
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            cursor
+                .shift_narrow::<u64, u32>(false)
+                .unwrap_err()
+                .to_string(),
+            "value 4294967301 at offset 0 is out of range"
+        );
+
+        cursor.set_position(8);
+        assert_eq!(cursor.shift_narrow::<u64, usize>(false), Ok(4096));
+        assert_eq!(
+            cursor.shift_narrow::<i32, u32>(true),
+            Err(NarrowError::OutOfRange {
+                offset: 16,
+                value: -1
+            })
+        );
+        assert_eq!(cursor.shift_narrow::<i32, i64>(true), Ok(-1));
+        assert_eq!(
+            cursor.shift_narrow::<u16, u8>(true),
+            Err(NarrowError::Truncated { offset: 20 })
+        );
+        assert_eq!(cursor.position(), 20);
+    }
+
+    #[test]
+    fn offset_table_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // A string table of a `u32` count and `u32` offsets, followed by length-prefixed strings, some shared by several entries.
+        let strings: [&[u8]; 3] = [b"alpha", b"", b"gamma"];
+        let targets = [2, 0, 2, 1, 0];
+
+        let mut cursor = Cursor::new(Vec::new());
+        let table_len = 4 + 4 * targets.len() as u32;
+        let mut string_offsets = Vec::new();
+        let mut blob = Vec::new();
+        for s in strings {
+            string_offsets.push(table_len + blob.len() as u32);
+            blob.push(s.len() as u8);
+            blob.extend_from_slice(s);
+        }
+
+        let offsets: Vec<u32> = targets.iter().map(|&t| string_offsets[t]).collect();
+        cursor.push_counted::<u32, u32>(&offsets, true).unwrap();
+        cursor.push_slice(&blob).unwrap();
+        cursor.set_position(0);
+
+        let read_string = |_: usize, r: &mut Cursor<Vec<u8>>| {
+            let len = r.shift::<u8>()?;
+            r.shift_string(len as usize)
+        };
+        let expected = ["gamma", "alpha", "gamma", "", "alpha"];
+
+        assert_eq!(
+            cursor.visit_offset_table::<u32, u32, _, _>(true, read_string),
+            Ok(expected.map(String::from).to_vec())
+        );
+        assert_eq!(cursor.position(), table_len as u64);
+
+        let mut visited = Vec::new();
+        cursor.set_position(0);
+        let deduped = cursor.visit_offset_table_dedup::<u32, u32, _, _>(true, |index, r| {
+            visited.push(index);
+            read_string(index, r)
+        });
+        assert_eq!(deduped, Ok(expected.map(String::from).to_vec()));
+        assert_eq!(visited, [0, 1, 3]);
+        assert_eq!(cursor.position(), table_len as u64);
+
+        // The last string is cut off, then the last offset is moved past the end.
+        let len = cursor.get_ref().len();
+        cursor.get_mut().truncate(len - 2);
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.visit_offset_table::<u32, u32, _, _>(true, read_string),
+            Err(OffsetTableError::Visit {
+                index: 0,
+                offset: string_offsets[2] as u64
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+
+        cursor.set_position(table_len as u64 - 4);
+        cursor.push_e(1000u32, true).unwrap();
+        cursor.set_position(0);
+        assert_eq!(
+            cursor.visit_offset_table_dedup::<u32, u32, _, _>(true, read_string),
+            Err(OffsetTableError::OutOfBounds { index: 4 })
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn available_test() {
+        use crate::prelude::*;
+        use crate::stats::StatsReader;
+        use crate::testutil::{NoPos, Unbounded};
+        use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+        /// A source which returns at most one byte per read.
+        struct Trickle(Cursor<Vec<u8>>);
+
+        impl Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = buf.len().min(1);
+                self.0.read(&mut buf[..len])
+            }
+        }
+
+        impl Seek for Trickle {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                self.0.seek(pos)
+            }
+        }
+
+        let mut seekable = Trickle(Cursor::new(vec![7; 10_000]));
+        seekable.set_pos(1000).unwrap();
+        assert_eq!(seekable.available(9000), Some(true));
+        assert_eq!(seekable.available(9001), Some(false));
+        assert_eq!(seekable.ensure(0), Ok(()));
+        assert_eq!(
+            seekable.ensure(9001),
+            Err(NotEnough {
+                offset: Some(1000),
+                needed: 9001,
+                remaining: Some(9000)
+            })
+        );
+        assert_eq!(seekable.pos(), Some(1000));
+
+        let mut unbounded = StatsReader::new(Unbounded(Trickle(Cursor::new(vec![7; 10_000]))));
+        unbounded.set_pos(1000).unwrap();
+        assert_eq!(unbounded.available(9000), Some(true));
+        assert_eq!(unbounded.stats().largest_read, 4096);
+        assert_eq!(unbounded.pos(), Some(1000));
+        assert_eq!(unbounded.available(9001), Some(false));
+        assert_eq!(unbounded.pos(), Some(1000));
+        assert_eq!(unbounded.available(usize::MAX), Some(false));
+        assert_eq!(
+            unbounded.ensure(9001),
+            Err(NotEnough {
+                offset: Some(1000),
+                needed: 9001,
+                remaining: None
+            })
+        );
+        assert_eq!(unbounded.shift::<u8>(), Some(7));
+
+        // Without a position, neither the offset nor what remains after it is made up.
+        let mut no_pos = NoPos(Cursor::new(vec![7; 10]));
+        let error = no_pos.ensure(4).unwrap_err();
+        assert_eq!(
+            error,
+            NotEnough {
+                offset: None,
+                needed: 4,
+                remaining: None
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "needed 4 bytes at an unknown offset, but the source ends first"
+        );
+    }
+
+    #[test]
+    fn delta_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // Millisecond timestamps of a sorted column, stored as gaps from a base.
+        let base = 1_700_000_000_000u64;
+        let timestamps = [
+            base,
+            base + 15,
+            base + 15,
+            base + 1000,
+            base + 70_000,
+            base + 70_250,
+        ];
+
+        let mut a = Vec::new();
+        a.push_delta_encoded::<u32>(&timestamps, base, true)
+            .unwrap();
+        a.push_varint_delta_encoded(&timestamps, base).unwrap();
+        assert_eq!(a.len(), 24 + 10);
+
+        let mut cursor = Cursor::new(a);
+        assert_eq!(
+            cursor.shift_delta_decoded::<u32>(timestamps.len(), base, true),
+            Ok(timestamps.to_vec())
+        );
+        assert_eq!(
+            cursor.shift_varint_delta_decoded(timestamps.len(), base),
+            Ok(timestamps.to_vec())
+        );
+        assert_eq!(cursor.position(), 34);
+
+        let mut unsorted = Vec::new();
+        assert!(unsorted
+            .push_delta_encoded::<u32>(&[base + 2, base + 1], base, true)
+            .is_none());
+        assert!(unsorted
+            .push_varint_delta_encoded(&[base - 1], base)
+            .is_none());
+        assert!(unsorted
+            .push_delta_encoded::<u8>(&[base + 255, base + 511], base, true)
+            .is_none());
+        assert!(unsorted.is_empty());
+
+        let mut overflow = Cursor::new(vec![0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(
+            overflow.shift_delta_decoded::<u32>(2, u64::MAX - 0xFFFF_FFFF, true),
+            Err(DeltaError::Overflow {
+                offset: 4,
+                index: 1
+            })
+        );
+        assert_eq!(overflow.position(), 0);
+
+        let mut overflow = Cursor::new(
+            vec![0xFF; 9]
+                .into_iter()
+                .chain([0x01, 0x01])
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            overflow.shift_varint_delta_decoded(2, 0),
+            Err(DeltaError::Overflow {
+                offset: 10,
+                index: 1
+            })
+        );
+        assert_eq!(overflow.position(), 0);
+
+        let mut truncated = Cursor::new(vec![0x05, 0x80]);
+        assert_eq!(
+            truncated.shift_varint_delta_decoded(2, 0),
+            Err(DeltaError::Truncated { offset: 1 })
+        );
+        assert_eq!(truncated.position(), 0);
+
+        let mut too_long = Cursor::new(vec![0xFF; 9].into_iter().chain([0x02]).collect::<Vec<_>>());
+        assert_eq!(
+            too_long.shift_varint_delta_decoded(1, 0),
+            Err(DeltaError::Truncated { offset: 0 })
+        );
+    }
+
+    #[test]
+    fn versioned_record_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // Version 1 records hold an id and a name, and version 2 records append a flags field.
+        let v1 = |r: &mut Cursor<&[u8]>| {
+            let id = r.shift_e::<u32>(true)?;
+            let len = r.shift::<u8>()?;
+
+            Some((id, r.shift_string(len as usize)?))
+        };
+        let v2 = |r: &mut Cursor<&[u8]>| {
+            let (id, name) = v1(r)?;
+
+            Some((id, name, r.shift_e::<u16>(true)?))
+        };
+        let len = VersionedLen::U16(Endianness::Big);
+
+        let mut a = Vec::new();
+        a.push_versioned_record(len, |r| {
+            r.push_e(7u32, true)?;
+            r.push_slice(&[4])?;
+            r.push_slice(b"main")?;
+            r.push_e(0x8001u16, true)
+        })
+        .unwrap();
+        a.push_versioned_record(len, |r| {
+            r.push_e(8u32, true)?;
+            r.push_slice(&[0])
+        })
+        .unwrap();
+        a.push_versioned_record(VersionedLen::Vlq, |r| r.push_slice(&[0xEE; 200]))
+            .unwrap();
+        assert_eq!(&a[..2], [0, 11]);
+        assert_eq!(&a[20..22], [0x81, 0x48]);
+
+        let mut old = Cursor::new(a.clone());
+        assert_eq!(
+            old.shift_versioned_record(len, v1),
+            Some((7, "main".to_string()))
+        );
+        assert_eq!(old.position(), 13);
+        assert_eq!(
+            old.shift_versioned_record(len, v1),
+            Some((8, String::new()))
+        );
+        assert_eq!(
+            old.shift_versioned_record(VersionedLen::Vlq, |r| r.shift::<u8>()),
+            Some(0xEE)
+        );
+        assert_eq!(old.position(), a.len() as u64);
+
+        let mut new = Cursor::new(a);
+        assert_eq!(
+            new.shift_versioned_record(len, v2),
+            Some((7, "main".to_string(), 0x8001))
+        );
+        assert_eq!(new.shift_versioned_record(len, v2), None);
+        assert_eq!(new.position(), 13);
+        assert_eq!(
+            new.shift_versioned_record(len, |r| {
+                r.set_position(6);
+                Some(())
+            }),
+            None
+        );
+        assert_eq!(new.position(), 13);
+
+        let mut truncated = Cursor::new(vec![0, 9, 1, 2, 3]);
+        assert_eq!(truncated.shift_versioned_record(len, |_| Some(())), None);
+        assert_eq!(truncated.position(), 0);
+
+        assert!(Vec::new()
+            .push_versioned_record(VersionedLen::U8, |r| r.push_slice(&[0; 256]))
+            .is_none());
+    }
+
+    #[test]
+    fn pointer_sized_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        assert_eq!(usize::size(), 8);
+        assert_eq!(isize::size(), 8);
+        assert_eq!(0x1234usize.to_bytes_e(true), [0, 0, 0, 0, 0, 0, 0x12, 0x34]);
+        assert_eq!((-2isize).to_bytes(), (-2i64).to_bytes());
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push(usize::MAX).unwrap();
+        cursor.push_e(isize::MIN, true).unwrap();
+        cursor.push(u32::MAX as u64 + 1).unwrap();
+        cursor.push(i32::MIN as i64 - 1).unwrap();
+        cursor.set_position(0);
+
+        assert_eq!(cursor.shift::<usize>(), Some(usize::MAX));
+        assert_eq!(cursor.shift_e::<isize>(true), Some(isize::MIN));
+        assert_eq!(cursor.position(), 16);
+
+        // Lengths above the 32-bit range only fit on 64-bit targets, and fail to convert elsewhere.
+        let large = cursor.shift::<usize>();
+        let small = cursor.shift::<isize>();
+        if cfg!(target_pointer_width = "64") {
+            assert_eq!(large, Some(0x1_0000_0000));
+            assert_eq!(small, Some(-0x8000_0001));
+        } else {
+            assert_eq!(large, None);
+            assert_eq!(small, None);
+        }
+        assert_eq!(usize::from_bytes(&[0; 4]), None);
+    }
+
+    #[test]
+    fn bool_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push(true).unwrap();
+        cursor.push_e(false, true).unwrap();
+        cursor.push_slice(&[0x02, 0xFF, 1]).unwrap();
+        assert_eq!(cursor.get_ref(), &[1, 0, 2, 0xFF, 1]);
+
+        cursor.set_position(0);
+        assert_eq!(cursor.shift::<bool>(), Some(true));
+        assert_eq!(cursor.next_e::<bool>(true), Some(false));
+        assert_eq!(cursor.shift_e::<bool>(false), Some(false));
+
+        // Corrupt flag bytes fail without moving, rather than reading as `true`.
+        assert_eq!(cursor.shift::<bool>(), None);
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.atomic(|r| r.shift_vec::<bool>(3)), None);
+        assert_eq!(cursor.position(), 2);
+        cursor.set_position(3);
+        assert_eq!(cursor.shift_e::<bool>(true), None);
+        assert_eq!(cursor.position(), 3);
+
+        cursor.set_position(4);
+        assert_eq!(cursor.shift::<bool>(), Some(true));
+        assert_eq!(bool::from_bytes(&[1, 0]), None);
+        assert_eq!(bool::from_bytes(&[]), None);
+    }
+
+    #[test]
+    fn atomicity_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::ops::ControlFlow;
+
+        type Method = fn(&mut Cursor<Vec<u8>>) -> bool;
+
+        /// Where a method reads from, and where it leaves the position when its input is cut short.
+        #[derive(Debug, Clone, Copy)]
+        enum Kind {
+            /// Reads forwards from the start of the input, and fails without moving.
+            Forward,
+            /// Reads backwards from the end of the input, and fails without moving.
+            Backward,
+            /// Reads forwards as far as the input goes, stopping at its end.
+            Partial,
+        }
+
+        /// Builds the table of methods, each with an input it reads successfully. Every method of the reader traits must have a row,
+        /// which the end of this test checks.
+        macro_rules! cases {
+            ($($kind:ident $name:ident($input:expr) => $method:expr;)*) => {
+                vec![$((stringify!($name), Kind::$kind, $input.to_vec(), {
+                    let method: Method = $method;
+                    method
+                }),)*]
+            };
+        }
+
+        struct Tag;
+        impl TryFrom<u16> for Tag {
+            type Error = ();
+
+            fn try_from(tag: u16) -> Result<Self, ()> {
+                (tag < 0x100).then_some(Tag).ok_or(())
+            }
+        }
+        impl TaggedEnum for Tag {
+            type Repr = u16;
+        }
+
+        fn name(r: &mut Cursor<Vec<u8>>) -> Option<String> {
+            let len = r.shift::<u8>()?;
+            r.shift_string(len as usize)
+        }
+        fn entry(r: &mut Cursor<Vec<u8>>) -> Option<(u8, u8)> {
+            Some((r.shift()?, r.shift()?))
+        }
+        /// Records of two bytes after the leading byte, as many as the input holds.
+        fn records(r: &mut Cursor<Vec<u8>>) -> Option<RecordGeometry> {
+            Some(RecordGeometry {
+                header_len: 1,
+                record_size: 2,
+                count: Some((r.end_pos()? - 1) / 2),
+            })
+        }
+
+        #[allow(unused_mut)]
+        let mut methods: Vec<(&str, Kind, Vec<u8>, Method)> = cases! {
+            Forward next([1, 2, 3, 4]) => |r| r.next::<u32>().is_some();
+            Forward shift([1, 2, 3, 4]) => |r| r.shift::<u32>().is_some();
+            Forward shift_vec([1, 0, 2, 0]) => |r| r.shift_vec::<u16>(2).is_some();
+            Forward next_slice([1, 2, 3]) => |r| r.next_slice(3).is_some();
+            Forward shift_slice([1, 2, 3]) => |r| r.shift_slice(3).is_some();
+            Backward shift_slice_back([1, 2, 3]) => |r| r.shift_slice_back(3).is_some();
+            Backward shift_back([1, 2, 3, 4]) => |r| r.shift_back::<u32>().is_some();
+            Backward rewind_by([1, 2, 3]) => |r| r.rewind_by(3).is_some();
+            Backward unshift([1, 2, 3, 4]) => |r| r.unshift::<u32>().is_some();
+            Forward next_string(b"abc") => |r| r.next_string(3).is_some();
+            Forward shift_string(b"abc") => |r| r.shift_string(3).is_some();
+            Forward shift_slice_into_vec([1, 2, 3]) => |r| r.shift_slice_into_vec(3, &mut Vec::new()).is_some();
+            Forward shift_string_into(b"abc") => |r| r.shift_string_into(3, &mut String::new()).is_some();
+            Forward shift_bitvec([0xFF, 0x0F]) => |r| r.shift_bitvec(12, BitOrder::LsbFirst).is_some();
+            Forward shift_gsm7([0xE8, 0x32, 0x9B, 0xFD, 0x06]) => |r| r.shift_gsm7(5).is_some();
+            Forward atomic([1, 2, 3]) => |r| r.atomic(|r| Some((r.shift::<u8>()?, r.shift::<u16>()?))).is_some();
+            Forward shift_validated([1, 0]) => |r| r.shift_validated::<u16, _>(|_| true).is_some();
+            Forward shift_checked([1, 0]) => |r| r.shift_checked::<u16, (), _>(|_| Ok(())).is_ok();
+            Forward shift_optional([1, 1, 0]) => |r| r.shift_optional::<u16>().is_some();
+            Forward next_optional([1, 1, 0]) => |r| r.next_optional::<u16>().is_some();
+            Forward shift_enum([7, 0]) => |r| r.shift_enum::<Tag>().is_some();
+            Forward next_enum([7, 0]) => |r| r.next_enum::<Tag>().is_some();
+            Forward starts_with(b"abc") => |r| r.starts_with(b"abc") == Some(true);
+            Forward consume_prefix(b"abc") => |r| r.consume_prefix(b"abc") == Some(true);
+            Forward skip_until(b"ab\xFF\xD8") => |r| r.skip_until(b"\xFF\xD8", true).is_some();
+            Forward remaining([1, 2, 3]) => |r| r.remaining() == Some(3);
+            // The input follows a leading byte, which the total length includes.
+            Forward total_len([1, 2, 3]) => |r| r.total_len() == Some(4);
+            Forward available([1, 2, 3]) => |r| r.available(3) == Some(true);
+            Forward ensure([1, 2, 3]) => |r| r.ensure(3).is_ok();
+            Forward skip_slice([1, 2, 3]) => |r| r.skip_slice(3).is_some();
+            Forward skip_value([1, 2, 3, 4]) => |r| r.skip_value::<u32>().is_some();
+            Forward align_to([0, 0, 0]) => |r| r.align_to(4).is_some();
+            Forward align_to_from([0, 0, 0, 0, 0, 0, 0]) => |r| r.align_to_from(8, 0).is_some();
+            Forward seek_to_record([1, 2, 3, 4]) => |r| {
+                records(r).is_some_and(|geometry| r.seek_to_record(geometry, 1).is_some())
+            };
+            Forward current_record([1, 2]) => |r| {
+                records(r).is_some_and(|geometry| r.current_record(geometry).is_some())
+            };
+            Forward skip_string_prefixed(b"\x03abc") => |r| r.skip_string_prefixed::<u8>().is_some();
+            Partial shift_chunks([1, 2, 3, 4, 5]) => |r| {
+                r.shift_chunks(5, 2, |_| ControlFlow::Continue(())) == Some(5)
+            };
+            Forward shift_vlq([0x81, 0x80, 0x00]) => |r| r.shift_vlq().is_some();
+            Forward shift_varint_delta_decoded([0xAC, 0x02, 0x05]) => |r| {
+                r.shift_varint_delta_decoded(2, 0).is_ok()
+            };
+            Forward shift_versioned_record([3, 1, 2, 9]) => |r| {
+                r.shift_versioned_record(VersionedLen::U8, |f| f.shift::<u16>()).is_some()
+            };
+            Forward expect([1, 2]) => |r| r.expect(0x0201u16).is_ok();
+            Forward expect_bytes(b"MAGIC") => |r| r.expect_bytes(b"MAGIC").is_ok();
+            Forward shift_vec_aligned([1, 0, 2, 0]) => |r| r.shift_vec_aligned::<u16>(2, 16).is_some();
+            Forward shift_ber_tag([0x1F, 0x81, 0x01]) => |r| r.shift_ber_tag().is_ok();
+            Forward shift_ber_len([0x82, 0x01, 0x00]) => |r| r.shift_ber_len().is_ok();
+            Forward shift_ber_tlv([0x04, 0x02, 0xAA, 0xBB]) => |r| r.shift_ber_tlv().is_ok();
+            Forward next_e([1, 2, 3, 4]) => |r| r.next_e::<u32>(true).is_some();
+            Forward shift_e([1, 2, 3, 4]) => |r| r.shift_e::<u32>(true).is_some();
+            Forward next_endian([1, 2, 3, 4]) => |r| r.next_endian::<u32>(Endianness::Big).is_some();
+            Forward shift_endian([1, 2, 3, 4]) => |r| r.shift_endian::<u32>(Endianness::Big).is_some();
+            Forward next_as([1, 2, 3, 4]) => |r| r.next_as::<u32, BE>().is_some();
+            Forward shift_as([1, 2, 3, 4]) => |r| r.shift_as::<u32, LE>().is_some();
+            Forward next_ne([1, 2, 3, 4]) => |r| r.next_ne::<u32>().is_some();
+            Forward shift_ne([1, 2, 3, 4]) => |r| r.shift_ne::<u32>().is_some();
+            Backward shift_back_e([1, 2, 3, 4]) => |r| r.shift_back_e::<u32>(true).is_some();
+            Forward shift_vec_e([0, 1, 0, 2]) => |r| r.shift_vec_e::<u16>(2, true).is_some();
+            Forward shift_extend([0, 1, 0, 2]) => |r| r.shift_extend::<u16, _>(2, &mut Vec::new(), true).is_some();
+            Forward shift_counted([2, 1, 0, 2, 0]) => |r| r.shift_counted::<u8, u16>(false).is_some();
+            Forward skip_string_prefixed_e(b"\0\x03abc") => |r| r.skip_string_prefixed_e::<u16>(true).is_some();
+            Forward shift_map([2, 1, 10, 2, 20]) => |r| r.shift_map::<u8, u8, u8, _>(false, entry).is_some();
+            Forward shift_hash_map([2, 1, 10, 2, 20]) => |r| r.shift_hash_map::<u8, u8, u8, _>(false, entry).is_ok();
+            Forward visit_offset_table(b"\x02\x04\x06\x01a\x01b") => |r| {
+                r.visit_offset_table::<u8, u8, _, _>(false, |_, r| name(r)).is_ok()
+            };
+            Forward visit_offset_table_dedup(b"\x02\x04\x06\x01a\x01b") => |r| {
+                r.visit_offset_table_dedup::<u8, u8, _, _>(false, |_, r| name(r)).is_ok()
+            };
+            Forward expect_e([1, 2, 3, 4]) => |r| r.expect_e(0x01020304u32, true).is_ok();
+            Forward shift_narrow([0, 5]) => |r| r.shift_narrow::<u16, u8>(true).is_ok();
+            Forward shift_delta_decoded([0, 1, 0, 2]) => |r| r.shift_delta_decoded::<u16>(2, 0, true).is_ok();
+            Forward shift_validated_e([0, 1]) => |r| r.shift_validated_e::<u16, _>(true, |_| true).is_some();
+            Forward shift_checked_e([0, 1]) => |r| r.shift_checked_e::<u16, (), _>(true, |_| Ok(())).is_ok();
+            Forward shift_optional_e([1, 0, 1]) => |r| r.shift_optional_e::<u16>(true).is_some();
+            Forward next_optional_e([1, 0, 1]) => |r| r.next_optional_e::<u16>(true).is_some();
+            Forward shift_enum_e([0, 7]) => |r| r.shift_enum_e::<Tag>(true).is_some();
+            Forward next_enum_e([0, 7]) => |r| r.next_enum_e::<Tag>(true).is_some();
+            Forward shift_deinterleaved([1, 0, 2, 0, 3, 0, 4, 0]) => |r| r.shift_deinterleaved::<u16>(2, 2, false).is_some();
+            Forward shift_strided([1, 0, 9, 9, 2, 0, 9, 9]) => |r| r.shift_strided::<u16>(2, 4, 0, false).is_some();
+            Forward binary_search_record([0, 1, 0, 2]) => |r| r.binary_search_record(1, 2, 2, 0, true, &2u16).is_some();
+            Forward walk_records([2, 7, 7, 1, 7]) => |r| r.walk_records::<u8, _, _>(false, |_| {}, |_, _| {}).is_ok();
+        };
+        #[cfg(feature = "bytemuck")]
+        methods.push(("shift_pod_slice", Kind::Forward, vec![1, 0, 2, 0], |r| {
+            r.shift_pod_slice::<u16>(2).is_some()
+        }));
+        #[cfg(feature = "crc")]
+        {
+            use crate::crc::CrcAlgo;
+
+            let mut frame = Vec::new();
+            frame
+                .push_checked_frame(b"abc", true, CrcAlgo::Crc32)
+                .unwrap();
+            methods.push(("shift_checked_frame", Kind::Forward, frame, |r| {
+                r.shift_checked_frame(true, CrcAlgo::Crc32).is_ok()
+            }));
+        }
+        #[cfg(feature = "zstd")]
+        {
+            let mut block = Vec::new();
+            block.push_zstd_block(&[7; 64], 3, true).unwrap();
+            methods.push(("shift_zstd_block", Kind::Forward, block, |r| {
+                r.shift_zstd_block(true, 64).is_some()
+            }));
+        }
+
+        for (name, kind, input, method) in &methods {
+            // A leading byte which is not part of the input, so a method restoring to offset 0 rather than its start is caught.
+            let cut = |len: usize| {
+                let mut bytes = vec![0xEE];
+                bytes.extend_from_slice(&input[..len]);
+
+                let mut cursor = Cursor::new(bytes);
+                cursor.set_position(match kind {
+                    Kind::Forward | Kind::Partial => 1,
+                    Kind::Backward => 1 + len as u64,
+                });
+                cursor
+            };
+
+            assert!(method(&mut cut(input.len())), "{} fails on its input", name);
+
+            let mut failures = 0;
+            for len in 0..input.len() {
+                let mut cursor = cut(len);
+                let start = cursor.position();
+                if !method(&mut cursor) {
+                    failures += 1;
+                    let expected = match kind {
+                        Kind::Forward | Kind::Backward => start,
+                        Kind::Partial => 1 + len as u64,
+                    };
+                    assert_eq!(
+                        cursor.position(),
+                        expected,
+                        "{} is left at the wrong position when cut to {} bytes",
+                        name,
+                        len
+                    );
+                }
+            }
+            assert!(failures > 0, "{} never fails", name);
+        }
+
+        // Every method of the reader traits has a row, apart from the shorthands for fixed types, which call `next` and `shift`, and
+        // methods behind features which are off.
+        let gated = [
+            ("shift_pod_slice", cfg!(feature = "bytemuck")),
+            ("shift_checked_frame", cfg!(feature = "crc")),
+            ("shift_zstd_block", cfg!(feature = "zstd")),
+        ];
+        let source = include_str!("bytes.rs");
+        let primitives = [
+            "u8", "i8", "u16", "i16", "u32", "i32", "f32", "u64", "i64", "f64",
+        ];
+        for header in ["pub trait SeqByteReader:", "pub trait ESeqByteReader:"] {
+            let start = source.find(header).unwrap();
+            let end = start + source[start..].find("\n}\n").unwrap();
+
+            for line in source[start..end].lines() {
+                let Some(signature) = line.strip_prefix("    fn ") else {
+                    continue;
+                };
+                let name = &signature[..signature.find(['<', '(']).unwrap()];
+                let shorthand = ["next_", "shift_"].iter().any(|prefix| {
+                    name.strip_prefix(prefix)
+                        .is_some_and(|t| primitives.contains(&t))
+                });
+
+                assert!(
+                    shorthand
+                        || gated.contains(&(name, false))
+                        || methods.iter().any(|(n, ..)| *n == name),
+                    "{} is missing from the atomicity table",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn char_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push('A').unwrap();
+        cursor.push_e('\u{1F600}', true).unwrap();
+        cursor.push_e('\u{1F600}', false).unwrap();
+        cursor.push_e(0xD800u32, true).unwrap();
+        cursor.push_e(0x110000u32, false).unwrap();
+        assert_eq!(
+            cursor.get_ref()[..12],
+            [0x41, 0, 0, 0, 0, 0x01, 0xF6, 0x00, 0x00, 0xF6, 0x01, 0]
+        );
+
+        cursor.set_position(0);
+        assert_eq!(cursor.shift::<char>(), Some('A'));
+        assert_eq!(cursor.next_e::<char>(false), None);
+        assert_eq!(cursor.shift_e::<char>(true), Some('\u{1F600}'));
+        assert_eq!(cursor.shift_e::<char>(false), Some('\u{1F600}'));
+
+        // A surrogate and a value past the last code point are not scalar values, and fail without moving.
+        assert_eq!(cursor.shift_e::<char>(true), None);
+        assert_eq!(cursor.position(), 12);
+        cursor.set_position(16);
+        assert_eq!(cursor.shift_e::<char>(false), None);
+        assert_eq!(cursor.position(), 16);
+
+        assert_eq!(char::from_bytes_e(&[0, 0, 0xDF, 0xFF], true), None);
+        assert_eq!(
+            char::from_bytes_e(&[0, 0x10, 0xFF, 0xFF], true),
+            Some(char::MAX)
+        );
+        assert_eq!(char::from_bytes(&[0x41, 0, 0]), None);
+    }
+
+    #[test]
+    fn nonzero_scalar_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::num::{NonZeroI32, NonZeroI64, NonZeroU16, NonZeroU32, NonZeroU8, NonZeroUsize};
+
+        /// Reads an identifier generically, as a parser shared between several protocols would.
+        fn shift_id<U: SizedNumber>(cursor: &mut Cursor<Vec<u8>>) -> Option<U> {
+            cursor.next::<U>()?;
+            cursor.shift::<U>()
+        }
+
+        assert_eq!(NonZeroU32::size(), 4);
+        assert_eq!(NonZeroUsize::size(), 8);
+        assert_eq!(NonZeroU8::from_bytes(&[0]), None);
+        assert_eq!(NonZeroU32::from_bytes(&[0; 4]), None);
+        assert_eq!(NonZeroI64::from_bytes_e(&[0; 8], true), None);
+        assert_eq!(NonZeroU32::from_bytes(&[1, 0, 0]), None);
+        assert_eq!(NonZeroI32::from_bytes(&[0xFF; 4]), NonZeroI32::new(-1));
+
+        for value in [1u16, 0x1234, u16::MAX] {
+            let id = NonZeroU16::new(value).unwrap();
+            for bigendian in [false, true] {
+                let bytes = id.to_bytes_e(bigendian);
+                assert_eq!(bytes, value.to_bytes_e(bigendian));
+                assert_eq!(NonZeroU16::from_bytes_e(&bytes, bigendian), Some(id));
+            }
+        }
+
+        let mut cursor = Cursor::new(vec![7, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(shift_id::<NonZeroU32>(&mut cursor), NonZeroU32::new(7));
+        assert_eq!(cursor.next::<NonZeroU32>(), None);
+        assert_eq!(shift_id::<NonZeroU32>(&mut cursor), None);
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.shift_e::<NonZeroU32>(true), None);
+        assert_eq!(cursor.position(), 4);
+    }
+
+    #[test]
+    fn byte_array_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let hash: [u8; 16] = std::array::from_fn(|i| i as u8 * 0x11);
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push(*b"MAGC").unwrap();
+        cursor.push_e(hash, true).unwrap();
+        cursor.push_e([0xFFu8], false).unwrap();
+        assert_eq!(cursor.get_ref().len(), 21);
+        assert_eq!(<[u8; 16]>::size(), 16);
+        assert_eq!(<[u8; 0]>::size(), 0);
+
+        cursor.set_position(0);
+        assert_eq!(cursor.next::<[u8; 4]>(), Some(*b"MAGC"));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.next_e::<[u8; 1]>(true), Some([b'M']));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.shift::<[u8; 4]>(), Some(*b"MAGC"));
+        assert_eq!(cursor.shift::<[u8; 0]>(), Some([]));
+        assert_eq!(cursor.position(), 4);
+
+        // The flag is ignored, since the bytes are kept in their stream order.
+        assert_eq!(cursor.next_e::<[u8; 16]>(true), Some(hash));
+        assert_eq!(cursor.shift_e::<[u8; 16]>(false), Some(hash));
+        assert_eq!(cursor.shift_e::<[u8; 1]>(true), Some([0xFF]));
+        assert_eq!(cursor.shift::<[u8; 1]>(), None);
+        assert_eq!(cursor.shift_e::<[u8; 0]>(true), Some([]));
+
+        assert_eq!(<[u8; 4]>::from_bytes(b"abc"), None);
+        assert_eq!(<[u8; 4]>::from_bytes_e(b"abcde", true), None);
+        assert_eq!(hash.to_bytes_e(false), hash);
+    }
+
+    #[test]
+    fn endian_array_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let position = [1.5f32, -2.0, 0.25];
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(position, true).unwrap();
+        cursor.push_e(position, false).unwrap();
+        cursor.push_e([0xFF00u16, 0x00FF, 0x1234, 0], true).unwrap();
+        assert_eq!(cursor.get_ref()[..4], 1.5f32.to_be_bytes());
+        assert_eq!(cursor.get_ref()[12..16], 1.5f32.to_le_bytes());
+        assert_eq!(<[f32; 3]>::size(), 12);
+
+        cursor.set_position(0);
+        assert_eq!(cursor.shift_e::<[f32; 3]>(true), Some(position));
+        assert_eq!(cursor.next_e::<[f32; 3]>(false), Some(position));
+        assert_eq!(cursor.position(), 12);
+        assert_eq!(cursor.shift_e::<[f32; 3]>(false), Some(position));
+        assert_eq!(
+            cursor.shift_e::<[[u8; 2]; 4]>(true),
+            Some([[0xFF, 0], [0, 0xFF], [0x12, 0x34], [0, 0]])
+        );
+        assert_eq!(cursor.shift_e::<[f32; 3]>(true), None);
+
+        // Elements which fail to convert fail the whole array, without moving.
+        let mut cursor = Cursor::new(vec![0, 0, 0, 0x41, 0, 0, 0xD8, 0]);
+        assert_eq!(cursor.shift_e::<[char; 2]>(true), None);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.shift_e::<[char; 1]>(true), Some(['A']));
+        assert_eq!(<[u16; 2]>::from_bytes_e(&[0; 3], true), None);
+    }
+
+    #[test]
+    fn tuple_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e((7u16, 0x8001u16, 1.5f32), true).unwrap();
+        cursor.push_e((7u16, 0x8001u16, 1.5f32), false).unwrap();
+        cursor.push((0xABu8, 'x')).unwrap();
+        assert_eq!(cursor.get_ref()[..4], [0, 7, 0x80, 0x01]);
+        assert_eq!(cursor.get_ref()[8..12], [7, 0, 0x01, 0x80]);
+        assert_eq!(<(u16, u16, f32)>::size(), 8);
+        assert_eq!(<(u8, u64)>::size(), 9);
+
+        cursor.set_position(0);
+        let (id, flags, weight): (u16, u16, f32) = cursor.shift_e(true).unwrap();
+        assert_eq!((id, flags, weight), (7, 0x8001, 1.5));
+        assert_eq!(
+            cursor.shift_e::<(u16, u16, f32)>(false),
+            Some((7, 0x8001, 1.5))
+        );
+        assert_eq!(cursor.shift::<(u8, char)>(), Some((0xAB, 'x')));
+
+        // The slice must be exactly the size of the tuple, and every element must convert.
+        assert_eq!(<(u8, u16)>::from_bytes(&[1, 2]), None);
+        assert_eq!(<(u8, u16)>::from_bytes(&[1, 2, 3, 4]), None);
+        assert_eq!(<(u8, bool)>::from_bytes_e(&[1, 2], true), None);
+        assert_eq!(<(u8, bool)>::from_bytes_e(&[1, 1], true), Some((1, true)));
+    }
+
+    #[test]
+    fn skip_test() {
+        use crate::prelude::*;
+        use crate::stats::StatsReader;
+        use crate::testutil::Unbounded;
+        use std::cell::Cell;
+        use std::io::Cursor;
+
+        // A record of a `u32` id, a `u16`-prefixed name, an 8-byte hash and a `u8`-prefixed blob of 10 KiB.
+        let mut record = Vec::new();
+        record.push_e(7u32, true).unwrap();
+        record.push_e(5u16, true).unwrap();
+        record.push_slice(b"hello").unwrap();
+        record.push_slice(&[0xAB; 8]).unwrap();
+        record.push_e(10_240u32, false).unwrap();
+        record.push_slice(&[1; 10_240]).unwrap();
+
+        let mut cursor = Cursor::new(record.clone());
+        let before = ALLOCATED.with(Cell::get);
+        assert_eq!(cursor.skip_value::<u32>(), Some(()));
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.skip_string_prefixed_e::<u16>(true), Some(5));
+        assert_eq!(cursor.position(), 11);
+        assert_eq!(cursor.skip_value::<[u8; 8]>(), Some(()));
+        assert_eq!(cursor.position(), 19);
+        assert_eq!(cursor.skip_string_prefixed::<u32>(), Some(10_240));
+        assert_eq!(cursor.position(), 10_263);
+        assert_eq!(cursor.skip_slice(0), Some(()));
+
+        // Only the lengths are read, into stack buffers, so nothing is allocated for the bytes they prefix.
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+
+        // Skipping past the end fails without moving, even though a cursor could be seeked there.
+        assert_eq!(cursor.skip_slice(1), None);
+        assert_eq!(cursor.position(), 10_263);
+        cursor.set_position(10_300);
+        assert_eq!(cursor.skip_slice(0), None);
+        assert_eq!(cursor.skip_value::<()>(), None);
+        assert_eq!(cursor.position(), 10_300);
+        cursor.set_position(19);
+        cursor.get_mut().truncate(10_000);
+        assert_eq!(cursor.skip_string_prefixed::<u32>(), None);
+        assert_eq!(cursor.position(), 19);
+
+        // Without a known end, the skipped bytes are read through in bounded chunks, still without allocating for them.
+        let mut unbounded = StatsReader::new(Unbounded(Cursor::new(record)));
+        unbounded.set_pos(19).unwrap();
+        let before = ALLOCATED.with(Cell::get);
+        assert_eq!(unbounded.skip_string_prefixed::<u32>(), Some(10_240));
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+        assert_eq!(unbounded.pos(), Some(10_263));
+        assert_eq!(unbounded.stats().largest_read, 4096);
+
+        unbounded.set_pos(10_000).unwrap();
+        assert_eq!(unbounded.skip_slice(264), None);
+        assert_eq!(unbounded.pos(), Some(10_000));
+        assert_eq!(unbounded.skip_slice(263), Some(()));
+    }
+
+    #[test]
+    fn wrapping_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::num::{Saturating, Wrapping};
+
+        for value in [0u32, 1, 0xDEAD_BEEF, u32::MAX] {
+            assert_eq!(Wrapping(value).to_bytes(), value.to_bytes());
+            assert_eq!(Wrapping(value).to_bytes_e(true), value.to_bytes_e(true));
+            assert_eq!(Saturating(value).to_bytes_e(false), value.to_bytes_e(false));
+        }
+        for value in [i16::MIN, -1, 0, 0x1234, i16::MAX] {
+            assert_eq!(Saturating(value).to_bytes_e(true), value.to_bytes_e(true));
+            assert_eq!(Saturating(value).to_bytes_e(false), value.to_bytes_e(false));
+        }
+        assert_eq!(Wrapping::<u64>::size(), 8);
+        assert_eq!(Saturating::<i16>::size(), 2);
+
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 1]);
+        let counter = cursor.shift::<Wrapping<u32>>().unwrap();
+        assert_eq!(counter + Wrapping(1), Wrapping(0));
+        let level = cursor.shift_e::<Saturating<i16>>(true).unwrap();
+        assert_eq!(level - Saturating(1), Saturating(i16::MIN));
+        assert_eq!(cursor.shift::<Wrapping<u16>>(), None);
+        assert_eq!(cursor.shift::<Wrapping<bool>>(), Some(Wrapping(true)));
+    }
+
+    #[test]
+    fn shift_chunks_test() {
+        use crate::prelude::*;
+        use crate::testutil::Unbounded;
+        use std::io::Cursor;
+        use std::ops::ControlFlow;
+
+        let payload: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+
+        // The whole payload, in chunks which do not divide it evenly.
+        let mut cursor = Cursor::new(payload.clone());
+        cursor.set_position(100);
+        let mut sizes = Vec::new();
+        let mut relayed = Vec::new();
+        let consumed = cursor.shift_chunks(9_000, 4096, |chunk| {
+            sizes.push(chunk.len());
+            relayed.extend_from_slice(chunk);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(consumed, Some(9_000));
+        assert_eq!(sizes, [4096, 4096, 808]);
+        assert_eq!(relayed, payload[100..9_100]);
+        assert_eq!(cursor.position(), 9_100);
+
+        // Breaking stops just after the chunk which broke.
+        cursor.set_position(0);
+        let mut calls = 0;
+        let consumed = cursor.shift_chunks(10_000, 1000, |_| {
+            calls += 1;
+            if calls == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(consumed, Some(3000));
+        assert_eq!(cursor.position(), 3000);
+        assert_eq!(cursor.shift::<u8>(), Some(payload[3000]));
+
+        // The source ends before `total`, with and without a known end.
+        cursor.set_position(9_500);
+        let mut relayed = Vec::new();
+        let consumed = cursor.shift_chunks(1000, 300, |chunk| {
+            relayed.extend_from_slice(chunk);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(consumed, Some(500));
+        assert_eq!(relayed, payload[9_500..]);
+        assert_eq!(cursor.position(), 10_000);
+
+        let mut unbounded = Unbounded(Cursor::new(payload.clone()));
+        unbounded.set_pos(9_500).unwrap();
+        let mut sizes = Vec::new();
+        let consumed = unbounded.shift_chunks(1000, 300, |chunk| {
+            sizes.push(chunk.len());
+            ControlFlow::Continue(())
+        });
+        assert_eq!(consumed, Some(500));
+        assert_eq!(sizes, [300, 200]);
+        assert_eq!(unbounded.pos(), Some(10_000));
+
+        assert_eq!(
+            cursor.shift_chunks(10, 4, |_| ControlFlow::Continue(())),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn int24_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        assert_eq!(U24::size(), 3);
+        assert_eq!(I24::size(), 3);
+
+        // The sign boundary: 0x800000 is the smallest I24 and a plain U24.
+        let boundary = [0x80, 0x00, 0x00];
+        assert_eq!(I24::from_bytes_e(&boundary, true), Some(I24::MIN));
+        assert_eq!(
+            U24::from_bytes_e(&boundary, true).map(U24::get),
+            Some(0x80_0000)
+        );
+        assert_eq!(
+            I24::from_bytes_e(&[0xFF, 0xFF, 0x7F], false),
+            Some(I24::MAX)
+        );
+        assert_eq!(
+            I24::from_bytes_e(&[0x00, 0x00, 0x80], false),
+            Some(I24::MIN)
+        );
+        assert_eq!(I24::from_bytes(&[0xFF; 3]).map(I24::get), Some(-1));
+        assert_eq!(I24::MIN.to_bytes_e(true), boundary);
+        assert_eq!(I24::wrapping_from(-1).to_bytes_e(false), [0xFF; 3]);
+        assert_eq!(U24::from_bytes(&[1, 2]), None);
+
+        // Out of range values are rejected, or masked on request.
+        assert_eq!(U24::new(0x0100_0000), None);
+        assert_eq!(U24::try_from(0x0100_0000u32), Err(TryFromNarrowIntError));
+        assert_eq!(U24::wrapping_from(0x0123_4567).get(), 0x23_4567);
+        assert_eq!(I24::new(0x80_0000), None);
+        assert_eq!(I24::try_from(-0x80_0001), Err(TryFromNarrowIntError));
+        assert_eq!(I24::try_from(-0x80_0000), Ok(I24::MIN));
+        assert_eq!(I24::wrapping_from(0x80_0000), I24::MIN);
+        assert_eq!(u32::from(U24::MAX), 0xFF_FFFF);
+        assert_eq!(i64::from(I24::MIN), -0x80_0000);
+        assert_eq!(I24::from(-2i16).get(), -2);
+        assert_eq!(U24::from(0xFFFFu16).get(), 0xFFFF);
+
+        let samples = [I24::MIN, I24::from(-1i8), I24::default(), I24::MAX];
+        for bigendian in [false, true] {
+            let mut cursor = Cursor::new(Vec::new());
+            for sample in samples {
+                cursor.push_e(sample, bigendian).unwrap();
+            }
+            cursor.push_e(U24::MAX, bigendian).unwrap();
+            assert_eq!(cursor.get_ref().len(), 15);
+
+            cursor.set_position(0);
+            assert_eq!(cursor.shift_vec_e::<I24>(4, bigendian).unwrap(), samples);
+            assert_eq!(cursor.shift_e::<U24>(bigendian), Some(U24::MAX));
+            assert_eq!(cursor.shift_e::<U24>(bigendian), None);
+        }
+    }
+
+    #[test]
+    fn int48_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        assert_eq!(U48::size(), 6);
+        assert_eq!(I48::size(), 6);
+        assert_eq!(U48::MAX.get(), (1 << 48) - 1);
+        assert_eq!(I48::MIN.get(), -(1 << 47));
+        assert_eq!(I48::MAX.get(), (1 << 47) - 1);
+
+        // The upper boundaries, in both byte orders.
+        let mac = [0x00, 0x1B, 0x63, 0x84, 0x45, 0xE6];
+        assert_eq!(
+            U48::from_bytes_e(&mac, true).map(U48::get),
+            Some(0x001B_6384_45E6)
+        );
+        assert_eq!(
+            U48::from_bytes_e(&mac, false).map(U48::get),
+            Some(0xE645_8463_1B00)
+        );
+        assert_eq!(U48::MAX.to_bytes_e(true), [0xFF; 6]);
+        assert_eq!(I48::from_bytes_e(&[0xFF; 6], true), I48::new(-1));
+        assert_eq!(
+            I48::from_bytes_e(&[0x80, 0, 0, 0, 0, 0], true),
+            Some(I48::MIN)
+        );
+        assert_eq!(
+            I48::from_bytes_e(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F], false),
+            Some(I48::MAX)
+        );
+        assert_eq!(I48::MIN.to_bytes_e(false), [0, 0, 0, 0, 0, 0x80]);
+        assert_eq!(I48::from_bytes(&[0; 8]), None);
+
+        assert_eq!(U48::new(1 << 48), None);
+        assert_eq!(U48::try_from(u64::MAX), Err(TryFromNarrowIntError));
+        assert_eq!(
+            U48::wrapping_from(0x0123_4567_89AB_CDEF).get(),
+            0x4567_89AB_CDEF
+        );
+        assert_eq!(I48::try_from(1i64 << 47), Err(TryFromNarrowIntError));
+        assert_eq!(I48::wrapping_from(1 << 47), I48::MIN);
+        assert_eq!(u64::from(U48::from(U24::MAX)), 0xFF_FFFF);
+        assert_eq!(i64::from(I48::from(i32::MIN)), i32::MIN as i64);
+
+        let stamps = [U48::MIN, U48::from(0x1234_5678u32), U48::MAX];
+        for bigendian in [false, true] {
+            let mut cursor = Cursor::new(Vec::new());
+            for stamp in stamps {
+                cursor.push_e(stamp, bigendian).unwrap();
+            }
+            cursor.push_e(I48::MIN, bigendian).unwrap();
+
+            cursor.set_position(0);
+            assert_eq!(cursor.shift_vec_e::<U48>(3, bigendian).unwrap(), stamps);
+            assert_eq!(cursor.shift_e::<I48>(bigendian), Some(I48::MIN));
+            assert_eq!(cursor.shift_e::<I48>(bigendian), None);
+        }
+    }
+
+    #[test]
+    fn duration_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::time::Duration;
+
+        let timeout = Duration::new(0x0102, 0x0304);
+        assert_eq!(Duration::size(), 12);
+        assert_eq!(
+            timeout.to_bytes_e(true),
+            [0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 3, 4]
+        );
+        assert_eq!(
+            timeout.to_bytes_e(false),
+            [2, 1, 0, 0, 0, 0, 0, 0, 4, 3, 0, 0]
+        );
+        for value in [
+            Duration::ZERO,
+            timeout,
+            Duration::from_millis(1500),
+            Duration::MAX,
+        ] {
+            assert_eq!(Duration::from_bytes(&value.to_bytes()), Some(value));
+            assert_eq!(
+                Duration::from_bytes_e(&value.to_bytes_e(true), true),
+                Some(value)
+            );
+        }
+
+        // Nanoseconds of a whole second or more are invalid, and the read is undone.
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(7u64, false).unwrap();
+        cursor.push_e(999_999_999u32, false).unwrap();
+        cursor.push_e(7u64, false).unwrap();
+        cursor.push_e(1_000_000_000u32, false).unwrap();
+        cursor.set_position(0);
+
+        assert_eq!(
+            cursor.shift_e::<Duration>(false),
+            Some(Duration::new(7, 999_999_999))
+        );
+        assert_eq!(cursor.shift_e::<Duration>(false), None);
+        assert_eq!(cursor.position(), 12);
+        assert_eq!(Duration::from_bytes(&[0; 11]), None);
+    }
+
+    #[test]
+    fn length_overflow_test() {
+        use crate::prelude::*;
+        use crate::shared::ReaderFactory;
+        use std::io::Cursor;
+
+        assert_eq!(checked_len(0, usize::MAX), Ok(0));
+        assert_eq!(checked_len(usize::MAX, 1), Ok(usize::MAX));
+        assert_eq!(checked_len(usize::MAX / 7, 7), Ok(usize::MAX / 7 * 7));
+        assert_eq!(
+            checked_len(usize::MAX / 7 + 1, 7),
+            Err(LengthOverflow {
+                count: usize::MAX / 7 + 1,
+                size: 7
+            })
+        );
+        assert_eq!(
+            checked_len(2, usize::MAX / 2 + 1).unwrap_err().to_string(),
+            format!(
+                "2 items of {} bytes overflow the length of a buffer",
+                usize::MAX / 2 + 1
+            )
+        );
+
+        // Counts whose byte length would wrap fail up front, without allocating or reading anything.
+        let mut cursor = Cursor::new(vec![0xFF; 16]);
+        assert_eq!(cursor.shift_vec::<u32>(usize::MAX / 4 + 1), None);
+        assert_eq!(cursor.shift_vec_e::<u64>(usize::MAX / 2, true), None);
+        assert_eq!(
+            cursor.shift_deinterleaved::<u16>(usize::MAX / 2 + 1, 2, false),
+            None
+        );
+        assert_eq!(
+            cursor.shift_deinterleaved::<u16>(usize::MAX / 4, 3, false),
+            None
+        );
+        assert_eq!(
+            cursor.shift_strided::<u8>(usize::MAX, usize::MAX, 0, false),
+            None
+        );
+        assert_eq!(cursor.shift_gsm7(usize::MAX / 7 + 1), None);
+        assert_eq!(
+            cursor.shift_vec_aligned::<u16>(usize::MAX / 2 + 1, 16),
+            None
+        );
+        assert_eq!(cursor.position(), 0);
+
+        // A count prefix near the top of the range is rejected whatever its element size.
+        let mut cursor = Cursor::new(vec![0xFF; 8]);
+        assert_eq!(cursor.shift_counted::<u64, u32>(true), None);
+        assert_eq!(cursor.position(), 0);
+
+        // Splitting a source of huge records into chunks does not wrap while scaling the record count.
+        let offsets = ReaderFactory::<Vec<u8>>::record_offsets(0, u64::MAX, 1, 4);
+        assert_eq!(offsets.len(), 5);
+        assert!(offsets.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn system_time_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        assert_eq!(SystemTime::size(), 12);
+        assert_eq!(UNIX_EPOCH.to_bytes_e(true), [0; 12]);
+
+        // 1969-07-20 20:17:40 UTC, a quarter of a second in.
+        let landing = UNIX_EPOCH - Duration::new(14_182_940, 0) + Duration::from_millis(250);
+        assert_eq!(
+            landing.to_bytes_e(true),
+            [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x27, 0x95, 0xE4, 0x0E, 0xE6, 0xB2, 0x80]
+        );
+
+        // 3000-01-01 00:00:00 UTC.
+        let far = UNIX_EPOCH + Duration::new(32_503_680_000, 123);
+
+        let mut cursor = Cursor::new(Vec::new());
+        for time in [UNIX_EPOCH, landing, far] {
+            cursor.push_e(time, false).unwrap();
+            cursor.push(time).unwrap();
+        }
+        cursor.set_position(0);
+        for time in [UNIX_EPOCH, landing, far] {
+            assert_eq!(cursor.shift_e::<SystemTime>(false), Some(time));
+            assert_eq!(cursor.shift::<SystemTime>(), Some(time));
+        }
+
+        // Invalid nanoseconds are rejected, and the read is undone.
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(0i64, true).unwrap();
+        cursor.push_e(1_000_000_000u32, true).unwrap();
+        cursor.set_position(0);
+        assert_eq!(cursor.shift_e::<SystemTime>(true), None);
+        assert_eq!(cursor.position(), 0);
+
+        // Extreme seconds either fail or round trip, depending on the range of the platform, but never panic.
+        for (secs, nanos) in [(i64::MAX, 999_999_999u32), (i64::MIN, 0), (i64::MIN, 1)] {
+            let mut bytes = secs.to_bytes_e(true);
+            bytes.extend(nanos.to_bytes_e(true));
+
+            if let Some(time) = SystemTime::from_bytes_e(&bytes, true) {
+                assert_eq!(time.to_bytes_e(true), bytes);
+            }
+        }
+        assert_eq!(SystemTime::from_bytes(&[0; 13]), None);
+    }
+
+    #[test]
+    fn ip_addr_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::net::{Ipv4Addr, Ipv6Addr};
+
+        assert_eq!((Ipv4Addr::size(), Ipv6Addr::size()), (4, 16));
+        assert_eq!(Ipv4Addr::LOCALHOST.to_bytes(), [127, 0, 0, 1]);
+        assert_eq!(Ipv4Addr::LOCALHOST.to_bytes_e(false), [1, 0, 0, 127]);
+        assert_eq!(Ipv6Addr::LOCALHOST.to_bytes(), Ipv6Addr::LOCALHOST.octets());
+
+        // An IPv4-mapped address, ::ffff:10.0.0.7, as it appears in a packet.
+        let mapped = Ipv4Addr::new(10, 0, 0, 7).to_ipv6_mapped();
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_slice(&mapped.octets()).unwrap();
+        cursor.push_slice(&[127, 0, 0, 1]).unwrap();
+        cursor.push_e(Ipv6Addr::LOCALHOST, false).unwrap();
+        cursor.set_position(0);
+
+        let read = cursor.shift::<Ipv6Addr>().unwrap();
+        assert_eq!(read, mapped);
+        assert_eq!(read.to_ipv4_mapped(), Some(Ipv4Addr::new(10, 0, 0, 7)));
+        assert_eq!(cursor.shift::<Ipv4Addr>(), Some(Ipv4Addr::LOCALHOST));
+        assert_eq!(
+            cursor.next::<Ipv6Addr>().map(|a| a.is_loopback()),
+            Some(false)
+        );
+        assert_eq!(cursor.shift_e::<Ipv6Addr>(false), Some(Ipv6Addr::LOCALHOST));
+
+        assert_eq!(Ipv4Addr::from_bytes(&[127, 0, 0]), None);
+        assert_eq!(Ipv6Addr::from_bytes(&[0; 17]), None);
+        assert_eq!(Ipv6Addr::from_bytes_e(&[0; 4], true), None);
+        assert_eq!(cursor.shift::<Ipv4Addr>(), None);
+    }
+
+    #[test]
+    fn socket_addr_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+        let v4 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 7), 0x1234);
+        let v6 = SocketAddrV6::new(Ipv4Addr::new(10, 0, 0, 7).to_ipv6_mapped(), 53, 0, 0);
+        assert_eq!((SocketAddrV4::size(), SocketAddrV6::size()), (6, 18));
+        assert_eq!(v4.to_bytes(), [10, 0, 0, 7, 0x12, 0x34]);
+        assert_eq!(v4.to_bytes_e(false), [7, 0, 0, 10, 0x34, 0x12]);
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_e(v4, true).unwrap();
+        cursor.push_e(v6, true).unwrap();
+        cursor.push_e(v6, false).unwrap();
+        cursor.set_position(0);
+
+        assert_eq!(cursor.shift_e::<SocketAddrV4>(true), Some(v4));
+        assert_eq!(cursor.shift::<SocketAddrV6>(), Some(v6));
+        assert_eq!(
+            cursor.next_e::<SocketAddrV6>(true).map(|a| a.port()),
+            Some(0x3500)
+        );
+        assert_eq!(cursor.shift_e::<SocketAddrV6>(false), Some(v6));
+
+        // The flow information and scope identifier are not part of the layout.
+        let scoped = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 7, 2);
+        let read = SocketAddrV6::from_bytes(&scoped.to_bytes()).unwrap();
+        assert_eq!((read.ip(), read.port()), (scoped.ip(), scoped.port()));
+        assert_eq!((read.flowinfo(), read.scope_id()), (0, 0));
+
+        assert_eq!(SocketAddrV4::from_bytes(&[0; 5]), None);
+        assert_eq!(SocketAddrV6::from_bytes_e(&[0; 16], true), None);
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+        use uuid::Uuid;
+
+        // The bytes of the UUID as stored by RFC 4122 formats, followed by the same UUID as a Microsoft GUID.
+        let mut cursor = Cursor::new(vec![
+            0x67, 0xE5, 0x50, 0x44, 0x10, 0xB1, 0x42, 0x6F, 0x92, 0x47, 0xBB, 0x68, 0x0E, 0x5F,
+            0xE0, 0xC8, //
+            0x44, 0x50, 0xE5, 0x67, 0xB1, 0x10, 0x6F, 0x42, 0x92, 0x47, 0xBB, 0x68, 0x0E, 0x5F,
+            0xE0, 0xC8,
+        ]);
+
+        let id = cursor.shift::<Uuid>().unwrap();
+        assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(cursor.shift_e::<Uuid>(false), Some(id));
+        assert_eq!(cursor.shift::<Uuid>(), None);
+
+        let mut out: Vec<u8> = Vec::new();
+        out.push_slice(&id.to_bytes()).unwrap();
+        out.push_e(id, false).unwrap();
+        assert_eq!(out, cursor.into_inner());
+        assert_eq!(id.to_bytes_e(true), id.to_bytes());
+
+        assert_eq!(Uuid::size(), 16);
+        assert_eq!(<Uuid as SizedNumber>::from_bytes(&[0; 15]), None);
+        assert_eq!(Uuid::from_bytes_e(&[0; 17], false), None);
+    }
+
+    #[test]
+    fn record_geometry_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // A three byte header followed by four records of four bytes each.
+        let geometry = RecordGeometry {
+            header_len: 3,
+            record_size: 4,
+            count: Some(4),
+        };
+        let mut cursor = Cursor::new((0..19).collect::<Vec<u8>>());
+
+        assert_eq!(geometry.offset_of(0), Some(3));
+        assert_eq!(geometry.index_at(3), Some((0, 0)));
+        assert_eq!(geometry.index_at(2), None);
+        assert_eq!(geometry.index_at(9), Some((1, 2)));
+
+        // The last valid record, and the byte ending it.
+        assert_eq!(cursor.seek_to_record(geometry, 3), Some(()));
+        assert_eq!(cursor.position(), 15);
+        assert_eq!(cursor.current_record(geometry), Some((3, 0)));
+        cursor.set_position(18);
+        assert_eq!(cursor.current_record(geometry), Some((3, 3)));
+
+        // One past the end is rejected, and the position is left unchanged.
+        assert_eq!(geometry.offset_of(4), None);
+        assert_eq!(cursor.seek_to_record(geometry, 4), None);
+        assert_eq!(cursor.position(), 18);
+        cursor.set_position(19);
+        assert_eq!(cursor.current_record(geometry), None);
+
+        // Without a count, only overflow limits the index.
+        let unbounded = RecordGeometry {
+            count: None,
+            ..geometry
+        };
+        assert_eq!(unbounded.offset_of(4), Some(19));
+        assert_eq!(unbounded.index_at(u64::MAX), Some((u64::MAX / 4, 0)));
+        assert_eq!(unbounded.offset_of(u64::MAX / 4), Some(u64::MAX));
+        assert_eq!(unbounded.offset_of(u64::MAX / 4 + 1), None);
+        assert_eq!(
+            RecordGeometry {
+                record_size: 0,
+                ..unbounded
+            }
+            .index_at(3),
+            None
+        );
+    }
+
+    #[test]
+    fn guid_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // The type GUID of an EFI System Partition as stored on disk by GPT, followed by the same GUID in the RFC 4122 layout.
+        let esp = Guid::from_u128(0xC12A7328_F81F_11D2_BA4B_00A0C93EC93B);
+        let mut cursor = Cursor::new(vec![
+            0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E,
+            0xC9, 0x3B, //
+            0xC1, 0x2A, 0x73, 0x28, 0xF8, 0x1F, 0x11, 0xD2, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E,
+            0xC9, 0x3B,
+        ]);
+
+        let guid = cursor.shift::<Guid>().unwrap();
+        assert_eq!(guid, esp);
+        assert_eq!(guid.data1, 0xC12A7328);
+        assert_eq!(guid.data4, [0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B]);
+        assert_eq!(guid.to_u128(), 0xC12A7328_F81F_11D2_BA4B_00A0C93EC93B);
+        assert_eq!(guid.to_string(), "c12a7328-f81f-11d2-ba4b-00a0c93ec93b");
+        assert_eq!(cursor.shift_e::<Guid>(true), Some(esp));
+        assert_eq!(cursor.shift::<Guid>(), None);
+
+        let mut out = Cursor::new(Vec::new());
+        out.push(esp).unwrap();
+        out.push_e(esp, true).unwrap();
+        assert_eq!(out.into_inner(), cursor.into_inner());
+
+        #[cfg(feature = "uuid")]
+        {
+            let id = uuid::Uuid::from(esp);
+            assert_eq!(id.to_string(), esp.to_string());
+            assert_eq!(Guid::from(id), esp);
+            assert_eq!(id.to_bytes_le().to_vec(), esp.to_bytes());
+        }
+    }
+
+    #[test]
+    fn failure_allocation_test() {
+        use crate::prelude::*;
+        use crate::reader::SeqReader;
+        use std::io::Cursor;
+
+        let mut cursor = Cursor::new(vec![1u8, 2, 3]);
+        let mut reader = SeqReader::new(Cursor::new(vec![1u8, 2, 3]));
+        reader.shift::<u8>().unwrap();
+
+        // Failing reads, and the rollback after them, neither allocate nor move the position.
+        let before = ALLOCATED.with(Cell::get);
+        for _ in 0..1_000_000 {
+            assert_eq!(cursor.shift::<u64>(), None);
+            assert_eq!(reader.shift_e::<u64>(true), None);
+        }
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(reader.pos(), Some(1));
+
+        // The cached position follows reads and seeks, and is queried again once the inner reader is exposed.
+        assert_eq!(reader.shift::<[u8; 2]>(), Some([2, 3]));
+        assert_eq!(reader.pos(), Some(3));
+        reader.get_mut().set_position(0);
+        assert_eq!(reader.pos(), Some(0));
+        assert_eq!(reader.next::<u8>(), Some(1));
+    }
+
+    #[test]
+    fn zero_sized_test() {
+        use crate::prelude::*;
+        use crate::reader::SeqReader;
+        use std::io::Cursor;
+        use std::marker::PhantomData;
+
+        let mut cursor = Cursor::new(vec![1u8]);
+
+        // Zero-size reads succeed without moving, even at the end of the source.
+        assert_eq!(cursor.next::<()>(), Some(()));
+        assert_eq!(cursor.shift::<()>(), Some(()));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(
+            cursor.shift::<(u8, PhantomData<str>)>(),
+            Some((1, PhantomData))
+        );
+        assert_eq!(cursor.shift::<()>(), Some(()));
+        assert_eq!(cursor.shift_e::<PhantomData<u64>>(true), Some(PhantomData));
+        assert_eq!(cursor.next_e::<()>(false), Some(()));
+        assert_eq!(cursor.shift_vec::<()>(3), Some(vec![(); 3]));
+        assert_eq!(cursor.position(), 1);
+
+        let mut reader = SeqReader::new(Cursor::new(Vec::new()));
+        assert_eq!(reader.shift::<()>(), Some(()));
+        assert!(reader.take_error().is_none());
+
+        let mut out = Vec::new();
+        out.push_e((), true).unwrap();
+        out.push_e(PhantomData::<u8>, false).unwrap();
+        assert!(out.is_empty());
+
+        assert_eq!(<()>::size(), 0);
+        assert_eq!(<()>::from_bytes(&[]), Some(()));
+        assert_eq!(<()>::from_bytes(&[0]), None);
+        assert_eq!(PhantomData::<u32>::from_bytes_e(&[0], true), None);
+    }
+
+    #[test]
+    fn optional_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // A present u32, an absent one, a present one cut short, and an invalid presence byte.
+        let mut cursor = Cursor::new(vec![1, 0, 0, 1, 0, 0, 1, 0, 0, 2]);
+
+        assert_eq!(cursor.next_optional_e::<u32>(true), Some(Some(256)));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.next_optional::<u32>(), Some(Some(0x0001_0000)));
+        assert_eq!(cursor.shift_optional_e::<u32>(true), Some(Some(256)));
+        assert_eq!(cursor.next_optional::<u32>(), Some(None));
+        assert_eq!(cursor.position(), 5);
+        assert_eq!(cursor.shift_optional::<u32>(), Some(None));
+
+        // The stream ends inside the value, which is distinct from the value being absent.
+        assert_eq!(cursor.shift_optional::<u32>(), None);
+        assert_eq!(cursor.next_optional::<u32>(), None);
+        assert_eq!(cursor.position(), 6);
+
+        cursor.set_position(9);
+        assert_eq!(cursor.shift_optional::<u8>(), None);
+        assert_eq!(cursor.shift_optional_e::<u8>(false), None);
+        assert_eq!(cursor.position(), 9);
+
+        cursor.set_position(10);
+        assert_eq!(cursor.shift_optional::<u8>(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn bf16_test() {
+        use crate::prelude::*;
+        use half::bf16;
+        use std::io::Cursor;
+
+        assert_eq!(bf16::size(), 2);
+
+        // Pi and 0.1 lose the low bits of their f32 mantissas, while the rest are exact.
+        let values = [
+            bf16::from_f32(std::f32::consts::PI),
+            bf16::from_f32(-0.1),
+            bf16::from_f32(1e-40),
+            bf16::MAX,
+            bf16::NEG_INFINITY,
+            bf16::NEG_ZERO,
+            bf16::from_bits(0x7FC1),
+        ];
+        assert_eq!(f32::from(values[0]), 3.140625);
+        assert_ne!(f32::from(values[1]), -0.1);
+
+        for bigendian in [false, true] {
+            let mut cursor = Cursor::new(Vec::new());
+            for value in values {
+                cursor.push_e(value, bigendian).unwrap();
+            }
+            assert_eq!(cursor.get_ref().len(), 14);
+
+            cursor.set_position(0);
+            let read = cursor.shift_vec_e::<bf16>(values.len(), bigendian).unwrap();
+            for (read, value) in read.into_iter().zip(values) {
+                // Widening to f32 and back is lossless, down to the bits of NaNs.
+                assert_eq!(bf16::from_f32(f32::from(read)).to_bits(), value.to_bits());
+                assert_eq!(read.to_bits(), value.to_bits());
+            }
+        }
+
+        // The bits are the upper half of the bits of the f32.
+        let value = bf16::from_f32(std::f32::consts::PI);
+        assert_eq!(value.to_bytes_e(true), [0x40, 0x49]);
+        assert_eq!(value.to_bytes(), [0x49, 0x40]);
+        assert_eq!(f32::from(value).to_bits() >> 16, 0x4049);
+        assert_eq!(bf16::from_bytes_e(&[0x40], true), None);
+    }
+
+    #[test]
+    fn size_const_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // The sizes of composite types fold into constants, such as the length of a stack buffer.
+        const HEADER: usize = <(u32, [u16; 3], bool)>::SIZE;
+        let mut buf = [0u8; HEADER];
+        assert_eq!(buf.len(), 11);
+
+        let mut cursor = Cursor::new((1..=11).collect::<Vec<u8>>());
+        cursor.fill(&mut buf).unwrap();
+        assert_eq!(buf[HEADER - 1], 11);
+
+        assert_eq!(U24::SIZE, 3);
+        assert_eq!(I48::SIZE, 6);
+        assert_eq!(char::SIZE, 4);
+        assert_eq!(Guid::SIZE, 16);
+        assert_eq!(<()>::SIZE, 0);
+        assert_eq!(std::time::Duration::SIZE, 12);
+        assert_eq!(std::net::SocketAddrV6::SIZE, 18);
+        assert_eq!(Option::<std::num::NonZeroU16>::SIZE, 2);
+        assert_eq!(usize::SIZE, 8);
+    }
+
+    #[test]
+    fn to_bytes_into_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        let record = (0x0102_0304u32, [-2i16, 3], 'é', true, 1.5f64);
+        let mut out = [0u8; 32];
+
+        let len = record.to_bytes_into_e(&mut out, true).unwrap();
+        assert_eq!(len, 21);
+        assert_eq!(out[..len], record.to_bytes_e(true));
+        assert_eq!(record.to_bytes_into(&mut out[..20]), None);
+
+        // Writing primitives and records of them through a writer does not allocate once the buffer has grown.
+        let mut cursor = Cursor::new(Vec::with_capacity(4096));
+        let before = ALLOCATED.with(Cell::get);
+        for i in 0..100u16 {
+            cursor.push_e(i, true).unwrap();
+            cursor.push(record).unwrap();
+        }
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+
+        cursor.set_position(2);
+        assert_eq!(
+            cursor.shift::<(u32, [i16; 2], char, bool, f64)>(),
+            Some(record)
+        );
+        assert_eq!(cursor.get_ref().len(), 100 * 23);
+
+        // Types without an override fall back to copying the bytes of to_bytes.
+        let wrapped = std::num::Wrapping(7u8);
+        assert_eq!(wrapped.to_bytes_into(&mut out), Some(1));
+        assert_eq!(out[0], 7);
+
+        // Writers only go through the stack buffer for types which write in place, and otherwise write every byte of to_bytes, even
+        // when it disagrees with the declared size.
+        struct Padded(u8);
+        impl SizedNumber for Padded {
+            const SIZE: usize = 2;
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                Some(Padded(bytes[0]))
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                vec![self.0; usize::from(self.0)]
+            }
+        }
+        const { assert!(u32::WRITES_IN_PLACE && <(u8, [char; 2])>::WRITES_IN_PLACE) };
+        const { assert!(!Padded::WRITES_IN_PLACE && !<(u8, Padded)>::WRITES_IN_PLACE) };
+
+        let mut written = Vec::new();
+        for len in 1..=3 {
+            assert_eq!(Padded(len).to_bytes_into(&mut out), (len == 2).then_some(2));
+            written.push_slice(&[0xFF]).unwrap();
+            SeqByteWriter::push(&mut written, Padded(len)).unwrap();
+        }
+        assert_eq!(written, [0xFF, 1, 0xFF, 2, 2, 0xFF, 3, 3, 3]);
+
+        // The same holds for the small buffers, which keep every byte of to_bytes on the heap.
+        for len in 1..=3 {
+            let small = Padded(len).to_bytes_small();
+            assert!(!small.is_inline());
+            assert_eq!(*small, Padded(len).to_bytes());
+        }
+    }
+
+    #[test]
+    fn from_bytes_prefix_test() {
+        use crate::prelude::*;
+
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05];
+
+        // Too short, exact, and longer than the type.
+        assert_eq!(u32::from_bytes_prefix(&bytes[..3]), None);
+        assert_eq!(
+            u32::from_bytes_prefix(&bytes[..4]),
+            Some((0x0403_0201, &[][..]))
+        );
+        assert_eq!(
+            u32::from_bytes_prefix_e(&bytes, true),
+            Some((0x0102_0304, &[0x05][..]))
+        );
+        assert_eq!(u32::from_bytes(&bytes), None);
+        assert_eq!(u32::from_bytes_e(&bytes[..4], true), Some(0x0102_0304));
+
+        // Decodes chain over a slice, and a value which fails to convert fails the prefix too.
+        let (flag, rest) = bool::from_bytes_prefix(&[1, 0x41, 0, 0, 0, 2]).unwrap();
+        let (letter, rest) = char::from_bytes_prefix(rest).unwrap();
+        assert_eq!((flag, letter), (true, 'A'));
+        assert_eq!(bool::from_bytes_prefix(rest), None);
+        assert_eq!(<()>::from_bytes_prefix(rest), Some(((), &[2][..])));
+    }
+
+    #[test]
+    fn decode_error_test() {
+        use crate::prelude::*;
+        use std::num::NonZeroU16;
+        use std::time::Duration;
+
+        assert_eq!(
+            u16::from_bytes_checked(&[1, 2, 3]),
+            Err(DecodeError::WrongLength {
+                expected: 2,
+                got: 3
+            })
+        );
+        assert_eq!(
+            bool::from_bytes_checked_e(&[2], true),
+            Err(DecodeError::InvalidValue("flag byte is neither 0 nor 1"))
+        );
+        assert_eq!(
+            char::from_bytes_checked_e(&[0, 0x11, 0, 0], true),
+            Err(DecodeError::InvalidValue("code point above U+10FFFF"))
+        );
+        assert_eq!(
+            char::from_bytes_checked(&[0x00, 0xDC, 0, 0]),
+            Err(DecodeError::InvalidValue("surrogate code point"))
+        );
+        assert_eq!(char::from_bytes_checked(&[0x41, 0, 0, 0]), Ok('A'));
+        assert!(matches!(
+            NonZeroU16::from_bytes_checked_e(&[0, 0], true),
+            Err(DecodeError::InvalidValue(_))
+        ));
+        assert_eq!(
+            NonZeroU16::from_bytes_checked(&[0]),
+            Err(DecodeError::WrongLength {
+                expected: 2,
+                got: 1
+            })
+        );
+
+        let mut bytes = Duration::new(5, 0).to_bytes_e(true);
+        assert_eq!(
+            Duration::from_bytes_checked_e(&bytes, true),
+            Ok(Duration::new(5, 0))
+        );
+        bytes[8..].copy_from_slice(&1_000_000_000u32.to_be_bytes());
+        assert_eq!(
+            Duration::from_bytes_checked_e(&bytes, true),
+            Err(DecodeError::InvalidValue(
+                "nanoseconds are a whole second or more"
+            ))
+        );
+
+        // Types without an override report a generic reason for bytes their conversion rejects.
+        assert_eq!(
+            <(u8, bool)>::from_bytes_checked(&[1, 7]),
+            Err(DecodeError::InvalidValue("rejected by from_bytes"))
+        );
+        assert_eq!(
+            DecodeError::WrongLength {
+                expected: 4,
+                got: 2
+            }
+            .to_string(),
+            "expected 4 bytes, got 2"
+        );
+    }
+
+    #[test]
+    fn endianness_test() {
+        use crate::prelude::*;
+        use crate::swap::maybe_swap;
+        use std::io::Cursor;
+
+        let native = if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        };
+        assert_eq!(Endianness::Native.is_big(), native.is_big());
+        assert!(Endianness::Big.is_big() && !Endianness::Little.is_big());
+
+        let value = 0x0102_0304u32;
+        assert_eq!(
+            value.to_bytes_endian(Endianness::Native),
+            value.to_ne_bytes()
+        );
+        assert_eq!(value.to_bytes_endian(Endianness::Big), value.to_be_bytes());
+        assert_eq!(
+            u32::from_bytes_endian(&value.to_ne_bytes(), Endianness::Native),
+            Some(value)
+        );
+        assert_eq!(
+            <(u16, char)>::from_bytes_endian(&[0, 1, 0, 0, 0, 0x41], Endianness::Big),
+            Some((1, 'A'))
+        );
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.push_endian(value, Endianness::Native).unwrap();
+        cursor.push_endian(-2.5f64, Endianness::Little).unwrap();
+        assert_eq!(cursor.get_ref()[..4], value.to_ne_bytes());
+
+        cursor.set_position(0);
+        assert_eq!(cursor.next_endian::<u32>(native), Some(value));
+        assert_eq!(cursor.shift_endian::<u32>(Endianness::Native), Some(value));
+        assert_eq!(cursor.shift_endian::<f64>(Endianness::Little), Some(-2.5));
+        assert_eq!(cursor.shift_endian::<u8>(Endianness::Native), None);
+
+        // Native data needs no swapping on the host.
+        let mut buf = value.to_ne_bytes();
+        maybe_swap(&mut buf, 4, Endianness::Native).unwrap();
+        assert_eq!(buf, value.to_ne_bytes());
+    }
+
+    #[test]
+    fn byte_order_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        /// A header written once for both byte orders: a magic number, a count and that many coordinates.
+        fn parse<O: ByteOrder>(r: &mut impl ESeqByteReader) -> Option<(u16, Vec<i32>)> {
+            let magic = r.next_as::<u16, O>()?;
+            r.shift_as::<u16, O>()?;
+            let count = r.shift_as::<u8, O>()?;
+            let coords = (0..count)
+                .map(|_| r.shift_as::<i32, O>())
+                .collect::<Option<_>>()?;
+
+            Some((magic, coords))
+        }
+
+        fn write<O: ByteOrder>(coords: &[i32]) -> Vec<u8> {
+            let mut out = Cursor::new(Vec::new());
+            out.push_as::<u16, O>(0xCAFE).unwrap();
+            out.push_as::<u8, O>(coords.len() as u8).unwrap();
+            for &c in coords {
+                out.push_as::<i32, O>(c).unwrap();
+            }
+
+            out.into_inner()
+        }
+
+        let coords = [1, -2, 300_000];
+        let be = write::<BE>(&coords);
+        let le = write::<LE>(&coords);
+        assert_eq!(be[..2], [0xCA, 0xFE]);
+        assert_eq!(le[..2], [0xFE, 0xCA]);
+
+        assert_eq!(
+            parse::<BE>(&mut Cursor::new(be.clone())),
+            Some((0xCAFE, coords.to_vec()))
+        );
+        assert_eq!(
+            parse::<LE>(&mut Cursor::new(le)),
+            Some((0xCAFE, coords.to_vec()))
+        );
+        assert_eq!(parse::<LE>(&mut Cursor::new(be[..8].to_vec())), None);
+
+        let mut out = [0u8; 4];
+        assert_eq!(BE::write(&0x0102_0304u32, &mut out), Some(4));
+        assert_eq!(out, [1, 2, 3, 4]);
+        assert_eq!(LE::read::<u32>(&out), Some(0x0403_0201));
+        assert_eq!(BE::read::<u32>(&out[..3]), None);
+        assert_eq!(LE::ENDIANNESS, Endianness::Little);
+    }
+
+    #[test]
+    fn native_endian_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // Pointer-sized types are checked against the bytes of their 8-byte wire type, whatever the width of the target.
+        macro_rules! check {
+            ($($t:ty $(as $wire:ty)? = $v:expr),*) => {$({
+                let value: $t = $v;
+                let native = (value $(as $wire)?).to_ne_bytes();
+
+                assert_eq!(value.to_bytes_ne(), native);
+                assert_eq!(<$t>::from_bytes_ne(&native), Some(value));
+                assert_eq!(<$t>::from_bytes_ne(&native[1..]), None);
+
+                let mut out = Cursor::new(Vec::new());
+                out.push_ne(value).unwrap();
+                assert_eq!(out.get_ref()[..], native);
+
+                let mut cursor = Cursor::new(native.to_vec());
+                assert_eq!(cursor.next_ne::<$t>(), Some(value));
+                assert_eq!(cursor.position(), 0);
+                assert_eq!(cursor.shift_ne::<$t>(), Some(value));
+                assert_eq!(cursor.shift_ne::<$t>(), None);
+            })*};
+        }
+
+        check!(
+            u16 = 0x0102,
+            i16 = -0x0102,
+            u32 = 0x0102_0304,
+            i32 = -0x0102_0304,
+            u64 = 0x0102_0304_0506_0708,
+            i64 = i64::MIN + 1,
+            u128 = u128::MAX / 3,
+            i128 = i128::MIN / 5,
+            usize as u64 = usize::MAX - 2,
+            isize as i64 = isize::MIN + 7,
+            f32 = -1.5,
+            f64 = std::f64::consts::PI
+        );
+        assert_eq!(
+            0x0102u16.to_bytes_ne(),
+            0x0102u16.to_bytes_endian(Endianness::Native)
+        );
+    }
+
+    #[test]
+    fn primitive_bytes_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // Every primitive, at both ends of its range and in between, in both byte orders, against the conversions of std.
+        macro_rules! matrix {
+            ($($t:ty: $($v:expr),*;)*) => {$($({
+                let value: $t = $v;
+                let (le, be) = (value.to_le_bytes(), value.to_be_bytes());
+
+                assert_eq!(<$t>::SIZE, le.len());
+                assert_eq!(value.to_bytes(), le);
+                assert_eq!(value.to_bytes_e(false), le);
+                assert_eq!(value.to_bytes_e(true), be);
+                assert_eq!(value.to_le_array(), le);
+                assert_eq!(value.to_be_array(), be);
+                assert_eq!(<$t>::from_bytes(&le).map(|v| v.to_bits_matrix()), Some(value.to_bits_matrix()));
+                assert_eq!(<$t>::from_bytes_e(&le, false).map(|v| v.to_bits_matrix()), Some(value.to_bits_matrix()));
+                assert_eq!(<$t>::from_bytes_e(&be, true).map(|v| v.to_bits_matrix()), Some(value.to_bits_matrix()));
+                assert_eq!(<$t>::from_bytes(&[0; 17][..le.len() + 1]).map(|v| v.to_bits_matrix()), None);
+
+                let mut out = [0xEEu8; 17];
+                assert_eq!(value.to_bytes_into_e(&mut out, true), Some(be.len()));
+                assert_eq!(out[..be.len()], be);
+                assert_eq!(out[be.len()], 0xEE);
+                assert_eq!(value.to_bytes_into_e(&mut out[17 - le.len() + 1..], false), None);
+
+                let mut writer = Cursor::new(Vec::new());
+                writer.push_e(value, true).unwrap();
+                writer.push_e(value, false).unwrap();
+                let mut reader = Cursor::new(writer.into_inner());
+                assert_eq!(reader.shift_e::<$t>(true).map(|v| v.to_bits_matrix()), Some(value.to_bits_matrix()));
+                assert_eq!(reader.shift_e::<$t>(false).map(|v| v.to_bits_matrix()), Some(value.to_bits_matrix()));
+            })*)*};
+        }
+
+        /// Compares floats by their bits, so NaNs and signed zeros are checked exactly.
+        trait Bits {
+            fn to_bits_matrix(&self) -> u128;
+        }
+        macro_rules! bits {
+            ($($t:ty),*) => {$(impl Bits for $t {
+                fn to_bits_matrix(&self) -> u128 {
+                    u128::from_le_bytes({
+                        let mut wide = [0; 16];
+                        wide[..<$t>::SIZE].copy_from_slice(&self.to_le_bytes());
+                        wide
+                    })
+                }
+            })*};
+        }
+        bits!(u8, i8, u16, i16, u32, i32, f32, u64, i64, f64, u128, i128);
+
+        matrix! {
+            u8: 0, 0x7F, u8::MAX;
+            i8: i8::MIN, -1, i8::MAX;
+            u16: 0, 0x0102, u16::MAX;
+            i16: i16::MIN, -0x0102, i16::MAX;
+            u32: 0, 0x0102_0304, u32::MAX;
+            i32: i32::MIN, -0x0102_0304, i32::MAX;
+            f32: -0.0, f32::MIN_POSITIVE / 2.0, f32::from_bits(0x7FC0_0001), f32::NEG_INFINITY;
+            u64: 0, 0x0102_0304_0506_0708, u64::MAX;
+            i64: i64::MIN, -0x0102_0304_0506_0708, i64::MAX;
+            f64: -0.0, f64::MIN_POSITIVE / 2.0, f64::from_bits(0xFFF8_0000_0000_0001), f64::MAX;
+            u128: 0, 0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10, u128::MAX;
+            i128: i128::MIN, -1, i128::MAX;
+        }
+
+        // A downstream type gets both traits from the helper alone, and composes with the built-in impls.
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct Celsius(i16);
+
+        impl PrimitiveBytes for Celsius {
+            type Array = [u8; 2];
+
+            fn from_le_array(bytes: [u8; 2]) -> Self {
+                Celsius(i16::from_le_bytes(bytes))
+            }
+            fn from_be_array(bytes: [u8; 2]) -> Self {
+                Celsius(i16::from_be_bytes(bytes))
+            }
+            fn to_le_array(&self) -> [u8; 2] {
+                self.0.to_le_bytes()
+            }
+            fn to_be_array(&self) -> [u8; 2] {
+                self.0.to_be_bytes()
+            }
+        }
+
+        let readings = [Celsius(-40), Celsius(0x0123)];
+        assert_eq!(<[Celsius; 2]>::SIZE, 4);
+        assert_eq!(readings.to_bytes_e(true), [0xFF, 0xD8, 0x01, 0x23]);
+        assert_eq!(
+            Cursor::new(vec![0xD8, 0xFF, 0x23, 0x01]).shift::<[Celsius; 2]>(),
+            Some(readings)
+        );
+        assert_eq!(
+            Celsius::from_bytes_checked(&[1]),
+            Err(DecodeError::WrongLength {
+                expected: 2,
+                got: 1
+            })
+        );
+    }
+
+    #[test]
+    fn to_bytes_small_test() {
+        use crate::prelude::*;
+
+        macro_rules! inline {
+            ($($v:expr),*) => {{
+                let before = ALLOCATED.with(Cell::get);
+                $(
+                    let (small, big) = ($v.to_bytes_small(), $v.to_bytes_small_e(true));
+                    assert!(small.is_inline() && big.is_inline());
+                    assert_eq!(small.len(), $v.to_bytes_into(&mut [0; 16]).unwrap());
+                    std::hint::black_box((small, big));
+                )*
+                assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+                $(
+                    assert_eq!(*$v.to_bytes_small(), $v.to_bytes());
+                    assert_eq!(*$v.to_bytes_small_e(true), $v.to_bytes_e(true));
+                )*
+            }};
+        }
+
+        // Serializing any primitive, or a short record of them, does not touch the heap.
+        inline!(
+            7u8,
+            -7i8,
+            0x0102u16,
+            -0x0102i16,
+            0x0102_0304u32,
+            -0x0102_0304i32,
+            1.5f32,
+            u64::MAX,
+            i64::MIN,
+            -2.5f64,
+            u128::MAX / 3,
+            i128::MIN,
+            usize::MAX,
+            isize::MIN,
+            true,
+            'é',
+            (3u8, -1i32, [0x0102u16; 2])
+        );
+
+        // Longer values are held on the heap, with the same bytes.
+        let long = [0x0102_0304_0506_0708u64; 3];
+        let bytes = long.to_bytes_small_e(true);
+        assert!(!bytes.is_inline());
+        assert_eq!(bytes.into_vec(), long.to_bytes_e(true));
+        assert_eq!(0x0102u16.to_bytes_small(), 0x0102u16.to_bytes_small());
+        assert_eq!(Vec::from(0x0102u16.to_bytes_small()), [2, 1]);
+    }
+
+    #[test]
+    fn tagged_enum_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[repr(u16)]
+        enum Chunk {
+            Header = 0x0001,
+            Data = 0x0002,
+            End = 0xFFFF,
+        }
+
+        impl TryFrom<u16> for Chunk {
+            type Error = u16;
+
+            fn try_from(tag: u16) -> Result<Self, u16> {
+                match tag {
+                    0x0001 => Ok(Chunk::Header),
+                    0x0002 => Ok(Chunk::Data),
+                    0xFFFF => Ok(Chunk::End),
+                    _ => Err(tag),
+                }
+            }
+        }
+
+        impl TaggedEnum for Chunk {
+            type Repr = u16;
+        }
+
+        let mut cursor = Cursor::new(vec![
+            0x00, 0x01, 0x00, 0x02, 0xFF, 0xFF, 0x00, 0x03, 0x02, 0x00, 0x00,
+        ]);
+
+        // Peeking leaves the position in place, whether or not the tag is known.
+        assert_eq!(cursor.next_enum_e::<Chunk>(true), Some(Chunk::Header));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.shift_enum_e::<Chunk>(true), Some(Chunk::Header));
+        assert_eq!(cursor.shift_enum_e::<Chunk>(true), Some(Chunk::Data));
+        assert_eq!(cursor.shift_enum::<Chunk>(), Some(Chunk::End));
+
+        // An unknown tag is rejected and left unread, and shift_narrow tells which it was.
+        assert_eq!(cursor.next_enum_e::<Chunk>(true), None);
+        assert_eq!(cursor.shift_enum_e::<Chunk>(true), None);
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(
+            cursor.shift_narrow::<u16, Chunk>(true),
+            Err(NarrowError::OutOfRange {
+                offset: 6,
+                value: 3
+            })
+        );
+
+        // Tags without an endianness are little endian, and a truncated one fails in place.
+        assert_eq!(cursor.shift_enum::<Chunk>(), None);
+        cursor.set_position(8);
+        assert_eq!(cursor.next_enum::<Chunk>(), Some(Chunk::Data));
+        assert_eq!(cursor.shift_enum::<Chunk>(), Some(Chunk::Data));
+        assert_eq!(cursor.shift_enum::<Chunk>(), None);
+        assert_eq!(cursor.position(), 10);
+        assert_eq!(Chunk::End as u16, 0xFFFF);
+    }
+
+    #[test]
+    fn rewind_test() {
+        use crate::prelude::*;
+        use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+        let mut cursor = Cursor::new(vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(cursor.shift::<u32>(), Some(0x0403_0201));
+        assert_eq!(cursor.unshift::<u16>(), Some(()));
+        assert_eq!(cursor.shift_e::<u16>(true), Some(0x0304));
+
+        // Rewinding to exactly the start succeeds, and one byte further fails without moving.
+        assert_eq!(cursor.rewind_by(4), Some(()));
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.rewind_by(0), Some(()));
+        assert_eq!(cursor.rewind_by(1), None);
+        assert_eq!(cursor.unshift::<u8>(), None);
+        assert_eq!(cursor.position(), 0);
+
+        cursor.set_position(5);
+        assert_eq!(cursor.rewind_by(usize::MAX), None);
+        assert_eq!(cursor.position(), 5);
+
+        /// A stream which refuses to seek backwards, like a pipe with a counter.
+        struct ForwardOnly(Cursor<Vec<u8>>);
+
+        impl Read for ForwardOnly {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+
+        impl Seek for ForwardOnly {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                let target = match pos {
+                    SeekFrom::Start(target) => target,
+                    SeekFrom::Current(delta) => self.0.position().saturating_add_signed(delta),
+                    SeekFrom::End(_) => return Err(io::ErrorKind::Unsupported.into()),
+                };
+                if target < self.0.position() {
+                    return Err(io::ErrorKind::Unsupported.into());
+                }
+
+                self.0.seek(SeekFrom::Start(target))
+            }
+        }
+
+        // Moving backwards fails rather than panicking, both directly and when peeking.
+        let mut forward = ForwardOnly(Cursor::new(vec![1, 2, 3, 4]));
+        assert_eq!(forward.shift::<u16>(), Some(0x0201));
+        assert_eq!(forward.rewind_by(2), None);
+        assert_eq!(forward.unshift::<u16>(), None);
+        assert_eq!(forward.pos(), Some(2));
+        // Peeking cannot give the byte back, so a failed peek consumes it.
+        assert_eq!(forward.next::<u8>(), None);
+        assert_eq!(forward.pos(), Some(3));
+        assert_eq!(forward.rewind_by(0), Some(()));
+    }
+
+    #[test]
+    fn align_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // A chunk at offset 3 holding a `u8` and a `u32` aligned to four bytes from the start of the chunk.
+        let mut bytes = vec![0xFF; 3];
+        bytes.extend_from_slice(&[1, 0, 0, 0, 0x78, 0x56, 0x34, 0x12, 2, 0]);
+        let mut cursor = Cursor::new(bytes);
+
+        cursor.set_position(3);
+        assert_eq!(cursor.shift::<u8>(), Some(1));
+        assert_eq!(cursor.align_to_from(4, 3), Some(3));
+        assert_eq!(cursor.position(), 7);
+        assert_eq!(cursor.align_to_from(4, 3), Some(0));
+        assert_eq!(cursor.shift::<u32>(), Some(0x1234_5678));
+
+        // Relative to the start of the source, the same position needs different padding.
+        assert_eq!(cursor.position(), 11);
+        assert_eq!(cursor.align_to(2), Some(1));
+        assert_eq!(cursor.align_to(1), Some(0));
+
+        // Padding past the end is not skipped, nor is a zero alignment or a base after the position.
+        assert_eq!(cursor.align_to(8), None);
+        assert_eq!(cursor.align_to(0), None);
+        assert_eq!(cursor.align_to_from(4, 20), None);
+        assert_eq!(cursor.position(), 12);
+        assert_eq!(cursor.align_to(4), Some(0));
+        assert_eq!(cursor.align_to(usize::MAX), None);
+    }
+
+    #[test]
+    fn remaining_test() {
+        use crate::prelude::*;
+        use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+        let mut empty = Cursor::new(Vec::<u8>::new());
+        assert_eq!(empty.remaining(), Some(0));
+        assert_eq!(empty.total_len(), Some(0));
+
+        // A header followed by records filling the rest of the source, counted from what remains.
+        let mut cursor = Cursor::new(vec![0xAA, 1, 0, 2, 0, 3, 0, 9]);
+        assert_eq!(cursor.shift::<u8>(), Some(0xAA));
+        assert_eq!(cursor.remaining(), Some(7));
+        assert_eq!(cursor.total_len(), Some(8));
+        assert_eq!(cursor.position(), 1);
+
+        let count = cursor.remaining().unwrap() / u16::SIZE as u64;
+        let records: Vec<u16> = (0..count).map(|_| cursor.shift().unwrap()).collect();
+        assert_eq!(records, [1, 2, 3]);
+        assert_eq!(cursor.remaining(), Some(1));
+
+        cursor.set_position(100);
+        assert_eq!(cursor.remaining(), Some(0));
+        assert_eq!(cursor.position(), 100);
+
+        /// A stream whose end cannot be sought.
+        struct Endless(Cursor<Vec<u8>>);
+
+        impl Read for Endless {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+
+        impl Seek for Endless {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                match pos {
+                    SeekFrom::End(_) => Err(io::ErrorKind::Unsupported.into()),
+                    pos => self.0.seek(pos),
+                }
+            }
+        }
+
+        // A failed seek to the end leaves the position where it was.
+        let mut endless = Endless(Cursor::new(vec![1, 2, 3]));
+        assert_eq!(endless.shift::<u8>(), Some(1));
+        assert_eq!(endless.remaining(), None);
+        assert_eq!(endless.total_len(), None);
+        assert_eq!(endless.pos(), Some(1));
+        assert_eq!(endless.shift::<u8>(), Some(2));
+    }
+
+    #[test]
+    fn seek_free_read_test() {
+        use crate::prelude::*;
+        use crate::testutil::NoPos;
+        use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+        /// Counts the seeks made on the wrapped reader, including position queries.
+        struct SeekCounter {
+            inner: Cursor<Vec<u8>>,
+            seeks: usize,
+        }
+
+        impl Read for SeekCounter {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.inner.read(buf)
+            }
+        }
+
+        impl Seek for SeekCounter {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                self.seeks += 1;
+                self.inner.seek(pos)
+            }
+        }
+
+        let mut bytes = Vec::new();
+        for i in 0..100u32 {
+            bytes.extend_from_slice(&i.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[2, 0xAA]);
+        let mut counter = SeekCounter {
+            inner: Cursor::new(bytes),
+            seeks: 0,
+        };
+
+        // Successful reads never seek.
+        for i in 0..25u32 {
+            assert_eq!(counter.shift::<u32>(), Some(2 * i));
+            assert_eq!(counter.shift_e::<u32>(false), Some(2 * i + 1));
+        }
+        assert_eq!(counter.shift_vec::<u32>(2), Some(vec![50, 51]));
+        assert_eq!(counter.shift_slice(4), Some(52u32.to_le_bytes().to_vec()));
+        assert_eq!(counter.seeks, 0);
+
+        // A peek seeks back once, and failures seek back over only what they consumed.
+        assert_eq!(counter.next::<u32>(), Some(53));
+        assert_eq!(counter.seeks, 1);
+        counter.inner.set_position(400);
+        assert_eq!(counter.shift::<bool>(), None);
+        assert_eq!(counter.inner.position(), 400);
+        assert_eq!(counter.shift::<u32>(), None);
+        assert_eq!(counter.inner.position(), 400);
+        assert_eq!(counter.shift::<u8>(), Some(2));
+
+        // A source which cannot report its position, but has the bytes.
+        let mut no_pos = NoPos(Cursor::new(vec![1, 0, 2, 0]));
+        assert_eq!(no_pos.shift::<u16>(), Some(1));
+        assert_eq!(no_pos.shift_e::<u16>(false), Some(2));
+        assert_eq!(no_pos.shift::<u8>(), None);
+    }
+
+    #[test]
+    fn length_check_test() {
+        use crate::prelude::*;
+        use std::io::Cursor;
+
+        // Lengths read from a corrupt header are rejected against the end of the source before anything is allocated for them.
+        let mut cursor = Cursor::new(vec![0u8; 8192]);
+        cursor.set_position(100);
+        let mut out = Vec::new();
+        let before = ALLOCATED.with(Cell::get);
+        assert_eq!(cursor.shift_slice(1 << 40), None);
+        assert_eq!(cursor.next_slice(1 << 40), None);
+        assert_eq!(cursor.shift_string(1 << 40), None);
+        assert_eq!(cursor.shift_slice_into_vec(1 << 40, &mut out), None);
+        assert_eq!(cursor.shift_vec::<u32>(1 << 40), None);
+        assert_eq!(cursor.shift_vec_e::<u64>(1 << 40, true), None);
+        assert_eq!(cursor.shift_deinterleaved::<u16>(1 << 30, 2, false), None);
+        assert_eq!(cursor.shift_slice(8093), None);
+        assert_eq!(ALLOCATED.with(Cell::get) - before, 0);
+        assert_eq!(cursor.position(), 100);
+
+        // Lengths which fit are still read.
+        assert_eq!(cursor.shift_slice(8092).map(|a| a.len()), Some(8092));
+    }
 }
@@ -0,0 +1,125 @@
+use num_traits::{Float, PrimInt};
+
+use super::bytes::ESeqByteReader;
+use super::traits::EndianNumber;
+
+/// A floating point type which can both be used in generic arithmetic through [`Float`] and be read or written with this crate. Implemented
+/// for every type implementing both traits, such as [`f32`] and [`f64`], so generic code only needs this one bound.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::num::SeqFloat;
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// fn peak<T: SeqFloat>(cursor: &mut Cursor<Vec<u8>>, count: usize) -> Option<T> {
+///     let mut peak = T::zero();
+///
+///     for _ in 0..count {
+///         peak = peak.max(cursor.shift_e::<T>(false)?.abs());
+///     }
+///
+///     Some(peak)
+/// }
+///
+/// let mut cursor = Cursor::new([0.5f32.to_bytes(), (-2.0f32).to_bytes()].concat());
+///
+/// assert_eq!(peak::<f32>(&mut cursor, 2), Some(2.0));
+/// ```
+pub trait SeqFloat: Float + EndianNumber {}
+
+impl<T: Float + EndianNumber> SeqFloat for T {}
+
+/// A primitive integer type which can both be used in generic arithmetic through [`PrimInt`] and be read or written with this crate.
+/// Implemented for every type implementing both traits, such as [`u32`] and [`i64`], so generic code only needs this one bound.
+pub trait SeqInt: PrimInt + EndianNumber {}
+
+impl<T: PrimInt + EndianNumber> SeqInt for T {}
+
+/// Reads `count` samples of `T` with the specified endianness, shifting the position. Returns [`None`] if there are not enough bytes to be
+/// read.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::num::read_samples;
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new([1.5f64.to_bytes_e(true), 3.0f64.to_bytes_e(true)].concat());
+///
+/// assert_eq!(read_samples::<f64, _>(&mut cursor, 2, true).unwrap(), [1.5, 3.0]);
+/// ```
+pub fn read_samples<T: SeqFloat, R: ESeqByteReader + ?Sized>(
+    reader: &mut R,
+    count: usize,
+    bigendian: bool,
+) -> Option<Vec<T>> {
+    reader.shift_vec_e(count, bigendian)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Reads `count` samples and returns their root mean square, relying only on the [`SeqFloat`] bound.
+    fn rms<T: SeqFloat>(cursor: &mut Cursor<Vec<u8>>, count: usize, bigendian: bool) -> Option<T> {
+        let mut sum = T::zero();
+
+        for _ in 0..count {
+            let sample = cursor.shift_e::<T>(bigendian)?;
+            sum = sum + sample * sample;
+        }
+
+        Some((sum / T::from(count)?).sqrt())
+    }
+
+    /// Sums `count` integers with wrapping, relying only on the [`SeqInt`] bound.
+    fn checksum<T: SeqInt>(cursor: &mut Cursor<Vec<u8>>, count: usize) -> Option<T> {
+        let mut sum = T::zero();
+
+        for _ in 0..count {
+            sum = sum ^ cursor.shift_e::<T>(true)?.rotate_left(1);
+        }
+
+        Some(sum)
+    }
+
+    #[test]
+    fn generic_float_test() {
+        let mut cursor = Cursor::new(
+            [3.0f32, -4.0, 3.0, -4.0]
+                .iter()
+                .flat_map(|v| v.to_bytes_e(true))
+                .collect(),
+        );
+        assert_eq!(rms::<f32>(&mut cursor, 4, true), Some(12.5f32.sqrt()));
+        assert_eq!(rms::<f32>(&mut cursor, 1, true), None);
+
+        let mut cursor = Cursor::new(
+            [1.0f64, -1.0]
+                .iter()
+                .flat_map(|v| v.to_bytes_e(false))
+                .collect(),
+        );
+        assert_eq!(rms::<f64>(&mut cursor, 2, false), Some(1.0));
+
+        cursor.set_position(0);
+        assert_eq!(
+            read_samples::<f64, _>(&mut cursor, 2, false).unwrap(),
+            [1.0, -1.0]
+        );
+        assert_eq!(read_samples::<f32, _>(&mut cursor, 1, false), None);
+    }
+
+    #[test]
+    fn generic_int_test() {
+        let mut cursor = Cursor::new(vec![0x80, 0x01, 0x00, 0x01]);
+        assert_eq!(checksum::<u16>(&mut cursor, 2), Some(0x0003 ^ 0x0002));
+
+        cursor.set_position(0);
+        assert_eq!(checksum::<i32>(&mut cursor, 1), Some(0x0002_0003));
+    }
+}
@@ -0,0 +1,414 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+/// The default size of a [`PrefetchReader`] window, in bytes.
+pub const DEFAULT_WINDOW: usize = 64 * 1024;
+
+enum Request {
+    Fill { id: u64, offset: u64 },
+    End,
+}
+
+enum Response {
+    Fill { id: u64, data: io::Result<Vec<u8>> },
+    End(io::Result<u64>),
+}
+
+/// A window of bytes read from the source, starting at `offset`.
+struct Window {
+    offset: u64,
+    data: Vec<u8>,
+}
+
+impl Window {
+    fn end(&self) -> u64 {
+        self.offset + self.data.len() as u64
+    }
+}
+
+/// A reader which fetches the next window of a high-latency source on a background thread while the current window is being consumed.
+///
+/// Reads are served from the current window. As soon as a full window is loaded, the window following it is requested from the background
+/// thread, so sequential parsing overlaps with the latency of the source. Seeking within the current window is free, and seeking elsewhere
+/// loads the window at the new position on demand. IO errors hit by the background thread are returned by the read which needs the bytes.
+///
+/// Dropping the reader stops the background thread, waiting for any read in progress to finish.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::prefetch::PrefetchReader;
+/// use std::io::Cursor;
+///
+/// let a = vec![69, 96, 255, 255, 0x68, 0x65, 0x6C, 0x6C, 0x6F];
+/// let mut reader = PrefetchReader::with_window(Cursor::new(a), 4);
+///
+/// let num : i32 = reader.shift().unwrap();
+/// let s = &*reader.shift_string(5).unwrap();
+///
+/// assert_eq!(num, -40891);
+/// assert_eq!(*s, *"hello");
+/// ```
+pub struct PrefetchReader<R> {
+    requests: Option<Sender<Request>>,
+    responses: Receiver<Response>,
+    handle: Option<JoinHandle<R>>,
+    window_size: usize,
+    pos: u64,
+    current: Option<Window>,
+    /// The id and offset of the window being prefetched.
+    pending: Option<(u64, u64)>,
+    /// A prefetched window received while waiting for another response.
+    prefetched: Option<(u64, io::Result<Vec<u8>>)>,
+    next_id: u64,
+}
+
+impl<R: Read + Seek + Send + 'static> PrefetchReader<R> {
+    /// Creates a new [`PrefetchReader`] over `inner`, with windows of [`DEFAULT_WINDOW`] bytes. Reading starts at offset 0.
+    pub fn new(inner: R) -> Self {
+        Self::with_window(inner, DEFAULT_WINDOW)
+    }
+    /// Creates a new [`PrefetchReader`] over `inner`, with windows of `window_size` bytes. Reading starts at offset 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_size` is 0.
+    pub fn with_window(inner: R, window_size: usize) -> Self {
+        assert!(window_size > 0, "window size must be non-zero");
+
+        let (requests, worker_requests) = mpsc::channel();
+        let (worker_responses, responses) = mpsc::channel();
+        let handle =
+            thread::spawn(move || worker(inner, window_size, worker_requests, worker_responses));
+
+        Self {
+            requests: Some(requests),
+            responses,
+            handle: Some(handle),
+            window_size,
+            pos: 0,
+            current: None,
+            pending: None,
+            prefetched: None,
+            next_id: 0,
+        }
+    }
+}
+
+impl<R> PrefetchReader<R> {
+    /// Returns the size of a window, in bytes.
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+    /// Returns the current position of the reader.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+    /// Stops the background thread and returns the underlying reader, positioned at an unspecified offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the background thread panicked.
+    pub fn into_inner(mut self) -> R {
+        self.requests = None;
+        self.handle
+            .take()
+            .unwrap()
+            .join()
+            .expect("prefetch thread panicked")
+    }
+
+    fn send(&mut self, request: Request) -> io::Result<()> {
+        self.requests
+            .as_ref()
+            .and_then(|requests| requests.send(request).ok())
+            .ok_or_else(stopped)
+    }
+
+    fn request_fill(&mut self, offset: u64) -> io::Result<u64> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.send(Request::Fill { id, offset })?;
+        Ok(id)
+    }
+
+    /// Waits for the window with the given id, discarding any stale windows received before it.
+    fn wait_fill(&mut self, id: u64) -> io::Result<Vec<u8>> {
+        if let Some((prefetched, data)) = self.prefetched.take() {
+            if prefetched == id {
+                return data;
+            }
+        }
+
+        loop {
+            match self.responses.recv().map_err(|_| stopped())? {
+                Response::Fill { id: received, data } if received == id => return data,
+                _ => {}
+            }
+        }
+    }
+
+    /// Makes the window starting at `offset` the current window, and requests the window after it.
+    fn load(&mut self, offset: u64) -> io::Result<&Window> {
+        let id = match self.pending.take() {
+            Some((id, pending)) if pending == offset => id,
+            _ => self.request_fill(offset)?,
+        };
+
+        let data = self.wait_fill(id)?;
+        let window = Window { offset, data };
+
+        if window.data.len() == self.window_size {
+            let next = window.end();
+            self.pending = Some((self.request_fill(next)?, next));
+        }
+
+        Ok(self.current.insert(window))
+    }
+
+    fn end(&mut self) -> io::Result<u64> {
+        self.send(Request::End)?;
+
+        loop {
+            match self.responses.recv().map_err(|_| stopped())? {
+                Response::End(end) => return end,
+                Response::Fill { id, data } => {
+                    if self.pending.is_some_and(|(pending, _)| pending == id) {
+                        self.prefetched = Some((id, data));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<R> Read for PrefetchReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let pos = self.pos;
+        let window_size = self.window_size;
+
+        // The current window holds `pos`, or ends at `pos` short of a full window, meaning `pos` is at the end of the source.
+        let window = match self.current.take() {
+            Some(window)
+                if window.offset <= pos
+                    && (pos < window.end()
+                        || (pos == window.end() && window.data.len() < window_size)) =>
+            {
+                self.current.insert(window)
+            }
+            _ => self.load(pos)?,
+        };
+
+        let available = &window.data[(pos - window.offset) as usize..];
+        let amount = available.len().min(buf.len());
+
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.pos += amount as u64;
+
+        Ok(amount)
+    }
+}
+
+impl<R> Seek for PrefetchReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::Current(n) => (self.pos, n),
+            SeekFrom::End(n) => (self.end()?, n),
+        };
+
+        self.pos = base.checked_add_signed(offset).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+
+        Ok(self.pos)
+    }
+}
+
+impl<R> Drop for PrefetchReader<R> {
+    fn drop(&mut self) {
+        // Closing the request channel stops the background thread once its current read finishes.
+        self.requests = None;
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn stopped() -> io::Error {
+    io::Error::other("prefetch thread stopped")
+}
+
+fn worker<R: Read + Seek>(
+    mut inner: R,
+    window_size: usize,
+    requests: Receiver<Request>,
+    responses: Sender<Response>,
+) -> R {
+    while let Ok(request) = requests.recv() {
+        let response = match request {
+            Request::Fill { id, offset } => Response::Fill {
+                id,
+                data: fill(&mut inner, offset, window_size),
+            },
+            Request::End => Response::End(inner.seek(SeekFrom::End(0))),
+        };
+
+        if responses.send(response).is_err() {
+            break;
+        }
+    }
+
+    inner
+}
+
+fn fill<R: Read + Seek>(inner: &mut R, offset: u64, window_size: usize) -> io::Result<Vec<u8>> {
+    inner.seek(SeekFrom::Start(offset))?;
+
+    let mut data = Vec::with_capacity(window_size);
+    inner.take(window_size as u64).read_to_end(&mut data)?;
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// A reader which sleeps on every read, counts its reads, and fails reads at or after `fail_at`.
+    struct SlowReader {
+        inner: Cursor<Vec<u8>>,
+        delay: Duration,
+        reads: Arc<AtomicUsize>,
+        fail_at: u64,
+    }
+
+    impl SlowReader {
+        fn new(data: Vec<u8>, delay: Duration) -> (Self, Arc<AtomicUsize>) {
+            let reads = Arc::new(AtomicUsize::new(0));
+            let reader = Self {
+                inner: Cursor::new(data),
+                delay,
+                reads: reads.clone(),
+                fail_at: u64::MAX,
+            };
+            (reader, reads)
+        }
+    }
+
+    impl Read for SlowReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            thread::sleep(self.delay);
+            self.reads.fetch_add(1, Ordering::SeqCst);
+
+            if self.inner.position() >= self.fail_at {
+                return Err(io::Error::other("bad sector"));
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for SlowReader {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn prefetch_overlaps_consumption() {
+        let (slow, reads) = SlowReader::new(data(4096), Duration::from_millis(10));
+        let mut reader = PrefetchReader::with_window(slow, 1024);
+
+        assert_eq!(reader.shift_slice(16).unwrap(), data(16));
+
+        // The second window is fetched in the background without the consumer asking for it.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while reads.load(Ordering::SeqCst) < 2 {
+            assert!(Instant::now() < deadline, "window was never prefetched");
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(reader.position(), 16);
+
+        // Consuming the rest of the stream returns the same bytes as reading it directly.
+        let rest = reader.shift_slice(4096 - 16).unwrap();
+        assert_eq!(rest, data(4096)[16..]);
+        assert_eq!(reader.shift::<u8>(), None);
+    }
+
+    #[test]
+    fn prefetch_seeks() {
+        let expected = data(10_000);
+        let (slow, _) = SlowReader::new(expected.clone(), Duration::ZERO);
+        let mut reader = PrefetchReader::with_window(slow, 256);
+        let mut cursor = Cursor::new(expected);
+
+        let seeks = [
+            SeekFrom::Current(100),
+            SeekFrom::Current(-50),
+            SeekFrom::Start(9_000),
+            SeekFrom::Start(0),
+            SeekFrom::End(-300),
+            SeekFrom::Current(250),
+            SeekFrom::Start(5_000),
+            SeekFrom::End(0),
+        ];
+
+        for seek in seeks {
+            assert_eq!(reader.seek(seek).unwrap(), cursor.seek(seek).unwrap());
+            assert_eq!(reader.next_slice(40), cursor.next_slice(40));
+            assert_eq!(reader.shift::<u64>(), cursor.shift::<u64>());
+            assert_eq!(reader.shift_slice(300), cursor.shift_slice(300));
+        }
+
+        assert!(reader.seek(SeekFrom::Current(-1_000_000)).is_err());
+        assert_eq!(reader.seek(SeekFrom::Start(20_000)).unwrap(), 20_000);
+        assert_eq!(reader.shift::<u8>(), None);
+    }
+
+    #[test]
+    fn prefetch_errors_and_shutdown() {
+        let (mut slow, reads) = SlowReader::new(data(4096), Duration::from_millis(5));
+        slow.fail_at = 2048;
+        let mut reader = PrefetchReader::with_window(slow, 1024);
+
+        // The second window is prefetched successfully, and the third fails in the background.
+        assert!(reader.shift_slice(2000).is_some());
+
+        let mut buf = [0u8; 100];
+        assert_eq!(reader.read(&mut buf).unwrap(), 48);
+        assert_eq!(reader.read(&mut buf).unwrap_err().to_string(), "bad sector");
+        assert_eq!(reader.position(), 2048);
+
+        // Dropping the reader joins the background thread, which drops the source.
+        drop(reader);
+        assert_eq!(Arc::strong_count(&reads), 1);
+
+        let (slow, _) = SlowReader::new(data(100), Duration::ZERO);
+        let mut reader = PrefetchReader::new(slow);
+        reader.seek(SeekFrom::Start(10)).unwrap();
+        assert_eq!(reader.into_inner().inner.into_inner(), data(100));
+    }
+}
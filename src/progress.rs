@@ -0,0 +1,205 @@
+use super::bytes::SeqByteReaderCore;
+
+/// A reader which reports how many bytes have been read through it to a callback, for driving a progress bar over long reads.
+///
+/// The callback is called with the number of bytes read so far and the number of bytes which remained in the source when the reader was
+/// wrapped, if the end of the source is known. It is called at most once per `granularity` bytes, and once more when the read bytes reach
+/// that total or when [`ProgressReader::finish`] is called, so the last call always carries the final count. Reads larger than
+/// `granularity` which are known to fit in the source are split, so a single long read reports as it goes. A failed read never calls back.
+///
+/// Only bytes consumed by reads are counted. Peeks and changes of the position are delegated to the inner reader without reporting.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::progress::ProgressReader;
+/// use std::io::Cursor;
+///
+/// let mut calls = Vec::new();
+/// let mut reader = ProgressReader::new(Cursor::new(vec![0; 2500]), 1000, |read, total| calls.push((read, total)));
+///
+/// let payload = reader.shift_slice(2500).unwrap();
+/// reader.finish();
+///
+/// assert_eq!(payload.len(), 2500);
+/// assert_eq!(calls, [(1000, Some(2500)), (2000, Some(2500)), (2500, Some(2500))]);
+/// ```
+pub struct ProgressReader<R, F> {
+    inner: R,
+    callback: F,
+    granularity: u64,
+    total: Option<u64>,
+    read: u64,
+    reported: u64,
+}
+
+impl<R: SeqByteReaderCore, F: FnMut(u64, Option<u64>)> ProgressReader<R, F> {
+    /// Wraps `inner`, calling `callback` at most once per `granularity` bytes read. A `granularity` of zero is treated as one byte.
+    pub fn new(mut inner: R, granularity: u64, callback: F) -> Self {
+        let total = match (inner.end_pos(), inner.pos()) {
+            (Some(end), Some(pos)) => Some(end.saturating_sub(pos)),
+            _ => None,
+        };
+
+        Self {
+            inner,
+            callback,
+            granularity: granularity.max(1),
+            total,
+            read: 0,
+            reported: 0,
+        }
+    }
+    /// Makes the final call with the number of bytes read, unless it has already been made, and unwraps the inner reader.
+    pub fn finish(mut self) -> R {
+        if self.read != self.reported {
+            (self.callback)(self.read, self.total);
+        }
+
+        self.inner
+    }
+    fn report(&mut self) {
+        let due = self.read - self.reported >= self.granularity || Some(self.read) == self.total;
+
+        if self.read != self.reported && due {
+            (self.callback)(self.read, self.total);
+            self.reported = self.read;
+        }
+    }
+    fn fits(&mut self, len: usize) -> bool {
+        match (self.inner.end_pos(), self.inner.pos()) {
+            (Some(end), Some(pos)) => pos.checked_add(len as u64).is_some_and(|pos| pos <= end),
+            _ => false,
+        }
+    }
+}
+
+impl<R, F> ProgressReader<R, F> {
+    /// Returns the number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.read
+    }
+    /// Returns the number of bytes which remained in the source when it was wrapped, if the end of the source is known.
+    pub fn total_hint(&self) -> Option<u64> {
+        self.total
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader. Reads made through it are not counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+}
+
+impl<R: SeqByteReaderCore, F: FnMut(u64, Option<u64>)> SeqByteReaderCore for ProgressReader<R, F> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        if buf.len() as u64 <= self.granularity || !self.fits(buf.len()) {
+            self.inner.fill(buf)?;
+            self.read += buf.len() as u64;
+            self.report();
+
+            return Some(());
+        }
+
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let until_due = self.granularity - (self.read - self.reported);
+            let (chunk, tail) = rest.split_at_mut(rest.len().min(until_due as usize));
+
+            self.inner.fill(chunk)?;
+            self.read += chunk.len() as u64;
+            self.report();
+
+            rest = tail;
+        }
+
+        Some(())
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.inner.peek_fill(buf)
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        self.inner.pos()
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        self.inner.set_pos(pos)
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        self.inner.end_pos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    fn assert_spacing(calls: &[(u64, Option<u64>)], granularity: u64) {
+        for pair in calls.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+        }
+        for pair in calls[..calls.len() - 1].windows(2) {
+            assert!(pair[1].0 - pair[0].0 >= granularity);
+        }
+    }
+
+    #[test]
+    fn long_read_test() {
+        let mut calls = Vec::new();
+        let mut cursor = Cursor::new(vec![7; 10_500]);
+        cursor.set_position(500);
+
+        let mut reader = ProgressReader::new(cursor, 1000, |read, total| calls.push((read, total)));
+        assert_eq!(reader.total_hint(), Some(10_000));
+        assert_eq!(reader.shift::<u16>(), Some(0x0707));
+        assert_eq!(reader.shift_slice(9_998).unwrap().len(), 9_998);
+        assert_eq!(reader.finish().position(), 10_500);
+
+        let expected: Vec<_> = (1..=10).map(|i| (i * 1000, Some(10_000))).collect();
+        assert_eq!(calls, expected);
+        assert_spacing(&calls, 1000);
+    }
+
+    #[test]
+    fn small_reads_test() {
+        let mut calls = Vec::new();
+        let mut reader = ProgressReader::new(Cursor::new(vec![0; 2500]), 1000, |read, total| {
+            calls.push((read, total))
+        });
+
+        for _ in 0..600 {
+            reader.shift::<u32>().unwrap();
+        }
+        assert_eq!(reader.next_slice(100).unwrap().len(), 100);
+        assert_eq!(reader.bytes_read(), 2400);
+        reader.finish();
+
+        assert_eq!(
+            calls,
+            [(1000, Some(2500)), (2000, Some(2500)), (2400, Some(2500))]
+        );
+        assert_spacing(&calls, 1000);
+    }
+
+    #[test]
+    fn failed_read_test() {
+        let mut calls = Vec::new();
+        let mut reader = ProgressReader::new(Cursor::new(vec![0; 100]), 10, |read, total| {
+            calls.push((read, total))
+        });
+
+        assert_eq!(reader.shift_slice(200), None);
+        assert_eq!(reader.bytes_read(), 0);
+        reader.finish();
+
+        assert!(calls.is_empty());
+    }
+}
@@ -0,0 +1,968 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use super::bytes::{ESeqByteReader, SeqByteReader, SeqByteReaderCore, SeqByteWriter};
+use super::traits::{EndianNumber, SizedNumber};
+
+/// A custom conversion of a type to and from bytes, registered in [`Codecs`] to override its [`SizedNumber`] implementation.
+#[derive(Debug)]
+pub struct Codec<T> {
+    /// Converts exactly `size` bytes to a value, returning [`None`] if they are not a valid value.
+    pub decode: fn(&[u8]) -> Option<T>,
+    /// Converts a value to its bytes, which must be exactly `size` bytes long.
+    pub encode: fn(&T) -> Vec<u8>,
+    /// The number of bytes of a value.
+    pub size: usize,
+}
+
+/// A registry of [`Codec`]s keyed by the type they convert, for patching how particular types are read and written, such as an obfuscated
+/// field, without wrapping them in newtypes.
+///
+/// [`Codecs::shift_dyn`] and [`Codecs::push_dyn`] use the codec registered for a type if there is one, and its [`SizedNumber`]
+/// implementation otherwise.
+#[derive(Default)]
+pub struct Codecs {
+    codecs: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Codecs {
+    /// Creates a registry without any codecs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Registers the codec of `T`, replacing the one registered before, if any.
+    pub fn register<T: 'static>(
+        &mut self,
+        decode: fn(&[u8]) -> Option<T>,
+        encode: fn(&T) -> Vec<u8>,
+        size: usize,
+    ) {
+        let codec = Codec {
+            decode,
+            encode,
+            size,
+        };
+        self.codecs.insert(TypeId::of::<T>(), Box::new(codec));
+    }
+    /// Removes the codec of `T`, so that its [`SizedNumber`] implementation is used again. Returns `true` if there was one.
+    pub fn unregister<T: 'static>(&mut self) -> bool {
+        self.codecs.remove(&TypeId::of::<T>()).is_some()
+    }
+    /// Returns the codec registered for `T`, if any.
+    pub fn get<T: 'static>(&self) -> Option<&Codec<T>> {
+        self.codecs.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+    /// Returns the number of codecs registered.
+    pub fn len(&self) -> usize {
+        self.codecs.len()
+    }
+    /// Returns `true` if no codecs are registered.
+    pub fn is_empty(&self) -> bool {
+        self.codecs.is_empty()
+    }
+    /// Reads a `T` from `reader` with its registered codec, or with [`SizedNumber::from_bytes`] if it has none, shifting the position by its
+    /// size. Returns [`None`] if there are not enough bytes to be read or they are not a valid `T`, in which case the position is restored.
+    pub fn shift_dyn<T: SizedNumber + 'static, R: SeqByteReaderCore + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Option<T> {
+        shift_with(reader, self.resolve::<T>())
+    }
+    /// Writes `value` to `writer` with the registered codec of `T`, or with [`SizedNumber::to_bytes`] if it has none. Returns [`None`] if
+    /// the bytes could not be written.
+    pub fn push_dyn<T: SizedNumber + 'static, W: SeqByteWriter + ?Sized>(
+        &self,
+        writer: &mut W,
+        value: &T,
+    ) -> Option<()> {
+        writer.push_slice(&(self.resolve::<T>().encode)(value))
+    }
+    /// Returns the codec registered for `T`, or one calling its [`SizedNumber`] implementation if it has none.
+    fn resolve<T: SizedNumber + 'static>(&self) -> Codec<T> {
+        match self.get::<T>() {
+            Some(codec) => Codec {
+                decode: codec.decode,
+                encode: codec.encode,
+                size: codec.size,
+            },
+            None => Codec {
+                decode: T::from_bytes,
+                encode: T::to_bytes,
+                size: T::SIZE,
+            },
+        }
+    }
+}
+
+/// Reads a value with `codec`, restoring the position if it cannot be read.
+fn shift_with<T, R: SeqByteReaderCore + ?Sized>(reader: &mut R, codec: Codec<T>) -> Option<T> {
+    let start = reader.pos()?;
+    let mut bytes = vec![0u8; codec.size];
+    let value = reader
+        .fill(&mut bytes)
+        .and_then(|()| (codec.decode)(&bytes));
+    if value.is_none() {
+        reader.set_pos(start)?;
+    }
+
+    value
+}
+
+impl fmt::Debug for Codecs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Codecs")
+            .field("len", &self.codecs.len())
+            .finish()
+    }
+}
+
+/// What a [`SeqReader`] does with NaN values read as floats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NanPolicy {
+    /// NaNs are returned with their exact bits, payload included.
+    #[default]
+    Allow,
+    /// NaNs fail the read with [`FloatError::Nan`], and the position is restored.
+    Reject,
+    /// NaNs are replaced with the canonical quiet NaN of the type, such as [`f32::NAN`], dropping the sign and payload.
+    CanonicalizeQuiet,
+}
+
+/// What a [`SeqReader`] does with subnormal values read as floats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SubnormalPolicy {
+    /// Subnormals are returned with their exact bits.
+    #[default]
+    Allow,
+    /// Subnormals are replaced with zero of the same sign.
+    FlushToZero,
+}
+
+/// How a [`SeqReader`] treats the floats read through [`SeqReader::shift_float`], [`SeqReader::shift_float_e`] and
+/// [`SeqReader::shift_dyn`]. The default passes every value through bit for bit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FloatPolicy {
+    /// What is done with NaNs.
+    pub nan: NanPolicy,
+    /// What is done with subnormals.
+    pub subnormals: SubnormalPolicy,
+}
+
+impl FloatPolicy {
+    /// Applies the policy to `value`, returning its bits as a [`u64`] in the error if it is a NaN which is rejected.
+    pub fn apply<F: PolicyFloat>(self, value: F) -> Result<F, u64> {
+        if value.is_nan() {
+            return match self.nan {
+                NanPolicy::Allow => Ok(value),
+                NanPolicy::Reject => Err(value.bits()),
+                NanPolicy::CanonicalizeQuiet => Ok(F::QUIET_NAN),
+            };
+        }
+        if value.is_subnormal() && self.subnormals == SubnormalPolicy::FlushToZero {
+            return Ok(value.signed_zero());
+        }
+
+        Ok(value)
+    }
+}
+
+/// A float which a [`FloatPolicy`] can be applied to. Implemented for [`f32`] and [`f64`], and for `half::f16` with the `half` feature.
+pub trait PolicyFloat: EndianNumber + Copy + 'static {
+    /// The canonical quiet NaN of the type.
+    const QUIET_NAN: Self;
+    /// Returns the bits of the value, widened to a [`u64`].
+    fn bits(self) -> u64;
+    /// Returns `true` if the value is a NaN.
+    fn is_nan(self) -> bool;
+    /// Returns `true` if the value is subnormal.
+    fn is_subnormal(self) -> bool;
+    /// Returns zero with the sign of the value.
+    fn signed_zero(self) -> Self;
+}
+
+impl PolicyFloat for f32 {
+    const QUIET_NAN: Self = f32::NAN;
+
+    fn bits(self) -> u64 {
+        self.to_bits().into()
+    }
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+    fn is_subnormal(self) -> bool {
+        f32::is_subnormal(self)
+    }
+    fn signed_zero(self) -> Self {
+        0.0f32.copysign(self)
+    }
+}
+
+impl PolicyFloat for f64 {
+    const QUIET_NAN: Self = f64::NAN;
+
+    fn bits(self) -> u64 {
+        self.to_bits()
+    }
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+    fn is_subnormal(self) -> bool {
+        f64::is_subnormal(self)
+    }
+    fn signed_zero(self) -> Self {
+        0.0f64.copysign(self)
+    }
+}
+
+#[cfg(feature = "half")]
+impl PolicyFloat for half::f16 {
+    const QUIET_NAN: Self = half::f16::NAN;
+
+    fn bits(self) -> u64 {
+        self.to_bits().into()
+    }
+    fn is_nan(self) -> bool {
+        half::f16::is_nan(self)
+    }
+    fn is_subnormal(self) -> bool {
+        self.classify() == std::num::FpCategory::Subnormal
+    }
+    fn signed_zero(self) -> Self {
+        if self.is_sign_negative() {
+            half::f16::NEG_ZERO
+        } else {
+            half::f16::ZERO
+        }
+    }
+}
+
+/// The error returned by [`SeqReader::shift_float`] and [`SeqReader::shift_float_e`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatError {
+    /// There were not enough bytes to read the value at `offset`.
+    Truncated {
+        /// The absolute offset of the value.
+        offset: u64,
+    },
+    /// The value at `offset` is a NaN, which the [`FloatPolicy`] rejects.
+    Nan {
+        /// The absolute offset of the value.
+        offset: u64,
+        /// The bits of the NaN, widened to a [`u64`].
+        bits: u64,
+    },
+    /// The position of the reader could not be queried before the read, or restored after a rejected NaN, such as on a reader which
+    /// cannot seek.
+    PositionUnknown,
+}
+
+impl FloatError {
+    /// Returns the [`FloatError`] held by `error`, if it is one recorded by [`SeqReader::shift_dyn`] and returned by
+    /// [`SeqReader::take_error`].
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for FloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloatError::Truncated { offset } => {
+                write!(f, "not enough bytes to read the float at offset {}", offset)
+            }
+            FloatError::Nan { offset, bits } => {
+                write!(f, "rejected NaN {:#x} at offset {}", bits, offset)
+            }
+            FloatError::PositionUnknown => write!(f, "the position of the reader is unknown"),
+        }
+    }
+}
+
+impl std::error::Error for FloatError {}
+
+/// Applies `policy` to `value` if it is one of the [`PolicyFloat`] types, returning the bits of a rejected NaN in the error.
+fn apply_dyn<T: 'static>(policy: FloatPolicy, mut value: T) -> Result<T, u64> {
+    fn apply<F: PolicyFloat>(policy: FloatPolicy, value: &mut dyn Any) -> Result<(), u64> {
+        if let Some(float) = value.downcast_mut::<F>() {
+            *float = policy.apply(*float)?;
+        }
+
+        Ok(())
+    }
+
+    apply::<f32>(policy, &mut value)?;
+    apply::<f64>(policy, &mut value)?;
+    #[cfg(feature = "half")]
+    apply::<half::f16>(policy, &mut value)?;
+
+    Ok(value)
+}
+
+/// A reader which carries configuration for how values are read through it, such as [`Codecs`] overriding the conversion of particular
+/// types and a [`FloatPolicy`] for NaNs and subnormals. Every method of [`SeqByteReaderCore`] is delegated to the inner reader, so it wraps
+/// any reader, including [`Retrying`] for sources whose interrupted reads should surface.
+///
+/// Reads which it fails itself keep the [`io::Error`] which failed them, so that [`SeqReader::take_error`] can tell why a method returned
+/// [`None`].
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::reader::SeqReader;
+/// use std::io::Cursor;
+///
+/// let mut reader = SeqReader::new(Cursor::new(vec![0x12, 0x34]));
+/// reader.register_codec::<u16>(|b| Some(u16::from_be_bytes(b.try_into().ok()?)), |v| v.to_be_bytes().to_vec(), 2);
+///
+/// assert_eq!(reader.shift_dyn::<u16>(), Some(0x1234));
+/// reader.set_pos(0).unwrap();
+/// assert_eq!(reader.shift::<u16>(), Some(0x3412));
+/// ```
+#[derive(Debug, Default)]
+pub struct SeqReader<R> {
+    inner: R,
+    codecs: Codecs,
+    float_policy: FloatPolicy,
+    error: Option<io::Error>,
+    /// The position of the inner reader, if known, so that capturing it to restore does not query the source.
+    pos: Option<u64>,
+}
+
+impl<R> SeqReader<R> {
+    /// Wraps `inner` with the default configuration.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            codecs: Codecs::new(),
+            float_policy: FloatPolicy::default(),
+            error: None,
+            pos: None,
+        }
+    }
+    /// Sets the policy for NaNs and subnormals read as floats. The default passes every value through bit for bit.
+    pub fn with_float_policy(mut self, float_policy: FloatPolicy) -> Self {
+        self.float_policy = float_policy;
+        self
+    }
+    /// Returns the policy for NaNs and subnormals read as floats.
+    pub fn float_policy(&self) -> FloatPolicy {
+        self.float_policy
+    }
+    /// Returns the error which failed the last read failed by this reader, such as a NaN rejected by [`SeqReader::shift_dyn`], clearing it.
+    /// Errors of the inner reader are kept by the inner reader, such as by [`Retrying::take_error`]. Failures which the readers of
+    /// [`SeqByteReader`] fold into [`None`], such as a [`crate::bytes::LengthOverflow`], are not recorded.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+    /// Registers the codec of `T` used by [`SeqReader::shift_dyn`], replacing the one registered before, if any.
+    pub fn register_codec<T: 'static>(
+        &mut self,
+        decode: fn(&[u8]) -> Option<T>,
+        encode: fn(&T) -> Vec<u8>,
+        size: usize,
+    ) {
+        self.codecs.register(decode, encode, size);
+    }
+    /// Returns a reference to the registered codecs, such as for writing values with [`Codecs::push_dyn`].
+    pub fn codecs(&self) -> &Codecs {
+        &self.codecs
+    }
+    /// Returns a mutable reference to the registered codecs.
+    pub fn codecs_mut(&mut self) -> &mut Codecs {
+        &mut self.codecs
+    }
+    /// Writes `value` to `writer` with the registered codec of `T`, or its [`SizedNumber`] implementation if it has none, as
+    /// [`Codecs::push_dyn`] does.
+    pub fn push_dyn<T: SizedNumber + 'static, W: SeqByteWriter + ?Sized>(
+        &self,
+        writer: &mut W,
+        value: &T,
+    ) -> Option<()> {
+        self.codecs.push_dyn(writer, value)
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader. Its position is queried again on the next read, in case it is moved through the
+    /// reference.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.pos = None;
+        &mut self.inner
+    }
+    /// Unwraps the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read + Seek> SeqReader<Retrying<R>> {
+    /// Wraps `inner` in a [`Retrying`] reader which retries interrupted reads at most `max_interrupts` times, with the default
+    /// configuration.
+    pub fn retrying(inner: R, max_interrupts: Option<u32>) -> Self {
+        Self::new(Retrying::new(inner).with_max_interrupts(max_interrupts))
+    }
+}
+
+impl<R: SeqByteReaderCore> SeqReader<R> {
+    /// Reads a `T` with its registered codec, or with its [`SizedNumber`] implementation if it has none, as [`Codecs::shift_dyn`] does.
+    /// Floats then go through the [`FloatPolicy`], and a NaN it rejects fails the read, recording an [`io::Error`] of kind
+    /// [`io::ErrorKind::InvalidData`] holding the [`FloatError`], which [`FloatError::from_io`] extracts.
+    pub fn shift_dyn<T: SizedNumber + 'static>(&mut self) -> Option<T> {
+        let codec = self.codecs.resolve::<T>();
+        let start = self.pos()?;
+        let value = shift_with(self, codec)?;
+
+        match apply_dyn(self.float_policy, value) {
+            Ok(value) => Some(value),
+            Err(bits) => {
+                self.set_pos(start)?;
+                self.fail(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    FloatError::Nan {
+                        offset: start,
+                        bits,
+                    },
+                ))
+            }
+        }
+    }
+    /// Reads a float, shifting the position, and applies the [`FloatPolicy`] to it. Returns [`FloatError::Truncated`] if there are not
+    /// enough bytes to be read, and [`FloatError::Nan`] if the value is a NaN which the policy rejects. On failure, the position is restored,
+    /// and [`FloatError::PositionUnknown`] is returned instead if it cannot be.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::reader::{FloatError, FloatPolicy, NanPolicy, SeqReader};
+    /// use std::io::Cursor;
+    ///
+    /// let policy = FloatPolicy { nan: NanPolicy::Reject, ..FloatPolicy::default() };
+    /// let mut reader = SeqReader::new(Cursor::new(vec![0x01, 0x00, 0xC0, 0x7F])).with_float_policy(policy);
+    ///
+    /// assert_eq!(
+    ///     reader.shift_float::<f32>(),
+    ///     Err(FloatError::Nan { offset: 0, bits: 0x7FC0_0001 })
+    /// );
+    /// ```
+    pub fn shift_float<F: PolicyFloat>(&mut self) -> Result<F, FloatError> {
+        self.shift_float_with(|r| r.shift::<F>())
+    }
+    /// Reads a float with the specified endianness, shifting the position, and applies the [`FloatPolicy`] to it, as
+    /// [`SeqReader::shift_float`] does.
+    pub fn shift_float_e<F: PolicyFloat>(&mut self, bigendian: bool) -> Result<F, FloatError> {
+        self.shift_float_with(|r| r.shift_e::<F>(bigendian))
+    }
+    fn shift_float_with<F: PolicyFloat>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Option<F>,
+    ) -> Result<F, FloatError> {
+        let offset = self.pos().ok_or(FloatError::PositionUnknown)?;
+        let value = read(self).ok_or(FloatError::Truncated { offset })?;
+
+        self.float_policy
+            .apply(value)
+            .map_err(|bits| match self.set_pos(offset) {
+                Some(()) => FloatError::Nan { offset, bits },
+                None => FloatError::PositionUnknown,
+            })
+    }
+    /// Records `error` as the reason the read failed. The position of the inner reader is unknown after a failed read or seek, so it is
+    /// queried again next time.
+    fn fail<T>(&mut self, error: io::Error) -> Option<T> {
+        self.error = Some(error);
+        self.pos = None;
+        None
+    }
+}
+
+impl<R: SeqByteReaderCore> SeqByteReaderCore for SeqReader<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let filled = self.inner.fill(buf);
+        self.pos = match filled {
+            Some(()) => self.pos.and_then(|pos| pos.checked_add(buf.len() as u64)),
+            None => None,
+        };
+
+        filled
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let filled = self.inner.peek_fill(buf);
+        if filled.is_none() {
+            self.pos = None;
+        }
+
+        filled
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        if self.pos.is_none() {
+            self.pos = self.inner.pos();
+        }
+
+        self.pos
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        let set = self.inner.set_pos(pos);
+        self.pos = set.map(|()| pos);
+
+        set
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        self.inner.end_pos()
+    }
+
+    fn try_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        // On failure the inner reader is left where it was, so the cached position stays valid.
+        self.inner.try_fill(buf)?;
+        self.pos = self.pos.and_then(|pos| pos.checked_add(buf.len() as u64));
+
+        Some(())
+    }
+
+    fn try_peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let filled = self.inner.try_peek_fill(buf);
+        if filled.is_none() {
+            self.pos = None;
+        }
+
+        filled
+    }
+
+    fn step_back(&mut self, amount: u64) -> Option<()> {
+        let stepped = self.inner.step_back(amount);
+        self.pos = match stepped {
+            Some(()) => self.pos.and_then(|pos| pos.checked_sub(amount)),
+            None => None,
+        };
+
+        stepped
+    }
+}
+
+/// A reader over a [`Read`] and [`Seek`] source which limits how many times a read which fails with [`io::ErrorKind::Interrupted`] is
+/// retried, for callers which want an interruption to surface. Any [`Read`] and [`Seek`] source is already a [`SeqByteReaderCore`], which
+/// retries interrupted reads without limit, as [`Read::read_exact`] does.
+///
+/// Reads which fail keep the [`io::Error`] which failed them, so that [`Retrying::take_error`] can tell why a method returned [`None`].
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::reader::Retrying;
+/// use std::io::{Cursor, ErrorKind};
+///
+/// let mut reader = Retrying::new(Cursor::new(vec![1, 2])).with_max_interrupts(Some(3));
+///
+/// assert_eq!(reader.shift::<u8>(), Some(1));
+/// assert_eq!(reader.shift::<u16>(), None);
+/// assert_eq!(reader.take_error().map(|e| e.kind()), Some(ErrorKind::UnexpectedEof));
+/// ```
+#[derive(Debug, Default)]
+pub struct Retrying<R> {
+    inner: R,
+    max_interrupts: Option<u32>,
+    error: Option<io::Error>,
+}
+
+impl<R> Retrying<R> {
+    /// Wraps `inner`, retrying interrupted reads without limit until [`Retrying::with_max_interrupts`] sets one.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            max_interrupts: None,
+            error: None,
+        }
+    }
+    /// Sets how many times a read which fails with [`io::ErrorKind::Interrupted`] is retried before it fails. [`None`] retries without
+    /// limit.
+    pub fn with_max_interrupts(mut self, max_interrupts: Option<u32>) -> Self {
+        self.max_interrupts = max_interrupts;
+        self
+    }
+    /// Returns how many times an interrupted read is retried, or [`None`] if there is no limit.
+    pub fn max_interrupts(&self) -> Option<u32> {
+        self.max_interrupts
+    }
+    /// Returns the error which failed the last read which failed, clearing it. Reads fail with [`io::ErrorKind::UnexpectedEof`] if the
+    /// source ends first, and with [`io::ErrorKind::Interrupted`] if they were interrupted more times than allowed.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// Unwraps the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read + Seek> Retrying<R> {
+    /// Reads into `buf` until it is full. On failure, records the error and returns the number of bytes consumed before it.
+    fn read_counted(&mut self, buf: &mut [u8]) -> Result<(), usize> {
+        let mut filled = 0;
+        let mut interrupts = 0;
+
+        while filled < buf.len() {
+            match self.inner.read(&mut buf[filled..]) {
+                Ok(0) => {
+                    self.error = Some(io::ErrorKind::UnexpectedEof.into());
+                    return Err(filled);
+                }
+                Ok(read) => filled += read,
+                Err(e)
+                    if e.kind() == io::ErrorKind::Interrupted
+                        && self.max_interrupts.is_none_or(|max| interrupts < max) =>
+                {
+                    interrupts += 1;
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                    return Err(filled);
+                }
+            }
+        }
+
+        Ok(())
+    }
+    /// Records the error of `result`, if any.
+    fn record<T>(&mut self, result: io::Result<T>) -> Option<T> {
+        result.map_err(|e| self.error = Some(e)).ok()
+    }
+}
+
+impl<R: Read + Seek> SeqByteReaderCore for Retrying<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.read_counted(buf).ok()
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        let pos = self.inner.stream_position();
+        self.record(pos)
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        let sought = self.inner.seek(SeekFrom::Start(pos));
+        self.record(sought).map(drop)
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        self.inner.end_pos()
+    }
+
+    fn try_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        if let Err(read) = self.read_counted(buf) {
+            let _ = self.step_back(read as u64);
+            return None;
+        }
+
+        Some(())
+    }
+
+    fn step_back(&mut self, amount: u64) -> Option<()> {
+        if amount == 0 {
+            return Some(());
+        }
+
+        let sought = self
+            .inner
+            .seek(SeekFrom::Current(-i64::try_from(amount).ok()?));
+        self.record(sought).map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    /// A source whose reads fail with [`io::ErrorKind::Interrupted`] until it has been interrupted `left` times.
+    struct Interrupting {
+        inner: Cursor<Vec<u8>>,
+        left: u32,
+    }
+
+    impl Read for Interrupting {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.left > 0 {
+                self.left -= 1;
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for Interrupting {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn interrupting(left: u32) -> Interrupting {
+        Interrupting {
+            inner: Cursor::new(0x0102_0304_0506_0708u64.to_le_bytes().to_vec()),
+            left,
+        }
+    }
+
+    const KEY: u32 = 0x5A5A_A5A5;
+
+    fn deobfuscate(bytes: &[u8]) -> Option<u32> {
+        Some(u32::from_bytes(bytes)? ^ KEY)
+    }
+
+    fn obfuscate(value: &u32) -> Vec<u8> {
+        (value ^ KEY).to_bytes()
+    }
+
+    #[test]
+    fn codec_test() {
+        let mut file = Vec::new();
+        file.push_slice(&obfuscate(&1234)).unwrap();
+        file.push_slice(&7u16.to_bytes()).unwrap();
+
+        let mut reader = SeqReader::new(Cursor::new(file));
+        reader.register_codec(deobfuscate, obfuscate, 4);
+
+        // Only the dynamic calls consult the codec, and types without one fall back to their implementation.
+        assert_eq!(reader.shift_dyn::<u32>(), Some(1234));
+        assert_eq!(reader.shift_dyn::<u16>(), Some(7));
+        reader.set_pos(0).unwrap();
+        assert_eq!(reader.shift::<u32>(), Some(1234 ^ KEY));
+        assert_eq!(reader.shift_dyn::<u32>(), None);
+        assert_eq!(reader.pos(), Some(4));
+
+        let mut out = Vec::new();
+        reader.push_dyn(&mut out, &1234u32).unwrap();
+        reader.push_dyn(&mut out, &7u16).unwrap();
+        assert_eq!(out, reader.get_ref().get_ref()[..]);
+    }
+
+    #[test]
+    fn precedence_test() {
+        let mut reader = SeqReader::new(Cursor::new(vec![1, 0, 0, 0, 2, 0, 0, 0, 3]));
+
+        // A codec may have a different size than the implementation it overrides, and the one registered last wins.
+        reader.register_codec::<u32>(|b| Some(b[0] as u32 * 100), |v| vec![(v / 100) as u8], 1);
+        reader.register_codec(deobfuscate, obfuscate, 4);
+        assert_eq!(reader.codecs().len(), 1);
+        assert_eq!(reader.shift_dyn::<u32>(), Some(1 ^ KEY));
+
+        reader.codecs_mut().register::<u32>(
+            |b| Some(b[0] as u32 * 100),
+            |v| vec![(v / 100) as u8],
+            1,
+        );
+        assert_eq!(reader.shift_dyn::<u32>(), Some(200));
+        assert_eq!(reader.pos(), Some(5));
+
+        assert!(reader.codecs_mut().unregister::<u32>());
+        assert!(!reader.codecs_mut().unregister::<u32>());
+        assert_eq!(reader.shift_dyn::<u32>(), Some(3 << 24));
+        assert_eq!(reader.shift_dyn::<u32>(), None);
+        reader.set_pos(4).unwrap();
+        assert_eq!(reader.shift_dyn::<u32>(), Some(2));
+        assert_eq!(reader.codecs().get::<u16>().map(|codec| codec.size), None);
+    }
+
+    #[test]
+    fn interrupted_test() {
+        // Interrupted reads are retried, both through any reader and through a `SeqReader` without a limit.
+        assert_eq!(interrupting(2).shift::<u64>(), Some(0x0102_0304_0506_0708));
+        let mut reader = SeqReader::new(interrupting(100));
+        assert_eq!(reader.shift::<u64>(), Some(0x0102_0304_0506_0708));
+        assert!(reader.take_error().is_none());
+        let mut reader = Retrying::new(interrupting(100));
+        assert_eq!(reader.shift::<u64>(), Some(0x0102_0304_0506_0708));
+        assert!(reader.take_error().is_none());
+
+        let mut reader = SeqReader::retrying(interrupting(2), Some(2));
+        assert_eq!(reader.shift::<u64>(), Some(0x0102_0304_0506_0708));
+
+        // One interruption too many surfaces, and leaves the position where it was.
+        let mut reader = SeqReader::retrying(interrupting(2), Some(1));
+        assert_eq!(reader.get_ref().max_interrupts(), Some(1));
+        assert_eq!(reader.shift::<u64>(), None);
+        assert_eq!(reader.pos(), Some(0));
+        assert_eq!(
+            reader.get_mut().take_error().map(|e| e.kind()),
+            Some(io::ErrorKind::Interrupted)
+        );
+        assert!(reader.get_mut().take_error().is_none());
+
+        assert_eq!(reader.shift::<u64>(), Some(0x0102_0304_0506_0708));
+        assert_eq!(reader.shift::<u8>(), None);
+        assert_eq!(reader.pos(), Some(8));
+        assert_eq!(
+            reader.get_mut().take_error().map(|e| e.kind()),
+            Some(io::ErrorKind::UnexpectedEof)
+        );
+        assert!(reader.take_error().is_none());
+
+        // The delegating reader wraps readers which are not sources at all.
+        let mut reader = SeqReader::new(SeqReader::new(Cursor::new([7u8, 0])));
+        assert_eq!(reader.next::<u16>(), Some(7));
+        assert_eq!(reader.end_pos(), Some(2));
+        assert_eq!(reader.shift::<u32>(), None);
+        assert_eq!(reader.shift::<u16>(), Some(7));
+    }
+
+    #[test]
+    fn float_policy_test() {
+        // A signalling NaN with a payload, a negative subnormal, and a normal value, as `f32` then `f64`, both big and little endian.
+        const SNAN: u32 = 0xFF80_0ABC;
+        const SUBNORMAL: u64 = 0x8000_0000_0000_0001;
+        let mut file = Vec::new();
+        for bigendian in [true, false] {
+            file.push_e(SNAN, bigendian).unwrap();
+            file.push_e(SUBNORMAL, bigendian).unwrap();
+            file.push_e(1.5f32, bigendian).unwrap();
+        }
+
+        let read_all = |policy: FloatPolicy| {
+            let mut reader = SeqReader::new(Cursor::new(file.clone())).with_float_policy(policy);
+            assert_eq!(reader.float_policy(), policy);
+
+            [true, false].map(|bigendian| {
+                (
+                    reader.shift_float_e::<f32>(bigendian).map(f32::to_bits),
+                    reader.shift_float_e::<f64>(bigendian).map(f64::to_bits),
+                    reader.shift_float_e::<f32>(bigendian),
+                )
+            })
+        };
+
+        // The default passes every bit through.
+        let exact = (Ok(SNAN), Ok(SUBNORMAL), Ok(1.5));
+        assert_eq!(read_all(FloatPolicy::default()), [exact; 2]);
+
+        let quiet = FloatPolicy {
+            nan: NanPolicy::CanonicalizeQuiet,
+            subnormals: SubnormalPolicy::FlushToZero,
+        };
+        let flushed = (Ok(f32::NAN.to_bits()), Ok((-0.0f64).to_bits()), Ok(1.5));
+        assert_eq!(read_all(quiet), [flushed; 2]);
+
+        // A rejected NaN restores the position, so the same value fails again.
+        let reject = FloatPolicy {
+            nan: NanPolicy::Reject,
+            ..FloatPolicy::default()
+        };
+        let mut reader = SeqReader::new(Cursor::new(file.clone())).with_float_policy(reject);
+        let error = FloatError::Nan {
+            offset: 0,
+            bits: SNAN as u64,
+        };
+        assert_eq!(reader.shift_float_e::<f32>(true), Err(error));
+        assert_eq!(reader.shift_float_e::<f32>(true), Err(error));
+        assert_eq!(reader.pos(), Some(0));
+        assert_eq!(error.to_string(), "rejected NaN 0xff800abc at offset 0");
+
+        // The dynamic path applies the policy too, recording why it failed, while plain shifts bypass it.
+        assert_eq!(reader.shift_dyn::<u32>(), Some(SNAN.swap_bytes()));
+        reader.set_pos(16).unwrap();
+        assert_eq!(reader.shift_dyn::<f32>(), None);
+        let recorded = reader.take_error().unwrap();
+        assert_eq!(
+            FloatError::from_io(&recorded),
+            Some(&FloatError::Nan {
+                offset: 16,
+                bits: SNAN as u64
+            })
+        );
+        assert_eq!(reader.pos(), Some(16));
+        assert_eq!(reader.shift::<f32>().map(f32::to_bits), Some(SNAN));
+
+        reader.set_pos(file.len() as u64 - 2).unwrap();
+        assert_eq!(
+            reader.shift_float::<f32>(),
+            Err(FloatError::Truncated {
+                offset: file.len() as u64 - 2
+            })
+        );
+
+        /// A source which only moves forwards, and which only knows its position if `knows_pos` is set.
+        struct Forward {
+            bytes: Vec<u8>,
+            at: usize,
+            knows_pos: bool,
+        }
+
+        impl SeqByteReaderCore for Forward {
+            fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+                buf.copy_from_slice(self.bytes.get(self.at..self.at + buf.len())?);
+                self.at += buf.len();
+                Some(())
+            }
+
+            fn pos(&mut self) -> Option<u64> {
+                self.knows_pos.then_some(self.at as u64)
+            }
+
+            fn set_pos(&mut self, pos: u64) -> Option<()> {
+                self.at = usize::try_from(pos).ok().filter(|pos| *pos >= self.at)?;
+                Some(())
+            }
+        }
+
+        // Without a position to report or restore, the error says so rather than making up an offset.
+        let policy = FloatPolicy {
+            nan: NanPolicy::Reject,
+            ..FloatPolicy::default()
+        };
+        for knows_pos in [false, true] {
+            let bytes = SNAN.to_le_bytes().to_vec();
+            let mut reader = SeqReader::new(Forward {
+                bytes,
+                at: 0,
+                knows_pos,
+            })
+            .with_float_policy(policy);
+
+            let error = reader.shift_float::<f32>().unwrap_err();
+            assert_eq!(error, FloatError::PositionUnknown);
+            assert_eq!(error.to_string(), "the position of the reader is unknown");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn f16_policy_test() {
+        use half::f16;
+
+        let policy = FloatPolicy {
+            nan: NanPolicy::CanonicalizeQuiet,
+            subnormals: SubnormalPolicy::FlushToZero,
+        };
+        let mut reader = SeqReader::new(Cursor::new(vec![
+            0x7D, 0x01, 0x00, 0x80, 0x83, 0x00, 0x3C, 0x00,
+        ]))
+        .with_float_policy(policy);
+
+        assert_eq!(
+            reader.shift_float_e::<f16>(true).map(f16::to_bits),
+            Ok(f16::NAN.to_bits())
+        );
+        assert_eq!(
+            reader.shift_float_e::<f16>(false).map(f16::to_bits),
+            Ok(0x8000)
+        );
+        assert_eq!(reader.shift_dyn::<f16>().map(f16::to_bits), Some(0x0000));
+        assert_eq!(reader.shift_float_e::<f16>(true), Ok(f16::ONE));
+    }
+}
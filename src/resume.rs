@@ -0,0 +1,249 @@
+use std::fmt;
+
+use super::bytes::SeqByteReaderCore;
+
+/// The bytes a [`ResumableParser`] has buffered so far, as read by its parsing closure. Reads running past the end fail as with any other
+/// reader, but are remembered, so a parse which failed for lack of bytes is told apart from one which rejected them. The end of the input
+/// is unknown, so methods which check lengths against the remaining bytes read on instead of rejecting them early.
+#[derive(Debug)]
+pub struct ResumeInput<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    starved: bool,
+}
+
+impl ResumeInput<'_> {
+    /// Returns `true` if a read has run past the bytes buffered so far.
+    pub fn starved(&self) -> bool {
+        self.starved
+    }
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        let available = self.bytes.get(self.pos..self.pos.checked_add(len)?);
+        if available.is_none() {
+            self.starved = true;
+        }
+
+        available
+    }
+}
+
+impl SeqByteReaderCore for ResumeInput<'_> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        buf.copy_from_slice(self.take(buf.len())?);
+        self.pos += buf.len();
+
+        Some(())
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        buf.copy_from_slice(self.take(buf.len())?);
+        Some(())
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        Some(self.pos as u64)
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        self.pos = usize::try_from(pos).ok()?;
+        Some(())
+    }
+}
+
+/// The error returned by [`ResumableParser::feed`] when the parsing closure rejects the bytes of a value, rather than running out of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResumeError<T> {
+    /// The offset of the rejected value from the start of the stream.
+    pub offset: u64,
+    /// The values completed by the same call to [`ResumableParser::feed`] before the rejected one.
+    pub values: Vec<T>,
+}
+
+impl<T> fmt::Display for ResumeError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse the value at offset {}", self.offset)
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for ResumeError<T> {}
+
+/// A parser for values arriving in pieces, such as records read from a socket, where a value may be split across any number of reads.
+///
+/// The parsing closure is written against the reader traits of this crate, reading one value from a [`ResumeInput`] over the bytes buffered
+/// so far. When it returns [`None`] after reading past them, the value is incomplete and the parse is retried once more bytes are fed. When
+/// it returns [`None`] otherwise, the bytes are rejected and [`ResumableParser::feed`] returns an error. The closure must read at least one
+/// byte for each value it returns.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::resume::ResumableParser;
+///
+/// // Length-prefixed strings.
+/// let mut parser = ResumableParser::new(|r| {
+///     let len = r.shift::<u8>()?;
+///     r.shift_string(len as usize)
+/// });
+///
+/// assert_eq!(parser.feed(b"\x05hel").unwrap(), Vec::<String>::new());
+/// assert_eq!(parser.feed(b"lo\x02h").unwrap(), ["hello"]);
+/// assert_eq!(parser.feed(b"i").unwrap(), ["hi"]);
+/// assert!(parser.buffered().is_empty());
+/// ```
+pub struct ResumableParser<T, F> {
+    parse: F,
+    buffer: Vec<u8>,
+    offset: u64,
+    _values: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T, F: FnMut(&mut ResumeInput) -> Option<T>> ResumableParser<T, F> {
+    /// Creates a parser which reads each value with `parse`.
+    pub fn new(parse: F) -> Self {
+        Self {
+            parse,
+            buffer: Vec::new(),
+            offset: 0,
+            _values: std::marker::PhantomData,
+        }
+    }
+    /// Appends `bytes` to the buffer and parses as many values as are complete, keeping only the bytes of the incomplete one that follows.
+    /// Returns [`ResumeError`] if a value is rejected, in which case its bytes stay buffered.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Vec<T>, ResumeError<T>> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut values = Vec::new();
+        let mut consumed = 0;
+        let result = loop {
+            if consumed == self.buffer.len() {
+                break Ok(());
+            }
+
+            let mut input = ResumeInput {
+                bytes: &self.buffer[consumed..],
+                pos: 0,
+                starved: false,
+            };
+
+            match (self.parse)(&mut input) {
+                Some(value) if input.pos > 0 => {
+                    values.push(value);
+                    consumed += input.pos.min(input.bytes.len());
+                }
+                None if input.starved => break Ok(()),
+                _ => break Err(self.offset + consumed as u64),
+            }
+        };
+
+        self.buffer.drain(..consumed);
+        self.offset += consumed as u64;
+
+        match result {
+            Ok(()) => Ok(values),
+            Err(offset) => Err(ResumeError { offset, values }),
+        }
+    }
+}
+
+impl<T, F> ResumableParser<T, F> {
+    /// Returns the bytes buffered for the next value.
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+    /// Returns the offset of the next value from the start of the stream.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+    /// Drops the buffered bytes, such as those of a rejected value, and skips the stream offset past them.
+    pub fn clear(&mut self) {
+        self.offset += self.buffer.len() as u64;
+        self.buffer.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    /// Records of a tag below 16, a big endian `u16` length and that many bytes of payload.
+    fn record(r: &mut ResumeInput) -> Option<(u8, Vec<u8>)> {
+        let tag = r.shift_validated::<u8, _>(|tag| *tag < 16)?;
+        let len = r.shift_e::<u16>(true)?;
+
+        Some((tag, r.shift_slice(len as usize)?))
+    }
+
+    fn stream() -> Vec<u8> {
+        let mut stream = Vec::new();
+        for (tag, payload) in [(1u8, &b"first"[..]), (2, &[]), (3, &[0xAB; 300])] {
+            stream.push(tag);
+            stream.push_e(payload.len() as u16, true).unwrap();
+            stream.extend_from_slice(payload);
+        }
+
+        stream
+    }
+
+    #[test]
+    fn chunking_test() {
+        let stream = stream();
+
+        let mut whole = ResumableParser::new(record);
+        let expected = whole.feed(&stream).unwrap();
+        assert_eq!(expected.len(), 3);
+        assert_eq!(expected[2], (3, vec![0xAB; 300]));
+        assert_eq!(whole.offset(), stream.len() as u64);
+
+        let mut bytewise = ResumableParser::new(record);
+        let mut values = Vec::new();
+        for byte in &stream {
+            values.extend(bytewise.feed(&[*byte]).unwrap());
+            assert!(bytewise.buffered().len() < 304);
+        }
+        assert_eq!(values, expected);
+
+        // Chunk sizes from a small linear congruential generator, so every run feeds the same chunks.
+        for seed in [1u32, 7, 42] {
+            let mut parser = ResumableParser::new(record);
+            let mut values = Vec::new();
+            let mut state = seed;
+            let mut rest = &stream[..];
+
+            while !rest.is_empty() {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let (chunk, tail) = rest.split_at(((state >> 16) as usize % 40).min(rest.len()));
+
+                values.extend(parser.feed(chunk).unwrap());
+                rest = tail;
+            }
+
+            assert_eq!(values, expected);
+            assert!(parser.buffered().is_empty());
+        }
+    }
+
+    #[test]
+    fn rejected_test() {
+        let mut stream = stream();
+        stream.truncate(11);
+        stream.extend([0x20, 0x00]);
+
+        let mut parser = ResumableParser::new(record);
+        assert_eq!(parser.feed(&stream[..9]).unwrap(), [(1, b"first".to_vec())]);
+        assert_eq!(
+            parser.feed(&stream[9..]),
+            Err(ResumeError {
+                offset: 11,
+                values: vec![(2, Vec::new())]
+            })
+        );
+        assert_eq!(parser.buffered(), [0x20, 0x00]);
+
+        parser.clear();
+        assert_eq!(parser.offset(), 13);
+        assert_eq!(parser.feed(&[4, 0, 1]).unwrap(), []);
+        assert_eq!(parser.feed(&[9]).unwrap(), [(4, vec![9])]);
+    }
+}
@@ -0,0 +1,440 @@
+use super::bytes::{ESeqByteReader, SeqByteReader, SeqByteReaderCore, SeqByteWriter};
+use super::traits::{EndianNumber, Endianness};
+
+/// The number of elements of a [`FieldType::Bytes`] or [`FieldType::Array`] field.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Count {
+    /// A count fixed by the schema.
+    Fixed(usize),
+    /// The value of the earlier integer field with the contained name. The field is looked up in the record being read, then in the records
+    /// enclosing it, innermost first.
+    Field(String),
+}
+
+/// The type of a field of a [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    /// A `u8`, read as [`Value::Int`].
+    U8,
+    /// An `i8`, read as [`Value::Int`].
+    I8,
+    /// A `u16` with the contained byte order, read as [`Value::Int`].
+    U16(Endianness),
+    /// An `i16` with the contained byte order, read as [`Value::Int`].
+    I16(Endianness),
+    /// A `u32` with the contained byte order, read as [`Value::Int`].
+    U32(Endianness),
+    /// An `i32` with the contained byte order, read as [`Value::Int`].
+    I32(Endianness),
+    /// A `u64` with the contained byte order, read as [`Value::Int`].
+    U64(Endianness),
+    /// An `i64` with the contained byte order, read as [`Value::Int`].
+    I64(Endianness),
+    /// An `f32` with the contained byte order, read as [`Value::Float`].
+    F32(Endianness),
+    /// An `f64` with the contained byte order, read as [`Value::Float`].
+    F64(Endianness),
+    /// Raw bytes, read as [`Value::Bytes`].
+    Bytes(Count),
+    /// A string of the contained number of bytes, padded with NUL bytes, read as [`Value::Str`] without the padding.
+    FixedStr(usize),
+    /// A string prefixed with its length in bytes as the contained integer type, read as [`Value::Str`].
+    PrefixedStr(Box<FieldType>),
+    /// Elements of the contained type one after another, read as [`Value::List`].
+    Array(Box<FieldType>, Count),
+    /// A nested record, read as [`Value::Record`].
+    Record(Schema),
+}
+
+/// A value read by [`Schema::parse`], or written by [`Schema::write`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An integer of any width or signedness.
+    Int(i128),
+    /// A floating point number of either width.
+    Float(f64),
+    /// Raw bytes.
+    Bytes(Vec<u8>),
+    /// A string, converted from lossy UTF-8.
+    Str(String),
+    /// The elements of an array.
+    List(Vec<Value>),
+    /// The fields of a record, with their names, in order.
+    Record(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Returns the value of the field named `name`, if this is a record with such a field.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Record(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+    /// Returns the contained integer, if this is an [`Value::Int`].
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// A description of the fields of a record built at runtime, such as from a format description loaded from a file, for reading and
+/// writing records without a type of their own.
+///
+/// Fields are read in order, and the counts of arrays and byte fields may be taken from earlier integer fields, such as an array of entries
+/// preceded by the number of entries.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::schema::{Count, FieldType, Schema, Value};
+/// use seqbytes::traits::Endianness;
+/// use std::io::Cursor;
+///
+/// let schema = Schema::new()
+///     .field("count", FieldType::U8)
+///     .field("ids", FieldType::Array(Box::new(FieldType::U16(Endianness::Big)), Count::Field("count".to_string())));
+///
+/// let value = schema.parse(&mut Cursor::new(vec![2, 0, 7, 1, 0])).unwrap();
+///
+/// assert_eq!(value.get("ids"), Some(&Value::List(vec![Value::Int(7), Value::Int(256)])));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    fields: Vec<(String, FieldType)>,
+}
+
+/// The records being read or written, innermost last, for looking up counts.
+type Scopes<'a> = Vec<&'a [(String, Value)]>;
+
+impl Schema {
+    /// Creates a schema without any fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Appends a field named `name` of type `ty`.
+    pub fn field(mut self, name: &str, ty: FieldType) -> Self {
+        self.fields.push((name.to_string(), ty));
+        self
+    }
+    /// Returns the fields, in order.
+    pub fn fields(&self) -> &[(String, FieldType)] {
+        &self.fields
+    }
+    /// Reads a record laid out as described, shifting the position past it, and returns it as a [`Value::Record`]. Returns [`None`] if
+    /// there are not enough bytes to be read, or if a count refers to a field which does not exist, is not an integer or is negative, in
+    /// which case the position is restored.
+    pub fn parse<R: SeqByteReaderCore + ?Sized>(&self, reader: &mut R) -> Option<Value> {
+        reader.atomic(|r| self.parse_record(r, &mut Vec::new()))
+    }
+    /// Writes `value`, a [`Value::Record`] with a value for every field in order, laid out as described. Returns [`None`] without writing
+    /// anything if `value` does not match the schema, such as an integer out of the range of its field, a string longer than its fixed
+    /// size or prefix allows, or an array whose length differs from the field holding its count, and [`None`] if the bytes could not be
+    /// written.
+    pub fn write<W: SeqByteWriter + ?Sized>(&self, value: &Value, writer: &mut W) -> Option<()> {
+        let mut bytes = Vec::new();
+        self.write_record(value, &mut bytes, &mut Vec::new())?;
+
+        writer.push_slice(&bytes)
+    }
+    fn parse_record<R: SeqByteReaderCore + ?Sized>(
+        &self,
+        reader: &mut R,
+        scopes: &mut Vec<Vec<(String, Value)>>,
+    ) -> Option<Value> {
+        scopes.push(Vec::new());
+
+        for (name, ty) in &self.fields {
+            let value = match parse_field(ty, reader, scopes) {
+                Some(value) => value,
+                None => {
+                    scopes.pop();
+                    return None;
+                }
+            };
+            scopes.last_mut()?.push((name.clone(), value));
+        }
+
+        scopes.pop().map(Value::Record)
+    }
+    fn write_record<'a>(
+        &self,
+        value: &'a Value,
+        out: &mut Vec<u8>,
+        scopes: &mut Scopes<'a>,
+    ) -> Option<()> {
+        let Value::Record(fields) = value else {
+            return None;
+        };
+        if fields.len() != self.fields.len() {
+            return None;
+        }
+
+        scopes.push(fields);
+        let result =
+            self.fields
+                .iter()
+                .zip(fields)
+                .try_for_each(|((name, ty), (field_name, value))| {
+                    if name != field_name {
+                        return None;
+                    }
+                    write_field(ty, value, out, scopes)
+                });
+        scopes.pop();
+
+        result
+    }
+}
+
+/// Returns the value of the count `count`, looking up fields in `scopes` innermost first.
+fn resolve<'a, I>(count: &Count, mut scopes: I) -> Option<usize>
+where
+    I: Iterator<Item = &'a [(String, Value)]>,
+{
+    match count {
+        Count::Fixed(n) => Some(*n),
+        Count::Field(name) => {
+            let value = scopes.find_map(|fields| fields.iter().rev().find(|(n, _)| n == name))?;
+            usize::try_from(value.1.as_int()?).ok()
+        }
+    }
+}
+
+fn parse_field<R: SeqByteReaderCore + ?Sized>(
+    ty: &FieldType,
+    reader: &mut R,
+    scopes: &mut Vec<Vec<(String, Value)>>,
+) -> Option<Value> {
+    let count = |count: &Count, scopes: &Vec<Vec<(String, Value)>>| {
+        resolve(count, scopes.iter().rev().map(|fields| fields.as_slice()))
+    };
+
+    Some(match ty {
+        FieldType::U8 => Value::Int(reader.shift::<u8>()?.into()),
+        FieldType::I8 => Value::Int(reader.shift::<i8>()?.into()),
+        FieldType::U16(e) => Value::Int(reader.shift_e::<u16>(e.is_big())?.into()),
+        FieldType::I16(e) => Value::Int(reader.shift_e::<i16>(e.is_big())?.into()),
+        FieldType::U32(e) => Value::Int(reader.shift_e::<u32>(e.is_big())?.into()),
+        FieldType::I32(e) => Value::Int(reader.shift_e::<i32>(e.is_big())?.into()),
+        FieldType::U64(e) => Value::Int(reader.shift_e::<u64>(e.is_big())?.into()),
+        FieldType::I64(e) => Value::Int(reader.shift_e::<i64>(e.is_big())?.into()),
+        FieldType::F32(e) => Value::Float(reader.shift_e::<f32>(e.is_big())?.into()),
+        FieldType::F64(e) => Value::Float(reader.shift_e::<f64>(e.is_big())?),
+        FieldType::Bytes(n) => {
+            let n = count(n, scopes)?;
+            if !reader.available(n)? {
+                return None;
+            }
+            Value::Bytes(reader.shift_slice(n)?)
+        }
+        FieldType::FixedStr(n) => {
+            let bytes = reader.shift_slice(*n)?;
+            let len = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+
+            Value::Str(String::from_utf8_lossy(&bytes[..len]).into_owned())
+        }
+        FieldType::PrefixedStr(len) => {
+            let len = usize::try_from(parse_field(len, reader, scopes)?.as_int()?).ok()?;
+            if !reader.available(len)? {
+                return None;
+            }
+            Value::Str(reader.shift_string(len)?)
+        }
+        FieldType::Array(element, n) => {
+            let n = count(n, scopes)?;
+            let mut values = Vec::new();
+            for _ in 0..n {
+                values.push(parse_field(element, reader, scopes)?);
+            }
+
+            Value::List(values)
+        }
+        FieldType::Record(schema) => schema.parse_record(reader, scopes)?,
+    })
+}
+
+/// Encodes `value` as a `U`, if it is an integer in the range of `U`.
+fn encode_int<U: EndianNumber + TryFrom<i128>>(
+    value: &Value,
+    endianness: Endianness,
+) -> Option<Vec<u8>> {
+    Some(
+        U::try_from(value.as_int()?)
+            .ok()?
+            .to_bytes_e(endianness.is_big()),
+    )
+}
+
+fn write_field<'a>(
+    ty: &FieldType,
+    value: &'a Value,
+    out: &mut Vec<u8>,
+    scopes: &mut Scopes<'a>,
+) -> Option<()> {
+    let count = |count: &Count, scopes: &Scopes| resolve(count, scopes.iter().rev().copied());
+    let float = || match value {
+        Value::Float(value) => Some(*value),
+        _ => None,
+    };
+
+    let bytes = match (ty, value) {
+        (FieldType::U8, _) => encode_int::<u8>(value, Endianness::Little)?,
+        (FieldType::I8, _) => encode_int::<i8>(value, Endianness::Little)?,
+        (FieldType::U16(e), _) => encode_int::<u16>(value, *e)?,
+        (FieldType::I16(e), _) => encode_int::<i16>(value, *e)?,
+        (FieldType::U32(e), _) => encode_int::<u32>(value, *e)?,
+        (FieldType::I32(e), _) => encode_int::<i32>(value, *e)?,
+        (FieldType::U64(e), _) => encode_int::<u64>(value, *e)?,
+        (FieldType::I64(e), _) => encode_int::<i64>(value, *e)?,
+        (FieldType::F32(e), _) => (float()? as f32).to_bytes_e(e.is_big()),
+        (FieldType::F64(e), _) => float()?.to_bytes_e(e.is_big()),
+        (FieldType::Bytes(n), Value::Bytes(bytes)) if count(n, scopes)? == bytes.len() => {
+            bytes.clone()
+        }
+        (FieldType::FixedStr(n), Value::Str(s)) if s.len() <= *n => {
+            let mut bytes = s.as_bytes().to_vec();
+            bytes.resize(*n, 0);
+            bytes
+        }
+        (FieldType::PrefixedStr(len), Value::Str(s)) => {
+            write_field(len, &Value::Int(s.len() as i128), out, &mut Vec::new())?;
+            s.as_bytes().to_vec()
+        }
+        (FieldType::Array(element, n), Value::List(values))
+            if count(n, scopes)? == values.len() =>
+        {
+            return values
+                .iter()
+                .try_for_each(|value| write_field(element, value, out, scopes));
+        }
+        (FieldType::Record(schema), _) => return schema.write_record(value, out, scopes),
+        _ => return None,
+    };
+
+    out.extend(bytes);
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A header of a magic number, a version, a count of sections, and the sections, each with a name, a count of offsets and the offsets.
+    fn header_schema() -> Schema {
+        let section = Schema::new()
+            .field("name", FieldType::PrefixedStr(Box::new(FieldType::U8)))
+            .field("count", FieldType::U16(Endianness::Little))
+            .field(
+                "offsets",
+                FieldType::Array(
+                    Box::new(FieldType::U32(Endianness::Little)),
+                    Count::Field("count".to_string()),
+                ),
+            )
+            .field(
+                "flags",
+                FieldType::Bytes(Count::Field("flag_len".to_string())),
+            );
+
+        Schema::new()
+            .field("magic", FieldType::FixedStr(4))
+            .field("version", FieldType::I16(Endianness::Big))
+            .field("scale", FieldType::F32(Endianness::Big))
+            .field("flag_len", FieldType::U8)
+            .field("count", FieldType::U8)
+            .field(
+                "sections",
+                FieldType::Array(
+                    Box::new(FieldType::Record(section)),
+                    Count::Field("count".to_string()),
+                ),
+            )
+    }
+
+    fn header_bytes() -> Vec<u8> {
+        let mut bytes = b"HD\0\0".to_vec();
+        bytes.extend((-2i16).to_be_bytes());
+        bytes.extend(0.5f32.to_be_bytes());
+        bytes.extend([1, 2]);
+        bytes.extend(b"\x04text\x02\x00");
+        bytes.extend(0x100u32.to_le_bytes());
+        bytes.extend(0x180u32.to_le_bytes());
+        bytes.push(0xA0);
+        bytes.extend(b"\x03bss\x00\x00");
+        bytes.push(0xB0);
+
+        bytes
+    }
+
+    #[test]
+    fn parse_test() {
+        let schema = header_schema();
+        let mut cursor = Cursor::new(header_bytes());
+
+        let value = schema.parse(&mut cursor).unwrap();
+        assert_eq!(cursor.position(), header_bytes().len() as u64);
+
+        assert_eq!(value.get("magic"), Some(&Value::Str("HD".to_string())));
+        assert_eq!(value.get("version"), Some(&Value::Int(-2)));
+        assert_eq!(value.get("scale"), Some(&Value::Float(0.5)));
+
+        let Some(Value::List(sections)) = value.get("sections") else {
+            panic!("sections is not a list");
+        };
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            sections[0].get("name"),
+            Some(&Value::Str("text".to_string()))
+        );
+        assert_eq!(
+            sections[0].get("offsets"),
+            Some(&Value::List(vec![Value::Int(0x100), Value::Int(0x180)]))
+        );
+        assert_eq!(sections[0].get("flags"), Some(&Value::Bytes(vec![0xA0])));
+        assert_eq!(sections[1].get("offsets"), Some(&Value::List(Vec::new())));
+
+        let mut truncated = Cursor::new(header_bytes()[..20].to_vec());
+        assert_eq!(schema.parse(&mut truncated), None);
+        assert_eq!(truncated.position(), 0);
+
+        let missing =
+            Schema::new().field("data", FieldType::Bytes(Count::Field("len".to_string())));
+        assert_eq!(missing.parse(&mut Cursor::new(vec![0; 4])), None);
+    }
+
+    #[test]
+    fn roundtrip_test() {
+        let schema = header_schema();
+        let value = schema.parse(&mut Cursor::new(header_bytes())).unwrap();
+
+        let mut written = Vec::new();
+        schema.write(&value, &mut written).unwrap();
+        assert_eq!(written, header_bytes());
+
+        // The count no longer matches the number of sections.
+        let Value::Record(mut fields) = value.clone() else {
+            unreachable!()
+        };
+        fields[4].1 = Value::Int(3);
+        assert_eq!(
+            schema.write(&Value::Record(fields.clone()), &mut written),
+            None
+        );
+
+        fields[4].1 = Value::Int(2);
+        fields[1].1 = Value::Int(i16::MAX as i128 + 1);
+        assert_eq!(
+            schema.write(&Value::Record(fields.clone()), &mut written),
+            None
+        );
+
+        fields[1].1 = Value::Int(1);
+        fields[0].1 = Value::Str("TOOLONG".to_string());
+        assert_eq!(schema.write(&Value::Record(fields), &mut written), None);
+        assert_eq!(written, header_bytes());
+    }
+}
@@ -0,0 +1,431 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::bytes::ForkableReader;
+
+/// A source which can read bytes at an arbitrary offset without a shared position, so that it can be read from several readers (and threads)
+/// at once.
+pub trait ReadAt {
+    /// Reads bytes starting at `offset` into `buf`, returning how many bytes were read. Returns `Ok(0)` at or after the end of the source.
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+    /// Returns the length of the source in bytes.
+    fn len(&self) -> io::Result<u64>;
+    /// Returns `true` if the source holds no bytes.
+    fn is_empty(&self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
+
+impl ReadAt for [u8] {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let start = usize::try_from(offset).map_or(self.len(), |offset| offset.min(self.len()));
+        let available = &self[start..];
+        let amount = available.len().min(buf.len());
+
+        buf[..amount].copy_from_slice(&available[..amount]);
+        Ok(amount)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(<[u8]>::len(self) as u64)
+    }
+}
+
+impl ReadAt for Vec<u8> {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.as_slice().read_at(buf, offset)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(Vec::len(self) as u64)
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        #[cfg(unix)]
+        return std::os::unix::fs::FileExt::read_at(self, buf, offset);
+        #[cfg(windows)]
+        return std::os::windows::fs::FileExt::seek_read(self, buf, offset);
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl ReadAt for memmap2::Mmap {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self[..].read_at(buf, offset)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(<[u8]>::len(self) as u64)
+    }
+}
+
+/// A reader over the range `[start, end)` of a shared [`ReadAt`] source, with its own position. Positions are relative to the start of the
+/// range, and reads stop at its end. Cloning the reader is cheap, and the clone has an independent position.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::shared::SharedReader;
+/// use std::io::{Seek, SeekFrom};
+/// use std::sync::Arc;
+///
+/// let source = Arc::new(vec![0, 0, 69, 96, 255, 255, 0]);
+/// let mut reader = SharedReader::new(source, 2, 6);
+///
+/// let num : i32 = reader.shift().unwrap();
+///
+/// assert_eq!(num, -40891);
+/// assert_eq!(reader.shift::<u8>(), None);
+/// assert_eq!(reader.seek(SeekFrom::Current(0)).unwrap(), 4);
+/// ```
+#[derive(Debug)]
+pub struct SharedReader<S: ?Sized> {
+    source: Arc<S>,
+    start: u64,
+    end: u64,
+    pos: u64,
+}
+
+impl<S: ?Sized> Clone for SharedReader<S> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            start: self.start,
+            end: self.end,
+            pos: self.pos,
+        }
+    }
+}
+
+impl<S: ?Sized> ForkableReader for SharedReader<S> {
+    fn fork(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<S: ReadAt + ?Sized> SharedReader<S> {
+    /// Creates a new [`SharedReader`] over the range `[start, end)` of `source`, positioned at the start of the range. `end` is clamped to
+    /// be at least `start`.
+    pub fn new(source: Arc<S>, start: u64, end: u64) -> Self {
+        Self {
+            source,
+            start,
+            end: end.max(start),
+            pos: 0,
+        }
+    }
+    /// Returns the absolute offset of the range in the source.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+    /// Returns the length of the range in bytes.
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+    /// Returns `true` if the range holds no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.end == self.start
+    }
+    /// Returns the current position, relative to the start of the range.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+    /// Returns the shared source.
+    pub fn source(&self) -> &Arc<S> {
+        &self.source
+    }
+}
+
+impl<S: ReadAt + ?Sized> Read for SharedReader<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len().saturating_sub(self.pos);
+        let amount = buf
+            .len()
+            .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let offset = self.start.checked_add(self.pos).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "position overflows the offsets of the source",
+            )
+        })?;
+        let read = self.source.read_at(&mut buf[..amount], offset)?;
+        self.pos += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl<S: ReadAt + ?Sized> Seek for SharedReader<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => (n, 0),
+            SeekFrom::Current(n) => (self.pos, n),
+            SeekFrom::End(n) => (self.len(), n),
+        };
+
+        self.pos = base.checked_add_signed(offset).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+
+        Ok(self.pos)
+    }
+}
+
+/// Mints independent [`SharedReader`]s over one shared source, such as a [`File`] or a memory map, for parsing it from several threads.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::shared::ReaderFactory;
+///
+/// let factory = ReaderFactory::new(vec![1, 0, 2, 0, 3, 0, 4, 0]);
+/// let readers = factory.split_at_offsets(&[0, 4]).unwrap();
+///
+/// let sums: Vec<u16> = readers
+///     .into_iter()
+///     .map(|mut r| r.shift::<u16>().unwrap() + r.shift::<u16>().unwrap())
+///     .collect();
+///
+/// assert_eq!(sums, [3, 7]);
+/// ```
+#[derive(Debug)]
+pub struct ReaderFactory<S: ?Sized> {
+    source: Arc<S>,
+}
+
+impl<S: ?Sized> Clone for ReaderFactory<S> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl ReaderFactory<File> {
+    /// Opens the file at `path` as a shared source.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(File::open(path)?))
+    }
+}
+
+impl<S: ReadAt> ReaderFactory<S> {
+    /// Creates a new [`ReaderFactory`] over `source`.
+    pub fn new(source: S) -> Self {
+        Self::from_arc(Arc::new(source))
+    }
+}
+
+impl<S: ReadAt + ?Sized> ReaderFactory<S> {
+    /// Creates a new [`ReaderFactory`] over an already shared `source`.
+    pub fn from_arc(source: Arc<S>) -> Self {
+        Self { source }
+    }
+    /// Returns the shared source.
+    pub fn source(&self) -> &Arc<S> {
+        &self.source
+    }
+    /// Returns a reader over the whole source.
+    pub fn reader(&self) -> io::Result<SharedReader<S>> {
+        Ok(self.range(0, self.source.len()?))
+    }
+    /// Returns a reader over the range `[start, end)` of the source.
+    pub fn range(&self, start: u64, end: u64) -> SharedReader<S> {
+        SharedReader::new(self.source.clone(), start, end)
+    }
+    /// Returns one reader per offset, each covering the source from its offset up to the next offset, with the last one reaching the end of the
+    /// source. Returns an error if the offsets are not in ascending order.
+    pub fn split_at_offsets(&self, offsets: &[u64]) -> io::Result<Vec<SharedReader<S>>> {
+        if offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "offsets are not in ascending order",
+            ));
+        }
+
+        let len = self.source.len()?;
+
+        Ok(offsets
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| self.range(start, offsets.get(i + 1).copied().unwrap_or(len)))
+            .collect())
+    }
+    /// Returns the offsets splitting the records of `record_size` bytes in `[start, end)` into at most `chunks` ranges of whole records,
+    /// including `end` as the last offset. Any trailing bytes too short to form a record are left to the last range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `record_size` is 0.
+    pub fn record_offsets(start: u64, end: u64, record_size: u64, chunks: usize) -> Vec<u64> {
+        assert!(record_size > 0, "record size must be non-zero");
+
+        let records = end.saturating_sub(start) / record_size;
+        let chunks = (chunks.max(1) as u64).min(records.max(1));
+
+        let mut offsets: Vec<u64> = (0..chunks)
+            // Widened, so that a chunk index times a record count near `u64::MAX` cannot wrap.
+            .map(|i| start + (records as u128 * i as u128 / chunks as u128) as u64 * record_size)
+            .collect();
+        offsets.push(end.max(start));
+        offsets
+    }
+    /// Divides the records of `record_size` bytes in `[start, end)` into at most `chunks` ranges of whole records, and runs `f` on a reader over
+    /// each range in parallel, returning the results in order of the ranges. Any trailing bytes too short to form a record are left to the last
+    /// range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `record_size` is 0.
+    #[cfg(feature = "rayon")]
+    pub fn par_records<T, F>(
+        &self,
+        start: u64,
+        end: u64,
+        record_size: u64,
+        chunks: usize,
+        f: F,
+    ) -> Vec<T>
+    where
+        S: Send + Sync,
+        T: Send,
+        F: Fn(&mut SharedReader<S>) -> T + Sync,
+    {
+        use rayon::prelude::*;
+
+        Self::record_offsets(start, end, record_size, chunks)
+            .windows(2)
+            .map(|range| self.range(range[0], range[1]))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|mut reader| f(&mut reader))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::{BufReader, Write};
+
+    const RECORD: u64 = 12;
+    const RECORDS: u64 = 1_000_000;
+    const HEADER: u64 = 16;
+
+    /// Writes a file with a 16-byte header followed by a million records of `u32 id, u64 value`.
+    fn record_file(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("seqbytes-{}-{}", name, std::process::id()));
+        let mut w = io::BufWriter::new(File::create(&path).unwrap());
+
+        w.push_slice(&[0xEE; HEADER as usize]).unwrap();
+        for i in 0..RECORDS as u32 {
+            w.push(i).unwrap();
+            w.push((i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                .unwrap();
+        }
+        w.flush().unwrap();
+
+        path
+    }
+
+    fn parse<R: SeqByteReader>(reader: &mut R) -> (u64, u64) {
+        let (mut count, mut sum) = (0, 0u64);
+        while let Some(id) = reader.shift::<u32>() {
+            let value: u64 = reader.shift().unwrap();
+            assert_eq!(value, (id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+            count += 1;
+            sum = sum.wrapping_add(value);
+        }
+        (count, sum)
+    }
+
+    #[test]
+    fn shared_chunks() {
+        let path = record_file("chunks");
+        let factory = ReaderFactory::open(&path).unwrap();
+
+        let mut sequential = BufReader::new(File::open(&path).unwrap());
+        sequential.seek(SeekFrom::Start(HEADER)).unwrap();
+        let expected = parse(&mut sequential);
+        assert_eq!(expected.0, RECORDS);
+
+        let len = factory.source().len().unwrap();
+        let offsets = ReaderFactory::<File>::record_offsets(HEADER, len, RECORD, 4);
+        assert_eq!(offsets.len(), 5);
+        assert!(offsets.iter().all(|o| (o - HEADER).is_multiple_of(RECORD)));
+
+        let readers = factory.split_at_offsets(&offsets[..4]).unwrap();
+        let results: Vec<(u64, u64)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = readers
+                .into_iter()
+                .map(|r| scope.spawn(move || parse(&mut BufReader::new(r))))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        assert_eq!(results.iter().map(|r| r.0).sum::<u64>(), expected.0);
+        assert_eq!(
+            results.iter().fold(0u64, |sum, r| sum.wrapping_add(r.1)),
+            expected.1
+        );
+
+        #[cfg(feature = "rayon")]
+        {
+            let results =
+                factory.par_records(HEADER, len, RECORD, 4, |r| parse(&mut BufReader::new(r)));
+            assert_eq!(results.len(), 4);
+            assert_eq!(results.iter().map(|r| r.0).sum::<u64>(), expected.0);
+        }
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn shared_ranges() {
+        let factory = ReaderFactory::new((0..100u8).collect::<Vec<u8>>());
+
+        let mut a = factory.range(10, 20);
+        let mut b = a.clone();
+        assert_eq!(a.shift_slice(4).unwrap(), [10, 11, 12, 13]);
+        assert_eq!(b.shift::<u8>().unwrap(), 10);
+        assert_eq!(a.seek(SeekFrom::End(-1)).unwrap(), 9);
+        assert_eq!(a.shift::<u8>().unwrap(), 19);
+        assert_eq!(a.shift::<u8>(), None);
+
+        assert!(factory.split_at_offsets(&[5, 1]).is_err());
+        let readers = factory.split_at_offsets(&[0, 50, 90]).unwrap();
+        let lens: Vec<u64> = readers.iter().map(|r| r.len()).collect();
+        assert_eq!(lens, [50, 40, 10]);
+
+        assert_eq!(
+            ReaderFactory::<Vec<u8>>::record_offsets(0, 10, 4, 4),
+            [0, 4, 10]
+        );
+        assert_eq!(ReaderFactory::<Vec<u8>>::record_offsets(3, 3, 4, 4), [3, 3]);
+
+        // Seeking far past the end of a range which does not start at 0 reads nothing rather than overflowing.
+        let mut far = factory.range(10, 20);
+        far.seek(SeekFrom::Start(u64::MAX)).unwrap();
+        assert_eq!(far.read(&mut [0; 4]).unwrap(), 0);
+        assert_eq!(far.shift::<u8>(), None);
+    }
+}
@@ -0,0 +1,188 @@
+use super::bytes::SeqByteReaderCore;
+
+/// A registered magic number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Magic {
+    name: String,
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+impl Magic {
+    /// Returns the offset just past the magic number, or [`None`] if it overflows.
+    fn end(&self) -> Option<usize> {
+        self.offset.checked_add(self.bytes.len())
+    }
+    fn matches(&self, head: &[u8]) -> bool {
+        self.end().and_then(|end| head.get(self.offset..end)) == Some(&self.bytes[..])
+    }
+}
+
+/// A registry of formats identified by magic numbers, for picking a parser from the first bytes of a source.
+///
+/// Magic numbers may sit at any offset from the start, such as the `ustar` of a TAR archive at offset 257. When several registered magic
+/// numbers match, the longest one wins, and among equally long ones the first registered.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::sniff::FormatSniffer;
+/// use std::io::Cursor;
+///
+/// let sniffer = FormatSniffer::new()
+///     .register("png", 0, b"\x89PNG\r\n\x1a\n")
+///     .register("gif", 0, b"GIF8");
+///
+/// let mut cursor = Cursor::new(b"GIF89a".to_vec());
+///
+/// assert_eq!(sniffer.sniff(&mut cursor), Some("gif"));
+/// assert_eq!(cursor.position(), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FormatSniffer {
+    magics: Vec<Magic>,
+    furthest: usize,
+}
+
+impl FormatSniffer {
+    /// Creates a sniffer without any formats.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Registers the format `name`, identified by `magic` at `offset` bytes from the start. A format may be registered more than once to
+    /// accept several magic numbers. A magic number which would end past [`usize::MAX`] can never match, so it is ignored.
+    pub fn register(mut self, name: &str, offset: usize, magic: &[u8]) -> Self {
+        let magic = Magic {
+            name: name.to_string(),
+            offset,
+            bytes: magic.to_vec(),
+        };
+        let Some(end) = magic.end() else {
+            return self;
+        };
+
+        self.furthest = self.furthest.max(end);
+        self.magics.push(magic);
+        self
+    }
+    /// Returns the number of bytes [`FormatSniffer::sniff`] peeks, which is the furthest end of any registered magic number.
+    pub fn peek_len(&self) -> usize {
+        self.furthest
+    }
+    /// Returns the name of the format whose magic number matches the bytes from the current position, without shifting the position.
+    /// Peeks at most [`FormatSniffer::peek_len`] bytes, and fewer if the source is shorter. Returns [`None`] if no format matches.
+    pub fn sniff<R: SeqByteReaderCore + ?Sized>(&self, reader: &mut R) -> Option<&str> {
+        let start = reader.pos()?;
+        let len = match reader.end_pos() {
+            Some(end) => self
+                .furthest
+                .min(usize::try_from(end.saturating_sub(start)).ok()?),
+            None => self.furthest,
+        };
+
+        let mut head = vec![0u8; len];
+        let best = if reader.peek_fill(&mut head).is_some() {
+            self.best(|magic| magic.matches(&head))
+        } else {
+            // The end of the source is unknown and it is shorter than the furthest magic number, so each magic number is peeked on its own.
+            reader.set_pos(start)?;
+            self.best(|magic| {
+                let Some(end) = magic.end() else {
+                    return false;
+                };
+                let mut head = vec![0u8; end];
+                let matched = reader.peek_fill(&mut head).is_some() && magic.matches(&head);
+
+                reader.set_pos(start).is_some() && matched
+            })
+        };
+
+        best.map(|magic| magic.name.as_str())
+    }
+    fn best<F: FnMut(&Magic) -> bool>(&self, mut matches: F) -> Option<&Magic> {
+        self.magics.iter().filter(|magic| matches(magic)).fold(
+            None,
+            |best: Option<&Magic>, magic| match best {
+                Some(best) if best.bytes.len() >= magic.bytes.len() => Some(best),
+                _ => Some(magic),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::StatsReader;
+    use crate::testutil::Unbounded;
+    use std::io::Cursor;
+
+    fn sniffer() -> FormatSniffer {
+        FormatSniffer::new()
+            .register("png", 0, b"\x89PNG\r\n\x1a\n")
+            .register("zip", 0, b"PK\x03\x04")
+            .register("zip", 0, b"PK\x05\x06")
+            .register("docx", 0, b"PK\x03\x04\x14\x00\x06\x00")
+            .register("tar", 257, b"ustar")
+    }
+
+    #[test]
+    fn sniff_test() {
+        let sniffer = sniffer();
+        assert_eq!(sniffer.peek_len(), 262);
+        assert_eq!(sniffer.clone().register("far", usize::MAX, b"!"), sniffer);
+
+        let mut tar = vec![0u8; 512];
+        tar[..8].copy_from_slice(b"file.txt");
+        tar[257..263].copy_from_slice(b"ustar\0");
+
+        let mut reader = StatsReader::new(Cursor::new(tar));
+        assert_eq!(sniffer.sniff(&mut reader), Some("tar"));
+        assert_eq!(reader.stats().peeks, 1);
+        assert_eq!(reader.stats().bytes_peeked, 262);
+        assert_eq!(reader.stats().bytes_read, 0);
+        assert_eq!(reader.get_ref().position(), 0);
+
+        let mut png = Cursor::new(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec());
+        assert_eq!(sniffer.sniff(&mut png), Some("png"));
+        assert_eq!(png.position(), 0);
+
+        let mut empty_zip = Cursor::new(b"PK\x05\x06".to_vec());
+        assert_eq!(sniffer.sniff(&mut empty_zip), Some("zip"));
+
+        let mut unknown = Cursor::new(b"%PDF-1.7".to_vec());
+        unknown.set_position(1);
+        assert_eq!(sniffer.sniff(&mut unknown), None);
+        assert_eq!(unknown.position(), 1);
+    }
+
+    #[test]
+    fn longest_match_test() {
+        let sniffer = sniffer();
+
+        let mut docx = Cursor::new(b"PK\x03\x04\x14\x00\x06\x00\x08\x00".to_vec());
+        assert_eq!(sniffer.sniff(&mut docx), Some("docx"));
+
+        let mut zip = Cursor::new(b"PK\x03\x04\x14\x00\x00\x00\x08\x00".to_vec());
+        assert_eq!(sniffer.sniff(&mut zip), Some("zip"));
+
+        let ties = FormatSniffer::new()
+            .register("first", 0, b"AB")
+            .register("second", 1, b"BC");
+        assert_eq!(ties.sniff(&mut Cursor::new(b"ABC".to_vec())), Some("first"));
+    }
+
+    #[test]
+    fn unknown_end_test() {
+        let sniffer = sniffer();
+
+        let mut short = Unbounded(Cursor::new(b"xPK\x03\x04\x14\x00\x06\x00".to_vec()));
+        short.set_pos(1).unwrap();
+        assert_eq!(sniffer.sniff(&mut short), Some("docx"));
+        assert_eq!(short.pos(), Some(1));
+
+        let mut empty = Unbounded(Cursor::new(Vec::new()));
+        assert_eq!(sniffer.sniff(&mut empty), None);
+        assert_eq!(empty.pos(), Some(0));
+    }
+}
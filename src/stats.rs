@@ -0,0 +1,214 @@
+use std::fmt;
+
+use super::bytes::SeqByteReaderCore;
+
+/// The counters collected by a [`StatsReader`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ReadStats {
+    /// The number of bytes consumed by successful reads.
+    pub bytes_read: u64,
+    /// The number of bytes returned by successful peeks. These are not included in `bytes_read`.
+    pub bytes_peeked: u64,
+    /// The number of bytes jumped over by setting the position forward.
+    pub bytes_skipped: u64,
+    /// The number of reads, including failed ones.
+    pub reads: u64,
+    /// The number of peeks, including failed ones.
+    pub peeks: u64,
+    /// The number of times the position was set, including failed ones.
+    pub seeks: u64,
+    /// The number of times the position was queried.
+    pub pos_queries: u64,
+    /// The size of the largest successful read, in bytes.
+    pub largest_read: usize,
+}
+
+impl fmt::Display for ReadStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "read:    {} bytes in {} reads (largest {})",
+            self.bytes_read, self.reads, self.largest_read
+        )?;
+        writeln!(
+            f,
+            "peeked:  {} bytes in {} peeks",
+            self.bytes_peeked, self.peeks
+        )?;
+        write!(
+            f,
+            "skipped: {} bytes in {} seeks, {} position queries",
+            self.bytes_skipped, self.seeks, self.pos_queries
+        )
+    }
+}
+
+/// A reader which counts the bytes and calls going through it, for finding out where a parser spends its bytes. Every call is delegated to
+/// the inner reader unchanged.
+///
+/// The counters are kept per method of [`SeqByteReaderCore`], which every method of [`crate::bytes::SeqByteReader`] and
+/// [`crate::bytes::ESeqByteReader`] is built on, so peeks such as [`crate::bytes::SeqByteReader::next`] are told apart from reads.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::stats::StatsReader;
+/// use std::io::Cursor;
+///
+/// let mut reader = StatsReader::new(Cursor::new(vec![69, 96, 255, 255, 0x68, 0x65, 0x6C, 0x6C, 0x6F]));
+///
+/// let _: Option<u16> = reader.next();
+/// let _: Option<i32> = reader.shift();
+/// let _ = reader.shift_string(5);
+///
+/// assert_eq!(reader.stats().bytes_read, 9);
+/// assert_eq!(reader.stats().bytes_peeked, 2);
+/// assert_eq!(reader.stats().largest_read, 5);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatsReader<R> {
+    inner: R,
+    stats: ReadStats,
+}
+
+impl<R> StatsReader<R> {
+    /// Wraps `inner` with every counter at zero.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            stats: ReadStats::default(),
+        }
+    }
+    /// Returns the counters collected so far.
+    pub fn stats(&self) -> &ReadStats {
+        &self.stats
+    }
+    /// Sets every counter back to zero.
+    pub fn reset(&mut self) {
+        self.stats = ReadStats::default();
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader. Calls made through it are not counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// Unwraps the inner reader, discarding the counters.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: SeqByteReaderCore> SeqByteReaderCore for StatsReader<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.stats.reads += 1;
+        self.inner.fill(buf)?;
+
+        self.stats.bytes_read += buf.len() as u64;
+        self.stats.largest_read = self.stats.largest_read.max(buf.len());
+        Some(())
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.stats.peeks += 1;
+        self.inner.peek_fill(buf)?;
+
+        self.stats.bytes_peeked += buf.len() as u64;
+        Some(())
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        self.stats.pos_queries += 1;
+        self.inner.pos()
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        self.stats.seeks += 1;
+        let from = self.inner.pos();
+        self.inner.set_pos(pos)?;
+
+        if let Some(from) = from {
+            self.stats.bytes_skipped += pos.saturating_sub(from);
+        }
+        Some(())
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        self.inner.end_pos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn counters_test() {
+        // A header of a magic number, a version and a length, followed by that many bytes of body and a trailer.
+        let mut bytes = b"SQB\x00".to_vec();
+        bytes.extend(3u16.to_bytes_e(true));
+        bytes.extend(5u32.to_bytes_e(true));
+        bytes.extend(b"hello");
+        bytes.extend([0; 8]);
+
+        let mut reader = StatsReader::new(Cursor::new(bytes));
+
+        assert_eq!(reader.next_slice(4).unwrap(), b"SQB\x00");
+        assert_eq!(reader.shift_slice(4).unwrap(), b"SQB\x00");
+        assert_eq!(reader.next_e::<u16>(true), Some(3));
+        assert_eq!(reader.shift_e::<u16>(true), Some(3));
+        let len = reader.shift_e::<u32>(true).unwrap() as usize;
+        assert_eq!(reader.shift_string(len).unwrap(), "hello");
+        assert_eq!(reader.shift::<u64>(), Some(0));
+        assert_eq!(reader.shift::<u8>(), None);
+
+        let stats = *reader.stats();
+        assert_eq!(stats.bytes_read, 4 + 2 + 4 + 5 + 8);
+        assert_eq!(stats.reads, 6);
+        assert_eq!(stats.largest_read, 8);
+        assert_eq!(stats.bytes_peeked, 4 + 2);
+        assert_eq!(stats.peeks, 2);
+        assert_eq!(stats.bytes_skipped, 0);
+        assert_eq!(reader.get_ref().position(), 23);
+
+        reader.reset();
+        reader.set_pos(4).unwrap();
+        reader.set_pos(10).unwrap();
+        assert_eq!(
+            *reader.stats(),
+            ReadStats {
+                bytes_skipped: 6,
+                seeks: 2,
+                ..ReadStats::default()
+            }
+        );
+        assert_eq!(
+            reader.stats().to_string(),
+            "read:    0 bytes in 0 reads (largest 0)\n\
+             peeked:  0 bytes in 0 peeks\n\
+             skipped: 6 bytes in 2 seeks, 0 position queries"
+        );
+    }
+
+    #[test]
+    fn delegation_test() {
+        let bytes: Vec<u8> = (0..32).collect();
+        let mut plain = Cursor::new(bytes.clone());
+        let mut reader = StatsReader::new(Cursor::new(bytes));
+
+        assert_eq!(reader.shift_back::<u32>(), plain.shift_back::<u32>());
+        assert_eq!(
+            reader.skip_until(&[20, 21], true),
+            plain.skip_until(&[20, 21], true)
+        );
+        assert_eq!(reader.shift_vec::<u16>(3), plain.shift_vec::<u16>(3));
+        assert_eq!(reader.shift_slice(4), plain.shift_slice(4));
+        assert_eq!(reader.pos(), plain.pos());
+        assert_eq!(reader.into_inner().position(), 32);
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::io;
+
+use super::bytes::{SeqByteReader, SeqByteWriter};
+use super::shared::ReadAt;
+
+/// The number of bytes [`LazyStringTable`] reads at once while looking for the end of a string.
+const LAZY_CHUNK: usize = 64;
+
+/// Builds a string table: a blob of NUL-terminated strings which records refer to by the offset of the string in the blob, such as the
+/// string tables of ELF files.
+///
+/// Each distinct string is stored once, so adding a string twice returns the same offset. Offsets are final as soon as they are returned,
+/// so records referring to the strings can be written before the table itself.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::strtab::{StringTable, StringTableBuilder};
+///
+/// let mut builder = StringTableBuilder::new();
+/// let text = builder.add(".text").unwrap();
+/// let data = builder.add(".data").unwrap();
+///
+/// assert_eq!(builder.add(".text"), Some(text));
+/// assert_eq!((text, data), (0, 6));
+///
+/// let mut blob = Vec::new();
+/// builder.push_to(&mut blob).unwrap();
+///
+/// let table = StringTable::from_bytes(blob);
+/// assert_eq!(table.get(data), Some(".data"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StringTableBuilder {
+    blob: Vec<u8>,
+    offsets: HashMap<String, u32>,
+}
+
+impl StringTableBuilder {
+    /// Creates a builder without any strings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns the offset of `s`, adding it to the table first if it is not there yet. Returns [`None`] if `s` contains a NUL byte, or if
+    /// the table, terminator included, would grow past [`u32::MAX`] bytes.
+    pub fn add(&mut self, s: &str) -> Option<u32> {
+        if let Some(&offset) = self.offsets.get(s) {
+            return Some(offset);
+        }
+        if s.as_bytes().contains(&0) {
+            return None;
+        }
+
+        let offset = u32::try_from(self.blob.len()).ok()?;
+        u32::try_from(self.blob.len() + s.len() + 1).ok()?;
+
+        self.blob.extend_from_slice(s.as_bytes());
+        self.blob.push(0);
+        self.offsets.insert(s.to_owned(), offset);
+
+        Some(offset)
+    }
+    /// Returns the offset of `s`, or [`None`] if it was never added.
+    pub fn offset_of(&self, s: &str) -> Option<u32> {
+        self.offsets.get(s).copied()
+    }
+    /// Returns the number of distinct strings in the table.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+    /// Returns `true` if no strings were added.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+    /// Returns the blob built so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.blob
+    }
+    /// Writes the blob to `writer`, returning its size in bytes, such as for filling in a placeholder written before the records which refer
+    /// to it. Returns [`None`] if the bytes could not be written, or without writing anything if the size does not fit a `u32`.
+    pub fn push_to<W: SeqByteWriter + ?Sized>(&self, writer: &mut W) -> Option<u32> {
+        let len = u32::try_from(self.blob.len()).ok()?;
+        writer.push_slice(&self.blob)?;
+
+        Some(len)
+    }
+}
+
+/// A string table loaded into memory, which resolves the offsets stored by records to strings.
+///
+/// See [`StringTableBuilder`] for building one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StringTable {
+    blob: Vec<u8>,
+}
+
+impl StringTable {
+    /// Creates a table from its blob.
+    pub fn from_bytes(blob: Vec<u8>) -> Self {
+        Self { blob }
+    }
+    /// Reads a table of `len` bytes from `reader`, shifting the position by `len` bytes. Returns [`None`] if there are not enough bytes to
+    /// be read.
+    pub fn shift_from<R: SeqByteReader + ?Sized>(reader: &mut R, len: usize) -> Option<Self> {
+        Some(Self::from_bytes(reader.shift_slice(len)?))
+    }
+    /// Returns the string starting at `offset`, without its terminator. Returns [`None`] if `offset` is outside the table, if no NUL byte
+    /// follows it in the table, or if the string is not valid UTF-8.
+    pub fn get(&self, offset: u32) -> Option<&str> {
+        let rest = self.blob.get(offset as usize..)?;
+        let len = rest.iter().position(|&b| b == 0)?;
+
+        std::str::from_utf8(&rest[..len]).ok()
+    }
+    /// Returns the size of the blob in bytes.
+    pub fn len(&self) -> usize {
+        self.blob.len()
+    }
+    /// Returns `true` if the blob is empty.
+    pub fn is_empty(&self) -> bool {
+        self.blob.is_empty()
+    }
+    /// Returns the blob.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.blob
+    }
+}
+
+/// A string table which is left in its source and only read a string at a time, for large tables of which only a few strings are needed.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::strtab::LazyStringTable;
+///
+/// let file = b"HEADmain\0printf\0".to_vec();
+/// let table = LazyStringTable::new(file, 4, 12);
+///
+/// assert_eq!(table.get(5).as_deref(), Some("printf"));
+/// assert_eq!(table.get(12), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LazyStringTable<S> {
+    source: S,
+    start: u64,
+    len: u64,
+}
+
+impl<S: ReadAt> LazyStringTable<S> {
+    /// Creates a table of the `len` bytes of `source` starting at `start`.
+    pub fn new(source: S, start: u64, len: u64) -> Self {
+        Self { source, start, len }
+    }
+    /// Reads the string starting at `offset`, without its terminator. Returns [`None`] in the same cases as [`StringTable::get`], and if the
+    /// source could not be read.
+    pub fn get(&self, offset: u32) -> Option<String> {
+        let mut at = offset as u64;
+        let mut bytes = Vec::new();
+        let mut chunk = [0u8; LAZY_CHUNK];
+
+        while at < self.len {
+            let want = (self.len - at).min(LAZY_CHUNK as u64) as usize;
+            let read = match self.source.read_at(&mut chunk[..want], self.start + at) {
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return None,
+            };
+            if read == 0 {
+                return None;
+            }
+
+            match chunk[..read].iter().position(|&b| b == 0) {
+                Some(end) => {
+                    bytes.extend_from_slice(&chunk[..end]);
+                    return String::from_utf8(bytes).ok();
+                }
+                None => bytes.extend_from_slice(&chunk[..read]),
+            }
+            at += read as u64;
+        }
+
+        None
+    }
+    /// Returns the size of the table in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+    /// Returns `true` if the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Unwraps the source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    const SYMBOLS: [(&str, u32); 6] = [
+        ("main", 0x1000),
+        ("printf", 0x2000),
+        ("main", 0x1010),
+        (
+            "a_rather_long_symbol_name_which_spans_more_than_one_lazy_chunk_of_reading",
+            0x3000,
+        ),
+        ("printf", 0x2010),
+        ("", 0),
+    ];
+
+    /// Writes a `u32` placeholder for the offset of the table, records of a `u32` name offset and a `u32` address, then the table.
+    fn write_file() -> Vec<u8> {
+        let mut builder = StringTableBuilder::new();
+        let mut file = Cursor::new(Vec::new());
+
+        file.push_e(0u32, false).unwrap();
+        file.push_e(SYMBOLS.len() as u32, false).unwrap();
+        for (name, address) in SYMBOLS {
+            file.push_e(builder.add(name).unwrap(), false).unwrap();
+            file.push_e(address, false).unwrap();
+        }
+        assert_eq!(builder.len(), 4);
+
+        let table_at = file.position() as u32;
+        let size = builder.push_to(&mut file).unwrap();
+        assert_eq!(size as usize, builder.as_bytes().len());
+
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.push_e(table_at, false).unwrap();
+
+        file.into_inner()
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let file = write_file();
+        let mut reader = Cursor::new(file.clone());
+
+        let table_at = reader.shift_e::<u32>(false).unwrap();
+        let count = reader.shift_e::<u32>(false).unwrap();
+        let records: Vec<(u32, u32)> = (0..count)
+            .map(|_| {
+                (
+                    reader.shift_e(false).unwrap(),
+                    reader.shift_e(false).unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(reader.position(), table_at as u64);
+        let table = StringTable::shift_from(&mut reader, file.len() - table_at as usize).unwrap();
+        let lazy = LazyStringTable::new(file, table_at as u64, table.len() as u64);
+
+        for ((name, address), (offset, read_address)) in SYMBOLS.iter().zip(&records) {
+            assert_eq!(table.get(*offset), Some(*name));
+            assert_eq!(lazy.get(*offset).as_deref(), Some(*name));
+            assert_eq!(address, read_address);
+        }
+
+        // Duplicate names share one offset.
+        assert_eq!(records[0].0, records[2].0);
+        assert_eq!(records[1].0, records[4].0);
+        assert_ne!(records[0].0, records[1].0);
+    }
+
+    #[test]
+    fn validation_test() {
+        let table = StringTable::from_bytes(b"ok\0\xFF\0tail".to_vec());
+
+        assert_eq!(table.get(0), Some("ok"));
+        assert_eq!(table.get(1), Some("k"));
+        assert_eq!(table.get(2), Some(""));
+        assert_eq!(table.get(3), None);
+        assert_eq!(table.get(5), None);
+        assert_eq!(table.get(9), None);
+        assert_eq!(table.get(100), None);
+
+        // The lazy table stops at its own end, even if the source goes on.
+        let lazy = LazyStringTable::new(b"xxok\0tail\0".to_vec(), 2, 7);
+        assert_eq!(lazy.get(0).as_deref(), Some("ok"));
+        assert_eq!(lazy.get(3), None);
+        assert_eq!(lazy.get(7), None);
+
+        let mut builder = StringTableBuilder::new();
+        assert_eq!(builder.add("nul\0inside"), None);
+        assert!(builder.is_empty());
+        assert_eq!(builder.offset_of("nul\0inside"), None);
+    }
+}
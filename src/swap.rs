@@ -0,0 +1,218 @@
+use super::traits::Endianness;
+
+macro_rules! swap_slices {
+    ($($name:ident: $t:ty, $n:literal;)*) => {
+        $(
+            #[doc = concat!("Reverses the byte order of every ", stringify!($n), " byte element of `buf` in place. Returns [`None`] without \
+                changing anything if the length of `buf` is not a multiple of ", stringify!($n), ".")]
+            pub fn $name(buf: &mut [u8]) -> Option<()> {
+                if !buf.len().is_multiple_of($n) {
+                    return None;
+                }
+
+                for chunk in buf.chunks_exact_mut($n) {
+                    let value = <$t>::from_ne_bytes(chunk.try_into().unwrap()).swap_bytes();
+                    chunk.copy_from_slice(&value.to_ne_bytes());
+                }
+
+                Some(())
+            }
+        )*
+    };
+}
+
+swap_slices! {
+    swap16_slice: u16, 2;
+    swap32_slice: u32, 4;
+    swap64_slice: u64, 8;
+    swap128_slice: u128, 16;
+}
+
+/// Returns the byte order of the host.
+pub const fn host_endianness() -> Endianness {
+    if cfg!(target_endian = "big") {
+        Endianness::Big
+    } else {
+        Endianness::Little
+    }
+}
+
+/// Converts every `size` byte element of `buf` from the byte order `source` to the byte order of the host in place, doing nothing if they
+/// are the same. `size` must be 1, 2, 4, 8 or 16. Returns [`None`] without changing anything if `size` is not one of those, or if the
+/// length of `buf` is not a multiple of it.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::swap::maybe_swap;
+/// use seqbytes::traits::Endianness;
+///
+/// let mut buf = [0x12, 0x34, 0x56, 0x78];
+/// maybe_swap(&mut buf, 2, Endianness::Big).unwrap();
+///
+/// assert_eq!(buf, [0x1234u16.to_ne_bytes(), 0x5678u16.to_ne_bytes()].concat()[..]);
+/// assert_eq!(maybe_swap(&mut buf, 3, Endianness::Big), None);
+/// ```
+pub fn maybe_swap(buf: &mut [u8], size: usize, source: Endianness) -> Option<()> {
+    if !matches!(size, 1 | 2 | 4 | 8 | 16) || !buf.len().is_multiple_of(size) {
+        return None;
+    }
+    if source.is_big() == host_endianness().is_big() {
+        return Some(());
+    }
+
+    match size {
+        1 => Some(()),
+        2 => swap16_slice(buf),
+        4 => swap32_slice(buf),
+        8 => swap64_slice(buf),
+        _ => swap128_slice(buf),
+    }
+}
+
+/// Reverses the byte order of every element of `values` in place, such as the samples returned by
+/// [`crate::bytes::SeqByteReader::shift_pod_slice`]. Returns [`None`] without changing anything if the size of `T` is not 1, 2, 4, 8 or 16,
+/// since the elements of larger types such as structs or arrays are not single values.
+#[cfg(feature = "bytemuck")]
+pub fn swap_pod_slice<T: bytemuck::Pod>(values: &mut [T]) -> Option<()> {
+    let size = std::mem::size_of::<T>();
+    let buf: &mut [u8] = bytemuck::cast_slice_mut(values);
+
+    match size {
+        1 => Some(()),
+        2 => swap16_slice(buf),
+        4 => swap32_slice(buf),
+        8 => swap64_slice(buf),
+        16 => swap128_slice(buf),
+        _ => None,
+    }
+}
+
+/// Converts every element of `values` from the byte order `source` to the byte order of the host in place, doing nothing if they are the
+/// same. Returns [`None`] without changing anything if the size of `T` is not 1, 2, 4, 8 or 16.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::swap::maybe_swap_pod;
+/// use seqbytes::traits::Endianness;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new([1.5f32.to_bytes_e(true), (-2.0f32).to_bytes_e(true)].concat());
+///
+/// let mut samples = cursor.shift_pod_slice::<f32>(2).unwrap();
+/// maybe_swap_pod(&mut samples, Endianness::Big).unwrap();
+///
+/// assert_eq!(samples, [1.5, -2.0]);
+/// ```
+#[cfg(feature = "bytemuck")]
+pub fn maybe_swap_pod<T: bytemuck::Pod>(values: &mut [T], source: Endianness) -> Option<()> {
+    maybe_swap(
+        bytemuck::cast_slice_mut(values),
+        std::mem::size_of::<T>(),
+        source,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    fn big_endian_bytes<U: EndianNumber>(values: &[U]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_bytes_e(true)).collect()
+    }
+
+    #[test]
+    fn matches_shift_e_test() {
+        let words: Vec<u16> = (0..37u16).map(|i| i.wrapping_mul(0x1F3D)).collect();
+        let dwords: Vec<i32> = (0..29i32).map(|i| i.wrapping_mul(-0x0F1E_2D3C)).collect();
+        let floats: Vec<f64> = (0..19).map(|i| i as f64 * -1.25e-3).collect();
+        let wide: Vec<u128> = (0..5u128).map(|i| i << 100 | i).collect();
+
+        let mut buf = big_endian_bytes(&words);
+        let expected: Vec<u16> = Cursor::new(buf.clone())
+            .shift_vec_e(words.len(), true)
+            .unwrap();
+        maybe_swap(&mut buf, 2, Endianness::Big).unwrap();
+        let swapped: Vec<u16> = buf
+            .chunks(2)
+            .map(|c| u16::from_ne_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(swapped, expected);
+        assert_eq!(swapped, words);
+
+        let mut buf = big_endian_bytes(&dwords);
+        maybe_swap(&mut buf, 4, Endianness::Big).unwrap();
+        let swapped: Vec<i32> = buf
+            .chunks(4)
+            .map(|c| i32::from_ne_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(swapped, dwords);
+
+        let mut buf = big_endian_bytes(&floats);
+        maybe_swap(&mut buf, 8, Endianness::Big).unwrap();
+        let swapped: Vec<f64> = buf
+            .chunks(8)
+            .map(|c| f64::from_ne_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(swapped, floats);
+
+        let mut buf = big_endian_bytes(&wide);
+        swap128_slice(&mut buf).unwrap();
+        assert_eq!(
+            buf,
+            wide.iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>()
+        );
+
+        let mut buf = floats
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect::<Vec<_>>();
+        maybe_swap(&mut buf, 8, host_endianness()).unwrap();
+        let unchanged: Vec<f64> = buf
+            .chunks(8)
+            .map(|c| f64::from_ne_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(unchanged, floats);
+    }
+
+    #[test]
+    fn uneven_length_test() {
+        let mut buf = vec![1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(swap16_slice(&mut buf), None);
+        assert_eq!(swap32_slice(&mut buf), None);
+        assert_eq!(swap64_slice(&mut buf), None);
+        assert_eq!(maybe_swap(&mut buf, 2, Endianness::Little), None);
+        assert_eq!(maybe_swap(&mut buf, 7, Endianness::Little), None);
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7]);
+
+        assert_eq!(maybe_swap(&mut buf, 1, Endianness::Big), Some(()));
+        assert_eq!(swap16_slice(&mut buf[..6]), Some(()));
+        assert_eq!(buf, [2, 1, 4, 3, 6, 5, 7]);
+        assert_eq!(swap16_slice(&mut []), Some(()));
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn pod_test() {
+        let values: Vec<u32> = (0..64).map(|i| i * 0x0101_0103).collect();
+        let mut cursor = Cursor::new(big_endian_bytes(&values));
+
+        let mut read = cursor.shift_pod_slice::<u32>(values.len()).unwrap();
+        maybe_swap_pod(&mut read, Endianness::Big).unwrap();
+        assert_eq!(read, values);
+
+        swap_pod_slice(&mut read).unwrap();
+        assert_eq!(
+            read,
+            values.iter().map(|v| v.swap_bytes()).collect::<Vec<_>>()
+        );
+        assert_eq!(swap_pod_slice(&mut [[0u8; 3]; 4]), None);
+        assert_eq!(swap_pod_slice(&mut [1u8, 2]), Some(()));
+    }
+}
@@ -0,0 +1,759 @@
+use std::fmt::Debug;
+use std::io::Cursor;
+
+use super::bytes::{
+    ESeqByteReader, ESeqByteWriter, SeqByteReader, SeqByteReaderCore, SeqByteWriter,
+};
+use super::resume::ResumableParser;
+use super::traits::*;
+
+/// Byte written before values in the reader and writer passes, so that they never start at offset zero.
+const PREFIX: u8 = 0xA5;
+
+/// Returns `true` if `value` equals itself, which is not the case for NaN and values containing it.
+#[allow(clippy::eq_op)]
+fn reflexive<T: PartialEq>(value: &T) -> bool {
+    value == value
+}
+
+/// Checks that `actual` is the value of `expected`, encoded as `bytes`. Values which do not equal themselves, such as NaN, are compared by
+/// their bytes alone, so that NaN payloads must survive.
+fn check<T: PartialEq + Debug>(
+    actual: Option<T>,
+    expected: &T,
+    bytes: &[u8],
+    encode: impl Fn(&T) -> Vec<u8>,
+    what: &str,
+) {
+    let actual = actual.unwrap_or_else(|| panic!("{} of {:?} returned None", what, expected));
+
+    assert_eq!(
+        encode(&actual),
+        bytes,
+        "{} of {:?} changed the bytes",
+        what,
+        expected
+    );
+    if reflexive(expected) {
+        assert_eq!(&actual, expected, "{} changed the value", what);
+    }
+}
+
+/// Asserts that `value` survives a round trip through [`SizedNumber`], both through the slice conversions and through a [`Cursor`]-based
+/// writer and reader pass. Checks that [`SizedNumber::size`] was not overridden to disagree with [`SizedNumber::SIZE`], that the encoding is that many bytes long,
+/// that [`SizedNumber::to_bytes_into`] and [`SizedNumber::to_bytes_small`] produce the same bytes, that slices of any other length are rejected, and that peeking, shifting,
+/// shifting backwards and reading a [`Vec`] all agree.
+///
+/// Values which do not equal themselves, such as NaN, are compared by their bytes instead, so payloads must be kept bit for bit.
+///
+/// # Panics
+///
+/// Panics with a description of the first check that fails.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::testutil::assert_roundtrip;
+///
+/// assert_roundtrip(-40891i32);
+/// assert_roundtrip(f64::NAN);
+/// assert_roundtrip([1u16, 2, 3]);
+/// ```
+pub fn assert_roundtrip<T: SizedNumber + PartialEq + Debug>(value: T) {
+    let bytes = value.to_bytes();
+    let size = T::SIZE;
+    let encode = |v: &T| v.to_bytes();
+
+    assert_eq!(
+        T::size(),
+        size,
+        "size() of {} differs from SIZE, so it was overridden",
+        std::any::type_name::<T>()
+    );
+    assert_eq!(
+        bytes.len(),
+        size,
+        "to_bytes of {:?} has the wrong length",
+        value
+    );
+    let mut into = vec![0u8; size + 1];
+    assert_eq!(
+        value.to_bytes_into(&mut into),
+        Some(size),
+        "to_bytes_into of {:?} wrote the wrong number of bytes",
+        value
+    );
+    assert_eq!(
+        into[..size],
+        bytes,
+        "to_bytes_into of {:?} differs from to_bytes",
+        value
+    );
+    if size > 0 {
+        assert!(
+            value.to_bytes_into(&mut into[..size - 1]).is_none(),
+            "to_bytes_into accepted a slice shorter than {} bytes",
+            size
+        );
+    }
+    assert_eq!(
+        *value.to_bytes_small(),
+        bytes,
+        "to_bytes_small of {:?} differs from to_bytes",
+        value
+    );
+    check(T::from_bytes(&bytes), &value, &bytes, encode, "from_bytes");
+    check(
+        T::from_bytes_checked(&bytes).ok(),
+        &value,
+        &bytes,
+        encode,
+        "from_bytes_checked",
+    );
+    assert_eq!(
+        T::from_bytes_checked(&[bytes.as_slice(), &[0]].concat()).err(),
+        Some(DecodeError::WrongLength {
+            expected: size,
+            got: size + 1
+        }),
+        "from_bytes_checked did not report the wrong length"
+    );
+    assert!(
+        T::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_none(),
+        "from_bytes accepted a slice longer than {} bytes",
+        size
+    );
+    if size > 0 {
+        assert!(
+            T::from_bytes(&bytes[..size - 1]).is_none(),
+            "from_bytes accepted a slice shorter than {} bytes",
+            size
+        );
+    }
+
+    let mut cursor = Cursor::new(vec![PREFIX]);
+    cursor.set_position(1);
+    for _ in 0..2 {
+        cursor
+            .push(T::from_bytes(&bytes).unwrap())
+            .unwrap_or_else(|| panic!("push of {:?} failed", value));
+    }
+    assert_eq!(cursor.get_ref()[1..], [bytes.as_slice(), &bytes].concat());
+
+    cursor.set_position(1);
+    check(cursor.next::<T>(), &value, &bytes, encode, "next");
+    check(cursor.shift::<T>(), &value, &bytes, encode, "shift");
+    check(cursor.shift::<T>(), &value, &bytes, encode, "shift");
+    assert_eq!(cursor.position(), 1 + 2 * size as u64);
+    check(
+        cursor.shift_back::<T>(),
+        &value,
+        &bytes,
+        encode,
+        "shift_back",
+    );
+    assert_eq!(cursor.position(), 1 + size as u64);
+
+    cursor.set_position(1);
+    let values = cursor
+        .shift_vec::<T>(2)
+        .unwrap_or_else(|| panic!("shift_vec of {:?} returned None", value));
+    assert_eq!(values.len(), 2);
+    for v in values {
+        check(Some(v), &value, &bytes, encode, "shift_vec");
+    }
+
+    assert!(cursor.shift::<T>().is_none() || size == 0);
+}
+
+/// Asserts that `value` survives a round trip through [`EndianNumber`] in both byte orders, as [`assert_roundtrip`] does through
+/// [`SizedNumber`], and also that the little endian encoding is the same as [`SizedNumber::to_bytes`]. Types whose canonical encoding is big
+/// endian, such as IP addresses, do not meet the latter, and are checked with [`assert_roundtrip`] instead.
+///
+/// # Panics
+///
+/// Panics with a description of the first check that fails.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::testutil::assert_roundtrip_e;
+///
+/// assert_roundtrip_e(u128::MAX - 1);
+/// assert_roundtrip_e(-0.0f32);
+/// ```
+pub fn assert_roundtrip_e<T: EndianNumber + PartialEq + Debug>(value: T) {
+    let size = T::SIZE;
+
+    assert_eq!(
+        value.to_bytes_e(false),
+        value.to_bytes(),
+        "to_bytes_e of {:?} in little endian differs from to_bytes",
+        value
+    );
+
+    for bigendian in [false, true] {
+        let bytes = value.to_bytes_e(bigendian);
+        let encode = |v: &T| v.to_bytes_e(bigendian);
+
+        assert_eq!(
+            bytes.len(),
+            size,
+            "to_bytes_e of {:?} has the wrong length",
+            value
+        );
+        let mut into = vec![0u8; size + 1];
+        assert_eq!(
+            value.to_bytes_into_e(&mut into, bigendian),
+            Some(size),
+            "to_bytes_into_e of {:?} wrote the wrong number of bytes",
+            value
+        );
+        assert_eq!(
+            into[..size],
+            bytes,
+            "to_bytes_into_e of {:?} differs from to_bytes_e",
+            value
+        );
+        if size > 0 {
+            assert!(
+                value
+                    .to_bytes_into_e(&mut into[..size - 1], bigendian)
+                    .is_none(),
+                "to_bytes_into_e accepted a slice shorter than {} bytes",
+                size
+            );
+        }
+        assert_eq!(
+            *value.to_bytes_small_e(bigendian),
+            bytes,
+            "to_bytes_small_e of {:?} differs from to_bytes_e",
+            value
+        );
+        check(
+            T::from_bytes_e(&bytes, bigendian),
+            &value,
+            &bytes,
+            encode,
+            "from_bytes_e",
+        );
+        check(
+            T::from_bytes_checked_e(&bytes, bigendian).ok(),
+            &value,
+            &bytes,
+            encode,
+            "from_bytes_checked_e",
+        );
+        assert!(
+            T::from_bytes_e(&[bytes.as_slice(), &[0]].concat(), bigendian).is_none(),
+            "from_bytes_e accepted a slice longer than {} bytes",
+            size
+        );
+        if size > 0 {
+            assert!(
+                T::from_bytes_e(&bytes[..size - 1], bigendian).is_none(),
+                "from_bytes_e accepted a slice shorter than {} bytes",
+                size
+            );
+        }
+
+        let mut cursor = Cursor::new(vec![PREFIX]);
+        cursor.set_position(1);
+        for _ in 0..2 {
+            cursor
+                .push_e(T::from_bytes_e(&bytes, bigendian).unwrap(), bigendian)
+                .unwrap_or_else(|| panic!("push_e of {:?} failed", value));
+        }
+        assert_eq!(cursor.get_ref()[1..], [bytes.as_slice(), &bytes].concat());
+
+        cursor.set_position(1);
+        check(
+            cursor.next_e::<T>(bigendian),
+            &value,
+            &bytes,
+            encode,
+            "next_e",
+        );
+        check(
+            cursor.shift_e::<T>(bigendian),
+            &value,
+            &bytes,
+            encode,
+            "shift_e",
+        );
+        check(
+            cursor.shift_back_e::<T>(bigendian),
+            &value,
+            &bytes,
+            encode,
+            "shift_back_e",
+        );
+
+        cursor.set_position(1);
+        let values = cursor
+            .shift_vec_e::<T>(2, bigendian)
+            .unwrap_or_else(|| panic!("shift_vec_e of {:?} returned None", value));
+        assert_eq!(values.len(), 2);
+        for v in values {
+            check(Some(v), &value, &bytes, encode, "shift_vec_e");
+        }
+    }
+}
+
+/// Asserts that `value`, encoded with the specified endianness and fed to a [`ResumableParser`] in two pieces split at `split` bytes, is
+/// only read once both pieces have arrived, and then unchanged. `split` is taken modulo one more than the size of `T`, so any number may be
+/// passed.
+///
+/// # Panics
+///
+/// Panics if the value is read before all of its bytes have been fed, or if it changes.
+pub fn assert_split_e<T: EndianNumber + PartialEq + Debug>(
+    value: &T,
+    bigendian: bool,
+    split: usize,
+) {
+    let bytes = value.to_bytes_e(bigendian);
+    if bytes.is_empty() {
+        return;
+    }
+
+    let (head, tail) = bytes.split_at(split % (bytes.len() + 1));
+    let mut parser = ResumableParser::new(|r| r.shift_e::<T>(bigendian));
+
+    let early = parser.feed(head).expect("the parser rejected a value");
+    assert!(
+        early.is_empty() || tail.is_empty(),
+        "{:?} was read from {} of its {} bytes",
+        value,
+        head.len(),
+        bytes.len()
+    );
+
+    let mut values = early;
+    values.extend(parser.feed(tail).expect("the parser rejected a value"));
+    assert_eq!(values.len(), 1);
+    check(
+        values.pop(),
+        value,
+        &bytes,
+        |v| v.to_bytes_e(bigendian),
+        "ResumableParser",
+    );
+}
+
+macro_rules! check_scalars {
+    ($($t:ty: [$($value:expr),* $(,)?];)*) => {
+        $(
+            for value in [$($value),*] {
+                assert_roundtrip(value);
+                assert_roundtrip_e(value);
+
+                let mut reversed = value.to_bytes_e(false);
+                reversed.reverse();
+                assert_eq!(
+                    value.to_bytes_e(true),
+                    reversed,
+                    "big endian bytes of {:?} are not the reversed little endian bytes",
+                    value
+                );
+
+                for split in 0..=<$t>::SIZE {
+                    assert_split_e(&value, true, split);
+                }
+            }
+        )*
+    };
+}
+
+/// Runs [`assert_roundtrip`] and [`assert_roundtrip_e`] over the boundary values of every implementation of [`SizedNumber`] and
+/// [`EndianNumber`] in this crate: zero, one, minus one, the minimum and maximum, and for floats negative zero, the infinities, the smallest
+/// positive values and NaNs with several payloads. Scalars are also checked to encode big endian as the reverse of little endian, and to be
+/// read intact when split across feeds of a [`ResumableParser`].
+///
+/// New implementations of the traits are expected to be added here.
+///
+/// # Panics
+///
+/// Panics with a description of the first check that fails.
+pub fn check_all_primitives() {
+    check_scalars! {
+        bool: [false, true];
+        char: ['\0', 'A', '\u{E9}', '\u{D7FF}', '\u{E000}', '\u{1F600}', char::MAX];
+        u8: [0, 1, 0x7F, 0x80, u8::MAX];
+        i8: [0, 1, -1, i8::MIN, i8::MAX];
+        u16: [0, 1, 0x1234, u16::MAX];
+        i16: [0, 1, -1, i16::MIN, i16::MAX];
+        u32: [0, 1, 0x1234_5678, u32::MAX];
+        i32: [0, 1, -1, i32::MIN, i32::MAX];
+        u64: [0, 1, 0x0123_4567_89AB_CDEF, u64::MAX];
+        i64: [0, 1, -1, i64::MIN, i64::MAX];
+        u128: [0, 1, 1 << 100, u128::MAX];
+        i128: [0, 1, -1, i128::MIN, i128::MAX];
+        usize: [0, 1, usize::MAX];
+        isize: [0, 1, -1, isize::MIN, isize::MAX];
+        f32: [
+            0.0, -0.0, 1.0, -1.0, f32::MIN, f32::MAX, f32::MIN_POSITIVE, f32::from_bits(1), f32::INFINITY, f32::NEG_INFINITY, f32::NAN,
+            -f32::NAN, f32::from_bits(0x7FC0_0001), f32::from_bits(0x7F80_0001), f32::from_bits(0xFFFF_FFFF),
+        ];
+        f64: [
+            0.0, -0.0, 1.0, -1.0, f64::MIN, f64::MAX, f64::MIN_POSITIVE, f64::from_bits(1), f64::INFINITY, f64::NEG_INFINITY, f64::NAN,
+            -f64::NAN, f64::from_bits(0x7FF8_0000_0000_0001), f64::from_bits(0x7FF0_0000_0000_0001), f64::from_bits(u64::MAX),
+        ];
+    }
+
+    for value in [0, 1, 0x80, u8::MAX] {
+        assert_roundtrip_e(Unorm8(value));
+    }
+    for value in [0, 1, 0x8000, u16::MAX] {
+        assert_roundtrip_e(Unorm16(value));
+    }
+    for value in [0, 1, -1, i8::MIN, i8::MAX] {
+        assert_roundtrip_e(Snorm8(value));
+    }
+    for value in [0, 1, -1, i16::MIN, i16::MAX] {
+        assert_roundtrip_e(Snorm16(value));
+    }
+
+    macro_rules! check_nonzero {
+        ($($nonzero:ident: [$($value:expr),*];)*) => {
+            $(
+                assert_roundtrip_e(None::<std::num::$nonzero>);
+                for value in [$($value),*] {
+                    assert_roundtrip_e(std::num::$nonzero::new(value));
+
+                    let nonzero = std::num::$nonzero::new(value).unwrap();
+                    assert_roundtrip(nonzero);
+                    assert_roundtrip_e(nonzero);
+                }
+            )*
+        };
+    }
+    check_nonzero! {
+        NonZeroU8: [1, u8::MAX];
+        NonZeroI8: [1, -1, i8::MIN, i8::MAX];
+        NonZeroU16: [1, u16::MAX];
+        NonZeroI16: [1, -1, i16::MIN, i16::MAX];
+        NonZeroU32: [1, u32::MAX];
+        NonZeroI32: [1, -1, i32::MIN, i32::MAX];
+        NonZeroU64: [1, u64::MAX];
+        NonZeroI64: [1, -1, i64::MIN, i64::MAX];
+        NonZeroU128: [1, u128::MAX];
+        NonZeroI128: [1, -1, i128::MIN, i128::MAX];
+        NonZeroUsize: [1, usize::MAX];
+        NonZeroIsize: [1, -1, isize::MIN, isize::MAX];
+    }
+
+    assert_roundtrip([0u8; 0]);
+    assert_roundtrip([u8::MAX; 3]);
+    assert_roundtrip_e([0u8; 0]);
+    assert_roundtrip_e(*b"\x7fELF");
+    assert_roundtrip_e([i16::MIN, -1, 0, i16::MAX]);
+    assert_roundtrip_e([f32::NAN, -0.0, f32::INFINITY]);
+    assert_roundtrip_e([[u64::MAX, 0], [1, u64::MAX - 1]]);
+    assert_roundtrip([i16::MIN, -1, 0, i16::MAX]);
+    assert_roundtrip([f32::NAN, -0.0, f32::INFINITY]);
+    assert_roundtrip([[u64::MAX, 0], [1, u64::MAX - 1]]);
+    assert_roundtrip([Unorm8(0), Unorm8(u8::MAX)]);
+    assert_roundtrip((u8::MAX,));
+    assert_roundtrip((i16::MIN, u64::MAX, f32::NAN));
+    assert_roundtrip_e((u16::MAX - 1, 0u16, -0.0f32));
+    assert_roundtrip_e((true, 'é', [i32::MIN, i32::MAX], (u128::MAX, -1i8)));
+    assert_roundtrip_e((1u8, 2u16, 3u32, 4u64, 5i8, 6i16, 7i32, 8i64));
+    for value in [U24::MIN, U24::MAX, U24::wrapping_from(0x80_0000)] {
+        assert_roundtrip(value);
+        assert_roundtrip_e(value);
+    }
+    for value in [I24::MIN, I24::MAX, I24::wrapping_from(-1), I24::default()] {
+        assert_roundtrip(value);
+        assert_roundtrip_e(value);
+    }
+    for value in [U48::MIN, U48::MAX, U48::wrapping_from(1 << 47)] {
+        assert_roundtrip(value);
+        assert_roundtrip_e(value);
+    }
+    for value in [I48::MIN, I48::MAX, I48::wrapping_from(-1), I48::default()] {
+        assert_roundtrip(value);
+        assert_roundtrip_e(value);
+    }
+    assert_roundtrip_e(std::time::Duration::ZERO);
+    assert_roundtrip_e(std::time::Duration::MAX);
+    assert_roundtrip_e(std::time::Duration::new(1, 999_999_999));
+    assert_roundtrip_e(std::time::UNIX_EPOCH);
+    assert_roundtrip_e(std::time::SystemTime::now());
+    assert_roundtrip(std::net::Ipv4Addr::BROADCAST);
+    assert_roundtrip(std::net::Ipv6Addr::LOCALHOST);
+    assert_roundtrip(std::net::SocketAddrV4::new(
+        std::net::Ipv4Addr::LOCALHOST,
+        80,
+    ));
+    assert_roundtrip(std::net::SocketAddrV6::new(
+        std::net::Ipv6Addr::LOCALHOST,
+        443,
+        0,
+        0,
+    ));
+    assert_roundtrip_e(Guid::from_u128(0xC12A7328_F81F_11D2_BA4B_00A0C93EC93B));
+    assert_roundtrip_e(());
+    assert_roundtrip_e(std::marker::PhantomData::<u64>);
+    assert_roundtrip_e(std::num::Wrapping(u32::MAX));
+    assert_roundtrip_e(std::num::Saturating(i16::MIN));
+    assert_roundtrip(std::num::Wrapping(-0.0f64));
+
+    #[cfg(feature = "num-complex")]
+    {
+        use num_complex::Complex;
+
+        for value in [
+            Complex::new(0.0f32, -0.0),
+            Complex::new(f32::MAX, f32::MIN),
+            Complex::new(f32::NAN, 1.0),
+        ] {
+            assert_roundtrip_e(value);
+        }
+        for value in [Complex::new(i16::MIN, i16::MAX), Complex::new(-1, 0)] {
+            assert_roundtrip_e(value);
+        }
+        assert_roundtrip_e(Complex::new(f64::NEG_INFINITY, f64::MIN_POSITIVE));
+    }
+
+    #[cfg(feature = "half")]
+    for value in [
+        half::f16::ZERO,
+        half::f16::NEG_ZERO,
+        half::f16::MIN_POSITIVE_SUBNORMAL,
+        half::f16::MAX,
+        half::f16::NEG_INFINITY,
+        half::f16::NAN,
+    ] {
+        assert_roundtrip(value);
+        assert_roundtrip_e(value);
+    }
+    #[cfg(feature = "half")]
+    for value in [
+        half::bf16::ZERO,
+        half::bf16::NEG_ZERO,
+        half::bf16::MIN_POSITIVE_SUBNORMAL,
+        half::bf16::MAX,
+        half::bf16::NEG_INFINITY,
+        half::bf16::NAN,
+    ] {
+        assert_roundtrip(value);
+        assert_roundtrip_e(value);
+    }
+
+    #[cfg(feature = "uuid")]
+    for value in [
+        uuid::Uuid::nil(),
+        uuid::Uuid::max(),
+        uuid::Uuid::from_u128(0x0011_2233_4455_6677_8899_AABB_CCDD_EEFF),
+    ] {
+        assert_roundtrip(value);
+    }
+}
+
+/// Runs [`assert_roundtrip`] on `cases` values generated by `strategy`, shrinking to the smallest failing value on failure.
+///
+/// # Panics
+///
+/// Panics with the smallest failing value if any check fails.
+#[cfg(feature = "proptest")]
+pub fn proptest_roundtrip<S>(strategy: S, cases: u32)
+where
+    S: proptest::strategy::Strategy,
+    S::Value: SizedNumber + PartialEq + Debug,
+{
+    run_proptest(strategy, cases, |value, _, _| assert_roundtrip(value));
+}
+
+/// Runs [`assert_roundtrip_e`] on `cases` values generated by `strategy`, along with [`assert_split_e`] at a random split in a random byte
+/// order, shrinking to the smallest failing value on failure.
+///
+/// # Panics
+///
+/// Panics with the smallest failing value if any check fails.
+///
+/// # Examples
+///
+/// ```
+/// use proptest::prelude::*;
+/// use seqbytes::testutil::proptest_roundtrip_e;
+/// use seqbytes::traits::Snorm16;
+///
+/// proptest_roundtrip_e(any::<f64>(), 64);
+/// proptest_roundtrip_e(any::<i16>().prop_map(Snorm16), 64);
+/// ```
+#[cfg(feature = "proptest")]
+pub fn proptest_roundtrip_e<S>(strategy: S, cases: u32)
+where
+    S: proptest::strategy::Strategy,
+    S::Value: EndianNumber + PartialEq + Debug,
+{
+    run_proptest(strategy, cases, |value, split, bigendian| {
+        assert_split_e(&value, bigendian, split);
+        assert_roundtrip_e(value);
+    });
+}
+
+#[cfg(feature = "proptest")]
+fn run_proptest<S, F>(strategy: S, cases: u32, test: F)
+where
+    S: proptest::strategy::Strategy,
+    S::Value: Debug,
+    F: Fn(S::Value, usize, bool),
+{
+    use proptest::prelude::any;
+    use proptest::test_runner::{Config, TestRunner};
+
+    let mut runner = TestRunner::new(Config {
+        cases,
+        ..Config::default()
+    });
+    let result = runner.run(
+        &(strategy, any::<usize>(), any::<bool>()),
+        |(value, split, bigendian)| {
+            test(value, split, bigendian);
+            Ok(())
+        },
+    );
+
+    if let Err(e) = result {
+        panic!("{}", e);
+    }
+}
+
+/// A reader which hides where the source of `R` ends, such as to check a reader on a pipe or socket whose end is only found by reading to
+/// it. Every method of [`SeqByteReaderCore`] but [`SeqByteReaderCore::end_pos`] is forwarded to `R`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::testutil::Unbounded;
+/// use std::io::Cursor;
+///
+/// let mut pipe = Unbounded(Cursor::new(vec![1, 2, 3]));
+///
+/// assert_eq!(pipe.end_pos(), None);
+/// assert_eq!(pipe.available(3), Some(true));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Unbounded<R>(pub R);
+
+impl<R: SeqByteReaderCore> SeqByteReaderCore for Unbounded<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.0.fill(buf)
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.0.peek_fill(buf)
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        self.0.pos()
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        self.0.set_pos(pos)
+    }
+
+    fn try_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.0.try_fill(buf)
+    }
+
+    fn try_peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.0.try_peek_fill(buf)
+    }
+
+    fn step_back(&mut self, amount: u64) -> Option<()> {
+        self.0.step_back(amount)
+    }
+}
+
+/// A reader which cannot report or set its position, such as a stream without any seeking, to check what readers report when the position
+/// is unknown. Only [`SeqByteReaderCore::fill`] is forwarded to `R`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::testutil::NoPos;
+/// use std::io::Cursor;
+///
+/// let mut stream = NoPos(Cursor::new(vec![1, 0]));
+///
+/// assert_eq!(stream.pos(), None);
+/// assert_eq!(stream.shift::<u16>(), Some(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NoPos<R>(pub R);
+
+impl<R: SeqByteReaderCore> SeqByteReaderCore for NoPos<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        self.0.fill(buf)
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        None
+    }
+
+    fn set_pos(&mut self, _: u64) -> Option<()> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_primitives_test() {
+        check_all_primitives();
+    }
+
+    /// A type whose big endian encoding forgets to swap its bytes.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Unswapped(u16);
+
+    impl SizedNumber for Unswapped {
+        fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            u16::from_bytes(bytes).map(Self)
+        }
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.to_bytes()
+        }
+    }
+    impl EndianNumber for Unswapped {
+        fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+            u16::from_bytes_e(bytes, bigendian).map(Self)
+        }
+        fn to_bytes_e(&self, _: bool) -> Vec<u8> {
+            self.0.to_bytes()
+        }
+    }
+
+    #[test]
+    fn catches_discrepancy_test() {
+        assert_roundtrip(Unswapped(0x1234));
+        assert_roundtrip_e(Unswapped(0x1111));
+
+        let result = std::panic::catch_unwind(|| assert_roundtrip_e(Unswapped(0x1234)));
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn proptest_test() {
+        use proptest::prelude::*;
+
+        proptest_roundtrip_e(any::<u8>(), 64);
+        proptest_roundtrip_e(any::<i32>(), 256);
+        proptest_roundtrip_e(any::<u64>(), 256);
+        proptest_roundtrip_e(any::<i128>(), 256);
+        proptest_roundtrip_e(any::<f32>(), 256);
+        proptest_roundtrip_e(any::<u32>().prop_map(f32::from_bits), 256);
+        proptest_roundtrip_e(any::<u64>().prop_map(f64::from_bits), 256);
+        proptest_roundtrip_e(any::<u16>().prop_map(Unorm16), 64);
+        proptest_roundtrip_e(any::<u32>().prop_map(std::num::NonZeroU32::new), 64);
+        proptest_roundtrip(any::<[i16; 5]>(), 64);
+    }
+}
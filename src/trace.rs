@@ -0,0 +1,274 @@
+use std::fmt::{Debug, Write};
+
+use super::bytes::{ESeqByteReader, SeqByteReader, SeqByteReaderCore};
+use super::traits::{EndianNumber, SizedNumber};
+
+/// The most bytes of a single read kept in a [`TraceEntry`].
+pub const MAX_TRACE_BYTES: usize = 16;
+
+/// The kind of call recorded in a [`TraceEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceKind {
+    /// Bytes were read, shifting the position.
+    Read,
+    /// Bytes were read without shifting the position.
+    Peek,
+    /// The position was set.
+    Seek,
+}
+
+/// A call made through a [`TraceReader`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceEntry {
+    /// The kind of call.
+    pub kind: TraceKind,
+    /// The absolute offset the call started at, or the offset the position was set to for [`TraceKind::Seek`].
+    pub offset: u64,
+    /// The number of bytes requested, or zero for [`TraceKind::Seek`].
+    pub len: usize,
+    /// The first [`MAX_TRACE_BYTES`] bytes read, or none if the call failed.
+    pub bytes: Vec<u8>,
+    /// The name of the type read, if the call was made through [`TraceReader::shift_traced`] or [`TraceReader::shift_traced_e`].
+    pub type_name: Option<&'static str>,
+    /// The value read, formatted with [`Debug`], if the call was made through [`TraceReader::shift_traced`] or
+    /// [`TraceReader::shift_traced_e`] and succeeded.
+    pub value: Option<String>,
+    /// Whether the call succeeded.
+    pub ok: bool,
+}
+
+/// A reader which records every call going through it, for producing an annotated dump of a parse, such as of a file a parser rejects.
+///
+/// Every read, peek and change of the position is recorded with its offset, length and the first [`MAX_TRACE_BYTES`] bytes read. Values
+/// read through [`TraceReader::shift_traced`] and [`TraceReader::shift_traced_e`] are also recorded with their type and decoded value.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::trace::TraceReader;
+/// use std::io::Cursor;
+///
+/// let mut reader = TraceReader::new(Cursor::new(vec![69, 96, 255, 255, 0x68, 0x65, 0x6C, 0x6C, 0x6F]));
+///
+/// reader.shift_traced::<i32>().unwrap();
+/// reader.shift_string(5).unwrap();
+/// reader.shift_traced::<u8>();
+///
+/// assert_eq!(
+///     reader.render(),
+///     "00000000  read     4  i32    45 60 ff ff  = -40891\n\
+///      00000004  read     5  -      68 65 6c 6c 6f\n\
+///      00000009  read     1  u8     FAILED\n\
+///      00000009  seek     0  -\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TraceReader<R> {
+    inner: R,
+    entries: Vec<TraceEntry>,
+}
+
+impl<R> TraceReader<R> {
+    /// Wraps `inner` with an empty trace.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            entries: Vec::new(),
+        }
+    }
+    /// Returns the calls recorded so far, in order.
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
+    }
+    /// Clears the recorded calls.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Unwraps the inner reader, discarding the trace.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+    /// Renders the recorded calls as a report of one line per call: the offset, the kind of call, the length, the type name, a hexdump of
+    /// the bytes read and the decoded value.
+    pub fn render(&self) -> String {
+        let mut report = String::new();
+
+        for entry in &self.entries {
+            let kind = match entry.kind {
+                TraceKind::Read => "read",
+                TraceKind::Peek => "peek",
+                TraceKind::Seek => "seek",
+            };
+            let mut line = format!(
+                "{:08x}  {:<5} {:>4}  {:<6}",
+                entry.offset,
+                kind,
+                entry.len,
+                entry.type_name.unwrap_or("-")
+            );
+
+            if !entry.ok {
+                line.push_str(" FAILED");
+            } else if entry.kind != TraceKind::Seek {
+                for b in &entry.bytes {
+                    let _ = write!(line, " {:02x}", b);
+                }
+                if entry.bytes.len() < entry.len {
+                    line.push_str(" ..");
+                }
+            }
+            if let Some(value) = &entry.value {
+                let _ = write!(line, "  = {}", value);
+            }
+
+            report.push_str(line.trim_end());
+            report.push('\n');
+        }
+
+        report
+    }
+}
+
+impl<R: SeqByteReaderCore> TraceReader<R> {
+    /// Reads the next `U`, shifting the position, and records its type and value along with the read.
+    pub fn shift_traced<U: SizedNumber + Debug>(&mut self) -> Option<U> {
+        self.traced::<U>(|r| r.shift::<U>())
+    }
+    /// Reads the next `U` with the specified endianness, shifting the position, and records its type and value along with the read.
+    pub fn shift_traced_e<U: EndianNumber + Debug>(&mut self, bigendian: bool) -> Option<U> {
+        self.traced::<U>(|r| r.shift_e::<U>(bigendian))
+    }
+    fn traced<U: Debug>(&mut self, read: impl FnOnce(&mut Self) -> Option<U>) -> Option<U> {
+        let first = self.entries.len();
+        let value = read(self);
+
+        if let Some(entry) = self.entries[first..]
+            .iter_mut()
+            .rfind(|entry| entry.kind == TraceKind::Read)
+        {
+            entry.type_name = Some(std::any::type_name::<U>());
+            entry.value = value.as_ref().map(|value| format!("{:?}", value));
+        }
+
+        value
+    }
+    fn record(&mut self, kind: TraceKind, offset: Option<u64>, buf: &[u8], ok: bool) {
+        self.entries.push(TraceEntry {
+            kind,
+            offset: offset.unwrap_or(0),
+            len: buf.len(),
+            bytes: if ok {
+                buf[..buf.len().min(MAX_TRACE_BYTES)].to_vec()
+            } else {
+                Vec::new()
+            },
+            type_name: None,
+            value: None,
+            ok,
+        });
+    }
+}
+
+impl<R: SeqByteReaderCore> SeqByteReaderCore for TraceReader<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let offset = self.inner.pos();
+        let result = self.inner.fill(buf);
+
+        self.record(TraceKind::Read, offset, buf, result.is_some());
+        result
+    }
+
+    fn peek_fill(&mut self, buf: &mut [u8]) -> Option<()> {
+        let offset = self.inner.pos();
+        let result = self.inner.peek_fill(buf);
+
+        self.record(TraceKind::Peek, offset, buf, result.is_some());
+        result
+    }
+
+    fn pos(&mut self) -> Option<u64> {
+        self.inner.pos()
+    }
+
+    fn set_pos(&mut self, pos: u64) -> Option<()> {
+        let result = self.inner.set_pos(pos);
+
+        self.record(TraceKind::Seek, Some(pos), &[], result.is_some());
+        result
+    }
+
+    fn end_pos(&mut self) -> Option<u64> {
+        self.inner.end_pos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn trace_test() {
+        // A header of a magic number, a big endian version and a count of two `u16` entries, the second of which is missing after padding.
+        let mut bytes = b"TRC1".to_vec();
+        bytes.extend(3u16.to_bytes_e(true));
+        bytes.push(2);
+        bytes.extend(0x1234u16.to_bytes());
+        bytes.extend([0u8; 20]);
+
+        let mut reader = TraceReader::new(Cursor::new(bytes));
+        assert_eq!(reader.next_slice(4).unwrap(), b"TRC1");
+        reader.set_pos(4).unwrap();
+        assert_eq!(reader.shift_traced_e::<u16>(true), Some(3));
+        assert_eq!(reader.shift_traced::<u8>(), Some(2));
+        assert_eq!(reader.shift_traced::<u16>(), Some(0x1234));
+        assert_eq!(reader.shift_slice(20).unwrap().len(), 20);
+        assert_eq!(reader.shift_traced::<u16>(), None);
+
+        let summary: Vec<_> = reader
+            .entries()
+            .iter()
+            .map(|e| {
+                (
+                    e.kind,
+                    e.offset,
+                    e.len,
+                    e.type_name,
+                    e.value.as_deref(),
+                    e.ok,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (TraceKind::Peek, 0, 4, None, None, true),
+                (TraceKind::Seek, 4, 0, None, None, true),
+                (TraceKind::Read, 4, 2, Some("u16"), Some("3"), true),
+                (TraceKind::Read, 6, 1, Some("u8"), Some("2"), true),
+                (TraceKind::Read, 7, 2, Some("u16"), Some("4660"), true),
+                (TraceKind::Read, 9, 20, None, None, true),
+                (TraceKind::Read, 29, 2, Some("u16"), None, false),
+                (TraceKind::Seek, 29, 0, None, None, true),
+            ]
+        );
+        assert_eq!(reader.entries()[0].bytes, b"TRC1");
+        assert_eq!(reader.entries()[5].bytes, [0; MAX_TRACE_BYTES]);
+        assert!(reader.entries()[6].bytes.is_empty());
+
+        let report = reader.render();
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "00000000  peek     4  -      54 52 43 31");
+        assert_eq!(lines[1], "00000004  seek     0  -");
+        assert_eq!(lines[4], "00000007  read     2  u16    34 12  = 4660");
+        assert!(lines[5].ends_with(" 00 00 .."));
+        assert_eq!(lines[6], "0000001d  read     2  u16    FAILED");
+        assert_eq!(lines[7], "0000001d  seek     0  -");
+    }
+}
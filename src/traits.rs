@@ -3,9 +3,32 @@ pub trait SizedNumber
 where
     Self: Sized,
 {
-    /// Returns the size of `Self` in bytes.
+    /// The size of `Self` in bytes, usable in const contexts.
     ///
-    /// If unimplemented, calls [`std::mem::size_of`] [`crate::traits::SizedNumber`] on `Self`.
+    /// If unimplemented, is [`std::mem::size_of`] `Self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use seqbytes::traits::SizedNumber;
+    ///
+    /// const RECORD: usize = u32::SIZE + <[u16; 3]>::SIZE;
+    ///
+    /// assert_eq!(RECORD, 10);
+    /// assert_eq!(bool::SIZE, 1);
+    /// ```
+    const SIZE: usize = std::mem::size_of::<Self>();
+    /// Whether [`SizedNumber::to_bytes_into`] and [`EndianNumber::to_bytes_into_e`] write the bytes in place rather than copying those of
+    /// [`SizedNumber::to_bytes`]. Writers and [`SizedNumber::to_bytes_small`] only serialize through a stack buffer for types which set
+    /// it, and call [`SizedNumber::to_bytes`] otherwise.
+    ///
+    /// If unimplemented, is `false`. Set it along with overriding both methods.
+    const WRITES_IN_PLACE: bool = false;
+
+    /// Returns the size of `Self` in bytes, which is [`SizedNumber::SIZE`].
+    ///
+    /// [`SizedNumber::SIZE`] is the only source of truth for the size of a type: no reader or writer of the crate calls this, so
+    /// overriding it is deprecated and has no effect on how values are read or written. Implementations set [`SizedNumber::SIZE`] instead.
     ///
     /// # Example
     ///
@@ -22,7 +45,7 @@ where
     /// assert_eq!(i128::size(), 16);
     /// ```
     fn size() -> usize {
-        std::mem::size_of::<Self>()
+        Self::SIZE
     }
 
     /// Converts the slice to `Self`. Will return [`None`] if the slice length is not equal to the size of the type.
@@ -53,6 +76,94 @@ where
     /// assert_eq!(c, 22.4);
     /// ```
     fn to_bytes(&self) -> Vec<u8>;
+    /// Writes the byte representation of `self` into the start of `out`, returning the number of bytes written, which is
+    /// [`SizedNumber::SIZE`]. Returns [`None`] if `out` is shorter than that, in which case nothing is written.
+    ///
+    /// If unimplemented, copies the bytes of [`SizedNumber::to_bytes`], returning [`None`] if they are not [`SizedNumber::SIZE`] bytes
+    /// long. The primitive implementations write in place without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use seqbytes::traits::*;
+    ///
+    /// let mut out = [0u8; 6];
+    ///
+    /// assert_eq!(0x0102u16.to_bytes_into(&mut out), Some(2));
+    /// assert_eq!((7u8, 3u16).to_bytes_into(&mut out[2..]), Some(3));
+    /// assert_eq!(out, [0x02, 0x01, 7, 3, 0, 0]);
+    /// assert_eq!(1u32.to_bytes_into(&mut out[3..]), None);
+    /// ```
+    fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+        copy_into(&self.to_bytes(), out, Self::SIZE)
+    }
+    /// Converts `self` to equivalent byte representation as [`SizedNumber::to_bytes`] does, but holds the bytes of types up to
+    /// [`ByteBuf::INLINE`] bytes long inline rather than on the heap.
+    ///
+    /// Built on [`SizedNumber::to_bytes_into`], so it does not allocate for any primitive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use seqbytes::traits::*;
+    ///
+    /// let bytes = 0x0102_0304u32.to_bytes_small();
+    ///
+    /// assert_eq!(*bytes, [4, 3, 2, 1]);
+    /// assert!(bytes.is_inline());
+    /// ```
+    fn to_bytes_small(&self) -> ByteBuf {
+        ByteBuf::build(
+            Self::SIZE,
+            Self::WRITES_IN_PLACE,
+            |out| self.to_bytes_into(out),
+            || self.to_bytes(),
+        )
+    }
+    /// Converts the first [`SizedNumber::SIZE`] bytes of the slice to `Self`, returning it with the rest of the slice, so values can be
+    /// decoded one after another from a slice without a reader. Will return [`None`] if the slice is shorter than the size of the type, or
+    /// if [`SizedNumber::from_bytes`] does. Use [`SizedNumber::from_bytes`] to require the slice to be exactly the size of the type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use seqbytes::traits::*;
+    ///
+    /// let bytes = [1, 0, 2, 0, 0, 0, 9];
+    /// let (a, rest) = u16::from_bytes_prefix(&bytes).unwrap();
+    /// let (b, rest) = u32::from_bytes_prefix(rest).unwrap();
+    ///
+    /// assert_eq!((a, b, rest), (1, 2, &[9][..]));
+    /// assert_eq!(u16::from_bytes_prefix(rest), None);
+    /// ```
+    fn from_bytes_prefix(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        let (head, tail) = bytes.split_at_checked(Self::SIZE)?;
+        Some((Self::from_bytes(head)?, tail))
+    }
+    /// Converts the slice to `Self` as [`SizedNumber::from_bytes`] does, returning a [`DecodeError`] which tells why it failed.
+    ///
+    /// If unimplemented, checks the length of the slice, and returns a generic [`DecodeError::InvalidValue`] if
+    /// [`SizedNumber::from_bytes`] rejects the bytes. Types which validate their bytes, such as [`bool`] and [`char`], describe why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use seqbytes::traits::*;
+    ///
+    /// assert_eq!(bool::from_bytes_checked(&[1]), Ok(true));
+    /// assert_eq!(
+    ///     u32::from_bytes_checked(&[1, 2]),
+    ///     Err(DecodeError::WrongLength { expected: 4, got: 2 })
+    /// );
+    /// assert_eq!(
+    ///     char::from_bytes_checked(&[0x00, 0xD8, 0, 0]),
+    ///     Err(DecodeError::InvalidValue("surrogate code point"))
+    /// );
+    /// ```
+    fn from_bytes_checked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_len(bytes, Self::SIZE)?;
+        Self::from_bytes(bytes).ok_or(DecodeError::InvalidValue(REJECTED))
+    }
 }
 
 /// A trait representing a sized type which can be converted to and from bytes with a specific endianness.
@@ -61,497 +172,1926 @@ pub trait EndianNumber: SizedNumber {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self>;
     /// Converts `self` to equivalent byte representation in the specified endianness.
     fn to_bytes_e(&self, bigendian: bool) -> Vec<u8>;
+    /// Writes the byte representation of `self` in the specified endianness into the start of `out`, as
+    /// [`SizedNumber::to_bytes_into`] does. If unimplemented, copies the bytes of [`EndianNumber::to_bytes_e`].
+    fn to_bytes_into_e(&self, out: &mut [u8], bigendian: bool) -> Option<usize> {
+        copy_into(&self.to_bytes_e(bigendian), out, Self::SIZE)
+    }
+    /// Converts `self` to equivalent byte representation with the specified endianness, holding short representations inline as
+    /// [`SizedNumber::to_bytes_small`] does.
+    fn to_bytes_small_e(&self, bigendian: bool) -> ByteBuf {
+        ByteBuf::build(
+            Self::SIZE,
+            Self::WRITES_IN_PLACE,
+            |out| self.to_bytes_into_e(out, bigendian),
+            || self.to_bytes_e(bigendian),
+        )
+    }
+    /// Converts the first [`SizedNumber::SIZE`] bytes of the slice to `Self` with the specified endianness, returning it with the rest of
+    /// the slice, as [`SizedNumber::from_bytes_prefix`] does.
+    fn from_bytes_prefix_e(bytes: &[u8], bigendian: bool) -> Option<(Self, &[u8])> {
+        let (head, tail) = bytes.split_at_checked(Self::SIZE)?;
+        Some((Self::from_bytes_e(head, bigendian)?, tail))
+    }
+    /// Converts the slice to `Self` with the specified endianness as [`EndianNumber::from_bytes_e`] does, returning a [`DecodeError`]
+    /// which tells why it failed, as [`SizedNumber::from_bytes_checked`] does.
+    fn from_bytes_checked_e(bytes: &[u8], bigendian: bool) -> Result<Self, DecodeError> {
+        check_len(bytes, Self::SIZE)?;
+        Self::from_bytes_e(bytes, bigendian).ok_or(DecodeError::InvalidValue(REJECTED))
+    }
+    /// Converts the slice to `Self` with the byte order `endianness`, as [`EndianNumber::from_bytes_e`] does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use seqbytes::traits::*;
+    ///
+    /// assert_eq!(u16::from_bytes_endian(&[1, 2], Endianness::Big), Some(0x0102));
+    /// assert_eq!(u16::from_bytes_endian(&[1, 2], Endianness::Native), Some(u16::from_ne_bytes([1, 2])));
+    /// ```
+    fn from_bytes_endian(bytes: &[u8], endianness: Endianness) -> Option<Self> {
+        Self::from_bytes_e(bytes, endianness.is_big())
+    }
+    /// Converts `self` to equivalent byte representation in the byte order `endianness`, as [`EndianNumber::to_bytes_e`] does.
+    fn to_bytes_endian(&self, endianness: Endianness) -> Vec<u8> {
+        self.to_bytes_e(endianness.is_big())
+    }
+    /// Converts a slice to an instance of `Self` in the byte order of the target, as the `from_ne_bytes` functions of the primitives do.
+    ///
+    /// Only the byte order is native, not the width: [`usize`] and [`isize`] still take their fixed 8 bytes, so on a 32-bit target they do
+    /// not match the in-memory layout of the type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::traits::*;
+    ///
+    /// assert_eq!(u32::from_bytes_ne(&[1, 2, 3, 4]), Some(u32::from_ne_bytes([1, 2, 3, 4])));
+    /// ```
+    fn from_bytes_ne(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_e(bytes, cfg!(target_endian = "big"))
+    }
+    /// Converts `self` to equivalent byte representation in the byte order of the target, as the `to_ne_bytes` functions of the primitives
+    /// do. Pointer-sized types keep their 8-byte width, as for [`EndianNumber::from_bytes_ne`].
+    fn to_bytes_ne(&self) -> Vec<u8> {
+        self.to_bytes_e(cfg!(target_endian = "big"))
+    }
 }
 
-impl SizedNumber for u8 {
-    fn size() -> usize {
-        1 // Size of byte is 1 byte, duhhh
-    }
+/// The error returned by [`SizedNumber::from_bytes_checked`] and [`EndianNumber::from_bytes_checked_e`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The slice is not the size of the type.
+    WrongLength {
+        /// The size of the type, in bytes.
+        expected: usize,
+        /// The length of the slice, in bytes.
+        got: usize,
+    },
+    /// The bytes are the right length but do not hold a value of the type, for the contained reason.
+    InvalidValue(&'static str),
+}
 
-    fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 1 {
-            return None;
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::WrongLength { expected, got } => {
+                write!(f, "expected {} bytes, got {}", expected, got)
+            }
+            DecodeError::InvalidValue(reason) => write!(f, "invalid value: {}", reason),
         }
-
-        Some(bytes[0])
     }
+}
 
-    fn to_bytes(&self) -> Vec<u8> {
-        vec![*self]
+impl std::error::Error for DecodeError {}
+
+/// The reason given by the default [`SizedNumber::from_bytes_checked`] when [`SizedNumber::from_bytes`] rejects bytes of the right length.
+const REJECTED: &str = "rejected by from_bytes";
+
+/// Returns [`DecodeError::WrongLength`] unless `bytes` is `size` bytes long.
+fn check_len(bytes: &[u8], size: usize) -> Result<(), DecodeError> {
+    if bytes.len() != size {
+        return Err(DecodeError::WrongLength {
+            expected: size,
+            got: bytes.len(),
+        });
     }
+
+    Ok(())
 }
-impl EndianNumber for u8 {
-    fn from_bytes_e(bytes: &[u8], _bigendian: bool) -> Option<Self> {
-        Self::from_bytes(bytes)
-    }
 
-    fn to_bytes_e(&self, _bigendian: bool) -> Vec<u8> {
-        self.to_bytes()
+/// Copies `bytes`, which are the `size` bytes of a value, into the start of `out`, returning `size`. Returns [`None`] if `out` is shorter,
+/// or `bytes` is not `size` bytes long.
+fn copy_into(bytes: &[u8], out: &mut [u8], size: usize) -> Option<usize> {
+    if bytes.len() != size {
+        return None;
     }
+
+    out.get_mut(..size)?.copy_from_slice(bytes);
+    Some(size)
 }
 
-impl SizedNumber for i8 {
-    fn size() -> usize {
-        1
+/// Copies the bytes of a primitive into the start of `out`, returning their number. Returns [`None`] if `out` is shorter.
+fn write_array<const N: usize>(bytes: [u8; N], out: &mut [u8]) -> Option<usize> {
+    out.get_mut(..N)?.copy_from_slice(&bytes);
+    Some(N)
+}
+
+/// The bytes of a value returned by [`SizedNumber::to_bytes_small`], held inline if there are at most [`ByteBuf::INLINE`] of them and
+/// on the heap otherwise. Dereferences to a slice.
+#[derive(Debug, Clone)]
+pub struct ByteBuf(ByteBufRepr);
+
+#[derive(Debug, Clone)]
+enum ByteBufRepr {
+    Inline {
+        bytes: [u8; ByteBuf::INLINE],
+        len: u8,
+    },
+    Heap(Vec<u8>),
+}
+
+impl ByteBuf {
+    /// The largest number of bytes held inline.
+    pub const INLINE: usize = 16;
+
+    /// Returns `true` if the bytes are held inline rather than on the heap.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, ByteBufRepr::Inline { .. })
     }
-    fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 1 {
-            return None;
+    /// Returns the bytes as a slice.
+    pub fn as_slice(&self) -> &[u8] {
+        match &self.0 {
+            ByteBufRepr::Inline { bytes, len } => &bytes[..*len as usize],
+            ByteBufRepr::Heap(bytes) => bytes,
+        }
+    }
+    /// Converts the buffer into a [`Vec`], which allocates if the bytes are held inline.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.0 {
+            ByteBufRepr::Inline { .. } => self.as_slice().to_vec(),
+            ByteBufRepr::Heap(bytes) => bytes,
+        }
+    }
+    /// Writes `size` bytes inline with `write` if the type writes in place, they fit, and `write` writes exactly `size` of them, and
+    /// calls `fallback` for them otherwise.
+    fn build(
+        size: usize,
+        in_place: bool,
+        write: impl FnOnce(&mut [u8]) -> Option<usize>,
+        fallback: impl FnOnce() -> Vec<u8>,
+    ) -> Self {
+        if in_place && size <= Self::INLINE {
+            let mut bytes = [0; Self::INLINE];
+            if write(&mut bytes[..size]) == Some(size) {
+                return Self(ByteBufRepr::Inline {
+                    bytes,
+                    len: size as u8,
+                });
+            }
         }
 
-        Some(bytes[0] as i8)
+        Self(ByteBufRepr::Heap(fallback()))
     }
+}
 
-    fn to_bytes(&self) -> Vec<u8> {
-        vec![(*self) as u8]
+impl std::ops::Deref for ByteBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
     }
 }
-impl EndianNumber for i8 {
-    fn from_bytes_e(bytes: &[u8], _bigendian: bool) -> Option<Self> {
-        Self::from_bytes(bytes)
+
+impl AsRef<[u8]> for ByteBuf {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
     }
+}
 
-    fn to_bytes_e(&self, _bigendian: bool) -> Vec<u8> {
-        self.to_bytes()
+impl PartialEq for ByteBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
     }
 }
 
-impl SizedNumber for u16 {
-    fn size() -> usize {
-        2
+impl Eq for ByteBuf {}
+
+impl From<ByteBuf> for Vec<u8> {
+    fn from(bytes: ByteBuf) -> Self {
+        bytes.into_vec()
     }
+}
 
-    fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 2 {
-            return None;
+/// The byte order of a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// The least significant byte comes first.
+    Little,
+    /// The most significant byte comes first.
+    Big,
+    /// The byte order of the host, which is [`Endianness::Big`] or [`Endianness::Little`] depending on `target_endian`.
+    Native,
+}
+
+impl Endianness {
+    /// Returns `true` for [`Endianness::Big`], and for [`Endianness::Native`] on big-endian hosts, as passed to the `bigendian` parameters
+    /// of this crate.
+    pub const fn is_big(self) -> bool {
+        match self {
+            Endianness::Little => false,
+            Endianness::Big => true,
+            Endianness::Native => cfg!(target_endian = "big"),
         }
+    }
+}
 
-        Some(Self::from_le_bytes([bytes[0], bytes[1]]))
+/// A byte order chosen at compile time, as a type parameter, so generic parsing code is written once and instantiated for each order
+/// without branching on it at run time. Implemented by the markers [`LE`] and [`BE`].
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+///
+/// fn header<O: ByteOrder>(bytes: &[u8]) -> Option<u16> {
+///     O::read(&bytes[..2])
+/// }
+///
+/// assert_eq!(header::<BE>(&[1, 2]), Some(0x0102));
+/// assert_eq!(header::<LE>(&[1, 2]), Some(0x0201));
+/// ```
+pub trait ByteOrder {
+    /// The byte order the type stands for.
+    const ENDIANNESS: Endianness;
+
+    /// Converts the slice to a `U` in this byte order, as [`EndianNumber::from_bytes_e`] does.
+    fn read<U: EndianNumber>(bytes: &[u8]) -> Option<U> {
+        U::from_bytes_e(bytes, Self::ENDIANNESS.is_big())
+    }
+    /// Writes `value` in this byte order into the start of `out`, as [`EndianNumber::to_bytes_into_e`] does.
+    fn write<U: EndianNumber>(value: &U, out: &mut [u8]) -> Option<usize> {
+        value.to_bytes_into_e(out, Self::ENDIANNESS.is_big())
+    }
+}
+
+/// The little-endian [`ByteOrder`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LE;
+
+impl ByteOrder for LE {
+    const ENDIANNESS: Endianness = Endianness::Little;
+}
+
+/// The big-endian [`ByteOrder`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BE;
+
+impl ByteOrder for BE {
+    const ENDIANNESS: Endianness = Endianness::Big;
+}
+
+/// An enum stored as its discriminant, a number of type [`TaggedEnum::Repr`], which is converted to the enum through [`TryFrom`].
+/// Read with [`SeqByteReader::shift_enum`] and [`ESeqByteReader::shift_enum_e`].
+///
+/// [`SeqByteReader::shift_enum`]: crate::bytes::SeqByteReader::shift_enum
+/// [`ESeqByteReader::shift_enum_e`]: crate::bytes::ESeqByteReader::shift_enum_e
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Kind {
+///     File = 1,
+///     Dir = 2,
+/// }
+///
+/// impl TryFrom<u8> for Kind {
+///     type Error = u8;
+///
+///     fn try_from(tag: u8) -> Result<Self, u8> {
+///         match tag {
+///             1 => Ok(Kind::File),
+///             2 => Ok(Kind::Dir),
+///             _ => Err(tag),
+///         }
+///     }
+/// }
+///
+/// impl TaggedEnum for Kind {
+///     type Repr = u8;
+/// }
+///
+/// let mut cursor = Cursor::new(vec![2, 5]);
+///
+/// assert_eq!(cursor.shift_enum::<Kind>(), Some(Kind::Dir));
+/// assert_eq!(cursor.shift_enum::<Kind>(), None);
+/// assert_eq!(cursor.position(), 1);
+/// ```
+pub trait TaggedEnum: TryFrom<Self::Repr> {
+    /// The number the discriminant is stored as.
+    type Repr: EndianNumber;
+}
+
+macro_rules! primitives {
+    ($($t:ident: $n:literal => $to_le:ident, $to_be:ident, $from_le:ident, $from_be:ident;)*) => {
+        $(
+            #[doc = concat!("Converts a [`", stringify!($t), "`] to its little-endian bytes. Usable in `const` items, unlike [`SizedNumber::to_bytes`].")]
+            pub const fn $to_le(value: $t) -> [u8; $n] {
+                value.to_le_bytes()
+            }
+            #[doc = concat!("Converts a [`", stringify!($t), "`] to its big-endian bytes. Usable in `const` items, unlike [`EndianNumber::to_bytes_e`].")]
+            pub const fn $to_be(value: $t) -> [u8; $n] {
+                value.to_be_bytes()
+            }
+            #[doc = concat!("Converts little-endian bytes to a [`", stringify!($t), "`]. Usable in `const` items, unlike [`SizedNumber::from_bytes`].")]
+            pub const fn $from_le(bytes: [u8; $n]) -> $t {
+                $t::from_le_bytes(bytes)
+            }
+            #[doc = concat!("Converts big-endian bytes to a [`", stringify!($t), "`]. Usable in `const` items, unlike [`EndianNumber::from_bytes_e`].")]
+            pub const fn $from_be(bytes: [u8; $n]) -> $t {
+                $t::from_be_bytes(bytes)
+            }
+
+            impl PrimitiveBytes for $t {
+                type Array = [u8; $n];
+
+                fn from_le_array(bytes: [u8; $n]) -> Self {
+                    $from_le(bytes)
+                }
+                fn from_be_array(bytes: [u8; $n]) -> Self {
+                    $from_be(bytes)
+                }
+                fn to_le_array(&self) -> [u8; $n] {
+                    $to_le(*self)
+                }
+                fn to_be_array(&self) -> [u8; $n] {
+                    $to_be(*self)
+                }
+            }
+        )*
+    };
+}
+
+primitives! {
+    u8: 1 => u8_to_le_array, u8_to_be_array, u8_from_le_array, u8_from_be_array;
+    i8: 1 => i8_to_le_array, i8_to_be_array, i8_from_le_array, i8_from_be_array;
+    u16: 2 => u16_to_le_array, u16_to_be_array, u16_from_le_array, u16_from_be_array;
+    i16: 2 => i16_to_le_array, i16_to_be_array, i16_from_le_array, i16_from_be_array;
+    u32: 4 => u32_to_le_array, u32_to_be_array, u32_from_le_array, u32_from_be_array;
+    i32: 4 => i32_to_le_array, i32_to_be_array, i32_from_le_array, i32_from_be_array;
+    f32: 4 => f32_to_le_array, f32_to_be_array, f32_from_le_array, f32_from_be_array;
+    u64: 8 => u64_to_le_array, u64_to_be_array, u64_from_le_array, u64_from_be_array;
+    i64: 8 => i64_to_le_array, i64_to_be_array, i64_from_le_array, i64_from_be_array;
+    f64: 8 => f64_to_le_array, f64_to_be_array, f64_from_le_array, f64_from_be_array;
+    u128: 16 => u128_to_le_array, u128_to_be_array, u128_from_le_array, u128_from_be_array;
+    i128: 16 => i128_to_le_array, i128_to_be_array, i128_from_le_array, i128_from_be_array;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl<const N: usize> Sealed for [u8; N] {}
+}
+
+/// The bytes of a [`PrimitiveBytes`] type. Only implemented for `[u8; N]`, so the size of the type is always the number of bytes it
+/// converts to, rather than the size of whatever holds them.
+///
+/// # Examples
+///
+/// ```compile_fail
+/// use seqbytes::traits::*;
+///
+/// struct Blob(Vec<u8>);
+///
+/// impl PrimitiveBytes for Blob {
+///     type Array = Vec<u8>;
+///
+///     fn from_le_array(bytes: Vec<u8>) -> Self {
+///         Blob(bytes)
+///     }
+///     fn from_be_array(bytes: Vec<u8>) -> Self {
+///         Blob(bytes)
+///     }
+///     fn to_le_array(&self) -> Vec<u8> {
+///         self.0.clone()
+///     }
+///     fn to_be_array(&self) -> Vec<u8> {
+///         self.0.clone()
+///     }
+/// }
+/// ```
+pub trait ByteArray: sealed::Sealed + AsRef<[u8]> + for<'a> TryFrom<&'a [u8]> {
+    /// The number of bytes.
+    const LEN: usize;
+}
+
+impl<const N: usize> ByteArray for [u8; N] {
+    const LEN: usize = N;
+}
+
+/// A type whose bytes are those of a primitive number, converted through its `to_le_bytes`, `to_be_bytes`, `from_le_bytes` and
+/// `from_be_bytes` functions. Every type implementing it implements [`SizedNumber`] and [`EndianNumber`], little-endian by default, so
+/// a transparent numeric type only needs these four functions.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::traits::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Meters(u32);
+///
+/// impl PrimitiveBytes for Meters {
+///     type Array = [u8; 4];
+///
+///     fn from_le_array(bytes: [u8; 4]) -> Self {
+///         Meters(u32::from_le_bytes(bytes))
+///     }
+///     fn from_be_array(bytes: [u8; 4]) -> Self {
+///         Meters(u32::from_be_bytes(bytes))
+///     }
+///     fn to_le_array(&self) -> [u8; 4] {
+///         self.0.to_le_bytes()
+///     }
+///     fn to_be_array(&self) -> [u8; 4] {
+///         self.0.to_be_bytes()
+///     }
+/// }
+///
+/// assert_eq!(Meters::SIZE, 4);
+/// assert_eq!(Meters(258).to_bytes_e(true), [0, 0, 1, 2]);
+/// assert_eq!(Meters::from_bytes(&[2, 1, 0, 0]), Some(Meters(258)));
+/// ```
+pub trait PrimitiveBytes: Sized {
+    /// The bytes of the type, which is `[u8; N]` for a type `N` bytes long.
+    type Array: ByteArray;
+
+    /// Converts little-endian bytes to `Self`.
+    fn from_le_array(bytes: Self::Array) -> Self;
+    /// Converts big-endian bytes to `Self`.
+    fn from_be_array(bytes: Self::Array) -> Self;
+    /// Converts `self` to its little-endian bytes.
+    fn to_le_array(&self) -> Self::Array;
+    /// Converts `self` to its big-endian bytes.
+    fn to_be_array(&self) -> Self::Array;
+}
+
+impl<T: PrimitiveBytes> SizedNumber for T {
+    const SIZE: usize = T::Array::LEN;
+    const WRITES_IN_PLACE: bool = true;
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(T::from_le_array(T::Array::try_from(bytes).ok()?))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        self.to_le_array().as_ref().to_vec()
+    }
+
+    fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+        copy_into(self.to_le_array().as_ref(), out, Self::SIZE)
     }
 }
-impl EndianNumber for u16 {
+impl<T: PrimitiveBytes> EndianNumber for T {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 2 {
-            return None;
+        let array = T::Array::try_from(bytes).ok()?;
+
+        if bigendian {
+            return Some(T::from_be_array(array));
         }
 
-        let slice = [bytes[0], bytes[1]];
+        Some(T::from_le_array(array))
+    }
 
+    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
         if bigendian {
-            return Some(Self::from_be_bytes(slice));
+            return self.to_be_array().as_ref().to_vec();
         }
 
-        Some(Self::from_le_bytes(slice))
+        self.to_bytes()
     }
 
-    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+    fn to_bytes_into_e(&self, out: &mut [u8], bigendian: bool) -> Option<usize> {
         if bigendian {
-            return self.to_be_bytes().to_vec();
+            return copy_into(self.to_be_array().as_ref(), out, Self::SIZE);
         }
 
-        return self.to_le_bytes().to_vec();
+        self.to_bytes_into(out)
     }
 }
 
-impl SizedNumber for i16 {
-    fn size() -> usize {
-        2
-    }
+/// Takes one byte, where `0` is `false` and `1` is `true`. Conversion from bytes returns [`None`] for any other byte, since a flag byte
+/// holding anything else is a sign of corruption rather than another way of writing `true`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(vec![1, 0, 2]);
+///
+/// assert_eq!(cursor.shift::<bool>(), Some(true));
+/// assert_eq!(cursor.shift::<bool>(), Some(false));
+/// assert_eq!(cursor.shift::<bool>(), None);
+/// ```
+impl SizedNumber for bool {
+    const SIZE: usize = 1;
+    const WRITES_IN_PLACE: bool = true;
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 2 {
-            return None;
+        match bytes {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
         }
-
-        Some(Self::from_le_bytes([bytes[0], bytes[1]]))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        vec![*self as u8]
     }
-}
-impl EndianNumber for i16 {
-    fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 2 {
-            return None;
-        }
 
-        let slice = [bytes[0], bytes[1]];
+    fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+        write_array([*self as u8], out)
+    }
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
+    fn from_bytes_checked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_len(bytes, Self::SIZE)?;
+        Self::from_bytes(bytes).ok_or(DecodeError::InvalidValue("flag byte is neither 0 nor 1"))
+    }
+}
+impl EndianNumber for bool {
+    fn from_bytes_e(bytes: &[u8], _: bool) -> Option<Self> {
+        Self::from_bytes(bytes)
+    }
 
-        Some(Self::from_le_bytes(slice))
+    fn from_bytes_checked_e(bytes: &[u8], _: bool) -> Result<Self, DecodeError> {
+        Self::from_bytes_checked(bytes)
     }
 
-    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
-        }
+    fn to_bytes_e(&self, _: bool) -> Vec<u8> {
+        self.to_bytes()
+    }
 
-        return self.to_le_bytes().to_vec();
+    fn to_bytes_into_e(&self, out: &mut [u8], _bigendian: bool) -> Option<usize> {
+        self.to_bytes_into(out)
     }
 }
 
-impl SizedNumber for u32 {
-    fn size() -> usize {
-        4
-    }
+/// Takes four bytes, holding the Unicode scalar value as a [`u32`], such as a code point of UTF-32 text. Conversion from bytes returns
+/// [`None`] if the value is not a scalar value, which is a surrogate (`0xD800` to `0xDFFF`) or anything above `0x10FFFF`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(vec![0x41, 0, 0, 0, 0x00, 0xD8, 0, 0]);
+///
+/// assert_eq!(cursor.shift_e::<char>(false), Some('A'));
+/// assert_eq!(cursor.shift_e::<char>(false), None);
+/// ```
+impl SizedNumber for char {
+    const SIZE: usize = 4;
+    const WRITES_IN_PLACE: bool = true;
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 4 {
-            return None;
-        }
-
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3],
-        ]))
+        char::from_u32(u32::from_bytes(bytes)?)
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        (*self as u32).to_bytes()
     }
-}
-impl EndianNumber for u32 {
-    fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 4 {
-            return None;
-        }
-
-        let slice = [bytes[0], bytes[1], bytes[2], bytes[3]];
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
+    fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+        (*self as u32).to_bytes_into(out)
+    }
 
-        Some(Self::from_le_bytes(slice))
+    fn from_bytes_checked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        char_from_scalar(u32::from_bytes_checked(bytes)?)
+    }
+}
+impl EndianNumber for char {
+    fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+        char::from_u32(u32::from_bytes_e(bytes, bigendian)?)
     }
 
     fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
-        }
+        (*self as u32).to_bytes_e(bigendian)
+    }
 
-        return self.to_le_bytes().to_vec();
+    fn to_bytes_into_e(&self, out: &mut [u8], bigendian: bool) -> Option<usize> {
+        (*self as u32).to_bytes_into_e(out, bigendian)
     }
-}
 
-impl SizedNumber for i32 {
-    fn size() -> usize {
-        4
+    fn from_bytes_checked_e(bytes: &[u8], bigendian: bool) -> Result<Self, DecodeError> {
+        char_from_scalar(u32::from_bytes_checked_e(bytes, bigendian)?)
     }
+}
+
+/// Converts a Unicode scalar value to a [`char`], telling surrogates apart from values above `0x10FFFF`.
+fn char_from_scalar(value: u32) -> Result<char, DecodeError> {
+    char::from_u32(value).ok_or(DecodeError::InvalidValue(if value > 0x10FFFF {
+        "code point above U+10FFFF"
+    } else {
+        "surrogate code point"
+    }))
+}
+
+/// Takes twelve bytes: the whole seconds as a [`u64`] followed by the nanoseconds as a [`u32`], each converted with the specified
+/// endianness. Conversion from bytes returns [`None`] if the nanoseconds are a whole second or more.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+/// use std::time::Duration;
+///
+/// let mut cursor = Cursor::new(Vec::new());
+/// cursor.push_e(Duration::new(90, 500), true).unwrap();
+/// cursor.push_e(5u64, true).unwrap();
+/// cursor.push_e(1_000_000_000u32, true).unwrap();
+/// cursor.set_position(0);
+///
+/// assert_eq!(cursor.shift_e::<Duration>(true), Some(Duration::new(90, 500)));
+/// assert_eq!(cursor.shift_e::<Duration>(true), None);
+/// ```
+impl SizedNumber for std::time::Duration {
+    const SIZE: usize = 12;
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 4 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3],
-        ]))
+        let (secs, nanos) = bytes.split_at(8);
+        duration_from_parts(u64::from_bytes(secs)?, u32::from_bytes(nanos)?)
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        let mut bytes = self.as_secs().to_bytes();
+        bytes.extend(self.subsec_nanos().to_bytes());
+
+        bytes
+    }
+
+    fn from_bytes_checked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes_checked_e(bytes, false)
     }
 }
-impl EndianNumber for i32 {
+impl EndianNumber for std::time::Duration {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 4 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        let slice = [bytes[0], bytes[1], bytes[2], bytes[3]];
-
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
-
-        Some(Self::from_le_bytes(slice))
+        let (secs, nanos) = bytes.split_at(8);
+        duration_from_parts(
+            u64::from_bytes_e(secs, bigendian)?,
+            u32::from_bytes_e(nanos, bigendian)?,
+        )
     }
 
     fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
-        }
+        let mut bytes = self.as_secs().to_bytes_e(bigendian);
+        bytes.extend(self.subsec_nanos().to_bytes_e(bigendian));
 
-        return self.to_le_bytes().to_vec();
+        bytes
     }
-}
 
-impl SizedNumber for f32 {
-    fn size() -> usize {
-        4
+    fn from_bytes_checked_e(bytes: &[u8], bigendian: bool) -> Result<Self, DecodeError> {
+        let (secs, nanos) = <(u64, u32)>::from_bytes_checked_e(bytes, bigendian)?;
+        duration_from_parts(secs, nanos).ok_or(DecodeError::InvalidValue(
+            "nanoseconds are a whole second or more",
+        ))
     }
+}
+
+/// Builds a [`Duration`](std::time::Duration) from its parts, rejecting nanoseconds which [`Duration::new`](std::time::Duration::new) would
+/// carry into the seconds.
+fn duration_from_parts(secs: u64, nanos: u32) -> Option<std::time::Duration> {
+    (nanos < 1_000_000_000).then(|| std::time::Duration::new(secs, nanos))
+}
+
+/// Takes twelve bytes, like [`Duration`](std::time::Duration), holding the time as seconds since the Unix epoch as an [`i64`] followed by
+/// nanoseconds as a [`u32`], each converted with the specified endianness. Times before the epoch have negative seconds, with the
+/// nanoseconds still counting forward, so a time half a second before the epoch is `-1` seconds and `500_000_000` nanoseconds. Conversion
+/// from bytes returns [`None`] if the nanoseconds are a whole second or more, or if the time cannot be represented on this platform.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x1D, 0xCD, 0x65, 0x00]);
+///
+/// assert_eq!(
+///     cursor.shift_e::<SystemTime>(true),
+///     UNIX_EPOCH.checked_sub(Duration::from_millis(500))
+/// );
+/// ```
+impl SizedNumber for std::time::SystemTime {
+    const SIZE: usize = 12;
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 4 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3],
-        ]))
+        let (secs, nanos) = bytes.split_at(8);
+        system_time_from_parts(i64::from_bytes(secs)?, u32::from_bytes(nanos)?)
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        let (secs, nanos) = system_time_to_parts(self);
+
+        let mut bytes = secs.to_bytes();
+        bytes.extend(nanos.to_bytes());
+
+        bytes
     }
 }
-impl EndianNumber for f32 {
+impl EndianNumber for std::time::SystemTime {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 4 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        let slice = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let (secs, nanos) = bytes.split_at(8);
+        system_time_from_parts(
+            i64::from_bytes_e(secs, bigendian)?,
+            u32::from_bytes_e(nanos, bigendian)?,
+        )
+    }
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
+    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+        let (secs, nanos) = system_time_to_parts(self);
+
+        let mut bytes = secs.to_bytes_e(bigendian);
+        bytes.extend(nanos.to_bytes_e(bigendian));
 
-        Some(Self::from_le_bytes(slice))
+        bytes
     }
+}
 
-    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
-        }
+/// Builds a [`SystemTime`](std::time::SystemTime) from seconds relative to the Unix epoch and nanoseconds counting forward from them. Returns
+/// [`None`] if the nanoseconds are a whole second or more, or if the platform cannot represent the time.
+fn system_time_from_parts(secs: i64, nanos: u32) -> Option<std::time::SystemTime> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let nanos = duration_from_parts(0, nanos)?;
+    let whole = Duration::from_secs(secs.unsigned_abs());
 
-        return self.to_le_bytes().to_vec();
+    if secs < 0 {
+        UNIX_EPOCH.checked_sub(whole)?.checked_add(nanos)
+    } else {
+        UNIX_EPOCH.checked_add(whole)?.checked_add(nanos)
     }
 }
 
-impl SizedNumber for u64 {
-    fn size() -> usize {
-        8
+/// Splits a [`SystemTime`](std::time::SystemTime) into seconds relative to the Unix epoch, rounded down, and the nanoseconds after them. The
+/// seconds saturate at the bounds of an [`i64`].
+fn system_time_to_parts(time: &std::time::SystemTime) -> (i64, u32) {
+    match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(after) => (
+            i64::try_from(after.as_secs()).unwrap_or(i64::MAX),
+            after.subsec_nanos(),
+        ),
+        Err(before) => {
+            let before = before.duration();
+            let secs = i64::try_from(before.as_secs()).map_or(i64::MIN, |secs| -secs);
+
+            match before.subsec_nanos() {
+                0 => (secs, 0),
+                nanos => (secs.saturating_sub(1), 1_000_000_000 - nanos),
+            }
+        }
     }
+}
+
+macro_rules! ip_addr {
+    ($($(#[$meta:meta])* $addr:ident($bits:ident)),*) => {
+        $(
+            #[doc = concat!("Takes the bytes of the address as a [`", stringify!($bits), "`], in network byte order, which is how packets and \
+                most formats store it.")]
+            impl SizedNumber for std::net::$addr {
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    Self::from_bytes_e(bytes, true)
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_bytes_e(true)
+                }
+            }
+            $(#[$meta])*
+            impl EndianNumber for std::net::$addr {
+                fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                    Some(Self::from($bits::from_bytes_e(bytes, bigendian)?))
+                }
+
+                fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                    $bits::from(*self).to_bytes_e(bigendian)
+                }
+            }
+        )*
+    };
+}
+
+ip_addr!(
+    /// Converting with `bigendian` set to `false` reverses the bytes of the address, for formats which store it as a little-endian integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let mut cursor = Cursor::new(vec![192, 168, 0, 1, 1, 0, 168, 192]);
+    ///
+    /// assert_eq!(cursor.shift::<Ipv4Addr>(), Some(Ipv4Addr::new(192, 168, 0, 1)));
+    /// assert_eq!(cursor.shift_e::<Ipv4Addr>(false), Some(Ipv4Addr::new(192, 168, 0, 1)));
+    /// ```
+    Ipv4Addr(u32),
+    /// Converting with `bigendian` set to `false` reverses the bytes of the address, for formats which store it as a little-endian integer.
+    Ipv6Addr(u128)
+);
+
+macro_rules! socket_addr {
+    ($($(#[$meta:meta])* $addr:ident($ip:ident, $ip_size:literal) => |$ip_var:ident, $port_var:ident| $new:expr),*) => {
+        $(
+            $(#[$meta])*
+            impl SizedNumber for std::net::$addr {
+                const SIZE: usize = $ip_size + 2;
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    Self::from_bytes_e(bytes, true)
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_bytes_e(true)
+                }
+            }
+            impl EndianNumber for std::net::$addr {
+                fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                    if bytes.len() != Self::SIZE {
+                        return None;
+                    }
+
+                    let (ip, port) = bytes.split_at($ip_size);
+                    let ($ip_var, $port_var) = (
+                        std::net::$ip::from_bytes_e(ip, bigendian)?,
+                        u16::from_bytes_e(port, bigendian)?,
+                    );
+
+                    Some($new)
+                }
+
+                fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                    let mut bytes = self.ip().to_bytes_e(bigendian);
+                    bytes.extend(self.port().to_bytes_e(bigendian));
+
+                    bytes
+                }
+            }
+        )*
+    };
+}
+
+socket_addr!(
+    /// Takes six bytes, like a `sockaddr_in` without its family: the four bytes of the address followed by the port as a [`u16`]. Both are
+    /// in network byte order, unless converted with `bigendian` set to `false`, which reverses each of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    /// use std::net::{Ipv4Addr, SocketAddrV4};
+    ///
+    /// let mut cursor = Cursor::new(vec![192, 168, 0, 1, 0x1F, 0x90]);
+    ///
+    /// assert_eq!(
+    ///     cursor.shift_e::<SocketAddrV4>(true),
+    ///     Some(SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 8080))
+    /// );
+    /// ```
+    SocketAddrV4(Ipv4Addr, 4) => |ip, port| std::net::SocketAddrV4::new(ip, port),
+    /// Takes eighteen bytes: the sixteen bytes of the address followed by the port as a [`u16`]. Both are in network byte order, unless
+    /// converted with `bigendian` set to `false`, which reverses each of them. The flow information and scope identifier of a `sockaddr_in6`
+    /// are not stored, so they are zero when read and dropped when written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    /// use std::net::{Ipv6Addr, SocketAddrV6};
+    ///
+    /// let mut bytes = Ipv6Addr::LOCALHOST.octets().to_vec();
+    /// bytes.extend([0x01, 0xBB]);
+    ///
+    /// assert_eq!(
+    ///     Cursor::new(bytes).shift_e::<SocketAddrV6>(true),
+    ///     Some(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0))
+    /// );
+    /// ```
+    SocketAddrV6(Ipv6Addr, 16) => |ip, port| std::net::SocketAddrV6::new(ip, port, 0, 0)
+);
+
+macro_rules! pointer_sized {
+    ($($t:ident($wire:ident)),*) => {
+        $(
+            #[doc = concat!("Always takes 8 bytes, encoded as an [`", stringify!($wire), "`], so that files are read and written the same way \
+                on every platform regardless of the width of [`", stringify!($t), "`]. Conversion from bytes returns [`None`] if the value does \
+                not fit in a [`", stringify!($t), "`], such as a value above [`u32::MAX`] on a 32-bit target.")]
+            impl SizedNumber for $t {
+                const SIZE: usize = 8;
+                const WRITES_IN_PLACE: bool = true;
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    $t::try_from($wire::from_bytes(bytes)?).ok()
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    (*self as $wire).to_bytes()
+                }
+
+                fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+                    (*self as $wire).to_bytes_into(out)
+                }
+            }
+            impl EndianNumber for $t {
+                fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                    $t::try_from($wire::from_bytes_e(bytes, bigendian)?).ok()
+                }
+
+                fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                    (*self as $wire).to_bytes_e(bigendian)
+                }
+
+                fn to_bytes_into_e(&self, out: &mut [u8], bigendian: bool) -> Option<usize> {
+                    (*self as $wire).to_bytes_into_e(out, bigendian)
+                }
+            }
+        )*
+    };
+}
+
+pointer_sized!(usize(u64), isize(i64));
+
+impl<T: SizedNumber, const N: usize> SizedNumber for [T; N] {
+    /// The size of `N` values of `T`, which are laid out one after another.
+    const SIZE: usize = T::SIZE * N;
+    const WRITES_IN_PLACE: bool = T::WRITES_IN_PLACE;
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 8 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ]))
+        let size = T::SIZE;
+        let values = (0..N)
+            .map(|i| T::from_bytes(&bytes[i * size..(i + 1) * size]))
+            .collect::<Option<Vec<T>>>()?;
+
+        values.try_into().ok()
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        self.iter().flat_map(|value| value.to_bytes()).collect()
+    }
+
+    fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+        let out = out.get_mut(..Self::SIZE)?;
+
+        let mut at = 0;
+        for value in self {
+            at += value.to_bytes_into(&mut out[at..])?;
+        }
+
+        Some(at)
     }
 }
-impl EndianNumber for u64 {
+/// Every element is converted with the requested endianness, so each keeps its own byte order while the elements stay in their order.
+/// For `[u8; N]`, such as a hash or magic value, the flag has no effect.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(vec![0, 1, 0, 2, 0, 3]);
+///
+/// assert_eq!(cursor.next_e::<[u16; 3]>(true), Some([1, 2, 3]));
+/// assert_eq!(cursor.shift_e::<[u8; 2]>(false), Some([0, 1]));
+/// ```
+impl<T: EndianNumber, const N: usize> EndianNumber for [T; N] {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 8 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        let slice = [
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ];
+        let size = T::SIZE;
+        let values = (0..N)
+            .map(|i| T::from_bytes_e(&bytes[i * size..(i + 1) * size], bigendian))
+            .collect::<Option<Vec<T>>>()?;
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
+        values.try_into().ok()
+    }
+
+    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+        self.iter()
+            .flat_map(|value| value.to_bytes_e(bigendian))
+            .collect()
+    }
+
+    fn to_bytes_into_e(&self, out: &mut [u8], bigendian: bool) -> Option<usize> {
+        let out = out.get_mut(..Self::SIZE)?;
+
+        let mut at = 0;
+        for value in self {
+            at += value.to_bytes_into_e(&mut out[at..], bigendian)?;
         }
 
-        Some(Self::from_le_bytes(slice))
+        Some(at)
     }
+}
 
-    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
+macro_rules! tuple {
+    ($(#[$meta:meta])* $($t:ident $i:tt),+) => {
+        /// Returns the sum of the sizes of the elements, which are laid out in order without padding. Conversion from bytes returns
+        /// [`None`] if the length of the slice is not exactly that size, or if any element fails to convert.
+        impl<$($t: SizedNumber),+> SizedNumber for ($($t,)+) {
+            const SIZE: usize = 0 $(+ $t::SIZE)+;
+            const WRITES_IN_PLACE: bool = true $(&& $t::WRITES_IN_PLACE)+;
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != Self::SIZE {
+                    return None;
+                }
+
+                let mut at = 0;
+                let mut next = |size: usize| {
+                    at += size;
+                    &bytes[at - size..at]
+                };
+                Some(($($t::from_bytes(next($t::SIZE))?,)+))
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                let mut bytes = Vec::with_capacity(Self::SIZE);
+                $(bytes.extend(self.$i.to_bytes());)+
+
+                bytes
+            }
+
+            fn to_bytes_into(&self, out: &mut [u8]) -> Option<usize> {
+                let out = out.get_mut(..Self::SIZE)?;
+
+                let mut at = 0;
+                $(at += self.$i.to_bytes_into(&mut out[at..])?;)+
+
+                Some(at)
+            }
+        }
+        /// Every element is converted with the requested endianness, while the elements stay in their order.
+        $(#[$meta])*
+        impl<$($t: EndianNumber),+> EndianNumber for ($($t,)+) {
+            fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                if bytes.len() != Self::SIZE {
+                    return None;
+                }
+
+                let mut at = 0;
+                let mut next = |size: usize| {
+                    at += size;
+                    &bytes[at - size..at]
+                };
+                Some(($($t::from_bytes_e(next($t::SIZE), bigendian)?,)+))
+            }
+
+            fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                let mut bytes = Vec::with_capacity(Self::SIZE);
+                $(bytes.extend(self.$i.to_bytes_e(bigendian));)+
+
+                bytes
+            }
+
+            fn to_bytes_into_e(&self, out: &mut [u8], bigendian: bool) -> Option<usize> {
+                let out = out.get_mut(..Self::SIZE)?;
+
+                let mut at = 0;
+                $(at += self.$i.to_bytes_into_e(&mut out[at..], bigendian)?;)+
+
+                Some(at)
+            }
+        }
+    };
+}
+
+macro_rules! zero_sized {
+    ($(#[$meta:meta])* $($generics:ident)? => $ty:ty, $value:expr) => {
+        $(#[$meta])*
+        impl$(<$generics: ?Sized>)? SizedNumber for $ty {
+            const SIZE: usize = 0;
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                bytes.is_empty().then_some($value)
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                Vec::new()
+            }
         }
+        impl$(<$generics: ?Sized>)? EndianNumber for $ty {
+            fn from_bytes_e(bytes: &[u8], _bigendian: bool) -> Option<Self> {
+                Self::from_bytes(bytes)
+            }
+
+            fn to_bytes_e(&self, _bigendian: bool) -> Vec<u8> {
+                Vec::new()
+            }
+        }
+    };
+}
 
-        return self.to_le_bytes().to_vec();
+zero_sized!(
+    /// Takes no bytes, such as for a unit field of a generated record. Conversion from bytes returns [`None`] unless the slice is empty,
+    /// and reading one succeeds without moving the position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![7]);
+    ///
+    /// assert_eq!(cursor.shift::<((), u8)>(), Some(((), 7)));
+    /// assert_eq!(cursor.shift::<()>(), Some(()));
+    /// assert_eq!(<()>::from_bytes(&[0]), None);
+    /// ```
+    => (), ()
+);
+zero_sized!(
+    /// Takes no bytes, like `()`, so a marker for the type of a field can sit among the fields read.
+    T => std::marker::PhantomData<T>, std::marker::PhantomData
+);
+
+tuple!(A 0);
+tuple!(A 0, B 1);
+tuple!(
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0, 7, 0x80, 0x01, 0x3F, 0x80, 0, 0]);
+    ///
+    /// let (id, flags, weight): (u16, u16, f32) = cursor.shift_e(true).unwrap();
+    /// assert_eq!((id, flags, weight), (7, 0x8001, 1.0));
+    /// ```
+    A 0, B 1, C 2
+);
+tuple!(A 0, B 1, C 2, D 3);
+tuple!(A 0, B 1, C 2, D 3, E 4);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+macro_rules! normalized {
+    ($(#[$meta:meta])* $name:ident($inner:ty), $max:expr, $min:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name(pub $inner);
+
+        impl $name {
+            /// Converts the normalized value to an [`f32`].
+            pub fn to_f32(self) -> f32 {
+                (self.0 as f32 / $max as f32).max($min)
+            }
+            /// Converts an [`f32`] to the nearest normalized value, clamping it to the representable range first. NaN converts to zero.
+            pub fn from_f32(value: f32) -> Self {
+                Self((value.clamp($min, 1.0) * $max as f32).round() as $inner)
+            }
+        }
+
+        impl SizedNumber for $name {
+            const SIZE: usize = <$inner>::SIZE;
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                <$inner>::from_bytes(bytes).map(Self)
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.to_bytes()
+            }
+        }
+        impl EndianNumber for $name {
+            fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                <$inner>::from_bytes_e(bytes, bigendian).map(Self)
+            }
+
+            fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                self.0.to_bytes_e(bigendian)
+            }
+        }
+    };
+}
+
+normalized!(
+    /// An unsigned normalized 8-bit value, where `0` maps to `0.0` and `255` maps to `1.0`, as used by GPU vertex and texture formats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![255, 0, 51, 255]);
+    /// let rgba = cursor.shift::<[Unorm8; 4]>().unwrap();
+    ///
+    /// assert_eq!(rgba.map(Unorm8::to_f32), [1.0, 0.0, 0.2, 1.0]);
+    /// ```
+    Unorm8(u8),
+    u8::MAX,
+    0.0
+);
+normalized!(
+    /// An unsigned normalized 16-bit value, where `0` maps to `0.0` and `65535` maps to `1.0`.
+    Unorm16(u16),
+    u16::MAX,
+    0.0
+);
+normalized!(
+    /// A signed normalized 8-bit value, where `-127` maps to `-1.0` and `127` maps to `1.0`. `-128` also maps to `-1.0`, so that zero is
+    /// exactly representable.
+    Snorm8(i8),
+    i8::MAX,
+    -1.0
+);
+normalized!(
+    /// A signed normalized 16-bit value, where `-32767` maps to `-1.0` and `32767` maps to `1.0`. `-32768` also maps to `-1.0`, so that zero
+    /// is exactly representable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(Vec::new());
+    /// cursor.push_e(Snorm16::from_f32(-1.5), true).unwrap();
+    /// cursor.push_e(Snorm16::from_f32(1.0), true).unwrap();
+    /// cursor.set_position(0);
+    ///
+    /// assert_eq!(cursor.get_ref(), &[0x80, 0x01, 0x7F, 0xFF]);
+    /// assert_eq!(cursor.shift_e::<Snorm16>(true).unwrap().to_f32(), -1.0);
+    /// assert_eq!(cursor.shift_e::<Snorm16>(true).unwrap().to_f32(), 1.0);
+    /// ```
+    Snorm16(i16),
+    i16::MAX,
+    -1.0
+);
+
+/// The error returned when converting an integer which does not fit to one of the narrow integer types, such as [`U24`] or [`I48`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TryFromNarrowIntError;
+
+impl std::fmt::Display for TryFromNarrowIntError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "value out of range of a narrow integer type")
     }
 }
 
-impl SizedNumber for i64 {
-    fn size() -> usize {
-        8
+impl std::error::Error for TryFromNarrowIntError {}
+
+/// Returns the first `len` of the little-endian bytes `le` in the specified byte order.
+fn narrow_to_bytes(le: &[u8], len: usize, bigendian: bool) -> Vec<u8> {
+    let mut bytes = le[..len].to_vec();
+    if bigendian {
+        bytes.reverse();
     }
 
-    fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 8 {
-            return None;
-        }
+    bytes
+}
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ]))
+/// Returns `bytes` in the specified byte order as the low bytes of a little-endian array of `N` bytes, the rest being zero. Returns [`None`]
+/// if `bytes` is not `len` bytes long.
+fn narrow_from_bytes<const N: usize>(bytes: &[u8], len: usize, bigendian: bool) -> Option<[u8; N]> {
+    if bytes.len() != len {
+        return None;
     }
 
-    fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+    let mut le = [0u8; N];
+    le[..len].copy_from_slice(bytes);
+    if bigendian {
+        le[..len].reverse();
     }
+
+    Some(le)
 }
-impl EndianNumber for i64 {
-    fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 8 {
-            return None;
+
+macro_rules! narrow_int {
+    ($(#[$meta:meta])* $name:ident($inner:ident), $bytes:literal, unsigned, $max:literal, from $($small:ty),*; into $($wide:ty),*) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name($inner);
+
+        impl $name {
+            /// The smallest value, `0`.
+            pub const MIN: Self = Self(0);
+            #[doc = concat!("The largest value, `", stringify!($max), "`.")]
+            pub const MAX: Self = Self($max);
+
+            #[doc = concat!("Returns `value` as a [`", stringify!($name), "`], or [`None`] if it does not fit in ", stringify!($bytes), " bytes.")]
+            pub const fn new(value: $inner) -> Option<Self> {
+                if value <= Self::MAX.0 {
+                    Some(Self(value))
+                } else {
+                    None
+                }
+            }
+            #[doc = concat!("Returns the lower ", stringify!($bytes), " bytes of `value`, discarding the rest.")]
+            pub const fn wrapping_from(value: $inner) -> Self {
+                Self(value & Self::MAX.0)
+            }
+            #[doc = concat!("Returns the value as a [`", stringify!($inner), "`].")]
+            pub const fn get(self) -> $inner {
+                self.0
+            }
         }
 
-        let slice = [
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ];
+        impl EndianNumber for $name {
+            fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                narrow_from_bytes(bytes, $bytes, bigendian).map(|le| Self($inner::from_le_bytes(le)))
+            }
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
+            fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                narrow_to_bytes(&self.0.to_le_bytes(), $bytes, bigendian)
+            }
         }
 
-        Some(Self::from_le_bytes(slice))
-    }
+        narrow_int!(@common $name($inner), $bytes, from $($small),*; into $($wide),*);
+    };
+    ($(#[$meta:meta])* $name:ident($inner:ident), $bytes:literal, signed, $min:literal, $max:literal, from $($small:ty),*; into $($wide:ty),*) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name($inner);
+
+        impl $name {
+            #[doc = concat!("The smallest value, `", stringify!($min), "`.")]
+            pub const MIN: Self = Self($min);
+            #[doc = concat!("The largest value, `", stringify!($max), "`.")]
+            pub const MAX: Self = Self($max);
+
+            #[doc = concat!("Returns `value` as a [`", stringify!($name), "`], or [`None`] if it does not fit in ", stringify!($bytes), " bytes.")]
+            pub const fn new(value: $inner) -> Option<Self> {
+                if value >= Self::MIN.0 && value <= Self::MAX.0 {
+                    Some(Self(value))
+                } else {
+                    None
+                }
+            }
+            #[doc = concat!("Returns the lower ", stringify!($bytes), " bytes of `value` as a two's complement integer, discarding the rest.")]
+            pub const fn wrapping_from(value: $inner) -> Self {
+                const SHIFT: u32 = $inner::BITS - $bytes * 8;
+                Self((value << SHIFT) >> SHIFT)
+            }
+            #[doc = concat!("Returns the value as an [`", stringify!($inner), "`].")]
+            pub const fn get(self) -> $inner {
+                self.0
+            }
+        }
 
-    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
+        impl EndianNumber for $name {
+            fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                narrow_from_bytes(bytes, $bytes, bigendian).map(|le| Self::wrapping_from($inner::from_le_bytes(le)))
+            }
+
+            fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                narrow_to_bytes(&self.0.to_le_bytes(), $bytes, bigendian)
+            }
+        }
+
+        narrow_int!(@common $name($inner), $bytes, from $($small),*; into $($wide),*);
+    };
+    (@common $name:ident($inner:ident), $bytes:literal, from $($small:ty),*; into $($wide:ty),*) => {
+        impl SizedNumber for $name {
+            const SIZE: usize = $bytes;
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                Self::from_bytes_e(bytes, false)
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                self.to_bytes_e(false)
+            }
         }
 
-        return self.to_le_bytes().to_vec();
+        $(
+            impl From<$small> for $name {
+                fn from(value: $small) -> Self {
+                    Self($inner::from(value))
+                }
+            }
+        )*
+        $(
+            impl From<$name> for $wide {
+                fn from(value: $name) -> Self {
+                    value.0 as $wide
+                }
+            }
+        )*
+        impl TryFrom<$inner> for $name {
+            type Error = TryFromNarrowIntError;
+
+            fn try_from(value: $inner) -> Result<Self, Self::Error> {
+                Self::new(value).ok_or(TryFromNarrowIntError)
+            }
+        }
+    };
+}
+
+narrow_int!(
+    /// An unsigned 24-bit integer, as used by 24-bit PCM audio and the lengths of TLS handshake messages. It is held in a [`u32`] which
+    /// never exceeds [`U24::MAX`], and takes 3 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x01, 0x00, 0x2C]);
+    ///
+    /// assert_eq!(cursor.shift_e::<U24>(true).map(U24::get), Some(0x01002C));
+    /// assert_eq!(U24::new(0x0100_0000), None);
+    /// ```
+    U24(u32), 3, unsigned, 16777215, from u8, u16; into u32, u64, i32, i64
+);
+narrow_int!(
+    /// A signed 24-bit integer in two's complement, as used by 24-bit PCM audio. It is held in an [`i32`] between [`I24::MIN`] and
+    /// [`I24::MAX`], and takes 3 bytes. Conversion from bytes sign-extends the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x00, 0x00, 0x80, 0xFF, 0xFF, 0x7F]);
+    ///
+    /// assert_eq!(cursor.shift_e::<I24>(false), Some(I24::MIN));
+    /// assert_eq!(cursor.shift_e::<I24>(true).map(I24::get), Some(-129));
+    /// ```
+    I24(i32), 3, signed, -8388608, 8388607, from u8, i8, u16, i16; into i32, i64
+);
+narrow_int!(
+    /// An unsigned 48-bit integer, as used by MAC addresses and the millisecond timestamps of some capture and database formats. It is held
+    /// in a [`u64`] which never exceeds [`U48::MAX`], and takes 6 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x00, 0x1B, 0x63, 0x84, 0x45, 0xE6]);
+    ///
+    /// assert_eq!(cursor.shift_e::<U48>(true).map(U48::get), Some(0x001B_6384_45E6));
+    /// assert_eq!(U48::new(1 << 48), None);
+    /// ```
+    U48(u64), 6, unsigned, 281474976710655, from u8, u16, u32, U24; into u64, i64, u128, i128
+);
+narrow_int!(
+    /// A signed 48-bit integer in two's complement. It is held in an [`i64`] between [`I48::MIN`] and [`I48::MAX`], and takes 6 bytes.
+    /// Conversion from bytes sign-extends the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seqbytes::prelude::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE]);
+    ///
+    /// assert_eq!(cursor.shift_e::<I48>(true).map(I48::get), Some(-2));
+    /// ```
+    I48(i64), 6, signed, -140737488355328, 140737488355327, from u8, i8, u16, i16, u32, i32; into i64, i128
+);
+
+/// A GUID as laid out by Windows and COM formats, such as OLE compound files, registry hives and GPT partition tables. It takes sixteen
+/// bytes: `data1`, `data2` and `data3` converted with the specified endianness, followed by the eight bytes of `data4` in order. The
+/// default little-endian layout is the mixed-endian one of Windows, while big endian is the RFC 4122 layout of a UUID. With the `uuid`
+/// feature, it converts to and from `uuid::Uuid`.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// // The type GUID of an EFI System Partition, as stored in a GPT partition entry.
+/// let mut cursor = Cursor::new(vec![
+///     0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B,
+/// ]);
+/// let guid = cursor.shift::<Guid>().unwrap();
+///
+/// assert_eq!(guid.to_string(), "c12a7328-f81f-11d2-ba4b-00a0c93ec93b");
+/// assert_eq!(guid, Guid::from_u128(0xC12A7328_F81F_11D2_BA4B_00A0C93EC93B));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Guid {
+    /// The first eight hex digits.
+    pub data1: u32,
+    /// The next four hex digits.
+    pub data2: u16,
+    /// The next four hex digits.
+    pub data3: u16,
+    /// The last sixteen hex digits, as bytes in order.
+    pub data4: [u8; 8],
+}
+
+impl Guid {
+    /// Returns the GUID whose hex digits, in the order they are written, are those of `value`.
+    pub const fn from_u128(value: u128) -> Self {
+        Self {
+            data1: (value >> 96) as u32,
+            data2: (value >> 80) as u16,
+            data3: (value >> 64) as u16,
+            data4: (value as u64).to_be_bytes(),
+        }
+    }
+    /// Returns the hex digits of the GUID, in the order they are written, as a [`u128`].
+    pub const fn to_u128(self) -> u128 {
+        ((self.data1 as u128) << 96)
+            | ((self.data2 as u128) << 80)
+            | ((self.data3 as u128) << 64)
+            | u64::from_be_bytes(self.data4) as u128
     }
 }
 
-impl SizedNumber for f64 {
-    fn size() -> usize {
-        8
+impl std::fmt::Display for Guid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let d = self.data4;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            self.data1, self.data2, self.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+        )
     }
+}
 
-    fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 8 {
-            return None;
-        }
+impl SizedNumber for Guid {
+    const SIZE: usize = 16;
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ]))
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_e(bytes, false)
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        self.to_bytes_e(false)
     }
 }
-impl EndianNumber for f64 {
+impl EndianNumber for Guid {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 8 {
-            return None;
-        }
-
-        let slice = [
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ];
-
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
-
-        Some(Self::from_le_bytes(slice))
+        let (data1, data2, data3, data4) =
+            <(u32, u16, u16, [u8; 8])>::from_bytes_e(bytes, bigendian)?;
+
+        Some(Self {
+            data1,
+            data2,
+            data3,
+            data4,
+        })
     }
 
     fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
-        }
-
-        return self.to_le_bytes().to_vec();
+        (self.data1, self.data2, self.data3, self.data4).to_bytes_e(bigendian)
     }
 }
 
-impl SizedNumber for u128 {
-    fn size() -> usize {
-        16
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Guid {
+    fn from(id: uuid::Uuid) -> Self {
+        let (data1, data2, data3, data4) = id.as_fields();
+
+        Self {
+            data1,
+            data2,
+            data3,
+            data4: *data4,
+        }
+    }
+}
+#[cfg(feature = "uuid")]
+impl From<Guid> for uuid::Uuid {
+    fn from(guid: Guid) -> Self {
+        Self::from_fields(guid.data1, guid.data2, guid.data3, &guid.data4)
     }
+}
+
+/// A complex number is laid out as its real part followed by its imaginary part, each converted with the specified endianness, so a
+/// `Complex<f32>` takes 8 bytes and a `Complex<f64>` takes 16. This matches the interleaved IQ layout of SDR sample captures.
+///
+/// # Examples
+///
+/// ```
+/// use num_complex::Complex;
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(Vec::new());
+/// cursor.push_e(Complex::new(1.0f32, -2.0), false).unwrap();
+/// cursor.push_e(Complex::new(0.5f32, 0.25), false).unwrap();
+/// cursor.set_position(0);
+///
+/// let samples = cursor.shift_vec::<Complex<f32>>(2).unwrap();
+///
+/// assert_eq!(samples, [Complex::new(1.0, -2.0), Complex::new(0.5, 0.25)]);
+/// ```
+#[cfg(feature = "num-complex")]
+impl<T: SizedNumber> SizedNumber for num_complex::Complex<T> {
+    const SIZE: usize = T::SIZE * 2;
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 16 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-            bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
-        ]))
+        let (re, im) = bytes.split_at(T::SIZE);
+
+        Some(Self::new(T::from_bytes(re)?, T::from_bytes(im)?))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        let mut bytes = self.re.to_bytes();
+        bytes.extend(self.im.to_bytes());
+
+        bytes
     }
 }
-impl EndianNumber for u128 {
+#[cfg(feature = "num-complex")]
+impl<T: EndianNumber> EndianNumber for num_complex::Complex<T> {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 16 {
+        if bytes.len() != Self::SIZE {
             return None;
         }
 
-        let slice = [
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-            bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
-        ];
+        let (re, im) = bytes.split_at(T::SIZE);
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
-
-        Some(Self::from_le_bytes(slice))
+        Some(Self::new(
+            T::from_bytes_e(re, bigendian)?,
+            T::from_bytes_e(im, bigendian)?,
+        ))
     }
 
     fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
-        if bigendian {
-            return self.to_be_bytes().to_vec();
-        }
+        let mut bytes = self.re.to_bytes_e(bigendian);
+        bytes.extend(self.im.to_bytes_e(bigendian));
 
-        return self.to_le_bytes().to_vec();
+        bytes
     }
 }
 
-impl SizedNumber for i128 {
-    fn size() -> usize {
-        16
+/// Takes the bits of the half-precision float, so NaN payloads and signed zeros survive a round trip.
+#[cfg(feature = "half")]
+impl SizedNumber for half::f16 {
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self::from_bits(u16::from_bytes(bytes)?))
     }
 
-    fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != 16 {
-            return None;
-        }
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bits().to_bytes()
+    }
+}
+/// # Examples
+///
+/// ```
+/// use half::f16;
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(vec![0x00, 0x3C, 0xC0, 0x00]);
+///
+/// assert_eq!(cursor.shift_e::<f16>(false), Some(f16::ONE));
+/// assert_eq!(cursor.shift_e::<f16>(true), Some(f16::from_f32(-2.0)));
+/// ```
+#[cfg(feature = "half")]
+impl EndianNumber for half::f16 {
+    fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+        Some(Self::from_bits(u16::from_bytes_e(bytes, bigendian)?))
+    }
 
-        Some(Self::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-            bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
-        ]))
+    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+        self.to_bits().to_bytes_e(bigendian)
+    }
+}
+
+/// Takes the bits of the bfloat16, which are the upper half of the bits of the [`f32`] it widens to through [`From`], so NaN payloads and
+/// signed zeros survive a round trip.
+#[cfg(feature = "half")]
+impl SizedNumber for half::bf16 {
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self::from_bits(u16::from_bytes(bytes)?))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.to_le_bytes().to_vec()
+        self.to_bits().to_bytes()
     }
 }
-impl EndianNumber for i128 {
+/// # Examples
+///
+/// ```
+/// use half::bf16;
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+///
+/// // The weights 1.0 and -0.5, as stored in a little-endian tensor dump.
+/// let mut cursor = Cursor::new(vec![0x80, 0x3F, 0x00, 0xBF]);
+/// let weights = cursor.shift_vec_e::<bf16>(2, false).unwrap();
+///
+/// assert_eq!(weights.into_iter().map(f32::from).collect::<Vec<_>>(), [1.0, -0.5]);
+/// ```
+#[cfg(feature = "half")]
+impl EndianNumber for half::bf16 {
     fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
-        if bytes.len() != 16 {
-            return None;
-        }
+        Some(Self::from_bits(u16::from_bytes_e(bytes, bigendian)?))
+    }
 
-        let slice = [
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-            bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
-        ];
+    fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+        self.to_bits().to_bytes_e(bigendian)
+    }
+}
 
-        if bigendian {
-            return Some(Self::from_be_bytes(slice));
-        }
+/// Takes the sixteen bytes of the UUID in the RFC 4122 order, with every field big endian, so the bytes read in order are the hex digits of
+/// its string form.
+#[cfg(feature = "uuid")]
+impl SizedNumber for uuid::Uuid {
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_slice(bytes).ok()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+/// Converting with `bigendian` set to `false` uses the mixed-endian layout of Microsoft GUIDs, where the first three fields are little
+/// endian and the last eight bytes are kept in order.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use std::io::Cursor;
+/// use uuid::Uuid;
+///
+/// let id = Uuid::parse_str("00112233-4455-6677-8899-aabbccddeeff").unwrap();
+/// let mut cursor = Cursor::new(Vec::new());
+/// cursor.push(id).unwrap();
+/// cursor.push_e(id, false).unwrap();
+///
+/// assert_eq!(cursor.get_ref()[..4], [0x00, 0x11, 0x22, 0x33]);
+/// assert_eq!(cursor.get_ref()[16..20], [0x33, 0x22, 0x11, 0x00]);
+/// ```
+#[cfg(feature = "uuid")]
+impl EndianNumber for uuid::Uuid {
+    fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+        let bytes: [u8; 16] = bytes.try_into().ok()?;
 
-        Some(Self::from_le_bytes(slice))
+        Some(if bigendian {
+            Self::from_bytes(bytes)
+        } else {
+            Self::from_bytes_le(bytes)
+        })
     }
 
     fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
         if bigendian {
-            return self.to_be_bytes().to_vec();
+            self.as_bytes().to_vec()
+        } else {
+            self.to_bytes_le().to_vec()
         }
-
-        return self.to_le_bytes().to_vec();
     }
 }
+
+/// The reason a non-zero integer fails to convert from bytes.
+const ZERO: &str = "zero is not a valid non-zero integer";
+
+macro_rules! nonzero {
+    ($($nonzero:ident($inner:ty)),*) => {
+        $(
+            /// Takes the bytes of the underlying integer. Conversion from bytes returns [`None`] if the value is zero, for fields which must
+            /// never be zero, such as identifiers. Use the [`Option`] of this type for fields where zero means absent.
+            impl SizedNumber for std::num::$nonzero {
+                const SIZE: usize = <$inner>::SIZE;
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    std::num::$nonzero::new(<$inner>::from_bytes(bytes)?)
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.get().to_bytes()
+                }
+
+                fn from_bytes_checked(bytes: &[u8]) -> Result<Self, DecodeError> {
+                    std::num::$nonzero::new(<$inner>::from_bytes_checked(bytes)?).ok_or(DecodeError::InvalidValue(ZERO))
+                }
+            }
+            impl EndianNumber for std::num::$nonzero {
+                fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                    std::num::$nonzero::new(<$inner>::from_bytes_e(bytes, bigendian)?)
+                }
+
+                fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                    self.get().to_bytes_e(bigendian)
+                }
+
+                fn from_bytes_checked_e(bytes: &[u8], bigendian: bool) -> Result<Self, DecodeError> {
+                    std::num::$nonzero::new(<$inner>::from_bytes_checked_e(bytes, bigendian)?)
+                        .ok_or(DecodeError::InvalidValue(ZERO))
+                }
+            }
+            /// Encodes [`None`] as zero and [`Some`] as the underlying integer, so the size is that of the integer alone. This is the niche
+            /// encoding of a zero sentinel, such as a null index, and not a presence byte followed by the value.
+            impl SizedNumber for Option<std::num::$nonzero> {
+                const SIZE: usize = <$inner>::SIZE;
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    <$inner>::from_bytes(bytes).map(std::num::$nonzero::new)
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.map_or(0, std::num::$nonzero::get).to_bytes()
+                }
+            }
+            impl EndianNumber for Option<std::num::$nonzero> {
+                fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                    <$inner>::from_bytes_e(bytes, bigendian).map(std::num::$nonzero::new)
+                }
+
+                fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                    self.map_or(0, std::num::$nonzero::get).to_bytes_e(bigendian)
+                }
+            }
+        )*
+    };
+}
+
+nonzero!(
+    NonZeroU8(u8),
+    NonZeroI8(i8),
+    NonZeroU16(u16),
+    NonZeroI16(i16),
+    NonZeroU32(u32),
+    NonZeroI32(i32),
+    NonZeroU64(u64),
+    NonZeroI64(i64),
+    NonZeroU128(u128),
+    NonZeroI128(i128),
+    NonZeroUsize(usize),
+    NonZeroIsize(isize)
+);
+
+macro_rules! transparent {
+    ($($wrapper:ident),*) => {
+        $(
+            /// Takes the bytes of the wrapped value, so the wrapper reads and writes exactly as the wrapped type does.
+            impl<T: SizedNumber> SizedNumber for std::num::$wrapper<T> {
+                const SIZE: usize = T::SIZE;
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    T::from_bytes(bytes).map(std::num::$wrapper)
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.0.to_bytes()
+                }
+            }
+            impl<T: EndianNumber> EndianNumber for std::num::$wrapper<T> {
+                fn from_bytes_e(bytes: &[u8], bigendian: bool) -> Option<Self> {
+                    T::from_bytes_e(bytes, bigendian).map(std::num::$wrapper)
+                }
+
+                fn to_bytes_e(&self, bigendian: bool) -> Vec<u8> {
+                    self.0.to_bytes_e(bigendian)
+                }
+            }
+        )*
+    };
+}
+
+transparent!(Wrapping, Saturating);
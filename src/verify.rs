@@ -0,0 +1,318 @@
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use super::deterministic::insert_range;
+
+/// When a [`VerifyingWriter`] reads back what it wrote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerifyPolicy {
+    /// Every write is read back as soon as it is made, so a mismatch fails the write itself.
+    EveryWrite,
+    /// Writes are only read back by [`VerifyingWriter::flush_verify`], all at once, which keeps a copy of the bytes written since the last
+    /// check in memory.
+    OnFlush,
+}
+
+/// A byte which read back differently from how it was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VerificationError {
+    /// The absolute offset of the first byte which differs.
+    pub offset: u64,
+    /// The byte which was written.
+    pub expected: u8,
+    /// The byte which was read back.
+    pub actual: u8,
+}
+
+impl VerificationError {
+    /// Returns the [`VerificationError`] held by `error`, if it is one returned by a [`VerifyingWriter`].
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "verification failed at offset {}: wrote {:#04x}, read back {:#04x}",
+            self.offset, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+/// A writer which reads back the bytes it writes and compares them with what was written, for writing firmware images and other data to
+/// media which may not keep it.
+///
+/// The inner writer must read back what was written to it through the same handle. A byte which reads back differently is reported as an
+/// [`io::Error`] of kind [`io::ErrorKind::InvalidData`] holding a [`VerificationError`], which [`VerificationError::from_io`] extracts.
+/// The position is restored after every read-back, so writing carries on where it left off.
+///
+/// # Examples
+///
+/// ```
+/// use seqbytes::prelude::*;
+/// use seqbytes::verify::{VerifyPolicy, VerifyingWriter};
+/// use std::io::Cursor;
+///
+/// let mut writer = VerifyingWriter::new(Cursor::new(Vec::new()), VerifyPolicy::OnFlush).unwrap();
+///
+/// writer.push_slice(b"FW").unwrap();
+/// writer.push_e(0x0102u16, true).unwrap();
+///
+/// assert_eq!(writer.unverified(), [0..4]);
+/// writer.flush_verify().unwrap();
+/// assert!(writer.unverified().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct VerifyingWriter<W> {
+    inner: W,
+    policy: VerifyPolicy,
+    pos: u64,
+    pending: Vec<(u64, Vec<u8>)>,
+}
+
+impl<W: Write + Read + Seek> VerifyingWriter<W> {
+    /// Wraps `inner`, verifying writes as `policy` says.
+    pub fn new(mut inner: W, policy: VerifyPolicy) -> io::Result<Self> {
+        let pos = inner.stream_position()?;
+
+        Ok(Self {
+            inner,
+            policy,
+            pos,
+            pending: Vec::new(),
+        })
+    }
+    /// Returns when writes are verified.
+    pub fn policy(&self) -> VerifyPolicy {
+        self.policy
+    }
+    /// Returns the ranges of offsets written since the last successful check, in order. Always empty with [`VerifyPolicy::EveryWrite`].
+    pub fn unverified(&self) -> Vec<Range<u64>> {
+        let mut ranges = Vec::new();
+        for (offset, bytes) in &self.pending {
+            insert_range(&mut ranges, *offset..*offset + bytes.len() as u64);
+        }
+
+        ranges
+    }
+    /// Flushes the inner writer, then reads back every range written since the last check and compares it with the bytes written last at
+    /// each offset. Returns an error holding the [`VerificationError`] of the lowest offset which differs. The ranges are only forgotten once
+    /// they verify, so a failed check can be repeated after writing the bytes again.
+    pub fn flush_verify(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+
+        let ranges = self.unverified();
+        let mut expected: Vec<Vec<u8>> = ranges
+            .iter()
+            .map(|range| vec![0; (range.end - range.start) as usize])
+            .collect();
+
+        // Later writes are applied over earlier ones, so each offset expects the byte written there last.
+        for (offset, bytes) in &self.pending {
+            let i = ranges.partition_point(|range| range.end <= *offset);
+            let at = (offset - ranges[i].start) as usize;
+            expected[i][at..at + bytes.len()].copy_from_slice(bytes);
+        }
+
+        for (range, expected) in ranges.iter().zip(&expected) {
+            self.read_back(range.start, expected)?;
+        }
+
+        self.pending.clear();
+        Ok(())
+    }
+    /// Reads back the bytes at `offset` and compares them with `expected`, restoring the position afterwards.
+    fn read_back(&mut self, offset: u64, expected: &[u8]) -> io::Result<()> {
+        let mut actual = vec![0u8; expected.len()];
+
+        self.inner.seek(SeekFrom::Start(offset))?;
+        let read = self.inner.read_exact(&mut actual);
+        self.inner.seek(SeekFrom::Start(self.pos))?;
+        read?;
+
+        match expected.iter().zip(&actual).position(|(e, a)| e != a) {
+            None => Ok(()),
+            Some(i) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                VerificationError {
+                    offset: offset + i as u64,
+                    expected: expected[i],
+                    actual: actual[i],
+                },
+            )),
+        }
+    }
+}
+
+impl<W> VerifyingWriter<W> {
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /// Unwraps the inner writer, without verifying anything still pending.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write + Read + Seek> Write for VerifyingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let offset = self.pos;
+        let written = self.inner.write(buf)?;
+        self.pos += written as u64;
+
+        match self.policy {
+            VerifyPolicy::EveryWrite => self.read_back(offset, &buf[..written])?,
+            VerifyPolicy::OnFlush => self.pending.push((offset, buf[..written].to_vec())),
+        }
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + Read + Seek> Seek for VerifyingWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    /// A backend which flips the bits of whatever is written at one offset, like a stuck cell of flash memory.
+    struct Faulty {
+        inner: Cursor<Vec<u8>>,
+        bad: u64,
+    }
+
+    impl Write for Faulty {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let start = self.inner.position();
+
+            let mut bytes = buf.to_vec();
+            if (start..start + buf.len() as u64).contains(&self.bad) {
+                bytes[(self.bad - start) as usize] ^= 0xFF;
+            }
+
+            self.inner.write(&bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Read for Faulty {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for Faulty {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn faulty(bad: u64) -> Faulty {
+        Faulty {
+            inner: Cursor::new(Vec::new()),
+            bad,
+        }
+    }
+
+    #[test]
+    fn every_write_test() {
+        let mut writer = VerifyingWriter::new(faulty(5), VerifyPolicy::EveryWrite).unwrap();
+
+        writer.push_e(0xAABBu16, true).unwrap();
+        writer.push_slice(b"ok").unwrap();
+        assert!(writer.unverified().is_empty());
+
+        // Pushes report the mismatch as `None`, while writing directly returns the error holding it.
+        assert_eq!(writer.push_slice(&[0x10, 0x20, 0x30]), None);
+        writer.seek(SeekFrom::Start(4)).unwrap();
+        let error = writer.write_all(&[0x10, 0x20, 0x30]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            VerificationError::from_io(&error),
+            Some(&VerificationError {
+                offset: 5,
+                expected: 0x20,
+                actual: 0xDF
+            })
+        );
+
+        // The position is left after the failed write, and writing carries on from there.
+        assert_eq!(writer.stream_position().unwrap(), 7);
+        writer.push(0x40u8).unwrap();
+        assert_eq!(
+            writer.into_inner().inner.into_inner(),
+            [0xAA, 0xBB, b'o', b'k', 0x10, 0xDF, 0x30, 0x40]
+        );
+    }
+
+    #[test]
+    fn on_flush_test() {
+        let mut writer = VerifyingWriter::new(faulty(9), VerifyPolicy::OnFlush).unwrap();
+
+        writer.push_slice(&[1; 8]).unwrap();
+        writer.seek(SeekFrom::Start(12)).unwrap();
+        writer.push_e(7u32, false).unwrap();
+        writer.seek(SeekFrom::Start(8)).unwrap();
+        writer.push_slice(&[2, 3]).unwrap();
+        assert_eq!(
+            writer.unverified(),
+            [Range { start: 0, end: 10 }, Range { start: 12, end: 16 }]
+        );
+
+        let error = writer.flush_verify().unwrap_err();
+        assert_eq!(
+            VerificationError::from_io(&error),
+            Some(&VerificationError {
+                offset: 9,
+                expected: 3,
+                actual: 0xFC
+            })
+        );
+        assert_eq!(writer.stream_position().unwrap(), 10);
+        assert_eq!(writer.unverified().len(), 2);
+
+        // Writing the bad offset again through a clean backend makes the check pass.
+        writer.inner.bad = u64::MAX;
+        writer.seek(SeekFrom::Start(9)).unwrap();
+        writer.push(3u8).unwrap();
+        writer.flush_verify().unwrap();
+        assert!(writer.unverified().is_empty());
+    }
+
+    #[test]
+    fn overwrite_test() {
+        let mut writer =
+            VerifyingWriter::new(Cursor::new(vec![0xEE; 4]), VerifyPolicy::OnFlush).unwrap();
+
+        // The length is written as a placeholder and then patched, so only the final bytes are expected.
+        writer.push_e(0u16, true).unwrap();
+        writer.push_slice(b"data").unwrap();
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        writer.push_e(4u16, true).unwrap();
+        writer.seek(SeekFrom::End(0)).unwrap();
+
+        writer.flush_verify().unwrap();
+        assert_eq!(writer.stream_position().unwrap(), 6);
+        assert_eq!(writer.get_ref().get_ref(), b"\0\x04data");
+    }
+}